@@ -0,0 +1,34 @@
+#!/usr/bin/env python3
+"""Minimal controller for `ratride slides.md --control 7070`.
+
+Shows the current slide, title and timer on one line as ratride reports
+them, and sends each line typed on stdin (`next_slide`, `goto 3`,
+`toggle_timer`, ...) as a command. A Stream Deck plugin or a Bitfocus
+Companion "Generic TCP" connection talks to the same port the same way.
+"""
+
+import socket
+import sys
+import threading
+
+port = int(sys.argv[1]) if len(sys.argv) > 1 else 7070
+conn = socket.create_connection(("127.0.0.1", port))
+
+
+def show_state():
+    state = {}
+    for line in conn.makefile(encoding="utf-8"):
+        key, _, value = line.rstrip("\n").partition(" ")
+        if key == "error":
+            print(f"\nratride: {value}", file=sys.stderr)
+            continue
+        state[key] = value
+        slide = state.get("slide", "").split()
+        where = f"{slide[0]}/{slide[1]}" if len(slide) >= 2 else "?"
+        print(f"\r[{where}] {state.get('title', '')} | {state.get('timer', '')}\x1b[K", end="", flush=True)
+    print("\nratride closed the connection")
+
+
+threading.Thread(target=show_state, daemon=True).start()
+for command in sys.stdin:
+    conn.sendall(command.encode())
@@ -1,25 +1,34 @@
 use crate::backend::CanvasBackend;
 use crate::overlay::DomOverlay;
 use ratatui::{
-    Terminal,
+    Frame, Terminal,
     buffer::Buffer,
-    layout::{Constraint, Flex, Layout, Margin, Rect},
+    layout::{Constraint, Layout, Rect},
     style::{Color, Style},
     text::Span,
 };
+use ratride::action::Action;
+use ratride::deck::DeckState;
 use ratride::markdown::{
-    FigletFn, FigletWebMode, Frontmatter, Slide, SlideDirection, SlideLayout, TransitionKind,
-    parse_slides,
+    Agenda, FigletFn, FigletWebMode, Frontmatter, LogoCorner, Slide, SlideDirection, SlideLayout,
+    TransitionKind, parse_slides,
 };
-use ratride::render::{self, ImagePlacement};
+use ratride::render::{self, ActiveColumn, ImageCrossfade, ImagePlacement};
 use ratride::theme::Theme;
+#[cfg(feature = "effects")]
+use ratride::transition::TransitionRegistry;
 use std::collections::{HashMap, HashSet};
+#[cfg(feature = "effects")]
 use tachyonfx::{Duration, Effect, EffectRenderer};
-use web_sys::HtmlImageElement;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{
+    CustomEvent, CustomEventInit, HtmlAudioElement, HtmlCanvasElement, HtmlImageElement,
+    HtmlVideoElement,
+};
 
 /// Parse the first hex color from a figlet_color value like "ff0000,ffff00,00ffff".
 fn parse_first_hex_color(s: &str) -> Option<Color> {
-    let first = s.split(|c: char| c == ',' || c == ' ').next()?;
+    let first = s.split([',', ' ']).next()?;
     if first.len() < 6 {
         return None;
     }
@@ -30,8 +39,37 @@ fn parse_first_hex_color(s: &str) -> Option<Color> {
     Some(Color::Rgb(r, g, b))
 }
 
+/// Stand-in for `tachyonfx::Effect` when built without transitions; never constructed.
+#[cfg(not(feature = "effects"))]
+enum Effect {}
+
+/// Advance the running transition by one frame, clearing it once done.
+#[cfg(feature = "effects")]
+fn render_transition(effect: &mut Option<Effect>, frame: &mut Frame, area: Rect) {
+    if let Some(eff) = effect {
+        let delta = Duration::from_millis(FRAME_DURATION_MS as u32);
+        frame.render_effect(eff, area, delta);
+        if eff.done() {
+            *effect = None;
+        }
+    }
+}
+
+#[cfg(not(feature = "effects"))]
+fn render_transition(_effect: &mut Option<Effect>, _frame: &mut Frame, _area: Rect) {}
+
+/// The slide a `#/<n>` link in the page's address points at, 0-based.
+fn page_from_hash() -> Option<usize> {
+    let hash = web_sys::window()?.location().hash().ok()?;
+    let page: usize = hash.strip_prefix("#/")?.parse().ok()?;
+    page.checked_sub(1)
+}
+
+#[cfg(feature = "effects")]
 const FRAME_DURATION_MS: f64 = 16.0; // ~60fps
+#[cfg(feature = "effects")]
 const LINE_DUR_MS: f32 = 600.0;
+#[cfg(feature = "effects")]
 const STAGGER_MS: f32 = 60.0;
 const FIGLET_WIPE_MS: f64 = 200.0;
 
@@ -51,10 +89,16 @@ struct FigletImage {
 pub struct WebApp {
     terminal: Terminal<CanvasBackend>,
     slides: Vec<Slide>,
-    current_page: usize,
-    scroll_offsets: Vec<u16>,
+    /// Wrapped slide content, kept between frames until the slides change.
+    layouts: render::LayoutCache,
+    /// Current page and per-slide scroll offsets. The web display is the
+    /// audience's, so presenter-only slides are skipped.
+    deck: DeckState,
     theme: Theme,
     effect: Option<Effect>,
+    /// Transitions slides can name, built once.
+    #[cfg(feature = "effects")]
+    transitions: TransitionRegistry,
     prev_buffer: Option<Buffer>,
     last_timestamp: f64,
     cols: u16,
@@ -62,17 +106,62 @@ pub struct WebApp {
     images: HashMap<String, HtmlImageElement>,
     image_dims_resolved: HashSet<String>,
     pending_placements: Vec<ImagePlacement>,
+    /// Image placements of the slide being left, matched against the next
+    /// slide's on its first frame to decide whether to crossfade.
+    crossfade_from: Option<Vec<ImagePlacement>>,
+    /// Images fading into their replacements, and when the fade started.
+    image_crossfade: Option<(Vec<ImageCrossfade>, f64)>,
     overlay: DomOverlay,
     overlay_last_page: usize,
     overlay_last_scroll: u16,
+    overlay_last_step: usize,
     /// Per-slide figlet heading images.
     figlet_images: Vec<Vec<FigletImage>>,
     is_mobile: bool,
     figlet_web_mode: FigletWebMode,
     /// Timestamp when figlet wipe started (after transition ends).
     figlet_wipe_start: Option<f64>,
+    /// Timestamp when `animate: words` builds started (after transition ends).
+    words_start: Option<f64>,
     /// Wipe direction for figlet images (derived from slide transition).
     figlet_wipe_dir: SlideDirection,
+    /// Frontmatter `aspect` ratio the slide area is letterboxed to.
+    aspect: Option<f64>,
+    /// Per-slide narration clips, keyed by path.
+    audio: HashMap<String, HtmlAudioElement>,
+    /// Path of the clip currently playing.
+    playing_audio: Option<String>,
+    audio_muted: bool,
+    /// Embedded `<video>` elements, keyed by path.
+    videos: HashMap<String, HtmlVideoElement>,
+    /// Highlight a fixed row while scrolling long slides (toggled with `r`).
+    reading_ruler: bool,
+    /// Timestamp key hints were last shown in the status bar (first frame or `?`).
+    hints_shown_at: Option<f64>,
+    /// Focused column on two-column slides; the other is dimmed (cycled with `c`).
+    active_column: Option<ActiveColumn>,
+    /// Show the deck outline in a left sidebar (toggled with `o`).
+    outline: bool,
+    /// Section progress in the status bar (frontmatter `agenda`).
+    agenda: Option<Agenda>,
+    /// Draw a box with the path where an image failed to load (frontmatter `image_errors`).
+    show_image_errors: bool,
+    /// Image drawn in a corner of every slide (frontmatter `logo` and `logo_position`).
+    logo: Option<(String, LogoCorner)>,
+    /// Grid of slide thumbnails shown instead of the slide (toggled with `g`).
+    overview: bool,
+    /// Slide highlighted in the overview.
+    overview_selected: usize,
+    /// Thumbnail buffers and their images, rendered at `thumbnail_size`.
+    thumbnails: Vec<(Buffer, Vec<ImagePlacement>)>,
+    thumbnail_size: (u16, u16),
+    /// What the overview was last drawn for (selection, canvas size, loaded images),
+    /// so the canvas is only repainted when that changes.
+    overview_drawn: Option<(usize, (u32, u32), usize)>,
+    /// Running in an iframe, which gets `ratride:slidechange` messages too.
+    embedded: bool,
+    /// Embedded without keyboard focus; the status bar asks for a click.
+    focus_prompt: bool,
 }
 
 impl WebApp {
@@ -87,21 +176,58 @@ impl WebApp {
     ) -> Self {
         let cols = backend.cols();
         let rows = backend.rows();
-        let slides = parse_slides(markdown, &theme, frontmatter, figlet_fn, is_mobile);
+        let (slides, warnings) = parse_slides(markdown, &theme, frontmatter, figlet_fn, is_mobile);
+        for w in &warnings {
+            web_sys::console::warn_1(&format!("ratride: {w}").into());
+        }
         let len = slides.len().max(1);
         let mut terminal = Terminal::new(backend).expect("terminal creation");
         terminal.backend_mut().set_bg_color(theme.bg);
 
         // Collect unique image paths and preload them
         let mut images: HashMap<String, HtmlImageElement> = HashMap::new();
+        let logo = frontmatter
+            .logo
+            .clone()
+            .map(|path| (path, frontmatter.logo_position.unwrap_or_default()));
+        let slide_images = slides.iter().flat_map(|slide| &slide.images);
+        let paths = logo
+            .iter()
+            .map(|(path, _)| path)
+            .chain(slide_images.flat_map(|img| img.paths()));
+        for path in paths {
+            if images.contains_key(path) {
+                continue;
+            }
+            let el = HtmlImageElement::new().expect("create img element");
+            el.set_src(path);
+            images.insert(path.clone(), el);
+        }
+
+        // Preload narration clips
+        let mut audio: HashMap<String, HtmlAudioElement> = HashMap::new();
         for slide in &slides {
-            for img in &slide.images {
-                if images.contains_key(&img.path) {
+            if let Some(path) = &slide.audio {
+                if audio.contains_key(path) {
                     continue;
                 }
-                let el = HtmlImageElement::new().expect("create img element");
-                el.set_src(&img.path);
-                images.insert(img.path.clone(), el);
+                if let Ok(el) = HtmlAudioElement::new_with_src(path) {
+                    el.set_preload("auto");
+                    audio.insert(path.clone(), el);
+                }
+            }
+        }
+
+        // Create video elements (hidden until their slide is shown)
+        let mut videos: HashMap<String, HtmlVideoElement> = HashMap::new();
+        for slide in &slides {
+            for video in &slide.videos {
+                if videos.contains_key(&video.path) {
+                    continue;
+                }
+                if let Some(el) = overlay.create_video(&video.path) {
+                    videos.insert(video.path.clone(), el);
+                }
             }
         }
 
@@ -111,10 +237,12 @@ impl WebApp {
         Self {
             terminal,
             slides,
-            current_page: 0,
-            scroll_offsets: vec![0; len],
+            layouts: render::LayoutCache::default(),
+            deck: DeckState::new(len, frontmatter.remember_scroll.unwrap_or(false)),
             theme,
             effect: None,
+            #[cfg(feature = "effects")]
+            transitions: TransitionRegistry::default(),
             prev_buffer: None,
             last_timestamp: 0.0,
             cols,
@@ -122,18 +250,46 @@ impl WebApp {
             images,
             image_dims_resolved: HashSet::new(),
             pending_placements: Vec::new(),
+            crossfade_from: None,
+            image_crossfade: None,
             overlay,
             overlay_last_page: usize::MAX,
             overlay_last_scroll: u16::MAX,
+            overlay_last_step: 0,
             figlet_images,
             is_mobile,
             figlet_web_mode,
             figlet_wipe_start: None,
+            words_start: None,
             figlet_wipe_dir: SlideDirection::default(),
+            aspect: frontmatter.aspect,
+            audio,
+            playing_audio: None,
+            audio_muted: false,
+            videos,
+            reading_ruler: frontmatter.reading_ruler.unwrap_or(false),
+            hints_shown_at: None,
+            active_column: None,
+            outline: false,
+            agenda: frontmatter.agenda,
+            show_image_errors: frontmatter.image_errors.unwrap_or(true),
+            overview: false,
+            overview_selected: 0,
+            thumbnails: Vec::new(),
+            thumbnail_size: (0, 0),
+            overview_drawn: None,
+            embedded: false,
+            focus_prompt: false,
+            logo,
         }
     }
 
     pub fn init(&mut self) {
+        // Open on the slide a `#/<n>` link points at
+        if let Some(page) = page_from_hash() {
+            self.deck.goto(page);
+        }
+        self.deck.skip_hidden(&self.slides);
         let should_image = match self.figlet_web_mode {
             FigletWebMode::Image => true,
             FigletWebMode::Auto => self.is_mobile,
@@ -143,18 +299,164 @@ impl WebApp {
             self.process_figlet_headings();
         }
         self.effect = self.create_transition();
+        self.play_slide_audio();
+        self.dispatch_slide_change();
+    }
+
+    /// Emit `ratride:slidechange` on the document with the new index and title,
+    /// so hosting pages can hook analytics or sync surrounding content.
+    fn dispatch_slide_change(&self) {
+        let Some(document) = web_sys::window().and_then(|w| w.document()) else {
+            return;
+        };
+        let detail = js_sys::Object::new();
+        let title = self.slides[self.deck.current()].title.as_deref();
+        let _ = js_sys::Reflect::set(
+            &detail,
+            &"index".into(),
+            &JsValue::from(self.deck.current() as u32),
+        );
+        let _ = js_sys::Reflect::set(
+            &detail,
+            &"title".into(),
+            &title.map(JsValue::from).unwrap_or(JsValue::NULL),
+        );
+        let init = CustomEventInit::new();
+        init.set_detail(&detail);
+        if let Ok(event) = CustomEvent::new_with_event_init_dict("ratride:slidechange", &init) {
+            let _ = document.dispatch_event(&event);
+        }
+        if self.embedded
+            && let Some(parent) = web_sys::window().and_then(|w| w.parent().ok().flatten())
+        {
+            let _ = js_sys::Reflect::set(&detail, &"ratride".into(), &"slidechange".into());
+            let _ = parent.post_message(&detail, "*");
+        }
+    }
+
+    /// Mark the deck as running in an iframe.
+    pub fn set_embedded(&mut self, embedded: bool) {
+        self.embedded = embedded;
+    }
+
+    /// Track keyboard focus; only an embedded deck shows the prompt.
+    pub fn set_focused(&mut self, focused: bool) {
+        self.focus_prompt = self.embedded && !focused;
+    }
+
+    /// Handle a `postMessage` from the embedding page: `{ ratride: "next" }`,
+    /// `"prev"`, `"goto"` with a 0-based `page`, or `"key"` with a `key` name.
+    pub fn handle_message(&mut self, data: &JsValue) {
+        let field = |name: &str| js_sys::Reflect::get(data, &name.into()).ok();
+        let Some(action) = field("ratride").and_then(|v| v.as_string()) else {
+            return;
+        };
+        match action.as_str() {
+            "next" => self.next_page(),
+            "prev" => self.prev_page(),
+            "goto" => {
+                if let Some(page) = field("page").and_then(|v| v.as_f64()) {
+                    self.goto_page(page as usize);
+                }
+            }
+            "key" => {
+                if let Some(key) = field("key").and_then(|v| v.as_string()) {
+                    self.handle_key(&key);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Stop the previous clip and start the current slide's narration, if any.
+    fn play_slide_audio(&mut self) {
+        if let Some(el) = self.playing_audio.take().and_then(|p| self.audio.get(&p)) {
+            let _ = el.pause();
+        }
+        let Some(path) = self.slides[self.deck.current()].audio.clone() else {
+            return;
+        };
+        if let Some(el) = self.audio.get(&path) {
+            el.set_current_time(0.0);
+            // Autoplay may be blocked until the first user gesture; ignore the rejection.
+            let _ = el.play();
+            self.playing_audio = Some(path);
+        }
+    }
+
+    pub fn toggle_mute(&mut self) {
+        self.audio_muted = !self.audio_muted;
+        for el in self.audio.values() {
+            el.set_muted(self.audio_muted);
+        }
+    }
+
+    /// Play or pause the videos on the current slide.
+    pub fn toggle_video(&mut self) {
+        for video in &self.slides[self.deck.current()].videos {
+            if let Some(el) = self.videos.get(&video.path) {
+                if el.paused() {
+                    let _ = el.play();
+                } else {
+                    let _ = el.pause();
+                }
+            }
+        }
+    }
+
+    /// Show the next (or previous) image of the current slide's galleries.
+    fn step_gallery(&mut self, forward: bool) {
+        let slide = &mut self.slides[self.deck.current()];
+        if !slide.step_galleries(forward) {
+            return;
+        }
+        for img in &mut slide.images {
+            if let Some(el) = self.images.get(&img.path).filter(|el| el.complete()) {
+                img.pixel_width = el.natural_width();
+                img.pixel_height = el.natural_height();
+            }
+        }
+    }
+
+    /// Position the current slide's videos over their placeholders; hide and pause the rest.
+    fn update_videos(&self, placements: &[ImagePlacement]) {
+        if self.videos.is_empty() {
+            return;
+        }
+        let cell_w = self.terminal.backend().cell_width();
+        let cell_h = self.terminal.backend().cell_height();
+        for (path, el) in &self.videos {
+            let style = el.style();
+            match placements.iter().find(|p| &p.path == path) {
+                Some(p) => {
+                    let _ = style.set_property("left", &format!("{}px", p.x as f64 * cell_w));
+                    let _ = style.set_property("top", &format!("{}px", p.y as f64 * cell_h));
+                    let _ = style.set_property("width", &format!("{}px", p.width as f64 * cell_w));
+                    let _ =
+                        style.set_property("height", &format!("{}px", p.height as f64 * cell_h));
+                    let _ = style.set_property("display", "block");
+                }
+                None => {
+                    let _ = style.set_property("display", "none");
+                    if !el.paused() {
+                        let _ = el.pause();
+                    }
+                }
+            }
+        }
     }
 
     /// Render figlet headings to images and replace content lines with placeholders.
     fn process_figlet_headings(&mut self) {
         let font_size = self.terminal.backend().font_size();
+        let screen_cols = self.slide_area().width;
 
         for (slide_idx, slide) in self.slides.iter_mut().enumerate() {
             if slide.figlet_headings.is_empty() {
                 continue;
             }
             let cell_h = font_size * slide.line_height;
-            let content_cols = self.cols.saturating_sub(4);
+            let content_cols = screen_cols.saturating_sub(4);
             let content_css_w = content_cols as f64 * self.terminal.backend().cell_width();
 
             // Process in reverse order so line index adjustments don't affect earlier headings
@@ -186,6 +488,7 @@ impl WebApp {
                 let start = heading.line_index;
                 let end = (start + heading.line_count).min(slide.content.lines.len());
                 slide.content.lines.drain(start..end);
+                self.layouts.forget(slide_idx);
 
                 // Extract foreground color: prefer figlet_color directive, fall back to first span
                 let fg_color = heading
@@ -213,21 +516,7 @@ impl WebApp {
 
                 // Adjust line indices for elements after this heading
                 if line_delta != 0 {
-                    for sem in &mut slide.semantics {
-                        match sem {
-                            ratride::markdown::SemanticElement::Heading { line_index, .. }
-                            | ratride::markdown::SemanticElement::Link { line_index, .. } => {
-                                if *line_index > start {
-                                    *line_index = (*line_index as i32 + line_delta).max(0) as usize;
-                                }
-                            }
-                        }
-                    }
-                    for img in &mut slide.images {
-                        if img.line_index > start {
-                            img.line_index = (img.line_index as i32 + line_delta).max(0) as usize;
-                        }
-                    }
+                    slide.shift_lines(start, line_delta);
                     for fi in &mut figlet_imgs {
                         if fi.line_index > start {
                             fi.line_index = (fi.line_index as i32 + line_delta).max(0) as usize;
@@ -255,114 +544,372 @@ impl WebApp {
         }
     }
 
-    fn total_pages(&self) -> usize {
-        self.slides.len()
+    /// Cell area used for the slide, letterboxed to the frontmatter `aspect` if set.
+    /// The letterboxed screen area, before the outline sidebar is carved out of it.
+    fn screen_area(&self) -> Rect {
+        let area = Rect::new(0, 0, self.cols, self.rows);
+        let Some(aspect) = self.aspect else {
+            return area;
+        };
+        let backend = self.terminal.backend();
+        render::letterbox(area, aspect, backend.cell_height() / backend.cell_width())
     }
 
-    fn scroll_offset(&self) -> u16 {
-        self.scroll_offsets[self.current_page]
+    fn slide_area(&self) -> Rect {
+        let area = self.screen_area();
+        if self.outline {
+            render::outline_areas(area)[1]
+        } else {
+            area
+        }
     }
 
-    fn scroll_offset_mut(&mut self) -> &mut u16 {
-        &mut self.scroll_offsets[self.current_page]
+    fn total_pages(&self) -> usize {
+        self.slides.len()
     }
 
     fn can_scroll(&self) -> bool {
-        let visible = self.rows.saturating_sub(3) as usize;
-        let content_width = self.cols.saturating_sub(4);
-        let content_len =
-            render::wrapped_content_height(&self.slides[self.current_page].content, content_width);
-        content_len > visible
+        self.max_scroll() > 0
     }
 
     fn max_scroll(&self) -> u16 {
-        let visible = self.rows.saturating_sub(3) as usize;
-        let content_width = self.cols.saturating_sub(4);
-        let slide = &self.slides[self.current_page];
-        let content_len = render::wrapped_content_height(&slide.content, content_width);
-        let right_len = slide
-            .right_content
-            .as_ref()
-            .map_or(0, |r| render::wrapped_content_height(r, content_width));
-        content_len.max(right_len).saturating_sub(visible) as u16
+        // Slide content is drawn above the 1-row status bar
+        let area = self.slide_area();
+        let main_area = Rect {
+            height: area.height.saturating_sub(1),
+            ..area
+        };
+        let page = self.deck.current();
+        render::max_scroll(
+            &self.slides[page],
+            self.layouts.slot(page, usize::MAX),
+            main_area,
+        )
     }
 
     fn goto_page(&mut self, page: usize) {
-        if page < self.total_pages() && page != self.current_page {
-            self.current_page = page;
-            self.figlet_wipe_start = None;
-            self.figlet_wipe_dir = match &self.slides[page].transition {
-                TransitionKind::Slide(dir) => dir.clone(),
-                _ => SlideDirection::default(),
-            };
-            self.effect = self.create_transition();
+        let changed = self.deck.goto(page);
+        // Links and the overview can point at a presenter-only slide
+        if self.deck.skip_hidden(&self.slides) || changed {
+            self.on_page_change();
         }
     }
 
+    /// Reset per-slide state, start the transition and the new slide's audio.
+    fn on_page_change(&mut self) {
+        let page = self.deck.current();
+        self.active_column = None;
+        self.figlet_wipe_start = None;
+        self.words_start = None;
+        self.crossfade_from = Some(self.pending_placements.clone());
+        self.image_crossfade = None;
+        self.figlet_wipe_dir = match &self.slides[page].transition {
+            TransitionKind::Slide(dir) => dir.clone(),
+            _ => SlideDirection::default(),
+        };
+        self.effect = self.create_transition();
+        self.play_slide_audio();
+        self.dispatch_slide_change();
+        self.update_hash();
+    }
+
+    /// Point the page's `#/<n>` link at the current slide, so the address
+    /// bar can be shared (and matches the presenter's `y` links).
+    fn update_hash(&self) {
+        let Some(history) = web_sys::window().and_then(|w| w.history().ok()) else {
+            return;
+        };
+        let hash = format!("#/{}", self.deck.current() + 1);
+        let _ = history.replace_state_with_url(&JsValue::NULL, "", Some(&hash));
+    }
+
     pub fn next_page(&mut self) {
-        let next = self.current_page + 1;
-        self.goto_page(next);
+        if !self.overview && self.deck.next(&self.slides) {
+            self.on_page_change();
+        }
     }
 
     pub fn prev_page(&mut self) {
-        if self.current_page > 0 {
-            self.goto_page(self.current_page - 1);
+        if !self.overview && self.deck.prev(&self.slides) {
+            self.on_page_change();
         }
     }
 
-    pub fn scroll_down(&mut self, lines: u16) {
-        if self.can_scroll() {
-            *self.scroll_offset_mut() = self
-                .scroll_offset()
-                .saturating_add(lines)
-                .min(self.max_scroll());
+    fn goto_section(&mut self, forward: bool) {
+        if self.deck.goto_section(&self.slides, forward) {
+            self.on_page_change();
         }
     }
 
+    pub fn scroll_down(&mut self, lines: u16) {
+        let max = self.max_scroll();
+        self.deck.scroll_by(lines as i32, max, self.last_timestamp);
+    }
+
     pub fn scroll_up(&mut self, lines: u16) {
-        if self.can_scroll() {
-            *self.scroll_offset_mut() = self.scroll_offset().saturating_sub(lines);
-        }
+        let max = self.max_scroll();
+        self.deck
+            .scroll_by(-(lines as i32), max, self.last_timestamp);
+    }
+
+    /// Scroll offset to draw this frame, part way through any scroll animation.
+    fn visible_scroll(&self) -> u16 {
+        self.deck.visible_scroll(self.last_timestamp)
     }
 
     pub fn cell_height(&self) -> f64 {
         self.terminal.backend().cell_height()
     }
 
+    /// Switch the canvas font (called once the frontmatter `web_font` has loaded).
+    pub fn set_font_family(&mut self, family: &str) {
+        self.terminal.backend_mut().set_font_family(family);
+        self.cols = self.terminal.backend().cols();
+        // Link overlay positions depend on the cell width
+        self.overlay_last_page = usize::MAX;
+    }
+
     pub fn handle_key(&mut self, key: &str) {
-        match key {
-            "ArrowRight" | "l" | " " => self.next_page(),
-            "ArrowLeft" | "h" => self.prev_page(),
-            "ArrowDown" | "j" => self.scroll_down(1),
-            "ArrowUp" | "k" => self.scroll_up(1),
-            "d" => self.scroll_down(10),
-            "u" => self.scroll_up(10),
+        if self.overview {
+            self.handle_overview_key(key);
+            return;
+        }
+        let Some(action) = Action::from_key(key) else {
+            return;
+        };
+        match action {
+            Action::NextSlide => self.next_page(),
+            Action::PrevSlide => self.prev_page(),
+            Action::ScrollDown(lines) => self.scroll_down(lines),
+            Action::ScrollUp(lines) => self.scroll_up(lines),
+            Action::ToggleMute => self.toggle_mute(),
+            Action::ToggleVideo => self.toggle_video(),
+            Action::ToggleReadingRuler => self.reading_ruler = !self.reading_ruler,
+            Action::ShowHints => self.hints_shown_at = Some(self.last_timestamp),
+            Action::CycleColumn => self.active_column = ActiveColumn::cycle(self.active_column),
+            Action::ToggleOutline => {
+                self.outline = !self.outline;
+                self.overlay_last_page = usize::MAX;
+            }
+            Action::NextSection => self.goto_section(true),
+            Action::PrevSection => self.goto_section(false),
+            Action::ToggleOverview => self.toggle_overview(),
+            Action::NextImage => self.step_gallery(true),
+            Action::PrevImage => self.step_gallery(false),
             _ => {}
         }
     }
 
+    pub fn toggle_overview(&mut self) {
+        self.overview = !self.overview;
+        self.overview_selected = self.deck.current();
+        self.overview_drawn = None;
+        self.overlay_last_page = usize::MAX;
+    }
+
+    fn handle_overview_key(&mut self, key: &str) {
+        let last = self.total_pages() - 1;
+        let columns = self.overview_grid().0;
+        let selected = self.overview_selected;
+        self.overview_selected = match key {
+            "ArrowRight" | "l" => (selected + 1).min(last),
+            "ArrowLeft" | "h" => selected.saturating_sub(1),
+            "ArrowDown" | "j" => (selected + columns).min(last),
+            "ArrowUp" | "k" => selected.saturating_sub(columns),
+            "Enter" | " " => {
+                self.goto_page(selected);
+                self.toggle_overview();
+                return;
+            }
+            "g" | "Escape" => {
+                self.toggle_overview();
+                return;
+            }
+            _ => selected,
+        };
+    }
+
+    /// Jump to the thumbnail under a click at (`x`, `y`) CSS pixels, if the overview is shown.
+    pub fn handle_click(&mut self, x: f64, y: f64) {
+        if !self.overview {
+            return;
+        }
+        let (_, rects) = self.overview_grid();
+        let (w, h) = self.thumbnail_css_size();
+        let hit = rects.iter().position(|&(tx, ty, scale)| {
+            x >= tx && x < tx + w * scale && y >= ty && y < ty + h * scale
+        });
+        if let Some(page) = hit {
+            self.goto_page(page);
+            self.toggle_overview();
+        }
+    }
+
+    /// Size of an unscaled thumbnail in CSS pixels: the slide as it is drawn, without the status bar.
+    fn thumbnail_css_size(&self) -> (f64, f64) {
+        let area = self.screen_area();
+        let backend = self.terminal.backend();
+        (
+            area.width as f64 * backend.cell_width(),
+            area.height.saturating_sub(1) as f64 * backend.cell_height(),
+        )
+    }
+
+    /// Lay the overview out as evenly spaced rows of thumbnails, picking the column
+    /// count that makes them largest. Returns the column count and each slide's
+    /// top-left corner and scale in CSS pixels.
+    fn overview_grid(&self) -> (usize, Vec<(f64, f64, f64)>) {
+        const GAP: f64 = 16.0;
+        let backend = self.terminal.backend();
+        let (canvas_w, canvas_h) = (
+            self.cols as f64 * backend.cell_width(),
+            self.rows as f64 * backend.cell_height(),
+        );
+        let (thumb_w, thumb_h) = self.thumbnail_css_size();
+        let count = self.total_pages();
+        if thumb_w <= 0.0 || thumb_h <= 0.0 {
+            return (1, vec![(0.0, 0.0, 0.0); count]);
+        }
+        let scale_for = |columns: usize| {
+            let rows = count.div_ceil(columns);
+            let sx = (canvas_w - GAP * (columns + 1) as f64) / (columns as f64 * thumb_w);
+            let sy = (canvas_h - GAP * (rows + 1) as f64) / (rows as f64 * thumb_h);
+            sx.min(sy).max(0.0)
+        };
+        let columns = (1..=count)
+            .max_by(|&a, &b| scale_for(a).total_cmp(&scale_for(b)))
+            .unwrap_or(1);
+        let scale = scale_for(columns);
+        let rows = count.div_ceil(columns);
+        let (cell_w, cell_h) = (thumb_w * scale + GAP, thumb_h * scale + GAP);
+        // Center the grid on the canvas
+        let left = (canvas_w - columns as f64 * cell_w + GAP) / 2.0;
+        let top = (canvas_h - rows as f64 * cell_h + GAP) / 2.0;
+        let rects = (0..count)
+            .map(|i| {
+                let (col, row) = (i % columns, i / columns);
+                (left + col as f64 * cell_w, top + row as f64 * cell_h, scale)
+            })
+            .collect();
+        (columns, rects)
+    }
+
+    /// One canvas per slide with its whole first screen drawn on it, for the
+    /// print stylesheet to lay out a page each.
+    pub fn print_canvases(&self) -> Vec<HtmlCanvasElement> {
+        let Some(document) = web_sys::window().and_then(|w| w.document()) else {
+            return Vec::new();
+        };
+        let screen = self.screen_area();
+        let (css_w, css_h) = self.thumbnail_css_size();
+        let dpr = web_sys::window().map_or(1.0, |w| w.device_pixel_ratio());
+        let mut canvases = Vec::new();
+        for slide in &self.slides {
+            let Some(canvas) = document
+                .create_element("canvas")
+                .ok()
+                .and_then(|el| el.dyn_into::<HtmlCanvasElement>().ok())
+            else {
+                continue;
+            };
+            canvas.set_width((css_w * dpr).ceil() as u32);
+            canvas.set_height((css_h * dpr).ceil() as u32);
+            let mut backend = self.terminal.backend().with_canvas(canvas.clone());
+            let (buf, placements) = ratride::print::render_slide_buffer(
+                slide,
+                screen.width,
+                screen.height.saturating_sub(1),
+            );
+            let images: Vec<_> = placements
+                .iter()
+                .filter_map(|p| Some((self.images.get(&p.path)?, p)))
+                .collect();
+            let bg = if slide.bg_fill {
+                slide.theme.bg
+            } else {
+                self.theme.bg
+            };
+            backend.draw_thumbnail(&buf, &images, 0.0, 0.0, 1.0, bg);
+            canvases.push(canvas);
+        }
+        canvases
+    }
+
+    /// Paint the overview grid, re-rendering thumbnails when the slide size changed.
+    fn draw_overview(&mut self) {
+        let screen = self.screen_area();
+        let size = (screen.width, screen.height.saturating_sub(1));
+        if self.thumbnail_size != size || self.thumbnails.len() != self.slides.len() {
+            self.thumbnails = self
+                .slides
+                .iter()
+                .map(|slide| ratride::print::render_slide_buffer(slide, size.0, size.1))
+                .collect();
+            self.thumbnail_size = size;
+            self.overview_drawn = None;
+        }
+
+        let loaded = self.images.values().filter(|el| el.complete()).count();
+        let canvas_size = self.terminal.backend().pixel_size();
+        let key = (self.overview_selected, canvas_size, loaded);
+        if self.overview_drawn == Some(key) {
+            return;
+        }
+        self.overview_drawn = Some(key);
+
+        let (_, rects) = self.overview_grid();
+        let (thumb_w, thumb_h) = self.thumbnail_css_size();
+        self.terminal.backend_mut().set_bg_color(self.theme.bg);
+        self.terminal.clear().ok();
+        let backend = self.terminal.backend_mut();
+        for (i, ((buf, placements), &(x, y, scale))) in
+            self.thumbnails.iter().zip(&rects).enumerate()
+        {
+            let slide = &self.slides[i];
+            let bg = if slide.bg_fill {
+                slide.theme.bg
+            } else {
+                self.theme.bg
+            };
+            let images: Vec<_> = placements
+                .iter()
+                .filter_map(|p| Some((self.images.get(&p.path)?, p)))
+                .collect();
+            backend.draw_thumbnail(buf, &images, x, y, scale, bg);
+            let (w, h) = (thumb_w * scale, thumb_h * scale);
+            if i == self.overview_selected {
+                backend.stroke_rect(x - 3.0, y - 3.0, w + 6.0, h + 6.0, self.theme.h1, 3.0);
+            } else {
+                backend.stroke_rect(x, y, w, h, self.theme.status_bg, 1.0);
+            }
+        }
+    }
+
     /// Resolve image dimensions for newly loaded images.
     /// Only adjusts placeholder height for images with max_width_percent;
     /// images without it keep the fixed placeholder (matching terminal behavior).
     fn resolve_image_dimensions(&mut self) {
-        let content_w = self.cols.saturating_sub(4) as f64;
+        let content_w = self.slide_area().width.saturating_sub(4) as f64;
         let cell_w = self.terminal.backend().cell_width();
         let cell_h = self.terminal.backend().cell_height();
         let mut slide_deltas: Vec<i32> = Vec::new();
         for slide in &mut self.slides {
+            // Canvas cells are measured, so the frontmatter's guess isn't needed
+            slide.cell_aspect = cell_w / cell_h;
             let mut line_delta: i32 = 0;
-            for img in &mut slide.images {
-                img.line_index = ((img.line_index as i32) + line_delta).max(0) as usize;
+            for i in 0..slide.images.len() {
+                let img = &mut slide.images[i];
                 if self.image_dims_resolved.contains(&img.path) {
                     continue;
                 }
-                let el = match self.images.get(&img.path) {
-                    Some(el) => el,
-                    None => continue,
-                };
-                if !el.complete() || el.natural_width() == 0 {
+                let images = &self.images;
+                let Some(ratio) = img.tallest_ratio(|path| {
+                    let el = images.get(path).filter(|el| el.complete())?;
+                    Some((el.natural_width(), el.natural_height()))
+                }) else {
                     continue;
-                }
+                };
+                let el = &images[&img.path];
                 img.pixel_width = el.natural_width();
                 img.pixel_height = el.natural_height();
                 self.image_dims_resolved.insert(img.path.clone());
@@ -372,31 +919,34 @@ impl WebApp {
                     None => continue,
                 };
                 let display_w = content_w * pct.clamp(0.0, 1.0);
-                let new_h = (display_w * cell_w * img.pixel_height as f64
-                    / (img.pixel_width as f64 * cell_h))
-                    .ceil() as u16;
+                let new_h = (display_w * cell_w * ratio / cell_h).ceil() as u16;
                 let new_h = new_h.max(1);
 
-                if new_h < img.height {
-                    let to_remove = (img.height - new_h) as usize;
-                    let start = img.line_index + new_h as usize;
+                let (line, height) = (img.line_index, img.height);
+                if new_h < height {
+                    img.height = new_h;
+                    let to_remove = (height - new_h) as usize;
+                    let start = line + new_h as usize;
                     if start + to_remove <= slide.content.lines.len() {
                         slide.content.lines.drain(start..start + to_remove);
-                        line_delta -= to_remove as i32;
+                        self.layouts.clear();
+                        let delta = -(to_remove as i32);
+                        slide.shift_lines(line, delta);
+                        line_delta += delta;
                     }
+                } else if new_h > height {
                     img.height = new_h;
-                } else if new_h > img.height {
-                    let to_add = (new_h - img.height) as usize;
-                    let insert_at =
-                        (img.line_index + img.height as usize).min(slide.content.lines.len());
+                    let to_add = (new_h - height) as usize;
+                    let insert_at = (line + height as usize).min(slide.content.lines.len());
                     for _ in 0..to_add {
                         slide
                             .content
                             .lines
                             .insert(insert_at, ratatui::text::Line::default());
                     }
+                    self.layouts.clear();
+                    slide.shift_lines(line, to_add as i32);
                     line_delta += to_add as i32;
-                    img.height = new_h;
                 }
             }
             slide_deltas.push(line_delta);
@@ -404,6 +954,7 @@ impl WebApp {
         // Adjust figlet image positions when image resolutions changed content lines
         for (slide_idx, &delta) in slide_deltas.iter().enumerate() {
             if delta != 0 {
+                self.thumbnails.clear();
                 for fi in &mut self.figlet_images[slide_idx] {
                     fi.line_index = ((fi.line_index as i32) + delta).max(0) as usize;
                 }
@@ -414,8 +965,19 @@ impl WebApp {
     pub fn tick(&mut self, timestamp: f64) {
         self.last_timestamp = timestamp;
 
+        if self.overview {
+            self.terminal.backend_mut().resize();
+            self.cols = self.terminal.backend().cols();
+            self.rows = self.terminal.backend().rows();
+            self.resolve_image_dimensions();
+            self.draw_overview();
+            self.overlay.set_visible(false);
+            self.update_videos(&[]);
+            return;
+        }
+
         // Update per-slide line_height if changed
-        let slide_lh = self.slides[self.current_page].line_height;
+        let slide_lh = self.slides[self.deck.current()].line_height;
         self.terminal.backend_mut().set_line_height(slide_lh);
 
         // Update cols/rows from backend
@@ -425,10 +987,7 @@ impl WebApp {
 
         // Clamp scroll offset after resize (window may now be tall enough to fit all content)
         let max = self.max_scroll();
-        let off = self.scroll_offset_mut();
-        if *off > max {
-            *off = max;
-        }
+        self.deck.settle(max, timestamp);
 
         // Resolve image dimensions for newly loaded images
         self.resolve_image_dimensions();
@@ -436,8 +995,10 @@ impl WebApp {
         // Canvas doesn't retain cell state like a terminal, so reset viewport
         // buffer every frame to force full redraw (prevents stale pixels on scroll).
         // Use slide's bg color for canvas clear when bg_fill is enabled.
-        let current_page = self.current_page;
-        let slide = self.slides[current_page].clone();
+        let current_page = self.deck.current();
+        let slide = self.slides[current_page]
+            .at_step(self.deck.step())
+            .into_owned();
         if slide.bg_fill {
             self.terminal.backend_mut().set_bg_color(slide.theme.bg);
         } else {
@@ -446,12 +1007,32 @@ impl WebApp {
         self.terminal.clear().ok();
 
         let total_pages = self.total_pages();
-        let scroll = self.scroll_offset();
+        let scroll = self.visible_scroll();
         let theme = self.theme.clone();
+        let screen_area = self.slide_area();
+        let outline_area = self
+            .outline
+            .then(|| render::outline_areas(self.screen_area())[0]);
+        let slides = &self.slides;
+        let slot = self.layouts.slot(current_page, self.deck.step());
+        let images = &self.images;
+        let show_image_errors = self.show_image_errors;
+        let logo = &self.logo;
+        let letterboxed = self.aspect.is_some();
+        let reading_ruler = self.reading_ruler && self.can_scroll();
+        let active_column = self.active_column;
+        let hints_shown_at = *self.hints_shown_at.get_or_insert(timestamp);
+        let show_hints = timestamp - hints_shown_at < render::HINT_DURATION_MS;
+        let focus_prompt = self.focus_prompt;
+        let agenda = self.agenda.filter(|_| !show_hints);
+        let words_elapsed = self.words_start.map_or(0.0, |start| timestamp - start);
 
         let had_effect = self.effect.is_some();
         let mut effect = self.effect.take();
+        let crossfade_from = self.crossfade_from.take();
+        let mut crossfades = Vec::new();
         let mut placements = Vec::new();
+        let mut video_placements = Vec::new();
 
         let completed = self
             .terminal
@@ -460,15 +1041,13 @@ impl WebApp {
 
                 // Also fill buffer cells so flush doesn't clear_rect them back to transparent
                 if slide.bg_fill {
-                    let slide_bg = slide.theme.bg;
-                    let buf = frame.buffer_mut();
-                    for y in area.y..area.y + area.height {
-                        for x in area.x..area.x + area.width {
-                            if let Some(cell) = buf.cell_mut((x, y)) {
-                                cell.set_bg(slide_bg);
-                            }
-                        }
-                    }
+                    render::fill_background(frame, area, slide.theme.bg);
+                } else if letterboxed {
+                    render::fill_background(frame, area, theme.bg);
+                }
+                let area = screen_area;
+                if let Some(outline_area) = outline_area {
+                    render::draw_outline(slides, current_page, false, frame, outline_area, &theme);
                 }
 
                 let [main_area, status_area] =
@@ -476,17 +1055,40 @@ impl WebApp {
 
                 // Draw slide content, collect image placements
                 let (img_placements, _hyperlinks) =
-                    render::draw_slide(&slide, scroll, frame, main_area);
+                    render::draw_slide(&slide, slot, scroll, frame, main_area);
                 placements = img_placements;
-
-                // Apply transition effect
-                if let Some(ref mut eff) = effect {
-                    let delta = Duration::from_millis(FRAME_DURATION_MS as u32);
-                    frame.render_effect(eff, main_area, delta);
-                    if eff.done() {
+                if let Some((path, corner)) = logo {
+                    placements.extend(render::logo_placement(path, *corner, main_area));
+                }
+                if show_image_errors {
+                    for placement in &placements {
+                        // A finished load with no size means the browser couldn't load it
+                        if images
+                            .get(&placement.path)
+                            .is_some_and(|el| el.complete() && el.natural_width() == 0)
+                        {
+                            render::draw_image_error(frame, placement, "failed to load", &theme);
+                        }
+                    }
+                }
+                if let Some(prev) = &crossfade_from {
+                    // Fade matching images into each other instead of the text buffer
+                    crossfades = render::image_crossfades(prev, &placements);
+                    if !crossfades.is_empty() {
                         effect = None;
                     }
                 }
+                video_placements = render::video_placements(&slide, slot, scroll, main_area);
+                if let Some(active) = active_column {
+                    render::dim_inactive_column(&slide, active, frame, main_area, &theme);
+                }
+                render::reveal_words(&slide, slot, scroll, words_elapsed, frame, main_area);
+                if reading_ruler {
+                    render::draw_reading_ruler(frame, main_area, &theme);
+                }
+
+                // Apply transition effect
+                render_transition(&mut effect, frame, main_area);
 
                 // Header (top-right overlay)
                 let _ = render::draw_header(&slide.header, frame, main_area, &theme);
@@ -499,61 +1101,102 @@ impl WebApp {
                     status_area,
                     &theme,
                     true,
+                    show_hints,
                 );
+                if let Some(agenda) = agenda {
+                    render::draw_agenda(
+                        slides,
+                        current_page,
+                        false,
+                        agenda,
+                        frame,
+                        status_area,
+                        &theme,
+                    );
+                }
+                if focus_prompt {
+                    render::draw_focus_prompt(frame, status_area, &theme);
+                }
+                if slide.ascii_only {
+                    render::to_ascii(frame.buffer_mut(), &placements);
+                }
             })
             .expect("draw");
 
+        if !crossfades.is_empty() {
+            self.image_crossfade = Some((crossfades, timestamp));
+        }
+        if self
+            .image_crossfade
+            .as_ref()
+            .is_some_and(|(_, start)| timestamp - start >= render::IMAGE_CROSSFADE_MS)
+        {
+            self.image_crossfade = None;
+        }
+
         // Detect transition end → start figlet wipe
         self.effect = effect;
         if had_effect && self.effect.is_none() && !self.figlet_images[current_page].is_empty() {
             self.figlet_wipe_start = Some(timestamp);
         }
+        if self.effect.is_none() && self.words_start.is_none() {
+            self.words_start = Some(timestamp);
+        }
 
         self.pending_placements = placements;
         self.prev_buffer = Some(completed.buffer.clone());
 
         // Fill gap below the last cell row with status bar color
-        self.terminal.backend().fill_bottom_padding(theme.status_bg);
+        // (or the letterbox background when the status bar isn't at the bottom)
+        let padding_color = if letterboxed {
+            theme.bg
+        } else {
+            theme.status_bg
+        };
+        self.terminal.backend().fill_bottom_padding(padding_color);
 
         // Draw images on top of the cell grid (only when not in transition)
         if self.effect.is_none() {
             self.draw_images();
             self.update_overlay();
+            self.update_videos(&video_placements);
         } else {
             self.overlay.set_visible(false);
+            self.update_videos(&[]);
         }
     }
 
     fn update_overlay(&mut self) {
-        let page = self.current_page;
-        let scroll = self.scroll_offset();
-        if page == self.overlay_last_page && scroll == self.overlay_last_scroll {
+        let (page, step) = (self.deck.current(), self.deck.step());
+        let scroll = self.visible_scroll();
+        if page == self.overlay_last_page
+            && step == self.overlay_last_step
+            && scroll == self.overlay_last_scroll
+        {
             self.overlay.set_visible(true);
             return;
         }
         self.overlay_last_page = page;
+        self.overlay_last_step = step;
         self.overlay_last_scroll = scroll;
 
-        let slide = &self.slides[page];
+        let area = self.slide_area();
+        let slide = self.slides[page].at_step(step);
         let cell_w = self.terminal.backend().cell_width();
         let cell_h = self.terminal.backend().cell_height();
-        // Content area offset: Margin::new(2, 1) in render.rs
-        let content_offset_x = 2.0 * cell_w;
-        let mut content_offset_y = 1.0 * cell_h;
-        let visible_rows = self.rows.saturating_sub(3);
-        let content_width = self.cols.saturating_sub(4);
+        // Content area offset: render::content_margin of the area above the status bar
+        let main_area = Rect::new(area.x, area.y, area.width, area.height.saturating_sub(1));
+        let margin = render::content_margin(main_area);
+        let content_offset_x = (area.x + margin.horizontal) as f64 * cell_w;
+        let mut content_offset_y = (area.y + margin.vertical) as f64 * cell_h;
+        let visible_rows = main_area.height.saturating_sub(2 * margin.vertical);
+        let content_width = area.width.saturating_sub(2 * margin.horizontal);
 
         let is_center = matches!(slide.layout, SlideLayout::Center);
         if is_center {
-            // Mirror the exact Layout used in render::draw_center
-            let main_area = Rect::new(0, 0, self.cols, self.rows.saturating_sub(1));
-            let content_area = main_area.inner(Margin::new(2, 1));
-            let content_height =
-                render::wrapped_content_height(&slide.content, content_area.width) as u16;
-            let [centered] = Layout::vertical([Constraint::Length(content_height)])
-                .flex(Flex::Center)
-                .areas(content_area);
-            content_offset_y = centered.y as f64 * cell_h;
+            let slot = self.layouts.slot(page, step);
+            content_offset_y =
+                render::slide_content_area(&slide, slot, main_area).y as f64 * cell_h;
         }
 
         self.overlay.update(
@@ -569,26 +1212,52 @@ impl WebApp {
             content_width,
         );
         // Header links (top-right overlay, row 0 of main_area)
-        self.overlay
-            .update_header_links(&slide.header, 0.0, 0.0, cell_w, cell_h, self.cols);
+        self.overlay.update_header_links(
+            &slide.header,
+            area.x as f64 * cell_w,
+            area.y as f64 * cell_h,
+            cell_w,
+            cell_h,
+            area.width,
+        );
         self.overlay.set_visible(true);
     }
 
     fn draw_images(&mut self) {
+        let backend = self.terminal.backend();
         for placement in &self.pending_placements {
-            if let Some(img_el) = self.images.get(&placement.path) {
-                self.terminal.backend().draw_image(img_el, placement);
+            let Some(img_el) = self.images.get(&placement.path) else {
+                continue;
+            };
+            let fade = self
+                .image_crossfade
+                .as_ref()
+                .and_then(|(crossfades, start)| {
+                    let crossfade = crossfades.iter().find(|c| c.to == placement.path)?;
+                    let t = (self.last_timestamp - start) / render::IMAGE_CROSSFADE_MS;
+                    Some((crossfade, t))
+                });
+            match fade {
+                Some((crossfade, t)) => {
+                    if let Some(from_el) = self.images.get(&crossfade.from) {
+                        backend.draw_image(from_el, placement);
+                    }
+                    backend.draw_image_with_alpha(img_el, placement, t.clamp(0.0, 1.0));
+                }
+                None => backend.draw_image(img_el, placement),
             }
         }
         self.draw_figlet_images();
     }
 
     fn draw_figlet_images(&mut self) {
-        let page = self.current_page;
+        let page = self.deck.current();
         let figlet_imgs = &self.figlet_images[page];
         if figlet_imgs.is_empty() {
             return;
         }
+        // Headings on fragments not revealed yet stay hidden
+        let hidden = self.slides[page].fragments.get(self.deck.step()).copied();
 
         // Compute wipe progress (0..1, left to right, ease-out)
         let wipe_progress = if let Some(start) = self.figlet_wipe_start {
@@ -603,27 +1272,24 @@ impl WebApp {
             1.0
         };
 
+        let area = self.slide_area();
         let slide = &self.slides[page];
-        let scroll = self.scroll_offset() as i32;
+        let scroll = self.visible_scroll() as i32;
         let cell_w = self.terminal.backend().cell_width();
         let cell_h = self.terminal.backend().cell_height();
-        let visible_rows = self.rows.saturating_sub(3) as i32;
 
-        // Content area offset: Margin::new(2, 1) in render.rs
-        let content_offset_x = 2.0 * cell_w;
-        let mut content_offset_y = 1.0 * cell_h;
-        let content_width = self.cols.saturating_sub(4);
+        // Content area offset: render::content_margin of the area above the status bar
+        let main_area = Rect::new(area.x, area.y, area.width, area.height.saturating_sub(1));
+        let margin = render::content_margin(main_area);
+        let content_offset_x = (area.x + margin.horizontal) as f64 * cell_w;
+        let mut content_offset_y = (area.y + margin.vertical) as f64 * cell_h;
+        let visible_rows = main_area.height.saturating_sub(2 * margin.vertical) as i32;
+        let content_width = area.width.saturating_sub(2 * margin.horizontal);
 
         let is_center = matches!(slide.layout, SlideLayout::Center);
         if is_center {
-            let main_area = Rect::new(0, 0, self.cols, self.rows.saturating_sub(1));
-            let content_area = main_area.inner(Margin::new(2, 1));
-            let content_height =
-                render::wrapped_content_height(&slide.content, content_area.width) as u16;
-            let [centered] = Layout::vertical([Constraint::Length(content_height)])
-                .flex(Flex::Center)
-                .areas(content_area);
-            content_offset_y = centered.y as f64 * cell_h;
+            let slot = self.layouts.slot(page, usize::MAX);
+            content_offset_y = render::slide_content_area(slide, slot, main_area).y as f64 * cell_h;
         }
 
         let content_css_w = content_width as f64 * cell_w;
@@ -631,6 +1297,9 @@ impl WebApp {
         let ctx = backend.ctx();
 
         for fi in figlet_imgs {
+            if hidden.is_some_and(|line| fi.line_index >= line) {
+                continue;
+            }
             let y_cell = fi.line_index as i32 - scroll;
             let end_cell = y_cell + fi.placeholder_lines as i32;
             // Skip if entirely off-screen
@@ -694,15 +1363,22 @@ impl WebApp {
         }
     }
 
+    #[cfg(not(feature = "effects"))]
+    fn create_transition(&self) -> Option<Effect> {
+        None
+    }
+
+    #[cfg(feature = "effects")]
     fn create_transition(&self) -> Option<Effect> {
-        let slide = &self.slides[self.current_page];
+        let slide = &self.slides[self.deck.current()];
         let bg = self.theme.bg;
         let prev_buf = self.prev_buffer.clone();
         ratride::transition::create_transition(
+            &self.transitions,
             &slide.transition,
             bg,
             prev_buf,
-            self.rows,
+            self.slide_area().height,
             slide.content.lines.len(),
             LINE_DUR_MS,
             STAGGER_MS,
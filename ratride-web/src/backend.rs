@@ -1,6 +1,6 @@
 use ratatui::{
     backend::{Backend, ClearType, WindowSize},
-    buffer::Cell,
+    buffer::{Buffer, Cell},
     layout::{Position, Size},
     style::{Color, Modifier},
     text::Line,
@@ -13,6 +13,8 @@ use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, HtmlImageElement};
 /// Extra padding (px) added to each cell's background fill to eliminate sub-pixel gaps.
 const BG_PAD: f64 = 0.5;
 
+const DEFAULT_FONT_FAMILY: &str = "monospace";
+
 pub struct CanvasBackend {
     canvas: HtmlCanvasElement,
     ctx: CanvasRenderingContext2d,
@@ -24,6 +26,8 @@ pub struct CanvasBackend {
     line_height: f64,
     dpr: f64,
     bg_css: Option<String>,
+    /// CSS font-family used for all text (frontmatter `web_font` prepends a webfont).
+    font_family: String,
 }
 
 impl CanvasBackend {
@@ -44,7 +48,7 @@ impl CanvasBackend {
 
         // Measure cell size in CSS pixels (after DPR scaling is applied)
         let scaled_font_size = font_size;
-        let font = format!("{scaled_font_size}px monospace");
+        let font = format!("{scaled_font_size}px {DEFAULT_FONT_FAMILY}");
         ctx.set_font(&font);
         let metrics = ctx.measure_text("W").unwrap();
         let cell_width = metrics.width();
@@ -68,9 +72,19 @@ impl CanvasBackend {
             line_height,
             dpr,
             bg_css: None,
+            font_family: DEFAULT_FONT_FAMILY.to_string(),
         }
     }
 
+    /// A backend drawing on `canvas` with the same font and cell size as this one.
+    pub fn with_canvas(&self, canvas: HtmlCanvasElement) -> Self {
+        let mut backend = Self::new(canvas, self.font_size, self.line_height);
+        backend.font_family = self.font_family.clone();
+        backend.cell_width = self.cell_width;
+        backend.ctx.set_font(&backend.css_font());
+        backend
+    }
+
     pub fn set_bg_color(&mut self, color: Color) {
         self.bg_css = Some(Self::color_to_css(color, "transparent"));
     }
@@ -91,10 +105,32 @@ impl CanvasBackend {
         self.cell_height
     }
 
+    /// Canvas bitmap size in physical pixels.
+    pub fn pixel_size(&self) -> (u32, u32) {
+        (self.canvas.width(), self.canvas.height())
+    }
+
     pub fn font_size(&self) -> f64 {
         self.font_size
     }
 
+    /// CSS font shorthand for regular text.
+    fn css_font(&self) -> String {
+        format!("{}px {}", self.font_size, self.font_family)
+    }
+
+    /// Switch to a different font family (e.g. once a webfont has loaded),
+    /// re-measuring the cell width.
+    pub fn set_font_family(&mut self, family: &str) {
+        self.font_family = format!("{family}, {DEFAULT_FONT_FAMILY}");
+        self.ctx.set_font(&self.css_font());
+        if let Ok(metrics) = self.ctx.measure_text("W") {
+            self.cell_width = metrics.width();
+        }
+        let css_w = self.canvas.width() as f64 / self.dpr;
+        self.cols = (css_w / self.cell_width) as u16;
+    }
+
     pub fn ctx(&self) -> &CanvasRenderingContext2d {
         &self.ctx
     }
@@ -118,32 +154,26 @@ impl CanvasBackend {
         self.cell_height = nominal_cell_height;
     }
 
-    pub fn line_height(&self) -> f64 {
-        self.line_height
-    }
-
     pub fn resize(&mut self) {
         // Apply pending size from JS (data-tw / data-th attributes) so that
         // the canvas bitmap clear, CSS resize, and redraw all happen in the
         // same rAF frame — preventing both flicker and bitmap stretching.
         let el: &web_sys::Element = self.canvas.as_ref();
-        if let (Some(tw), Some(th)) = (el.get_attribute("data-tw"), el.get_attribute("data-th")) {
-            if let (Ok(pw), Ok(ph)) = (tw.parse::<u32>(), th.parse::<u32>()) {
-                if self.canvas.width() != pw || self.canvas.height() != ph {
-                    self.canvas.set_width(pw);
-                    self.canvas.set_height(ph);
-                    // Update CSS size to match (CSS px = physical px / dpr)
-                    let style = self.canvas.style();
-                    let _ = style.set_property("width", &format!("{}px", pw as f64 / self.dpr));
-                    let _ = style.set_property("height", &format!("{}px", ph as f64 / self.dpr));
-                    // Re-apply DPR scale + font after canvas size change resets context
-                    let _ = self
-                        .ctx
-                        .set_transform(self.dpr, 0.0, 0.0, self.dpr, 0.0, 0.0);
-                    let font = format!("{}px monospace", self.font_size);
-                    self.ctx.set_font(&font);
-                }
-            }
+        if let (Some(tw), Some(th)) = (el.get_attribute("data-tw"), el.get_attribute("data-th"))
+            && let (Ok(pw), Ok(ph)) = (tw.parse::<u32>(), th.parse::<u32>())
+            && (self.canvas.width() != pw || self.canvas.height() != ph)
+        {
+            self.canvas.set_width(pw);
+            self.canvas.set_height(ph);
+            // Update CSS size to match (CSS px = physical px / dpr)
+            let style = self.canvas.style();
+            let _ = style.set_property("width", &format!("{}px", pw as f64 / self.dpr));
+            let _ = style.set_property("height", &format!("{}px", ph as f64 / self.dpr));
+            // Re-apply DPR scale + font after canvas size change resets context
+            let _ = self
+                .ctx
+                .set_transform(self.dpr, 0.0, 0.0, self.dpr, 0.0, 0.0);
+            self.ctx.set_font(&self.css_font());
         }
 
         let css_w = self.canvas.width() as f64 / self.dpr;
@@ -157,8 +187,19 @@ impl CanvasBackend {
         let _ = self
             .ctx
             .set_transform(self.dpr, 0.0, 0.0, self.dpr, 0.0, 0.0);
-        let font = format!("{}px monospace", self.font_size);
-        self.ctx.set_font(&font);
+        self.ctx.set_font(&self.css_font());
+    }
+
+    /// Draw an image at `alpha` opacity (used to crossfade images between slides).
+    pub fn draw_image_with_alpha(
+        &self,
+        img: &HtmlImageElement,
+        placement: &ImagePlacement,
+        alpha: f64,
+    ) {
+        self.ctx.set_global_alpha(alpha);
+        self.draw_image(img, placement);
+        self.ctx.set_global_alpha(1.0);
     }
 
     /// Draw an image on the canvas with optional clipping when partially off-screen.
@@ -230,6 +271,62 @@ impl CanvasBackend {
         }
     }
 
+    /// Draw a slide thumbnail: `buf` and its images scaled by `scale` with the
+    /// top-left corner at (`x`, `y`) in CSS pixels, on a `bg` backdrop.
+    pub fn draw_thumbnail(
+        &mut self,
+        buf: &Buffer,
+        images: &[(&HtmlImageElement, &ImagePlacement)],
+        x: f64,
+        y: f64,
+        scale: f64,
+        bg: Color,
+    ) {
+        let dpr = self.dpr;
+        let _ = self
+            .ctx
+            .set_transform(dpr * scale, 0.0, 0.0, dpr * scale, dpr * x, dpr * y);
+        let area = buf.area;
+        self.ctx
+            .set_fill_style_str(&Self::color_to_css(bg, "transparent"));
+        self.ctx.fill_rect(
+            0.0,
+            0.0,
+            area.width as f64 * self.cell_width,
+            area.height as f64 * self.cell_height,
+        );
+        // Only non-blank cells; unset backgrounds take the backdrop instead of clearing it
+        let cells: Vec<(u16, u16, Cell)> = area
+            .positions()
+            .filter_map(|pos| {
+                let cell = &buf[pos];
+                if cell.bg == Color::Reset && cell.symbol() == " " {
+                    return None;
+                }
+                let mut cell = cell.clone();
+                if cell.bg == Color::Reset {
+                    cell.bg = bg;
+                }
+                Some((pos.x, pos.y, cell))
+            })
+            .collect();
+        let _ = self.draw(cells.iter().map(|(x, y, cell)| (*x, *y, cell)));
+        for (img, placement) in images {
+            self.draw_image(img, placement);
+        }
+        let _ = self.ctx.set_transform(dpr, 0.0, 0.0, dpr, 0.0, 0.0);
+        self.ctx.set_font(&self.css_font());
+    }
+
+    /// Outline a rectangle given in CSS pixels.
+    pub fn stroke_rect(&self, x: f64, y: f64, w: f64, h: f64, color: Color, line_width: f64) {
+        self.ctx
+            .set_stroke_style_str(&Self::color_to_css(color, "#cccccc"));
+        self.ctx.set_line_width(line_width);
+        self.ctx.stroke_rect(x, y, w, h);
+        self.ctx.set_line_width(1.0);
+    }
+
     /// Fill gaps outside the cell grid with the given color:
     /// the strip below the last row and the right edge of the status-bar row.
     pub fn fill_bottom_padding(&self, color: Color) {
@@ -278,8 +375,7 @@ impl CanvasBackend {
             .dyn_into()
             .ok()?;
         let _ = ctx.set_transform(self.dpr, 0.0, 0.0, self.dpr, 0.0, 0.0);
-        let font = format!("{}px monospace", self.font_size);
-        ctx.set_font(&font);
+        ctx.set_font(&self.css_font());
 
         let baseline_offset = ctx
             .measure_text("M")
@@ -392,10 +488,10 @@ impl Backend for CanvasBackend {
                 let bold = mods.contains(Modifier::BOLD);
                 let italic = mods.contains(Modifier::ITALIC);
                 let font = match (bold, italic) {
-                    (true, true) => format!("bold italic {}px monospace", self.font_size),
-                    (true, false) => format!("bold {}px monospace", self.font_size),
-                    (false, true) => format!("italic {}px monospace", self.font_size),
-                    (false, false) => format!("{}px monospace", self.font_size),
+                    (true, true) => format!("bold italic {}", self.css_font()),
+                    (true, false) => format!("bold {}", self.css_font()),
+                    (false, true) => format!("italic {}", self.css_font()),
+                    (false, false) => self.css_font(),
                 };
                 self.ctx.set_font(&font);
 
@@ -423,8 +519,7 @@ impl Backend for CanvasBackend {
 
                 // Reset font if modified
                 if bold || italic {
-                    let base_font = format!("{}px monospace", self.font_size);
-                    self.ctx.set_font(&base_font);
+                    self.ctx.set_font(&self.css_font());
                 }
                 }
             }
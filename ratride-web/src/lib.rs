@@ -5,12 +5,52 @@ mod overlay;
 use app::WebApp;
 use backend::CanvasBackend;
 use overlay::DomOverlay;
-use ratride::markdown::{parse_frontmatter, DEFAULT_LINE_HEIGHT};
+use ratride::markdown::{parse_frontmatter, FigletFn, DEFAULT_LINE_HEIGHT};
 use ratride::theme;
 use std::cell::RefCell;
 use std::rc::Rc;
 use wasm_bindgen::prelude::*;
-use web_sys::{HtmlCanvasElement, KeyboardEvent};
+use web_sys::{Document, FontFace, HtmlCanvasElement, KeyboardEvent, MessageEvent, MouseEvent};
+
+/// Font family name registered for the frontmatter `web_font`.
+const WEB_FONT_FAMILY: &str = "ratride-web-font";
+
+#[cfg(feature = "figlet")]
+fn render_figlet(text: &str, font: Option<&str>, color: Option<&str>) -> Result<String, String> {
+    if let Some(color_spec) = color
+        && let Some(art) = ratride::figlet::render_figrat(text, font, color_spec)
+    {
+        return Ok(art);
+    }
+    ratride::figlet::render_builtin(text, font)
+        .ok_or_else(|| "not a bundled font, and the web has no others".to_string())
+}
+
+/// Class of the element holding one canvas per slide while the page prints.
+const PRINT_CLASS: &str = "ratride-print";
+
+/// Print layout: hide the live deck and give each slide canvas its own landscape page.
+const PRINT_CSS: &str = "
+.ratride-print { display: none; }
+@media print {
+    @page { size: landscape; margin: 0; }
+    html, body { width: auto !important; height: auto !important; overflow: visible !important; }
+    body { visibility: hidden; }
+    .ratride-print { display: block; visibility: visible; position: absolute; top: 0; left: 0; width: 100%; }
+    .ratride-print canvas { display: block; width: 100%; height: auto; break-after: page; }
+}
+";
+
+/// Append a `<style>` element with `css` to the document head.
+fn inject_css(document: &Document, css: &str) {
+    let Ok(style) = document.create_element("style") else {
+        return;
+    };
+    style.set_text_content(Some(css));
+    if let Some(head) = document.head() {
+        let _ = head.append_child(&style);
+    }
+}
 
 #[wasm_bindgen]
 pub struct RatRide {
@@ -57,14 +97,10 @@ impl RatRide {
             })
             .unwrap_or_default();
 
-        let figlet_fn = |text: &str, font: Option<&str>, color: Option<&str>| -> Option<String> {
-            if let Some(color_spec) = color {
-                if let Some(art) = ratride::figlet::render_figrat(text, font, color_spec) {
-                    return Some(art);
-                }
-            }
-            ratride::figlet::render_builtin(text, font)
-        };
+        #[cfg(feature = "figlet")]
+        let figlet_fn: Option<&FigletFn> = Some(&render_figlet);
+        #[cfg(not(feature = "figlet"))]
+        let figlet_fn: Option<&FigletFn> = None;
 
         let is_mobile = window
             .match_media("(max-width: 768px)")
@@ -83,13 +119,32 @@ impl RatRide {
             resolved_theme,
             &frontmatter,
             overlay,
-            Some(&figlet_fn),
+            figlet_fn,
             is_mobile,
         );
         web_app.init();
 
         let app = Rc::new(RefCell::new(web_app));
 
+        if let Some(css) = &frontmatter.web_css {
+            inject_css(&document, css);
+        }
+
+        // Load the webfont, then re-measure cells with it
+        if let Some(src) = &frontmatter.web_font
+            && let Ok(face) = FontFace::new_with_str(WEB_FONT_FAMILY, &format!("url(\"{src}\")"))
+        {
+            let _ = document.fonts().add(&face);
+            if let Ok(promise) = face.load() {
+                let app = Rc::clone(&app);
+                let on_load = Closure::once(move |_: JsValue| {
+                    app.borrow_mut().set_font_family(WEB_FONT_FAMILY);
+                });
+                let _ = promise.then(&on_load);
+                on_load.forget();
+            }
+        }
+
         // Key event listener
         {
             let app = Rc::clone(&app);
@@ -103,10 +158,124 @@ impl RatRide {
             closure.forget();
         }
 
+        // Inside an iframe, keys only arrive once the frame has focus: take it
+        // on click/tap and ask for a click while it's elsewhere
+        let embedded = window
+            .top()
+            .ok()
+            .flatten()
+            .is_some_and(|top| !js_sys::Object::is(&top, &window));
+        if embedded {
+            app.borrow_mut().set_embedded(true);
+            app.borrow_mut()
+                .set_focused(document.has_focus().unwrap_or(false));
+            let _ = canvas.set_attribute("tabindex", "0");
+            let target = canvas.clone();
+            let on_pointer = Closure::<dyn FnMut()>::new(move || {
+                let _ = target.focus();
+            });
+            let _ = canvas.add_event_listener_with_callback(
+                "pointerdown",
+                on_pointer.as_ref().unchecked_ref(),
+            );
+            on_pointer.forget();
+            for (event, focused) in [("focus", true), ("blur", false)] {
+                let app = Rc::clone(&app);
+                let closure = Closure::<dyn FnMut()>::new(move || {
+                    app.borrow_mut().set_focused(focused);
+                });
+                let _ = window
+                    .add_event_listener_with_callback(event, closure.as_ref().unchecked_ref());
+                closure.forget();
+            }
+        }
+
+        // Navigation via postMessage from the embedding page or the one that
+        // opened the deck, when it's on our own origin or one the frontmatter
+        // allows in `web_embed_origins`
+        {
+            let app = Rc::clone(&app);
+            let own_origin = window.location().origin().ok();
+            let allowed = frontmatter.web_embed_origins.clone();
+            let this = window.clone();
+            let closure = Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+                let origin = event.origin();
+                if own_origin.as_deref() != Some(origin.as_str()) && !allowed.contains(&origin) {
+                    return;
+                }
+                let Some(source) = event.source() else {
+                    return;
+                };
+                let from_parent = embedded
+                    && this
+                        .parent()
+                        .ok()
+                        .flatten()
+                        .is_some_and(|parent| js_sys::Object::is(&source, &parent));
+                let from_opener = this
+                    .opener()
+                    .is_ok_and(|opener| !opener.is_null() && js_sys::Object::is(&source, &opener));
+                if from_parent || from_opener {
+                    app.borrow_mut().handle_message(&event.data());
+                }
+            });
+            window
+                .add_event_listener_with_callback("message", closure.as_ref().unchecked_ref())
+                .expect("add message listener");
+            closure.forget();
+        }
+
+        // Click listener (picks a slide in the overview)
+        {
+            let app = Rc::clone(&app);
+            let closure = Closure::<dyn FnMut(MouseEvent)>::new(move |event: MouseEvent| {
+                app.borrow_mut()
+                    .handle_click(event.offset_x() as f64, event.offset_y() as f64);
+            });
+            canvas
+                .add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())
+                .expect("add click listener");
+            closure.forget();
+        }
+
+        // Print handout: render every slide into the document before printing,
+        // and drop them again afterwards
+        {
+            inject_css(&document, PRINT_CSS);
+            let app = Rc::clone(&app);
+            let doc = document.clone();
+            let before = Closure::<dyn FnMut()>::new(move || {
+                let (Ok(container), Some(body)) = (doc.create_element("div"), doc.body()) else {
+                    return;
+                };
+                container.set_class_name(PRINT_CLASS);
+                for canvas in app.borrow().print_canvases() {
+                    let _ = container.append_child(&canvas);
+                }
+                let _ = body.append_child(&container);
+            });
+            let doc = document.clone();
+            let after = Closure::<dyn FnMut()>::new(move || {
+                let printed = doc.get_elements_by_class_name(PRINT_CLASS);
+                while let Some(el) = printed.item(0) {
+                    el.remove();
+                }
+            });
+            window
+                .add_event_listener_with_callback("beforeprint", before.as_ref().unchecked_ref())
+                .expect("add beforeprint listener");
+            window
+                .add_event_listener_with_callback("afterprint", after.as_ref().unchecked_ref())
+                .expect("add afterprint listener");
+            before.forget();
+            after.forget();
+        }
+
         // requestAnimationFrame loop
         {
             let app = Rc::clone(&app);
-            let f: Rc<RefCell<Option<Closure<dyn FnMut(f64)>>>> = Rc::new(RefCell::new(None));
+            type FrameCallback = Rc<RefCell<Option<Closure<dyn FnMut(f64)>>>>;
+            let f: FrameCallback = Rc::new(RefCell::new(None));
             let g = Rc::clone(&f);
 
             *g.borrow_mut() = Some(Closure::new(move |timestamp: f64| {
@@ -150,6 +319,18 @@ impl RatRide {
         self.app.borrow_mut().scroll_up(lines);
     }
 
+    /// Toggle the grid of slide thumbnails.
+    #[wasm_bindgen]
+    pub fn toggle_overview(&self) {
+        self.app.borrow_mut().toggle_overview();
+    }
+
+    /// Toggle muting of per-slide audio narration.
+    #[wasm_bindgen]
+    pub fn toggle_mute(&self) {
+        self.app.borrow_mut().toggle_mute();
+    }
+
     #[wasm_bindgen]
     pub fn cell_height(&self) -> f64 {
         self.app.borrow().cell_height()
@@ -2,7 +2,7 @@ use ratride::markdown::{HeaderItem, SemanticElement};
 use ratride::render::wrapped_line_height;
 use ratatui::text::Text;
 use wasm_bindgen::JsCast;
-use web_sys::{Document, HtmlElement};
+use web_sys::{Document, HtmlElement, HtmlVideoElement};
 
 pub struct DomOverlay {
     container: HtmlElement,
@@ -24,6 +24,26 @@ impl DomOverlay {
         }
     }
 
+    /// Create a hidden `<video>` element next to the overlay container.
+    /// It lives outside the container so overlay updates don't restart playback.
+    pub fn create_video(&self, src: &str) -> Option<HtmlVideoElement> {
+        let video: HtmlVideoElement = self
+            .document
+            .create_element("video")
+            .ok()?
+            .dyn_into()
+            .ok()?;
+        video.set_src(src);
+        video.set_preload("metadata");
+        let _ = video.set_attribute(
+            "style",
+            "position:absolute;display:none;object-fit:contain;pointer-events:auto",
+        );
+        let parent = self.container.parent_element()?;
+        parent.append_child(&video).ok()?;
+        Some(video)
+    }
+
     pub fn set_visible(&self, visible: bool) {
         let _ = self
             .container
@@ -31,6 +51,7 @@ impl DomOverlay {
             .set_property("display", if visible { "" } else { "none" });
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn update(
         &self,
         semantics: &[SemanticElement],
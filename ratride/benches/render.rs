@@ -0,0 +1,54 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use ratride::markdown::{SlideDirection, TransitionKind, parse_frontmatter, parse_slides};
+use ratride::theme::Theme;
+use ratride::{bench, print};
+
+const DECK: &str = include_str!("../../examples/layout.md");
+const WIDTH: u16 = 120;
+const HEIGHT: u16 = 40;
+
+fn parse(c: &mut Criterion) {
+    let (frontmatter, body) = parse_frontmatter(DECK);
+    let theme = Theme::default();
+    c.bench_function("parse", |b| {
+        b.iter(|| parse_slides(body, &theme, &frontmatter, None, false))
+    });
+}
+
+fn render(c: &mut Criterion) {
+    let (frontmatter, body) = parse_frontmatter(DECK);
+    let (slides, _) = parse_slides(body, &Theme::default(), &frontmatter, None, false);
+    for (i, slide) in slides.iter().enumerate() {
+        c.bench_function(&format!("render slide {}", i + 1), |b| {
+            b.iter(|| print::render_slide_buffer(slide, WIDTH, HEIGHT))
+        });
+    }
+}
+
+fn transitions(c: &mut Criterion) {
+    let (frontmatter, body) = parse_frontmatter(DECK);
+    let (mut slides, _) = parse_slides(body, &Theme::default(), &frontmatter, None, false);
+    let next = slides.remove(1);
+    let prev = slides.remove(0);
+    for kind in [
+        TransitionKind::Slide(SlideDirection::default()),
+        TransitionKind::Fade,
+        TransitionKind::Dissolve,
+        TransitionKind::Coalesce,
+        TransitionKind::SweepIn,
+        TransitionKind::Lines,
+        TransitionKind::LinesCross,
+        TransitionKind::LinesRgb,
+        TransitionKind::SlideRgb,
+    ] {
+        let name = kind.name().unwrap_or_default().to_string();
+        let mut slide = next.clone();
+        slide.transition = kind;
+        c.bench_function(&format!("transition {name}"), |b| {
+            b.iter(|| bench::run_transition(&prev, &slide, WIDTH, HEIGHT, 400.0, 30.0))
+        });
+    }
+}
+
+criterion_group!(benches, parse, render, transitions);
+criterion_main!(benches);
@@ -0,0 +1,313 @@
+//! What a key press does, shared by the terminal and web players.
+//!
+//! Keys are named like the DOM's `KeyboardEvent.key` (`"ArrowRight"`, `"Escape"`,
+//! `" "`, `"j"`), with `"Ctrl+"` prefixed while Control is held. Each frontend
+//! carries out the actions it supports and ignores the rest. A [`Keymap`]
+//! lays a user's own bindings over the defaults.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    NextSlide,
+    PrevSlide,
+    /// Jump to the next section (H1 slide).
+    NextSection,
+    /// Jump back to the start of this section or the previous one.
+    PrevSection,
+    ScrollDown(u16),
+    ScrollUp(u16),
+    /// Focus the next column on two-column slides.
+    CycleColumn,
+    ToggleOutline,
+    ToggleReadingRuler,
+    /// Show the key hints in the status bar again.
+    ShowHints,
+    Quit,
+    /// Suspend to the shell (Ctrl-Z).
+    Suspend,
+    CycleTheme,
+    /// Preview the next slide beside the current one.
+    TogglePeek,
+    ToggleTimer,
+    ResetTimer,
+    ToggleBreak,
+    /// Cover the slide with the theme background, to pull attention away
+    /// from the screen.
+    BlankScreen,
+    /// Cover the slide with white.
+    WhiteScreen,
+    ToggleMute,
+    ToggleVideo,
+    /// Grid of slide thumbnails.
+    ToggleOverview,
+    /// Current and next slide with the speaker notes.
+    TogglePresenterView,
+    /// Speaker notes in a margin beside the slide.
+    ToggleNotesMargin,
+    /// Copy the web-published deck's link to the current slide.
+    CopyLink,
+    /// Show the next image of the slide's galleries.
+    NextImage,
+    PrevImage,
+    /// Magnify the slide's images, or back out.
+    ZoomIn,
+    ZoomOut,
+    /// Move the zoomed region of the slide's images by (columns, rows),
+    /// each -1, 0 or 1.
+    PanImage(i8, i8),
+}
+
+impl Action {
+    /// The default binding for `key`.
+    pub fn from_key(key: &str) -> Option<Self> {
+        Some(match key {
+            "ArrowRight" | "l" | " " => Action::NextSlide,
+            "ArrowLeft" | "h" => Action::PrevSlide,
+            "]" => Action::NextSection,
+            "[" => Action::PrevSection,
+            "ArrowDown" | "j" => Action::ScrollDown(1),
+            "ArrowUp" | "k" => Action::ScrollUp(1),
+            "d" => Action::ScrollDown(10),
+            "u" => Action::ScrollUp(10),
+            "c" => Action::CycleColumn,
+            "o" => Action::ToggleOutline,
+            "r" => Action::ToggleReadingRuler,
+            "?" => Action::ShowHints,
+            "q" | "Escape" => Action::Quit,
+            "Ctrl+z" => Action::Suspend,
+            "T" => Action::CycleTheme,
+            "n" => Action::TogglePeek,
+            "t" => Action::ToggleTimer,
+            "R" => Action::ResetTimer,
+            "B" => Action::ToggleBreak,
+            "b" => Action::BlankScreen,
+            "w" => Action::WhiteScreen,
+            "m" => Action::ToggleMute,
+            "p" => Action::ToggleVideo,
+            "g" => Action::ToggleOverview,
+            "s" => Action::TogglePresenterView,
+            "N" => Action::ToggleNotesMargin,
+            "y" => Action::CopyLink,
+            "." => Action::NextImage,
+            "," => Action::PrevImage,
+            "+" | "=" => Action::ZoomIn,
+            "-" => Action::ZoomOut,
+            "H" => Action::PanImage(-1, 0),
+            "J" => Action::PanImage(0, 1),
+            "K" => Action::PanImage(0, -1),
+            "L" => Action::PanImage(1, 0),
+            _ => return None,
+        })
+    }
+
+    /// The action called `name`, its variant in snake case (`next_slide`,
+    /// `toggle_timer`), as external controllers name it. `page_down` and
+    /// `page_up` scroll as far as `d` and `u`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "next_slide" => Action::NextSlide,
+            "prev_slide" => Action::PrevSlide,
+            "next_section" => Action::NextSection,
+            "prev_section" => Action::PrevSection,
+            "scroll_down" => Action::ScrollDown(1),
+            "scroll_up" => Action::ScrollUp(1),
+            "page_down" => Action::ScrollDown(10),
+            "page_up" => Action::ScrollUp(10),
+            "cycle_column" => Action::CycleColumn,
+            "toggle_outline" => Action::ToggleOutline,
+            "toggle_reading_ruler" => Action::ToggleReadingRuler,
+            "show_hints" => Action::ShowHints,
+            "quit" => Action::Quit,
+            "suspend" => Action::Suspend,
+            "cycle_theme" => Action::CycleTheme,
+            "toggle_peek" => Action::TogglePeek,
+            "toggle_timer" => Action::ToggleTimer,
+            "reset_timer" => Action::ResetTimer,
+            "toggle_break" => Action::ToggleBreak,
+            "blank_screen" => Action::BlankScreen,
+            "white_screen" => Action::WhiteScreen,
+            "toggle_mute" => Action::ToggleMute,
+            "toggle_video" => Action::ToggleVideo,
+            "toggle_overview" => Action::ToggleOverview,
+            "toggle_presenter_view" => Action::TogglePresenterView,
+            "toggle_notes_margin" => Action::ToggleNotesMargin,
+            "copy_link" => Action::CopyLink,
+            "next_image" => Action::NextImage,
+            "prev_image" => Action::PrevImage,
+            "zoom_in" => Action::ZoomIn,
+            "zoom_out" => Action::ZoomOut,
+            "pan_left" => Action::PanImage(-1, 0),
+            "pan_down" => Action::PanImage(0, 1),
+            "pan_up" => Action::PanImage(0, -1),
+            "pan_right" => Action::PanImage(1, 0),
+            _ => return None,
+        })
+    }
+
+    /// The name [`Action::from_name`] takes for this action.
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::NextSlide => "next_slide",
+            Action::PrevSlide => "prev_slide",
+            Action::NextSection => "next_section",
+            Action::PrevSection => "prev_section",
+            Action::ScrollDown(1) => "scroll_down",
+            Action::ScrollDown(_) => "page_down",
+            Action::ScrollUp(1) => "scroll_up",
+            Action::ScrollUp(_) => "page_up",
+            Action::CycleColumn => "cycle_column",
+            Action::ToggleOutline => "toggle_outline",
+            Action::ToggleReadingRuler => "toggle_reading_ruler",
+            Action::ShowHints => "show_hints",
+            Action::Quit => "quit",
+            Action::Suspend => "suspend",
+            Action::CycleTheme => "cycle_theme",
+            Action::TogglePeek => "toggle_peek",
+            Action::ToggleTimer => "toggle_timer",
+            Action::ResetTimer => "reset_timer",
+            Action::ToggleBreak => "toggle_break",
+            Action::BlankScreen => "blank_screen",
+            Action::WhiteScreen => "white_screen",
+            Action::ToggleMute => "toggle_mute",
+            Action::ToggleVideo => "toggle_video",
+            Action::ToggleOverview => "toggle_overview",
+            Action::TogglePresenterView => "toggle_presenter_view",
+            Action::ToggleNotesMargin => "toggle_notes_margin",
+            Action::CopyLink => "copy_link",
+            Action::NextImage => "next_image",
+            Action::PrevImage => "prev_image",
+            Action::ZoomIn => "zoom_in",
+            Action::ZoomOut => "zoom_out",
+            Action::PanImage(dx, _) if dx < 0 => "pan_left",
+            Action::PanImage(dx, _) if dx > 0 => "pan_right",
+            Action::PanImage(_, dy) if dy > 0 => "pan_down",
+            Action::PanImage(..) => "pan_up",
+        }
+    }
+}
+
+/// Key bindings: the defaults of [`Action::from_key`] with a user's
+/// overrides on top.
+#[derive(Clone, Debug, Default)]
+pub struct Keymap {
+    /// Keys the user bound, and to what.
+    bound: Vec<(String, Action)>,
+    /// Actions whose default keys the user replaced.
+    replaced: Vec<Action>,
+}
+
+impl Keymap {
+    /// Parse overrides: a TOML table from action names (see
+    /// [`Action::from_name`]) to a key or a list of keys, such as
+    /// `next_slide = ["ArrowRight", "Enter"]`. Listing an action replaces its
+    /// default keys; `[]` leaves it unbound.
+    pub fn from_toml(s: &str) -> Result<Self, String> {
+        let table: toml::Table = toml::from_str(s).map_err(|e| e.to_string())?;
+        let mut keymap = Keymap::default();
+        for (name, keys) in table {
+            let action =
+                Action::from_name(&name).ok_or_else(|| format!("unknown action `{name}`"))?;
+            let keys = match keys {
+                toml::Value::String(key) => vec![key],
+                toml::Value::Array(keys) => keys
+                    .into_iter()
+                    .map(|key| match key {
+                        toml::Value::String(key) => Ok(key),
+                        _ => Err(format!("keys for `{name}` must be strings")),
+                    })
+                    .collect::<Result<_, _>>()?,
+                _ => return Err(format!("`{name}` takes a key or a list of keys")),
+            };
+            keymap.replaced.push(action);
+            keymap
+                .bound
+                .extend(keys.into_iter().map(|key| (key, action)));
+        }
+        Ok(keymap)
+    }
+
+    /// What `key` does: the user's binding, else the default unless the user
+    /// moved that action elsewhere.
+    pub fn action(&self, key: &str) -> Option<Action> {
+        if let Some((_, action)) = self.bound.iter().find(|(bound, _)| bound == key) {
+            return Some(*action);
+        }
+        Action::from_key(key).filter(|action| !self.replaced.contains(action))
+    }
+}
+
+/// The color the slide is hidden behind, [`Action::BlankScreen`] or
+/// [`Action::WhiteScreen`], until the next key.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Blank(Option<Action>);
+
+impl Blank {
+    /// The action that hid the slide, while it is hidden.
+    pub fn shown(self) -> Option<Action> {
+        self.0
+    }
+
+    /// Hide the slide behind `action`'s color, or show it again when it
+    /// already is.
+    pub fn toggle(&mut self, action: Action) {
+        self.0 = (self.0 != Some(action)).then_some(action);
+    }
+
+    /// What pressing a key bound to `action` does: while the slide is
+    /// hidden, any key only brings it back, so there is nothing to run.
+    pub fn press(&mut self, action: Option<Action>) -> Option<Action> {
+        match self.0.take() {
+            Some(_) => None,
+            None => action,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keymap_overrides_defaults() {
+        let keymap = Keymap::from_toml(
+            "next_slide = [\"Enter\", \"PageDown\"]\nquit = \"Q\"\ntoggle_video = []\n",
+        )
+        .unwrap();
+        assert_eq!(keymap.action("PageDown"), Some(Action::NextSlide));
+        assert_eq!(keymap.action("ArrowRight"), None);
+        assert_eq!(keymap.action("Q"), Some(Action::Quit));
+        assert_eq!(keymap.action("q"), None);
+        assert_eq!(keymap.action("p"), None);
+        assert_eq!(keymap.action("h"), Some(Action::PrevSlide));
+        assert!(Keymap::from_toml("dance = \"x\"").is_err());
+        assert!(Keymap::from_toml("quit = 1").is_err());
+    }
+
+    #[test]
+    fn action_names_round_trip() {
+        let keys = "lhjkduqHJKL+-.,[]cor?TntRBbwmpgsNy"
+            .chars()
+            .map(String::from);
+        for key in keys.chain(["Ctrl+z".to_string()]) {
+            let action = Action::from_key(&key).unwrap();
+            assert_eq!(Action::from_name(action.name()), Some(action), "{key}");
+        }
+    }
+
+    #[test]
+    fn any_key_brings_back_a_blank_screen_and_does_nothing_else() {
+        let mut blank = Blank::default();
+        blank.toggle(Action::BlankScreen);
+        assert_eq!(blank.shown(), Some(Action::BlankScreen));
+        assert_eq!(blank.press(Some(Action::NextSlide)), None);
+        assert_eq!(blank.shown(), None);
+        let next = Some(Action::NextSlide);
+        assert_eq!(blank.press(next), next);
+
+        // Unbound keys count too, and the other color swaps in
+        blank.toggle(Action::WhiteScreen);
+        blank.toggle(Action::BlankScreen);
+        assert_eq!(blank.shown(), Some(Action::BlankScreen));
+        assert_eq!(blank.press(None), None);
+        assert_eq!(blank.shown(), None);
+    }
+}
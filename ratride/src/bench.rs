@@ -0,0 +1,89 @@
+//! Timing of the render pipeline for `ratride bench` and the criterion
+//! benches: drawing slides offscreen and stepping transitions frame by frame.
+
+use std::time::{Duration, Instant};
+
+use ratatui::Terminal;
+use ratatui::backend::TestBackend;
+use tachyonfx::EffectRenderer;
+
+use crate::markdown::Slide;
+use crate::render::LayoutCache;
+use crate::{print, render, transition};
+
+/// Frame interval transitions are stepped by (~60fps, as in the players).
+pub const FRAME_MS: u32 = 16;
+
+/// Give up on a transition that hasn't finished after this many frames.
+const MAX_FRAMES: usize = 10_000;
+
+/// Mean time to draw `slide` into a fresh `width`×`height` buffer, wrapping
+/// its content every time as a slide's first draw does.
+pub fn time_render(slide: &Slide, width: u16, height: u16, iterations: u32) -> Duration {
+    let iterations = iterations.max(1);
+    let start = Instant::now();
+    // print keeps no layout cache, so nothing carries over between draws
+    for _ in 0..iterations {
+        std::hint::black_box(print::render_slide_buffer(slide, width, height));
+    }
+    start.elapsed() / iterations
+}
+
+/// Run the transition from `prev` into `slide` the way the players do: the
+/// slide is drawn every frame with the effect over it, advancing
+/// [`FRAME_MS`] at a time until it is done. Returns the number of frames, or
+/// `None` when `slide` has no transition.
+pub fn run_transition(
+    prev: &Slide,
+    slide: &Slide,
+    width: u16,
+    height: u16,
+    line_dur_ms: f32,
+    stagger_ms: f32,
+) -> Option<usize> {
+    let (prev_buf, _) = print::render_slide_buffer(prev, width, height);
+    let mut effect = transition::create_transition(
+        &transition::TransitionRegistry::default(),
+        &slide.transition,
+        slide.theme.bg,
+        Some(prev_buf),
+        height,
+        slide.content.lines.len(),
+        line_dur_ms,
+        stagger_ms,
+    )?;
+    let Ok(mut terminal) = Terminal::new(TestBackend::new(width, height));
+    // Kept across frames, as the players keep theirs
+    let layouts = LayoutCache::default();
+    let mut frames = 0;
+    while !effect.done() && frames < MAX_FRAMES {
+        let _ = terminal.draw(|frame| {
+            let area = frame.area();
+            if slide.bg_fill {
+                render::fill_background(frame, area, slide.theme.bg);
+            }
+            render::draw_slide(slide, layouts.slot(0, usize::MAX), 0, frame, area);
+            frame.render_effect(
+                &mut effect,
+                area,
+                tachyonfx::Duration::from_millis(FRAME_MS),
+            );
+        });
+        frames += 1;
+    }
+    Some(frames)
+}
+
+/// [`run_transition`], timed: the number of frames and the mean time per frame.
+pub fn time_transition(
+    prev: &Slide,
+    slide: &Slide,
+    width: u16,
+    height: u16,
+    line_dur_ms: f32,
+    stagger_ms: f32,
+) -> Option<(usize, Duration)> {
+    let start = Instant::now();
+    let frames = run_transition(prev, slide, width, height, line_dur_ms, stagger_ms)?;
+    Some((frames, start.elapsed() / frames.max(1) as u32))
+}
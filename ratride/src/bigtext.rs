@@ -0,0 +1,136 @@
+//! Block letters for `<!-- bigtext -->` headings: a built-in 5x7 pixel
+//! font drawn with half-block characters, so each text row holds two pixel
+//! rows. Glyphs are scaled by whole pixels to fill the requested height and
+//! shrunk when the line would be wider than the screen.
+
+/// Pixel size of a glyph, not counting the one-pixel gap after it.
+const GLYPH_WIDTH: usize = 5;
+const GLYPH_HEIGHT: usize = 7;
+
+/// Rows of the glyph for `c`, the leftmost pixel in bit 4. Letters are
+/// drawn in capitals; characters the font lacks are drawn as `?`.
+fn glyph(c: char) -> [u8; GLYPH_HEIGHT] {
+    match c.to_ascii_uppercase() {
+        ' ' => [0; GLYPH_HEIGHT],
+        'A' => [0x0e, 0x11, 0x11, 0x1f, 0x11, 0x11, 0x11],
+        'B' => [0x1e, 0x11, 0x11, 0x1e, 0x11, 0x11, 0x1e],
+        'C' => [0x0e, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0e],
+        'D' => [0x1c, 0x12, 0x11, 0x11, 0x11, 0x12, 0x1c],
+        'E' => [0x1f, 0x10, 0x10, 0x1e, 0x10, 0x10, 0x1f],
+        'F' => [0x1f, 0x10, 0x10, 0x1e, 0x10, 0x10, 0x10],
+        'G' => [0x0e, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0f],
+        'H' => [0x11, 0x11, 0x11, 0x1f, 0x11, 0x11, 0x11],
+        'I' => [0x0e, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0e],
+        'J' => [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0c],
+        'K' => [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11],
+        'L' => [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1f],
+        'M' => [0x11, 0x1b, 0x15, 0x15, 0x11, 0x11, 0x11],
+        'N' => [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11],
+        'O' => [0x0e, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0e],
+        'P' => [0x1e, 0x11, 0x11, 0x1e, 0x10, 0x10, 0x10],
+        'Q' => [0x0e, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0d],
+        'R' => [0x1e, 0x11, 0x11, 0x1e, 0x14, 0x12, 0x11],
+        'S' => [0x0f, 0x10, 0x10, 0x0e, 0x01, 0x01, 0x1e],
+        'T' => [0x1f, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04],
+        'U' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0e],
+        'V' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x0a, 0x04],
+        'W' => [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0a],
+        'X' => [0x11, 0x11, 0x0a, 0x04, 0x0a, 0x11, 0x11],
+        'Y' => [0x11, 0x11, 0x0a, 0x04, 0x04, 0x04, 0x04],
+        'Z' => [0x1f, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1f],
+        '0' => [0x0e, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0e],
+        '1' => [0x04, 0x0c, 0x04, 0x04, 0x04, 0x04, 0x0e],
+        '2' => [0x0e, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1f],
+        '3' => [0x1e, 0x01, 0x01, 0x0e, 0x01, 0x01, 0x1e],
+        '4' => [0x02, 0x06, 0x0a, 0x12, 0x1f, 0x02, 0x02],
+        '5' => [0x1f, 0x10, 0x1e, 0x01, 0x01, 0x11, 0x0e],
+        '6' => [0x06, 0x08, 0x10, 0x1e, 0x11, 0x11, 0x0e],
+        '7' => [0x1f, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => [0x0e, 0x11, 0x11, 0x0e, 0x11, 0x11, 0x0e],
+        '9' => [0x0e, 0x11, 0x11, 0x0f, 0x01, 0x02, 0x0c],
+        '!' => [0x04, 0x04, 0x04, 0x04, 0x04, 0x00, 0x04],
+        '?' => [0x0e, 0x11, 0x01, 0x02, 0x04, 0x00, 0x04],
+        '.' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x0c, 0x0c],
+        ',' => [0x00, 0x00, 0x00, 0x00, 0x0c, 0x04, 0x08],
+        ':' => [0x00, 0x0c, 0x0c, 0x00, 0x0c, 0x0c, 0x00],
+        ';' => [0x00, 0x0c, 0x0c, 0x00, 0x0c, 0x04, 0x08],
+        '-' => [0x00, 0x00, 0x00, 0x1f, 0x00, 0x00, 0x00],
+        '+' => [0x00, 0x04, 0x04, 0x1f, 0x04, 0x04, 0x00],
+        '\'' => [0x04, 0x04, 0x08, 0x00, 0x00, 0x00, 0x00],
+        '"' => [0x0a, 0x0a, 0x00, 0x00, 0x00, 0x00, 0x00],
+        '(' => [0x02, 0x04, 0x08, 0x08, 0x08, 0x04, 0x02],
+        ')' => [0x08, 0x04, 0x02, 0x02, 0x02, 0x04, 0x08],
+        '/' => [0x01, 0x01, 0x02, 0x04, 0x08, 0x10, 0x10],
+        '&' => [0x0c, 0x12, 0x14, 0x08, 0x15, 0x12, 0x0d],
+        '#' => [0x0a, 0x0a, 0x1f, 0x0a, 0x1f, 0x0a, 0x0a],
+        '%' => [0x19, 0x19, 0x02, 0x04, 0x08, 0x13, 0x13],
+        '=' => [0x00, 0x00, 0x1f, 0x00, 0x1f, 0x00, 0x00],
+        '_' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1f],
+        '*' => [0x00, 0x15, 0x0e, 0x1f, 0x0e, 0x15, 0x00],
+        '<' => [0x02, 0x04, 0x08, 0x10, 0x08, 0x04, 0x02],
+        '>' => [0x08, 0x04, 0x02, 0x01, 0x02, 0x04, 0x08],
+        '@' => [0x0e, 0x11, 0x17, 0x15, 0x17, 0x10, 0x0f],
+        _ => glyph('?'),
+    }
+}
+
+/// `text` in block letters at most `rows` lines tall and `max_width`
+/// columns wide, as large as fits; every line has the same width. `None`
+/// when it doesn't fit even at the smallest size, which needs 4 rows.
+pub fn render(text: &str, rows: usize, max_width: usize) -> Option<Vec<String>> {
+    let glyphs: Vec<[u8; GLYPH_HEIGHT]> = text.trim().chars().map(glyph).collect();
+    if glyphs.is_empty() {
+        return None;
+    }
+    let cells = glyphs.len() * (GLYPH_WIDTH + 1) - 1;
+    let mut scale = 2 * rows / GLYPH_HEIGHT;
+    while scale > 0 && cells * scale > max_width {
+        scale -= 1;
+    }
+    if scale == 0 {
+        return None;
+    }
+    let pixel = |x: usize, y: usize| {
+        let (col, row) = (x / scale, y / scale);
+        let (index, col) = (col / (GLYPH_WIDTH + 1), col % (GLYPH_WIDTH + 1));
+        col < GLYPH_WIDTH
+            && row < GLYPH_HEIGHT
+            && glyphs[index][row] & (1 << (GLYPH_WIDTH - 1 - col)) != 0
+    };
+    let lines = (GLYPH_HEIGHT * scale).div_ceil(2);
+    let lines = (0..lines)
+        .map(|line| {
+            (0..cells * scale)
+                .map(|x| match (pixel(x, 2 * line), pixel(x, 2 * line + 1)) {
+                    (true, true) => '█',
+                    (true, false) => '▀',
+                    (false, true) => '▄',
+                    (false, false) => ' ',
+                })
+                .collect()
+        })
+        .collect();
+    Some(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn letters_scale_to_the_rows_and_shrink_to_the_width() {
+        let small = render("Hi", 4, 80).unwrap();
+        assert_eq!(
+            small,
+            ["█   █  ▀█▀ ", "█▄▄▄█   █  ", "█   █   █  ", "▀   ▀  ▀▀▀ "]
+        );
+        // 8 rows double every pixel, unless that would be too wide
+        let big = render("Hi", 8, 80).unwrap();
+        assert_eq!(big.len(), 7);
+        assert_eq!(big[0].chars().count(), 22);
+        assert_eq!(render("Hi", 8, 21).unwrap(), small);
+        assert_eq!(render("Hi", 3, 80), None);
+        assert_eq!(render("Hi", 4, 10), None);
+        assert_eq!(render("~", 4, 80), render("?", 4, 80));
+    }
+}
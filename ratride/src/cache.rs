@@ -0,0 +1,150 @@
+//! Per-user directories for cached downloads and renders and for saved
+//! state, and the stable hash that names files in them.
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// 64-bit FNV-1a hash of `bytes`. Unlike `DefaultHasher` it is the same in
+/// every build and run, so file names derived from it survive upgrades.
+pub fn stable_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| {
+        (hash ^ b as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// `$XDG_CACHE_HOME/ratride/name` (`~/.cache/ratride/name` by default),
+/// created readable by the user only.
+pub fn cache_dir(name: &str) -> io::Result<PathBuf> {
+    let dir = user_dir("XDG_CACHE_HOME", ".cache")?.join(name);
+    create_private_dir(&dir)?;
+    Ok(dir)
+}
+
+/// `$XDG_STATE_HOME/ratride` (`~/.local/state/ratride` by default), created
+/// readable by the user only.
+pub fn state_dir() -> io::Result<PathBuf> {
+    let dir = user_dir("XDG_STATE_HOME", ".local/state")?;
+    create_private_dir(&dir)?;
+    Ok(dir)
+}
+
+/// `ratride` under the directory named by `var`, or under `fallback` in the
+/// home directory when `var` is unset or not absolute, as the XDG spec says.
+fn user_dir(var: &str, fallback: &str) -> io::Result<PathBuf> {
+    #[cfg(test)]
+    if let Some(home) = TEST_HOME.with_borrow(Clone::clone) {
+        return Ok(home.join(fallback).join("ratride"));
+    }
+    let base = std::env::var_os(var)
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(fallback)))
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("neither {var} nor HOME is set"),
+            )
+        })?;
+    Ok(base.join("ratride"))
+}
+
+#[cfg(test)]
+thread_local! {
+    /// Home directory of the current test's [`TempHome`], if any.
+    static TEST_HOME: std::cell::RefCell<Option<PathBuf>> = const { std::cell::RefCell::new(None) };
+}
+
+/// A scratch home directory that [`cache_dir`] and [`state_dir`] use instead
+/// of the user's on the test's thread, removed again on drop.
+#[cfg(test)]
+pub(crate) struct TempHome(PathBuf);
+
+#[cfg(test)]
+impl TempHome {
+    pub(crate) fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("ratride-home-{name}-{}", std::process::id()));
+        TEST_HOME.set(Some(dir.clone()));
+        TempHome(dir)
+    }
+}
+
+#[cfg(test)]
+impl Drop for TempHome {
+    fn drop(&mut self) {
+        TEST_HOME.set(None);
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Create `dir` and its missing parents, with mode 0700 on Unix.
+pub fn create_private_dir(dir: &Path) -> io::Result<()> {
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    builder.create(dir)
+}
+
+/// Replace `path` with `data` through a temporary file beside it and a
+/// rename, so readers never see a partial file. The temporary file is
+/// created exclusively (`O_EXCL`, mode 0600 on Unix), so a file or link
+/// someone else left under its name is never written through.
+pub fn write_atomic(path: &Path, data: &[u8]) -> io::Result<()> {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}.tmp", std::process::id()));
+    let tmp = path.with_file_name(name);
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = match options.open(&tmp) {
+        Ok(file) => file,
+        // Left over from a run that crashed with our pid; removing it
+        // removes a link, never what it points to
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+            fs::remove_file(&tmp)?;
+            options.open(&tmp)?
+        }
+        Err(e) => return Err(e),
+    };
+    let written = file.write_all(data).and_then(|()| file.sync_all());
+    drop(file);
+    match written.and_then(|()| fs::rename(&tmp, path)) {
+        Ok(()) => Ok(()),
+        Err(e) => {
+            let _ = fs::remove_file(&tmp);
+            Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stable_hash_is_fnv1a() {
+        assert_eq!(stable_hash(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(stable_hash(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(stable_hash(b"foobar"), 0x8594_4171_f739_67e8);
+    }
+
+    #[test]
+    fn atomic_writes_replace_without_leaving_temp_files() {
+        let dir = std::env::temp_dir().join(format!("ratride-cache-test-{}", std::process::id()));
+        create_private_dir(&dir).unwrap();
+        let path = dir.join("state.toml");
+        write_atomic(&path, b"one").unwrap();
+        write_atomic(&path, b"two").unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"two");
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&dir).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o700);
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,206 @@
+//! Chapter markers for talk recordings. While presenting with
+//! `--timings FILE`, every slide change is appended to a JSON Lines log;
+//! `--chapters FILE` turns such a log into chapters for YouTube or FFmpeg.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+use crate::markdown::Slide;
+use crate::timer::format_clock;
+
+/// Slides shown for less than this are skipped over while chaptering, as
+/// when paging through to find one; YouTube also ignores shorter chapters.
+pub const MIN_CHAPTER_MS: u64 = 10_000;
+
+/// One line of a timing log: a slide coming up.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Timing {
+    /// Time since presenting started.
+    pub time_ms: u64,
+    /// 1-based.
+    pub slide: usize,
+    #[serde(default)]
+    pub title: Option<String>,
+}
+
+/// A timing log being written while presenting.
+pub struct TimingLog {
+    file: File,
+    started: Instant,
+}
+
+impl TimingLog {
+    /// Start a log at `path`, replacing an earlier one.
+    pub fn create(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(path)?;
+        Ok(Self {
+            file,
+            started: Instant::now(),
+        })
+    }
+
+    /// Note that slide `page` (0-based) is on screen now. Recording the
+    /// slide again when the talk ends marks where its chapter ends.
+    pub fn record(&mut self, page: usize, title: Option<&str>) -> io::Result<()> {
+        let timing = Timing {
+            time_ms: self.started.elapsed().as_millis() as u64,
+            slide: page + 1,
+            title: title.map(str::to_string),
+        };
+        let line = serde_json::to_string(&timing).map_err(io::Error::other)?;
+        writeln!(self.file, "{line}")
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChapterFormat {
+    /// `0:00 Title` lines, for a YouTube video description.
+    Youtube,
+    /// An FFmpeg metadata file (`ffmpeg -i talk.mp4 -i chapters.txt -map_metadata 1 ...`).
+    Ffmetadata,
+}
+
+impl ChapterFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "youtube" => Some(ChapterFormat::Youtube),
+            "ffmetadata" => Some(ChapterFormat::Ffmetadata),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Chapter {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub title: String,
+}
+
+/// Parse a timing log; errors name the offending line.
+pub fn parse_log(text: &str) -> Result<Vec<Timing>, String> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| serde_json::from_str(line).map_err(|e| format!("line {}: {e}", i + 1)))
+        .collect()
+}
+
+/// Chapters from a timing log: a slide per chapter, titled from the log or
+/// else from `slides`. Slides shown only briefly are left out, repeats of
+/// the chapter before merge into it, and the first chapter starts at 0 as
+/// YouTube requires.
+pub fn chapters(timings: &[Timing], slides: &[Slide]) -> Vec<Chapter> {
+    let mut chapters: Vec<Chapter> = Vec::new();
+    for (i, timing) in timings.iter().enumerate() {
+        let end_ms = timings
+            .get(i + 1)
+            .map_or(timing.time_ms, |next| next.time_ms);
+        let last = i + 1 == timings.len();
+        if end_ms.saturating_sub(timing.time_ms) < MIN_CHAPTER_MS && !last {
+            continue;
+        }
+        let title = timing
+            .title
+            .clone()
+            .or_else(|| slides.get(timing.slide.checked_sub(1)?)?.title.clone())
+            .unwrap_or_else(|| format!("Slide {}", timing.slide));
+        match chapters.last_mut() {
+            Some(prev) if prev.title == title => prev.end_ms = end_ms,
+            Some(prev) => {
+                prev.end_ms = timing.time_ms;
+                chapters.push(Chapter {
+                    start_ms: timing.time_ms,
+                    end_ms,
+                    title,
+                });
+            }
+            None => chapters.push(Chapter {
+                start_ms: 0,
+                end_ms,
+                title,
+            }),
+        }
+    }
+    chapters
+}
+
+/// `chapters` written out in `format`.
+pub fn format_chapters(chapters: &[Chapter], format: ChapterFormat) -> String {
+    let mut out = String::new();
+    match format {
+        ChapterFormat::Youtube => {
+            for chapter in chapters {
+                let start = format_clock(chapter.start_ms as f64);
+                out.push_str(&format!("{start} {}\n", chapter.title));
+            }
+        }
+        ChapterFormat::Ffmetadata => {
+            out.push_str(";FFMETADATA1\n");
+            for chapter in chapters {
+                out.push_str(&format!(
+                    "\n[CHAPTER]\nTIMEBASE=1/1000\nSTART={}\nEND={}\ntitle={}\n",
+                    chapter.start_ms,
+                    chapter.end_ms,
+                    escape_ffmetadata(&chapter.title)
+                ));
+            }
+        }
+    }
+    out
+}
+
+/// Escape the characters FFmpeg metadata files give a meaning to.
+fn escape_ffmetadata(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '=' | ';' | '#' | '\\' | '\n') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timings_become_chapters() {
+        let log = r#"{"time_ms":800,"slide":1,"title":"Intro"}
+{"time_ms":30000,"slide":2,"title":"Setup"}
+{"time_ms":31000,"slide":3,"title":"Skipped"}
+{"time_ms":32000,"slide":2,"title":"Setup"}
+{"time_ms":95000,"slide":4}
+{"time_ms":120000,"slide":4}
+"#;
+        let chapters = chapters(&parse_log(log).unwrap(), &[]);
+        let titles: Vec<(u64, &str)> = chapters
+            .iter()
+            .map(|c| (c.start_ms, c.title.as_str()))
+            .collect();
+        assert_eq!(titles, [(0, "Intro"), (32000, "Setup"), (95000, "Slide 4")]);
+        assert_eq!(
+            format_chapters(&chapters, ChapterFormat::Youtube),
+            "0:00 Intro\n0:32 Setup\n1:35 Slide 4\n"
+        );
+        assert!(
+            format_chapters(&chapters, ChapterFormat::Ffmetadata)
+                .contains("START=95000\nEND=120000\ntitle=Slide 4\n")
+        );
+        assert!(
+            parse_log("{\"slide\":1}")
+                .unwrap_err()
+                .starts_with("line 1")
+        );
+    }
+}
@@ -0,0 +1,248 @@
+//! A line protocol for external controllers (a Stream Deck, a foot pedal,
+//! a script) on a localhost TCP port, opened with `--control PORT`.
+//!
+//! Each line a client sends is one command:
+//!
+//! - an action name such as `next_slide` or `toggle_timer` (see
+//!   [`Action::from_name`]),
+//! - `goto N` to jump to slide N (1-based),
+//! - `status` to have the whole state sent again.
+//!
+//! Commands that can't be parsed are answered with `error <message>`. The
+//! state goes to every client in full when it connects, then line by line as
+//! it changes:
+//!
+//! - `slide N TOTAL STEP`: current slide (1-based), slide count and the
+//!   pause steps revealed on it,
+//! - `title TEXT`: the slide's first heading, empty when it has none,
+//! - `timer M:SS running|paused`: the presenter timer, once a second.
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, Sender, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+
+use crate::action::Action;
+
+/// Something a controller asked for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Command {
+    Action(Action),
+    /// Jump to a slide, 0-based.
+    Goto(usize),
+}
+
+impl Command {
+    /// Parse one protocol line other than `status`; `Ok(None)` when blank.
+    pub fn parse(line: &str) -> Result<Option<Self>, String> {
+        let mut words = line.split_whitespace();
+        let Some(name) = words.next() else {
+            return Ok(None);
+        };
+        let command = match name {
+            "goto" => match words.next().and_then(|n| n.parse::<usize>().ok()) {
+                Some(n) if n > 0 => Command::Goto(n - 1),
+                _ => return Err("goto takes a slide number from 1".to_string()),
+            },
+            _ => Command::Action(
+                Action::from_name(name).ok_or_else(|| format!("unknown command `{name}`"))?,
+            ),
+        };
+        match words.next() {
+            Some(extra) => Err(format!("unexpected `{extra}` after `{name}`")),
+            None => Ok(Some(command)),
+        }
+    }
+}
+
+/// What controllers are told about the talk.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Status {
+    /// Current slide, 0-based.
+    pub page: usize,
+    pub total: usize,
+    pub step: usize,
+    pub title: String,
+    /// Elapsed time on the presenter timer, as `M:SS`.
+    pub clock: String,
+    pub paused: bool,
+}
+
+impl Status {
+    fn lines(&self) -> [String; 3] {
+        let state = if self.paused { "paused" } else { "running" };
+        [
+            format!("slide {} {} {}\n", self.page + 1, self.total, self.step),
+            format!("title {}\n", self.title),
+            format!("timer {} {state}\n", self.clock),
+        ]
+    }
+}
+
+/// Updates a client may fall behind by before it is dropped, so a
+/// controller that stops reading never holds up the presenter.
+const CLIENT_BACKLOG: usize = 64;
+
+struct Client {
+    /// Updates for the client's writer thread.
+    updates: SyncSender<String>,
+    /// Shut down when the client is dropped, ending its threads.
+    stream: TcpStream,
+    /// Due the whole state on the next publish.
+    fresh: Arc<Mutex<bool>>,
+}
+
+/// Handle to a running control port; the presenter polls it for commands
+/// and publishes its state every frame.
+pub struct Control {
+    commands: Receiver<Command>,
+    clients: Arc<Mutex<Vec<Client>>>,
+    last: Option<Status>,
+}
+
+impl Control {
+    /// Listen for controllers on `port` of localhost.
+    pub fn start(port: u16) -> io::Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", port))?;
+        let (tx, commands) = mpsc::channel();
+        let clients = Arc::new(Mutex::new(Vec::new()));
+
+        let clients_l = Arc::clone(&clients);
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let (Ok(reader), Ok(mut writer)) = (stream.try_clone(), stream.try_clone()) else {
+                    continue;
+                };
+                let (updates, pending) = mpsc::sync_channel::<String>(CLIENT_BACKLOG);
+                let fresh = Arc::new(Mutex::new(true));
+                clients_l.lock().unwrap().push(Client {
+                    updates,
+                    stream,
+                    fresh: Arc::clone(&fresh),
+                });
+                std::thread::spawn(move || {
+                    for update in pending {
+                        if writer.write_all(update.as_bytes()).is_err() {
+                            break;
+                        }
+                    }
+                });
+                let tx = tx.clone();
+                std::thread::spawn(move || read_commands(reader, &tx, &fresh));
+            }
+        });
+
+        Ok(Self {
+            commands,
+            clients,
+            last: None,
+        })
+    }
+
+    /// The next command a controller sent, if any.
+    pub fn poll(&self) -> Option<Command> {
+        self.commands.try_recv().ok()
+    }
+
+    /// Tell controllers what changed since the last call, and new ones
+    /// everything. Nothing here waits on the network: clients that went
+    /// away or fell [`CLIENT_BACKLOG`] updates behind are dropped.
+    pub fn publish(&mut self, status: &Status) {
+        let lines = status.lines();
+        let last = self.last.as_ref().map(Status::lines);
+        let changed: String = lines
+            .iter()
+            .enumerate()
+            .filter(|(i, line)| last.as_ref().is_none_or(|last| last[*i] != **line))
+            .map(|(_, line)| line.as_str())
+            .collect();
+        let mut clients = self.clients.lock().unwrap();
+        clients.retain_mut(|client| {
+            let fresh = std::mem::take(&mut *client.fresh.lock().unwrap());
+            let out = if fresh {
+                lines.concat()
+            } else {
+                changed.clone()
+            };
+            if out.is_empty() {
+                return true;
+            }
+            match client.updates.try_send(out) {
+                Ok(()) => true,
+                Err(TrySendError::Full(_) | TrySendError::Disconnected(_)) => {
+                    let _ = client.stream.shutdown(Shutdown::Both);
+                    false
+                }
+            }
+        });
+        self.last = Some(status.clone());
+    }
+}
+
+/// Forward a client's commands until it disconnects, answering bad ones.
+fn read_commands(stream: TcpStream, tx: &Sender<Command>, fresh: &Mutex<bool>) {
+    let Ok(mut writer) = stream.try_clone() else {
+        return;
+    };
+    for line in BufReader::new(stream).lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if line.trim() == "status" {
+            *fresh.lock().unwrap() = true;
+            continue;
+        }
+        match Command::parse(&line) {
+            Ok(Some(command)) => {
+                if tx.send(command).is_err() {
+                    break;
+                }
+            }
+            Ok(None) => {}
+            Err(message) => {
+                if writeln!(writer, "error {message}").is_err() {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_commands() {
+        assert_eq!(
+            Command::parse("next_slide"),
+            Ok(Some(Command::Action(Action::NextSlide)))
+        );
+        assert_eq!(Command::parse("goto 3"), Ok(Some(Command::Goto(2))));
+        assert_eq!(Command::parse("  "), Ok(None));
+        assert!(Command::parse("goto 0").is_err());
+        assert!(Command::parse("next_slide now").is_err());
+        assert!(Command::parse("dance").is_err());
+    }
+
+    #[test]
+    fn clients_that_stop_reading_are_dropped() {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        drop(listener);
+        let mut control = Control::start(port).unwrap();
+        let _stalled = TcpStream::connect(("127.0.0.1", port)).unwrap();
+        while control.clients.lock().unwrap().is_empty() {
+            std::thread::yield_now();
+        }
+
+        // Far more than the socket buffers hold, without blocking here
+        for i in 0..1000 {
+            control.publish(&Status {
+                title: format!("{i:065535}"),
+                ..Status::default()
+            });
+        }
+        assert!(control.clients.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,56 @@
+//! Blended frames for image crossfades between slides, built at the size
+//! the incoming image is drawn at rather than the size of its file.
+
+use image::imageops::FilterType;
+use image::{DynamicImage, RgbaImage};
+
+/// Scale `to` down to fit within `width`x`height` pixels, keeping its aspect
+/// ratio as the image backends do, and stretch `from` to the same size.
+pub fn fit_pair(
+    from: DynamicImage,
+    to: DynamicImage,
+    (width, height): (u32, u32),
+) -> (RgbaImage, RgbaImage) {
+    let to = if to.width() > width || to.height() > height {
+        to.resize(width.max(1), height.max(1), FilterType::Triangle)
+    } else {
+        to
+    };
+    let from = from.resize_exact(to.width(), to.height(), FilterType::Triangle);
+    (from.to_rgba8(), to.to_rgba8())
+}
+
+/// Blend `from` into `to` by `t` (0 = `from`, 1 = `to`). Both are the same
+/// size, as [`fit_pair`] leaves them.
+pub fn blend(from: &RgbaImage, to: &RgbaImage, t: f32) -> DynamicImage {
+    DynamicImage::ImageRgba8(RgbaImage::from_fn(to.width(), to.height(), |x, y| {
+        let (a, b) = (from.get_pixel(x, y), to.get_pixel(x, y));
+        image::Rgba(std::array::from_fn(|i| {
+            (a[i] as f32 + (b[i] as f32 - a[i] as f32) * t).round() as u8
+        }))
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pairs_are_scaled_to_the_drawn_size_and_blended() {
+        let black = RgbaImage::from_pixel(800, 400, image::Rgba([0, 0, 0, 255]));
+        let white = RgbaImage::from_pixel(1000, 1000, image::Rgba([255, 255, 255, 255]));
+        let (from, to) = fit_pair(black.into(), white.into(), (80, 40));
+        assert_eq!(to.dimensions(), (40, 40));
+        assert_eq!(from.dimensions(), (40, 40));
+
+        assert_eq!(blend(&from, &to, 0.0).to_rgba8(), from);
+        assert_eq!(blend(&from, &to, 1.0).to_rgba8(), to);
+        let half = blend(&from, &to, 0.5).to_rgba8();
+        assert_eq!(half.get_pixel(20, 20).0, [128, 128, 128, 255]);
+
+        // Small images aren't scaled up
+        let small = RgbaImage::from_pixel(10, 5, image::Rgba([9, 9, 9, 255]));
+        let (_, to) = fit_pair(half.into(), small.into(), (80, 40));
+        assert_eq!(to.dimensions(), (10, 5));
+    }
+}
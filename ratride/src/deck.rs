@@ -0,0 +1,238 @@
+//! Which slide is shown and how far each slide is scrolled, shared by the
+//! terminal and web players.
+//!
+//! Like [`crate::timer`], times are milliseconds on the frontend's clock,
+//! passed in as `now`.
+
+use crate::markdown::{Slide, adjacent_section, adjacent_slide};
+use crate::render;
+
+#[derive(Clone, Debug)]
+pub struct DeckState {
+    current: usize,
+    /// Fragments of the current slide revealed so far (see [`Slide::at_step`]).
+    step: usize,
+    scroll_offsets: Vec<u16>,
+    /// Offset and start time of an in-flight scroll animation.
+    scroll_from: Option<(u16, f64)>,
+    /// Keep scroll offsets when leaving a slide; otherwise slides open at the top.
+    remember_scroll: bool,
+    /// Stop on presenter-only slides, for screens only the presenter sees.
+    presenter: bool,
+}
+
+impl DeckState {
+    pub fn new(len: usize, remember_scroll: bool) -> Self {
+        Self {
+            current: 0,
+            step: 0,
+            scroll_offsets: vec![0; len.max(1)],
+            scroll_from: None,
+            remember_scroll,
+            presenter: false,
+        }
+    }
+
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    pub fn step(&self) -> usize {
+        self.step
+    }
+
+    pub fn set_step(&mut self, step: usize) {
+        self.step = step;
+    }
+
+    pub fn set_presenter(&mut self, presenter: bool) {
+        self.presenter = presenter;
+    }
+
+    /// Follow the deck after it was re-parsed into `len` slides, staying on
+    /// the same page when it still exists.
+    pub fn resize(&mut self, len: usize) {
+        let len = len.max(1);
+        self.scroll_offsets.resize(len, 0);
+        self.current = self.current.min(len - 1);
+    }
+
+    /// Show `page`. Returns whether the page changed; out-of-range pages are ignored.
+    pub fn goto(&mut self, page: usize) -> bool {
+        if page >= self.scroll_offsets.len() || page == self.current {
+            return false;
+        }
+        self.current = page;
+        self.step = 0;
+        self.scroll_from = None;
+        if !self.remember_scroll {
+            self.scroll_offsets[page] = 0;
+        }
+        true
+    }
+
+    /// Move off a presenter-only slide on screens that skip them, to the
+    /// next shown slide or else the previous one. Returns whether the page
+    /// changed.
+    pub fn skip_hidden(&mut self, slides: &[Slide]) -> bool {
+        if self.presenter || !slides.get(self.current).is_some_and(|s| s.presenter_only) {
+            return false;
+        }
+        adjacent_slide(slides, self.current, true, false)
+            .or_else(|| adjacent_slide(slides, self.current, false, false))
+            .is_some_and(|p| self.goto(p))
+    }
+
+    /// Reveal the current slide's next fragment, or move on to the next
+    /// slide. Returns whether the page changed.
+    pub fn next(&mut self, slides: &[Slide]) -> bool {
+        let fragments = slides.get(self.current).map_or(0, |s| s.fragments.len());
+        if self.step < fragments {
+            self.step += 1;
+            return false;
+        }
+        adjacent_slide(slides, self.current, true, self.presenter).is_some_and(|p| self.goto(p))
+    }
+
+    /// Hide the last revealed fragment, or go back to the previous slide with
+    /// all of it shown. Returns whether the page changed.
+    pub fn prev(&mut self, slides: &[Slide]) -> bool {
+        if self.step > 0 {
+            self.step -= 1;
+            return false;
+        }
+        let changed = adjacent_slide(slides, self.current, false, self.presenter)
+            .is_some_and(|p| self.goto(p));
+        if changed {
+            self.step = slides[self.current].fragments.len();
+        }
+        changed
+    }
+
+    /// Jump to the next section (H1 slide), or back to the start of this or the previous one.
+    pub fn goto_section(&mut self, slides: &[Slide], forward: bool) -> bool {
+        adjacent_section(slides, self.current, forward, self.presenter)
+            .is_some_and(|p| self.goto(p))
+    }
+
+    /// Where the current slide is scrolled to, ignoring any animation.
+    pub fn scroll_offset(&self) -> u16 {
+        self.scroll_offsets[self.current]
+    }
+
+    /// Scroll the current slide to `target`, clamped to `max`, animating from
+    /// where it is at `now`.
+    pub fn scroll_to(&mut self, target: u16, max: u16, now: f64) {
+        let from = self.visible_scroll(now);
+        let target = target.min(max);
+        self.scroll_offsets[self.current] = target;
+        self.scroll_from = (from != target).then_some((from, now));
+    }
+
+    /// Scroll the current slide by `lines`, negative for up.
+    pub fn scroll_by(&mut self, lines: i32, max: u16, now: f64) {
+        let target = (self.scroll_offset() as i32 + lines).clamp(0, u16::MAX as i32) as u16;
+        self.scroll_to(target, max, now);
+    }
+
+    /// Scroll offset to draw at `now`, part way through any scroll animation.
+    pub fn visible_scroll(&self, now: f64) -> u16 {
+        let target = self.scroll_offset();
+        match self.scroll_from {
+            Some((from, start)) => {
+                render::eased_scroll(from, target, (now - start) / render::SCROLL_ANIM_MS)
+            }
+            None => target,
+        }
+    }
+
+    /// Once per frame: clamp the offset to `max` (the window may have grown)
+    /// and drop a finished scroll animation.
+    pub fn settle(&mut self, max: u16, now: f64) {
+        let offset = &mut self.scroll_offsets[self.current];
+        *offset = (*offset).min(max);
+        if self
+            .scroll_from
+            .is_some_and(|(_, start)| now - start >= render::SCROLL_ANIM_MS)
+        {
+            self.scroll_from = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scroll_resets_unless_remembered() {
+        let mut deck = DeckState::new(3, false);
+        deck.scroll_by(5, 8, 0.0);
+        assert_eq!(deck.visible_scroll(0.0), 0);
+        assert_eq!(deck.visible_scroll(render::SCROLL_ANIM_MS), 5);
+        deck.scroll_by(-10, 8, 0.0);
+        assert_eq!(deck.scroll_offset(), 0);
+        deck.scroll_by(20, 8, 0.0);
+        assert_eq!(deck.scroll_offset(), 8);
+
+        assert!(deck.goto(1));
+        assert!(!deck.goto(1));
+        assert!(!deck.goto(3));
+        assert!(deck.goto(0));
+        assert_eq!(deck.scroll_offset(), 0);
+
+        let mut deck = DeckState::new(3, true);
+        deck.scroll_by(4, 8, 0.0);
+        deck.goto(2);
+        deck.goto(0);
+        assert_eq!(deck.scroll_offset(), 4);
+        deck.resize(1);
+        deck.settle(2, 0.0);
+        assert_eq!((deck.current(), deck.scroll_offset()), (0, 2));
+    }
+
+    #[test]
+    fn pauses_reveal_before_advancing() {
+        let md = "a\n\n<!-- pause -->\n\nb\n\n---\n\nc\n";
+        let (slides, _) = crate::markdown::parse_slides(
+            md,
+            &crate::theme::Theme::default(),
+            &crate::markdown::Frontmatter::default(),
+            None,
+            false,
+        );
+        let mut deck = DeckState::new(slides.len(), false);
+        assert!(!deck.next(&slides));
+        assert_eq!((deck.current(), deck.step()), (0, 1));
+        assert!(deck.next(&slides));
+        assert_eq!((deck.current(), deck.step()), (1, 0));
+        assert!(deck.prev(&slides));
+        assert_eq!((deck.current(), deck.step()), (0, 1));
+        assert!(!deck.prev(&slides));
+        assert_eq!(deck.step(), 0);
+    }
+
+    #[test]
+    fn audience_screens_start_past_presenter_only_slides() {
+        let md = "<!-- presenter-only -->\ncrib\n\n---\n\nhello\n\n---\n\n<!-- presenter-only -->\nq&a\n";
+        let (slides, _) = crate::markdown::parse_slides(
+            md,
+            &crate::theme::Theme::default(),
+            &crate::markdown::Frontmatter::default(),
+            None,
+            false,
+        );
+        let mut deck = DeckState::new(slides.len(), false);
+        assert!(deck.skip_hidden(&slides));
+        assert_eq!(deck.current(), 1);
+        assert!(!deck.prev(&slides));
+        deck.goto(2);
+        assert!(deck.skip_hidden(&slides));
+        assert_eq!(deck.current(), 1);
+
+        let mut deck = DeckState::new(slides.len(), false);
+        deck.set_presenter(true);
+        assert!(!deck.skip_hidden(&slides));
+        assert_eq!(deck.current(), 0);
+    }
+}
@@ -0,0 +1,200 @@
+//! Half-block images in a reduced palette (`--dither-colors`), for
+//! terminals that have no image protocol and may lack true color.
+
+use std::collections::HashMap;
+
+use image::DynamicImage;
+use image::imageops::FilterType;
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span};
+
+use crate::markdown::{Dither, DitherColors};
+
+/// xterm's default RGB values for the 16 ANSI colors.
+const ANSI_16: [u32; 16] = [
+    0x000000, 0xcd0000, 0x00cd00, 0xcdcd00, 0x0000ee, 0xcd00cd, 0x00cdcd, 0xe5e5e5, 0x7f7f7f,
+    0xff0000, 0x00ff00, 0xffff00, 0x5c5cff, 0xff00ff, 0x00ffff, 0xffffff,
+];
+
+/// Thresholds of ordered dithering, from 0 to 15.
+const BAYER_4X4: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// An image drawn as rows of `▀` cells, dithered once per drawn size.
+pub struct DitheredImage {
+    image: DynamicImage,
+    colors: DitherColors,
+    dither: Dither,
+    cells: HashMap<(u16, u16), Vec<Line<'static>>>,
+}
+
+impl DitheredImage {
+    pub fn new(image: DynamicImage, colors: DitherColors, dither: Dither) -> Self {
+        Self {
+            image,
+            colors,
+            dither,
+            cells: HashMap::new(),
+        }
+    }
+
+    pub fn image(&self) -> &DynamicImage {
+        &self.image
+    }
+
+    /// The image fitted into `width` x `height` cells of `font_size`
+    /// pixels, as ratatui-image would place it. It grows past its own size
+    /// only when `scale_up`.
+    pub fn lines(
+        &mut self,
+        width: u16,
+        height: u16,
+        font_size: (u16, u16),
+        scale_up: bool,
+    ) -> &[Line<'static>] {
+        let (cols, rows) = fit(&self.image, width, height, font_size, scale_up);
+        self.cells.entry((cols, rows)).or_insert_with(|| {
+            let pixels =
+                self.image
+                    .resize_exact(cols as u32, rows as u32 * 2, FilterType::Triangle);
+            encode(&pixels, self.colors, self.dither)
+        })
+    }
+}
+
+/// Cells `image` takes when fitted into `width` x `height`.
+fn fit(
+    image: &DynamicImage,
+    width: u16,
+    height: u16,
+    (font_w, font_h): (u16, u16),
+    scale_up: bool,
+) -> (u16, u16) {
+    let (w, h) = (image.width().max(1) as f64, image.height().max(1) as f64);
+    let (font_w, font_h) = (font_w.max(1) as f64, font_h.max(1) as f64);
+    let mut scale = (width as f64 * font_w / w).min(height as f64 * font_h / h);
+    if !scale_up {
+        scale = scale.min(1.0);
+    }
+    let cols = (w * scale / font_w).round().clamp(1.0, width.max(1) as f64);
+    let rows = (h * scale / font_h)
+        .round()
+        .clamp(1.0, height.max(1) as f64);
+    (cols as u16, rows as u16)
+}
+
+/// Colors of `colors` as (xterm index, RGB).
+fn palette(colors: DitherColors) -> Vec<(u8, [f32; 3])> {
+    let rgb = |c: u32| {
+        [
+            (c >> 16) as f32,
+            ((c >> 8) & 0xff) as f32,
+            (c & 0xff) as f32,
+        ]
+    };
+    match colors {
+        DitherColors::Ansi16 => (0..16).map(|i| (i, rgb(ANSI_16[i as usize]))).collect(),
+        DitherColors::Xterm256 => {
+            // 16-231 are a 6x6x6 cube, 232-255 a ramp of grays
+            let level = |i: u8| if i == 0 { 0.0 } else { 55.0 + 40.0 * i as f32 };
+            let cube =
+                (0..216u8).map(|i| (16 + i, [level(i / 36), level(i / 6 % 6), level(i % 6)]));
+            let grays = (0..24u8).map(|i| (232 + i, [8.0 + 10.0 * i as f32; 3]));
+            cube.chain(grays).collect()
+        }
+    }
+}
+
+/// Rows of `▀` cells for `pixels`, which has two pixel rows per cell.
+fn encode(pixels: &DynamicImage, colors: DitherColors, dither: Dither) -> Vec<Line<'static>> {
+    let pixels = pixels.to_rgb8();
+    let (w, h) = (pixels.width() as usize, pixels.height() as usize);
+    let palette = palette(colors);
+    // Ordered dithering nudges each pixel by up to about one palette step
+    let spread = match colors {
+        DitherColors::Ansi16 => 96.0,
+        DitherColors::Xterm256 => 40.0,
+    };
+    let mut rgb: Vec<[f32; 3]> = pixels.pixels().map(|p| p.0.map(f32::from)).collect();
+    let mut indices = vec![0u8; w * h];
+    for y in 0..h {
+        for x in 0..w {
+            let mut color = rgb[y * w + x];
+            if dither == Dither::Ordered {
+                let offset = (BAYER_4X4[y % 4][x % 4] as f32 + 0.5) / 16.0 - 0.5;
+                color = color.map(|c| c + offset * spread);
+            }
+            let (index, chosen) = nearest(&palette, color);
+            indices[y * w + x] = index;
+            if dither == Dither::Diffusion {
+                // Floyd-Steinberg: pass the error on to unvisited neighbors
+                let error = [0, 1, 2].map(|c| color[c] - chosen[c]);
+                let mut pass_on = |dx: isize, dy: usize, weight: f32| {
+                    let nx = x as isize + dx;
+                    if nx < 0 || nx as usize >= w || y + dy >= h {
+                        return;
+                    }
+                    let pixel = &mut rgb[(y + dy) * w + nx as usize];
+                    for c in 0..3 {
+                        pixel[c] += error[c] * weight;
+                    }
+                };
+                pass_on(1, 0, 7.0 / 16.0);
+                pass_on(-1, 1, 3.0 / 16.0);
+                pass_on(0, 1, 5.0 / 16.0);
+                pass_on(1, 1, 1.0 / 16.0);
+            }
+        }
+    }
+    (0..h / 2)
+        .map(|row| {
+            let spans: Vec<Span<'static>> = (0..w)
+                .map(|x| {
+                    let upper = Color::Indexed(indices[row * 2 * w + x]);
+                    let lower = Color::Indexed(indices[(row * 2 + 1) * w + x]);
+                    Span::styled("▀", Style::default().fg(upper).bg(lower))
+                })
+                .collect();
+            Line::from(spans)
+        })
+        .collect()
+}
+
+/// The palette color closest to `color`, as (xterm index, RGB).
+fn nearest(palette: &[(u8, [f32; 3])], color: [f32; 3]) -> (u8, [f32; 3]) {
+    let distance = |rgb: &[f32; 3]| (0..3).map(|c| (rgb[c] - color[c]).powi(2)).sum::<f32>();
+    palette
+        .iter()
+        .copied()
+        .min_by(|a, b| distance(&a.1).total_cmp(&distance(&b.1)))
+        .unwrap_or((0, [0.0; 3]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dithering_mixes_palette_colors_for_shades_between_them() {
+        let gray = image::RgbImage::from_pixel(8, 8, image::Rgb([0x60; 3]));
+        let mut image = DitheredImage::new(gray.into(), DitherColors::Ansi16, Dither::None);
+        let cells = |image: &mut DitheredImage| {
+            let lines = image.lines(8, 4, (1, 2), false);
+            let mut colors: Vec<_> = lines
+                .iter()
+                .flat_map(|line| &line.spans)
+                .flat_map(|span| [span.style.fg, span.style.bg])
+                .collect();
+            colors.sort_by_key(|c| format!("{c:?}"));
+            colors.dedup();
+            colors
+        };
+        assert_eq!(cells(&mut image), [Some(Color::Indexed(8))]);
+
+        image.dither = Dither::Diffusion;
+        image.cells.clear();
+        assert_eq!(cells(&mut image).len(), 2);
+        image.dither = Dither::Ordered;
+        image.cells.clear();
+        assert_eq!(cells(&mut image).len(), 2);
+    }
+}
@@ -1,8 +1,11 @@
+use crate::cache;
+use crate::generate::Generator;
+use crate::markdown::{Slide, parse_frontmatter, parse_slides, select_conditional};
+use crate::theme::Theme;
 use pulldown_cmark::{Event, Parser, Tag};
-use std::collections::HashMap;
 use std::fs;
-use std::io;
-use std::path::Path;
+use std::io::{self, Write};
+use std::path::{Component, Path};
 
 const HTML_TEMPLATE: &str = r#"<!doctype html>
 <html>
@@ -13,36 +16,97 @@ const HTML_TEMPLATE: &str = r#"<!doctype html>
         <style>
             * { margin: 0; padding: 0; box-sizing: border-box; }
             body { background: #1e1e2e; width: 100vw; height: 100vh; overflow: hidden; }
+            nav { color: #cdd6f4; font-family: monospace; font-size: 1.25rem; padding: 2rem; }
+            nav h1 { margin-bottom: 1rem; }
+            nav li { list-style: none; margin: 0.5rem 0; }
+            nav a { color: #89b4fa; }
         </style>
     </head>
     <body>
+        <nav id="decks" hidden><h1>Decks</h1><ul></ul></nav>
         <script type="module">
             import { run } from "https://unpkg.com/ratride@latest/dist/index.js";
-            const md = await fetch("./slides.md").then((r) => r.text());
-            run(md{{THEME_OPT}});
+            const decks = {{DECKS}};
+            const param = new URLSearchParams(location.search).get("deck");
+            const deck = param ?? (decks.length === 1 ? decks[0] : null);
+            if (decks.includes(deck)) {
+                const md = await fetch(`./${deck}.md`).then((r) => r.text());
+                run(md{{THEME_OPT}});
+            } else {
+                // Index page: link to every deck
+                const nav = document.getElementById("decks");
+                for (const name of decks) {
+                    const a = document.createElement("a");
+                    a.href = `?deck=${encodeURIComponent(name)}`;
+                    a.textContent = name;
+                    const li = document.createElement("li");
+                    li.appendChild(a);
+                    nav.querySelector("ul").appendChild(li);
+                }
+                nav.hidden = false;
+                document.body.style.overflow = "auto";
+            }
         </script>
     </body>
 </html>
 "#;
 
-/// Extract local image paths from markdown (skip http/https URLs).
-fn extract_image_paths(md: &str) -> Vec<String> {
+/// Extract local image, gallery, audio, video and FIGlet font paths from
+/// markdown (skip http/https URLs).
+pub(crate) fn extract_asset_paths(md: &str) -> Vec<String> {
     let parser = Parser::new(md);
     let mut paths = Vec::new();
     for event in parser {
-        if let Event::Start(Tag::Image { dest_url, .. }) = event {
-            let url = dest_url.as_ref();
-            if !url.starts_with("http://") && !url.starts_with("https://") {
-                if !paths.contains(&url.to_string()) {
-                    paths.push(url.to_string());
-                }
+        let urls = match &event {
+            Event::Start(Tag::Image { dest_url, .. }) => vec![dest_url.as_ref()],
+            Event::Html(html) | Event::InlineHtml(html) => directive_paths(html),
+            _ => continue,
+        };
+        for url in urls {
+            if !url.starts_with("http://")
+                && !url.starts_with("https://")
+                && !paths.iter().any(|p| p == url)
+            {
+                paths.push(url.to_string());
             }
         }
     }
     paths
 }
 
-/// Copy image files to out_dir/assets/, returning (old_path, new_relative_path) pairs.
+/// Paths from an `<!-- audio: path -->`, `<!-- video: path -->`,
+/// `<!-- gallery: path path ... -->` or `<!-- figlet: font.flf -->`
+/// directive comment.
+fn directive_paths(html: &str) -> Vec<&str> {
+    let Some(inner) = html
+        .trim()
+        .strip_prefix("<!--")
+        .and_then(|html| html.strip_suffix("-->"))
+        .map(str::trim)
+    else {
+        return Vec::new();
+    };
+    if let Some(paths) = inner.strip_prefix("gallery:") {
+        return paths.split_whitespace().collect();
+    }
+    if let Some(font) = inner.strip_prefix("figlet:").map(str::trim) {
+        return Vec::from_iter(is_font_file(font).then_some(font));
+    }
+    let path = inner
+        .strip_prefix("audio:")
+        .or_else(|| inner.strip_prefix("video:"))
+        .map(str::trim);
+    path.filter(|path| !path.is_empty()).into_iter().collect()
+}
+
+/// Whether a FIGlet font name is a `.flf` file rather than a font looked up
+/// by name.
+pub fn is_font_file(font: &str) -> bool {
+    font.ends_with(".flf")
+}
+
+/// Copy asset files under out_dir/assets/ (see [`asset_name`]), returning
+/// (old_path, new_relative_path) pairs.
 fn copy_assets(
     paths: &[String],
     base_dir: &Path,
@@ -52,69 +116,251 @@ fn copy_assets(
     fs::create_dir_all(&assets_dir)?;
 
     let mut rewrites = Vec::new();
-    let mut seen: HashMap<String, bool> = HashMap::new();
-
     for old_path in paths {
         let src = base_dir.join(old_path);
-        let filename = Path::new(old_path)
-            .file_name()
-            .map(|f| f.to_string_lossy().to_string())
-            .unwrap_or_else(|| old_path.replace('/', "_"));
-
-        if seen.contains_key(&filename) {
-            eprintln!("warning: filename collision for '{}', overwriting", filename);
-        }
-        seen.insert(filename.clone(), true);
-
-        let dst = assets_dir.join(&filename);
-        if src.exists() {
-            fs::copy(&src, &dst)?;
-        } else {
+        if !src.exists() {
             eprintln!("warning: missing file '{}'", src.display());
             continue;
         }
-
-        let new_path = format!("./assets/{}", filename);
-        rewrites.push((old_path.clone(), new_path));
+        let name = asset_name(old_path);
+        let dst = assets_dir.join(&name);
+        if let Some(parent) = dst.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(&src, &dst)?;
+        rewrites.push((old_path.clone(), format!("./assets/{name}")));
     }
 
     Ok(rewrites)
 }
 
-/// Rewrite image paths in markdown text.
-fn rewrite_image_paths(md: &str, rewrites: &[(String, String)]) -> String {
-    let mut result = md.to_string();
-    for (old, new) in rewrites {
-        result = result.replace(old.as_str(), new.as_str());
+/// Where the asset at `path` goes under an `assets/` directory: `path`
+/// itself when it stays inside the deck's directory, otherwise its file name
+/// in a directory named by a hash of `path`, so that two files with the same
+/// name never overwrite each other.
+pub(crate) fn asset_name(path: &str) -> String {
+    let path = path.trim_start_matches("./");
+    if is_contained(Path::new(path)) {
+        return path.to_string();
+    }
+    let file_name = Path::new(path)
+        .file_name()
+        .map_or_else(|| "asset".into(), |f| f.to_string_lossy());
+    format!("{:016x}/{file_name}", cache::stable_hash(path.as_bytes()))
+}
+
+/// Whether `path` is relative and never leaves its directory.
+fn is_contained(path: &Path) -> bool {
+    path.components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+}
+
+/// Rewrite asset paths in markdown text. Only whole paths are replaced, so
+/// rewriting `a.png` leaves `img/a.png` alone.
+pub(crate) fn rewrite_asset_paths(md: &str, rewrites: &[(String, String)]) -> String {
+    let is_path_char = |c: char| c.is_alphanumeric() || "/._-~%".contains(c);
+    let mut result = String::with_capacity(md.len());
+    let mut rest = md;
+    let mut prev = None;
+    while let Some(c) = rest.chars().next() {
+        if !prev.is_some_and(is_path_char) {
+            let found = rewrites
+                .iter()
+                .filter(|(old, _)| {
+                    !old.is_empty()
+                        && rest.starts_with(old.as_str())
+                        && !rest[old.len()..].starts_with(is_path_char)
+                })
+                .max_by_key(|(old, _)| old.len());
+            if let Some((old, new)) = found {
+                result.push_str(new);
+                prev = old.chars().last();
+                rest = &rest[old.len()..];
+                continue;
+            }
+        }
+        result.push(c);
+        prev = Some(c);
+        rest = &rest[c.len_utf8()..];
     }
     result
 }
 
-/// Export slides as a static HTML directory.
-pub fn export(file: &str, out_dir: &str, theme: Option<&str>) -> io::Result<()> {
-    let path = Path::new(file);
-    let base_dir = path.parent().unwrap_or(Path::new("."));
-    let md = fs::read_to_string(path)?;
+/// Deck names used for `?deck=` routing: `slides` for a single deck,
+/// otherwise each file's stem.
+fn deck_names(files: &[String]) -> io::Result<Vec<String>> {
+    if files.len() == 1 {
+        return Ok(vec!["slides".to_string()]);
+    }
+    let mut names: Vec<String> = Vec::new();
+    for file in files {
+        let name = Path::new(file)
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| file.clone());
+        if names.contains(&name) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("duplicate deck name '{}'", name),
+            ));
+        }
+        names.push(name);
+    }
+    Ok(names)
+}
 
+/// Export slides as a static HTML directory.
+/// Several decks share one index.html and are selected via `?deck=name`.
+/// With a `generator`, `generate` directives are replaced with their commands'
+/// output in the exported Markdown. `if` guards are settled at export time,
+/// against `defines` and the environment.
+pub fn export(
+    files: &[String],
+    out_dir: &str,
+    theme: Option<&str>,
+    mut generator: Option<&mut Generator>,
+    defines: &[String],
+) -> io::Result<()> {
     let out = Path::new(out_dir);
     fs::create_dir_all(out)?;
 
-    // Copy assets and rewrite paths
-    let image_paths = extract_image_paths(&md);
-    let rewrites = copy_assets(&image_paths, base_dir, out)?;
-    let rewritten_md = rewrite_image_paths(&md, &rewrites);
+    let names = deck_names(files)?;
+    for (file, name) in files.iter().zip(&names) {
+        let path = Path::new(file);
+        let base_dir = path.parent().unwrap_or(Path::new("."));
+        let md = fs::read_to_string(path)?;
+        let md = match generator.as_deref_mut() {
+            Some(generator) => generator.expand(&md, base_dir).into_owned(),
+            None => md,
+        };
+        let md = select_conditional(&md, defines, 0).0.into_owned();
 
-    // Write slides.md
-    fs::write(out.join("slides.md"), &rewritten_md)?;
+        // Copy assets and rewrite paths
+        let mut asset_paths = extract_asset_paths(&md);
+        let frontmatter = parse_frontmatter(&md).0;
+        for asset in [frontmatter.web_font, frontmatter.logo]
+            .into_iter()
+            .flatten()
+        {
+            if !asset.starts_with("http://")
+                && !asset.starts_with("https://")
+                && !asset_paths.contains(&asset)
+            {
+                asset_paths.push(asset);
+            }
+        }
+        let rewrites = copy_assets(&asset_paths, base_dir, out)?;
+        let rewritten_md = rewrite_asset_paths(&md, &rewrites);
+
+        // Write <deck>.md
+        fs::write(out.join(format!("{}.md", name)), &rewritten_md)?;
+    }
 
     // Write index.html
     let theme_opt = match theme {
         Some(t) => format!(", {{ theme: \"{}\" }}", t),
         None => String::new(),
     };
-    let html = HTML_TEMPLATE.replace("{{THEME_OPT}}", &theme_opt);
+    let decks = names
+        .iter()
+        .map(|n| format!("{:?}", n))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let html = HTML_TEMPLATE
+        .replace("{{THEME_OPT}}", &theme_opt)
+        .replace("{{DECKS}}", &format!("[{}]", decks));
     fs::write(out.join("index.html"), &html)?;
 
     eprintln!("exported to {}", out.display());
     Ok(())
 }
+
+/// Speaking script: each slide's title followed by its speaker notes, in
+/// order, as Markdown for a teleprompter or for recording narration.
+pub fn script(slides: &[Slide]) -> String {
+    let mut out = String::new();
+    for (i, slide) in slides.iter().enumerate() {
+        let n = i + 1;
+        match &slide.title {
+            Some(title) => out.push_str(&format!("## {n}. {title}\n\n")),
+            None => out.push_str(&format!("## {n}. Slide {n}\n\n")),
+        }
+        if let Some(notes) = &slide.notes {
+            out.push_str(notes);
+            out.push_str("\n\n");
+        }
+    }
+    out.truncate(out.trim_end().len());
+    out.push('\n');
+    out
+}
+
+/// Write the speaking script for the deck at `file` to `out` (`-` for stdout).
+pub fn export_script(file: &Path, out: &str) -> io::Result<()> {
+    let md = fs::read_to_string(file)?;
+    let (frontmatter, body) = parse_frontmatter(&md);
+    let (slides, _) = parse_slides(body, &Theme::default(), &frontmatter, None, false);
+    let mut writer: Box<dyn Write> = if out == "-" {
+        Box::new(io::stdout().lock())
+    } else {
+        Box::new(fs::File::create(out)?)
+    };
+    writer.write_all(script(&slides).as_bytes())?;
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scripts_list_titles_and_notes_in_order() {
+        let md = "# Intro\n\n<!-- notes: Say hi. -->\n\n---\n\nno title\n\n---\n\n# End\n\n```notes\nThank them.\n```\n";
+        let (slides, _) = parse_slides(md, &Theme::default(), &Default::default(), None, false);
+        assert_eq!(
+            script(&slides),
+            "## 1. Intro\n\nSay hi.\n\n## 2. Slide 2\n\n## 3. End\n\nThank them.\n"
+        );
+    }
+
+    #[test]
+    fn every_gallery_image_is_an_asset() {
+        let md = "![](a.png)\n\n<!-- gallery: b.png a.png https://x/c.png -->\n\n<!-- audio: d.ogg -->\n\n<!-- figlet: slant -->\n# A\n\n<!-- figlet: fonts/x.flf -->\n# B\n";
+        assert_eq!(
+            extract_asset_paths(md),
+            ["a.png", "b.png", "d.ogg", "fonts/x.flf"]
+        );
+        let rewrites = [("b.png".to_string(), "./assets/b.png".to_string())];
+        assert!(rewrite_asset_paths(md, &rewrites).contains("gallery: ./assets/b.png a.png"));
+    }
+
+    #[test]
+    fn assets_keep_their_paths_and_only_whole_paths_are_rewritten() {
+        let dir = std::env::temp_dir().join(format!("ratride-export-test-{}", std::process::id()));
+        fs::create_dir_all(dir.join("deck/img")).unwrap();
+        fs::create_dir_all(dir.join("shared")).unwrap();
+        fs::write(dir.join("deck/a.png"), b"top").unwrap();
+        fs::write(dir.join("deck/img/a.png"), b"nested").unwrap();
+        fs::write(dir.join("shared/a.png"), b"shared").unwrap();
+        let md = "![](a.png)\n![](img/a.png)\n![](../shared/a.png)\n";
+        fs::write(dir.join("deck/talk.md"), md).unwrap();
+
+        let out = dir.join("out");
+        let file = dir.join("deck/talk.md").to_string_lossy().to_string();
+        export(&[file], out.to_str().unwrap(), None, None, &[]).unwrap();
+
+        let exported = fs::read_to_string(out.join("slides.md")).unwrap();
+        let shared = asset_name("../shared/a.png");
+        assert_eq!(
+            exported,
+            format!("![](./assets/a.png)\n![](./assets/img/a.png)\n![](./assets/{shared})\n")
+        );
+        assert_eq!(fs::read(out.join("assets/a.png")).unwrap(), b"top");
+        assert_eq!(fs::read(out.join("assets/img/a.png")).unwrap(), b"nested");
+        assert_eq!(
+            fs::read(out.join("assets").join(shared)).unwrap(),
+            b"shared"
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
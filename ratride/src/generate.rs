@@ -0,0 +1,301 @@
+//! Slide content produced by a command when the deck is loaded:
+//! `<!-- generate: "git log --oneline -5" -->` is replaced with the command's
+//! output. Commands only run with `--generate`, and only for local decks,
+//! since a deck could otherwise run anything on the presenter's machine.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use crate::cache;
+use crate::markdown::{CodeFences, slide_separators};
+
+/// How long a command may run before it is killed and reported as failed.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Replace every `generate` directive on a line of its own in `markdown`
+/// with the output of its command, run by `sh` in `dir`. The output becomes
+/// a code block, or Markdown with a trailing `markdown` flag
+/// (`<!-- generate: "./notes.sh" markdown -->`). A failing command leaves a
+/// code block saying so on the slide.
+///
+/// `<!-- cwd: demos/service -->` runs the commands after it on the same
+/// slide in that directory instead, relative to `dir`.
+///
+/// With a `cache` flag, the first successful output is kept and shown again
+/// on later loads, under a "cached" badge, instead of running the command:
+/// rehearsals skip slow commands, and a flaky demo has known-good output.
+/// It is kept in the user's cache directory; [`Generator::new`] can run the
+/// commands again to replace it.
+pub fn expand<'a>(markdown: &'a str, dir: &Path) -> Cow<'a, str> {
+    Generator::default().expand(markdown, dir)
+}
+
+/// Runs `generate` directives for a deck that is reloaded as it is edited,
+/// keeping what each command printed so that only new or changed commands
+/// run again.
+#[derive(Default)]
+pub struct Generator {
+    /// Output of each command that succeeded by the directory it ran in;
+    /// failed commands run again on the next load.
+    outputs: HashMap<(String, PathBuf), String>,
+    /// Run commands with a `cache` flag too, replacing their cached output.
+    refresh: bool,
+}
+
+impl Generator {
+    /// With `refresh`, commands with a `cache` flag run the first time they
+    /// are expanded instead of showing the output cached by earlier loads.
+    pub fn new(refresh: bool) -> Self {
+        Self {
+            outputs: HashMap::new(),
+            refresh,
+        }
+    }
+
+    /// [`expand`], running only the commands not run before.
+    pub fn expand<'a>(&mut self, markdown: &'a str, dir: &Path) -> Cow<'a, str> {
+        if !markdown.contains("generate:") {
+            return Cow::Borrowed(markdown);
+        }
+        let mut out = String::with_capacity(markdown.len());
+        let mut fences = CodeFences::default();
+        let mut cwd: Option<PathBuf> = None;
+        let mut separators = slide_separators(markdown).into_iter().peekable();
+        let mut start = 0;
+        for line in markdown.split_inclusive('\n') {
+            let trimmed = line.trim();
+            start += line.len();
+            if separators.next_if(|&at| at < start).is_some() {
+                // A new slide runs in the deck's directory again
+                cwd = None;
+                out.push_str(line);
+            } else if fences.in_code(trimmed) {
+                out.push_str(line);
+            } else if let Some(path) = parse_cwd(trimmed) {
+                cwd = Some(dir.join(path));
+                out.push_str(line);
+            } else if let Some((command, as_markdown, cache)) = parse_directive(trimmed) {
+                let dir = cwd.as_deref().unwrap_or(dir);
+                let cache = cache.then(|| cache_path(command, dir)).flatten();
+                let cached = cache
+                    .as_ref()
+                    .filter(|_| !self.refresh)
+                    .and_then(|path| fs::read_to_string(path).ok());
+                let is_cached = cached.is_some();
+                let key = (command.to_string(), dir.to_path_buf());
+                let output = match cached.or_else(|| self.outputs.get(&key).cloned()) {
+                    Some(output) => Ok(output),
+                    None => run(command, dir, TIMEOUT).inspect(|output| {
+                        if let Some(path) = &cache {
+                            let _ = cache::write_atomic(path, output.as_bytes());
+                        }
+                        self.outputs.insert(key, output.clone());
+                    }),
+                };
+                let text = match output {
+                    Ok(output) if as_markdown => output,
+                    Ok(output) => code_block(&output),
+                    Err(message) => code_block(&format!("generate: `{command}` {message}")),
+                };
+                out.push_str(&text);
+                out.push('\n');
+                if is_cached {
+                    out.push_str("\n[[badge:cached]]\n");
+                }
+            } else {
+                out.push_str(line);
+            }
+        }
+        Cow::Owned(out)
+    }
+}
+
+/// The command of a `<!-- generate: "..." -->` line, whether its output is
+/// Markdown, and whether it is cached.
+fn parse_directive(line: &str) -> Option<(&str, bool, bool)> {
+    let inner = line.strip_prefix("<!--")?.strip_suffix("-->")?.trim();
+    let value = inner.strip_prefix("generate:")?.trim();
+    let (command, flags) = value.strip_prefix('"')?.rsplit_once('"')?;
+    let (mut as_markdown, mut cache) = (false, false);
+    for flag in flags.split_whitespace() {
+        match flag {
+            "markdown" => as_markdown = true,
+            "cache" => cache = true,
+            _ => return None,
+        }
+    }
+    Some((command, as_markdown, cache))
+}
+
+/// The directory of a `<!-- cwd: ... -->` line.
+fn parse_cwd(line: &str) -> Option<&str> {
+    let inner = line.strip_prefix("<!--")?.strip_suffix("-->")?.trim();
+    let path = inner.strip_prefix("cwd:")?.trim();
+    (!path.is_empty()).then_some(path)
+}
+
+/// Where the output of `command` run in `dir` is cached, in the user's
+/// cache directory; `None` when there is none.
+fn cache_path(command: &str, dir: &Path) -> Option<PathBuf> {
+    let dir = fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf());
+    let key = format!("{command}\0{}", dir.display());
+    let name = format!("{:016x}.txt", cache::stable_hash(key.as_bytes()));
+    Some(cache::cache_dir("generate").ok()?.join(name))
+}
+
+/// Standard output of `command`, without its trailing newline. It is killed
+/// once it has run for `timeout`, along with everything it started.
+fn run(command: &str, dir: &Path, timeout: Duration) -> Result<String, String> {
+    let mut sh = Command::new("sh");
+    sh.arg("-c")
+        .arg(command)
+        .current_dir(dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    // A process group of its own, for a timeout to kill as a whole
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut sh, 0);
+    let mut child = sh.spawn().map_err(|e| format!("could not run: {e}"))?;
+    // Read both pipes as the command runs, so it never blocks on a full one
+    let stdout = child.stdout.take().map(read_in_background);
+    let stderr = child.stderr.take().map(read_in_background);
+    let started = Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if started.elapsed() >= timeout => {
+                kill_group(&mut child);
+                return Err(format!("timed out after {timeout:?}"));
+            }
+            Ok(None) => std::thread::sleep(Duration::from_millis(10)),
+            Err(e) => return Err(format!("could not run: {e}")),
+        }
+    };
+    let output = |pipe: Option<JoinHandle<String>>| {
+        pipe.and_then(|pipe| pipe.join().ok()).unwrap_or_default()
+    };
+    if !status.success() {
+        return Err(format!("failed ({status}): {}", output(stderr).trim()));
+    }
+    Ok(output(stdout).trim_end().to_string())
+}
+
+/// Kill `child` and, on Unix, the rest of the process group it leads.
+fn kill_group(child: &mut Child) {
+    #[cfg(unix)]
+    let _ = Command::new("kill")
+        .args(["-KILL", "--", &format!("-{}", child.id())])
+        .stderr(Stdio::null())
+        .status();
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// Everything `pipe` yields until it closes, read on a thread of its own.
+fn read_in_background(mut pipe: impl Read + Send + 'static) -> JoinHandle<String> {
+    std::thread::spawn(move || {
+        let mut data = Vec::new();
+        let _ = pipe.read_to_end(&mut data);
+        String::from_utf8_lossy(&data).into_owned()
+    })
+}
+
+/// `text` as a fenced code block, fenced with more backticks than it contains.
+fn code_block(text: &str) -> String {
+    let longest = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest.max(2) + 1);
+    format!("{fence}text\n{text}\n{fence}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn directives_become_command_output() {
+        let md = "# Build\n<!-- generate: \"echo '- one'\" markdown -->\n<!-- generate: \"printf '```'\" -->\n```\n<!-- generate: \"echo no\" -->\n```\n";
+        assert_eq!(
+            expand(md, Path::new(".")),
+            "# Build\n- one\n````text\n```\n````\n```\n<!-- generate: \"echo no\" -->\n```\n"
+        );
+        assert!(expand("<!-- generate: \"exit 3\" -->", Path::new(".")).contains("failed"));
+    }
+
+    #[test]
+    fn commands_run_once_per_generator_and_are_killed_on_timeout() {
+        let dir =
+            std::env::temp_dir().join(format!("ratride-generate-runs-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let md = "<!-- generate: \"echo run >> runs; wc -l < runs\" -->\n";
+        let mut generator = Generator::default();
+        let first = generator.expand(md, &dir).into_owned();
+        assert_eq!(generator.expand(md, &dir), first);
+        assert_eq!(fs::read_to_string(dir.join("runs")).unwrap(), "run\n");
+        fs::remove_dir_all(&dir).unwrap();
+
+        let started = Instant::now();
+        let result = run("sleep 5", Path::new("."), Duration::from_millis(100));
+        assert_eq!(result, Err("timed out after 100ms".to_string()));
+        assert!(started.elapsed() < Duration::from_secs(2));
+
+        // What the command started in the background goes with it
+        fs::create_dir_all(&dir).unwrap();
+        let timeout = Duration::from_millis(100);
+        assert!(run("(sleep 0.5; touch late) & wait", &dir, timeout).is_err());
+        std::thread::sleep(Duration::from_secs(1));
+        assert!(!dir.join("late").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn failed_commands_run_again_on_the_next_load() {
+        let dir =
+            std::env::temp_dir().join(format!("ratride-generate-retry-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let md = "<!-- generate: \"echo run >> runs; test $(wc -l < runs) -gt 1\" -->\n";
+        let mut generator = Generator::default();
+        assert!(generator.expand(md, &dir).contains("failed"));
+        assert!(!generator.expand(md, &dir).contains("failed"));
+        assert_eq!(fs::read_to_string(dir.join("runs")).unwrap(), "run\nrun\n");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn cwd_directive_applies_until_the_next_slide() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let md = "<!-- cwd: src -->\n<!-- generate: \"ls lib.rs\" -->\n\n- - -\n<!-- generate: \"ls src/lib.rs\" -->\n";
+        let out = expand(md, dir);
+        assert!(!out.contains("failed"), "{out}");
+        // A leaked directory fails, and a setext underline is no separator
+        let md = "<!-- cwd: src -->\nTitle\n---\n<!-- generate: \"ls src/lib.rs\" -->\n";
+        assert!(expand(md, dir).contains("failed"));
+    }
+
+    #[test]
+    fn cached_output_is_shown_again_without_running() {
+        let _home = cache::TempHome::new("generate");
+        let dir =
+            std::env::temp_dir().join(format!("ratride-generate-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let md = "<!-- generate: \"echo $$\" cache -->\n";
+        let first = expand(md, &dir).into_owned();
+        assert!(!first.contains("[[badge:cached]]"));
+        let second = expand(md, &dir);
+        assert_eq!(second, format!("{first}\n[[badge:cached]]\n"));
+
+        // Refreshing runs the command again and caches the new output
+        let refreshed = Generator::new(true).expand(md, &dir).into_owned();
+        assert_ne!(refreshed, first);
+        assert!(!refreshed.contains("[[badge:cached]]"));
+        assert_eq!(expand(md, &dir), format!("{refreshed}\n[[badge:cached]]\n"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,510 @@
+//! Syntax highlighting of fenced code blocks. The backend is picked per deck
+//! (frontmatter `highlighter`) from the ones this build has: syntect (the
+//! default `syntect` feature), tree-sitter (the `tree-sitter` feature), or
+//! none.
+//!
+//! tree-sitter only has the grammars `grammar` lists; other languages stay
+//! plain under it. It takes its colors from the theme's tmTheme through
+//! syntect, which is why the `tree-sitter` feature turns `syntect` on too.
+
+use ratatui::style::Style;
+#[cfg(feature = "syntect")]
+use ratatui::style::{Color, Modifier};
+
+use crate::theme::Theme;
+
+/// A syntax highlighting backend (frontmatter `highlighter: tree-sitter`).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HighlighterKind {
+    /// syntect's TextMate grammars, for most languages.
+    Syntect,
+    /// tree-sitter grammars, which parse the code rather than match it line
+    /// by line: more accurate, for fewer languages.
+    TreeSitter,
+    /// Code in the theme's text color.
+    None,
+}
+
+impl HighlighterKind {
+    /// Whether this build of ratride has the backend.
+    pub fn is_available(self) -> bool {
+        match self {
+            Self::Syntect => cfg!(feature = "syntect"),
+            Self::TreeSitter => cfg!(feature = "tree-sitter"),
+            Self::None => true,
+        }
+    }
+}
+
+impl Default for HighlighterKind {
+    fn default() -> Self {
+        match cfg!(feature = "syntect") {
+            true => Self::Syntect,
+            false => Self::None,
+        }
+    }
+}
+
+/// Parse frontmatter `highlighter`.
+pub fn parse_highlighter(value: &str) -> Option<HighlighterKind> {
+    match value {
+        "syntect" => Some(HighlighterKind::Syntect),
+        "tree-sitter" | "treesitter" => Some(HighlighterKind::TreeSitter),
+        "none" => Some(HighlighterKind::None),
+        _ => None,
+    }
+}
+
+/// Telltale snippets of the languages [`guess_language`] knows, with how
+/// strongly each one points at its language.
+const LANGUAGE_SIGNS: &[(&str, &str, u32)] = &[
+    ("bash", "echo ", 1),
+    ("bash", "$(", 1),
+    ("bash", "\nfi", 2),
+    ("bash", "; then", 2),
+    ("bash", "export ", 1),
+    ("bash", "sudo ", 2),
+    ("bash", "$ ", 1),
+    ("c", "#include", 3),
+    ("c", "int main(", 2),
+    ("c", "printf(", 1),
+    ("c", "void ", 1),
+    ("c", "->", 1),
+    ("c", "NULL", 1),
+    ("css", "px;", 2),
+    ("css", "color:", 1),
+    ("css", "margin:", 1),
+    ("css", "@media", 3),
+    ("css", "display:", 1),
+    ("go", "package ", 2),
+    ("go", "func ", 2),
+    ("go", ":= ", 2),
+    ("go", "fmt.", 2),
+    ("go", "err != nil", 3),
+    ("html", "<!DOCTYPE", 3),
+    ("html", "<div", 2),
+    ("html", "</", 1),
+    ("html", "<html", 3),
+    ("html", "class=\"", 1),
+    ("js", "function ", 1),
+    ("js", "const ", 1),
+    ("js", "=> ", 1),
+    ("js", "console.log", 3),
+    ("js", "require(", 2),
+    ("js", "===", 2),
+    ("js", "document.", 2),
+    ("python", "def ", 2),
+    ("python", "import ", 1),
+    ("python", "self.", 2),
+    ("python", "elif ", 3),
+    ("python", "print(", 1),
+    ("python", "__init__", 3),
+    ("python", "None", 1),
+    ("rust", "fn ", 2),
+    ("rust", "let mut ", 3),
+    ("rust", "impl ", 2),
+    ("rust", "::", 1),
+    ("rust", "-> ", 1),
+    ("rust", "println!", 3),
+    ("rust", "&str", 2),
+    ("rust", "pub ", 1),
+    ("sql", "SELECT ", 2),
+    ("sql", "FROM ", 1),
+    ("sql", "WHERE ", 1),
+    ("sql", "INSERT INTO", 3),
+    ("sql", "CREATE TABLE", 3),
+    ("sql", "JOIN ", 1),
+    ("ts", "interface ", 2),
+    ("ts", ": string", 3),
+    ("ts", ": number", 3),
+    ("ts", "const ", 1),
+    ("ts", "=> ", 1),
+    ("ts", "export ", 1),
+];
+
+/// A guess at the language of unlabeled `code` (frontmatter
+/// `detect_language: true`), among the fence tags `known` accepts, such as
+/// the ones the deck's [`Highlighter::knows`]. `None` unless one language
+/// stands out.
+pub fn guess_language(code: &str, known: impl Fn(&str) -> bool) -> Option<&'static str> {
+    let first_line = code.lines().next().unwrap_or("");
+    if let Some(interpreter) = first_line.strip_prefix("#!") {
+        let interpreter = interpreter.split_whitespace().last().unwrap_or("");
+        let lang = match interpreter.rsplit('/').next() {
+            Some("bash" | "sh" | "zsh") => Some("bash"),
+            Some(name) if name.starts_with("python") => Some("python"),
+            Some("node") => Some("js"),
+            _ => None,
+        };
+        return lang.filter(|lang| known(lang));
+    }
+    let trimmed = code.trim_start();
+    if (trimmed.starts_with('{') || trimmed.starts_with('[')) && code.contains("\":") {
+        return Some("json").filter(|lang| known(lang));
+    }
+
+    let mut scores: Vec<(&str, u32)> = Vec::new();
+    let signs = LANGUAGE_SIGNS.iter().filter(|(lang, _, _)| known(lang));
+    for &(lang, sign, weight) in signs {
+        let weight = if code.contains(sign) { weight } else { 0 };
+        match scores.iter_mut().find(|(l, _)| *l == lang) {
+            Some((_, score)) => *score += weight,
+            None => scores.push((lang, weight)),
+        }
+    }
+    scores.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+    match scores[..] {
+        [(lang, best), (_, next), ..] if best >= 3 && best > next => Some(lang),
+        _ => None,
+    }
+}
+
+/// Highlighted code: the pieces of each line, styled with a foreground and
+/// modifiers but no background, which the code block sets.
+pub type HighlightedLines = Vec<Vec<(Style, String)>>;
+
+pub trait Highlighter {
+    /// Take the code colors of `theme`, which a slide may switch to.
+    fn set_theme(&mut self, theme: &Theme);
+
+    /// Highlight `lines` of `lang` code, or `None` for a language the
+    /// backend doesn't know.
+    fn highlight(&mut self, lang: &str, lines: &[&str]) -> Option<HighlightedLines>;
+
+    /// Whether the backend can highlight `lang` code.
+    fn knows(&self, lang: &str) -> bool;
+}
+
+/// The highlighter for `kind` in `theme`'s colors, or one that leaves code
+/// plain when this build lacks the backend.
+#[cfg_attr(not(feature = "syntect"), allow(unused_variables))]
+pub fn highlighter(kind: HighlighterKind, theme: &Theme) -> Box<dyn Highlighter> {
+    match kind {
+        #[cfg(feature = "syntect")]
+        HighlighterKind::Syntect => Box::new(SyntectHighlighter::new(theme)),
+        #[cfg(feature = "tree-sitter")]
+        HighlighterKind::TreeSitter => Box::new(TreeSitterHighlighter::new(theme)),
+        _ => Box::new(NoHighlighter),
+    }
+}
+
+/// `highlighter: none`: every language is unknown, so code is drawn plain.
+struct NoHighlighter;
+
+impl Highlighter for NoHighlighter {
+    fn set_theme(&mut self, _theme: &Theme) {}
+
+    fn highlight(&mut self, _lang: &str, _lines: &[&str]) -> Option<HighlightedLines> {
+        None
+    }
+
+    fn knows(&self, _lang: &str) -> bool {
+        false
+    }
+}
+
+#[cfg(feature = "syntect")]
+fn to_style(style: syntect::highlighting::Style) -> Style {
+    use syntect::highlighting::FontStyle;
+
+    let fg = style.foreground;
+    let mut result = Style::default().fg(Color::Rgb(fg.r, fg.g, fg.b));
+    for (font_style, modifier) in [
+        (FontStyle::BOLD, Modifier::BOLD),
+        (FontStyle::ITALIC, Modifier::ITALIC),
+        (FontStyle::UNDERLINE, Modifier::UNDERLINED),
+    ] {
+        if style.font_style.contains(font_style) {
+            result = result.add_modifier(modifier);
+        }
+    }
+    result
+}
+
+#[cfg(feature = "syntect")]
+struct SyntectHighlighter {
+    syntax_set: syntect::parsing::SyntaxSet,
+    theme: syntect::highlighting::Theme,
+}
+
+#[cfg(feature = "syntect")]
+impl SyntectHighlighter {
+    fn new(theme: &Theme) -> Self {
+        Self {
+            syntax_set: syntect::parsing::SyntaxSet::load_defaults_newlines(),
+            theme: theme.syntect_theme(),
+        }
+    }
+
+    fn syntax(&self, lang: &str) -> Option<&syntect::parsing::SyntaxReference> {
+        self.syntax_set.find_syntax_by_token(lang).or_else(|| {
+            // Fallback: map common tokens missing from syntect defaults
+            let fallback = match lang {
+                "jsx" | "tsx" | "ts" | "typescript" => Some("js"),
+                _ => None,
+            };
+            fallback.and_then(|f| self.syntax_set.find_syntax_by_token(f))
+        })
+    }
+}
+
+#[cfg(feature = "syntect")]
+impl Highlighter for SyntectHighlighter {
+    fn set_theme(&mut self, theme: &Theme) {
+        self.theme = theme.syntect_theme();
+    }
+
+    fn highlight(&mut self, lang: &str, lines: &[&str]) -> Option<HighlightedLines> {
+        let syntax = self.syntax(lang)?;
+        let mut h = syntect::easy::HighlightLines::new(syntax, &self.theme);
+        let lines = lines.iter().map(|line| {
+            let regions = h.highlight_line(line, &self.syntax_set).unwrap_or_default();
+            regions
+                .into_iter()
+                .map(|(style, text)| (to_style(style), text.to_string()))
+                .collect()
+        });
+        Some(lines.collect())
+    }
+
+    fn knows(&self, lang: &str) -> bool {
+        self.syntax(lang).is_some()
+    }
+}
+
+/// TextMate scopes that tree-sitter capture names are colored as, so the
+/// theme's tmTheme serves both backends. Names missing here take their
+/// parent's scope (`function.method` is a `function`).
+#[cfg(feature = "tree-sitter")]
+const CAPTURE_SCOPES: &[(&str, &str)] = &[
+    ("attribute", "entity.other.attribute-name"),
+    ("comment", "comment"),
+    ("constant", "constant"),
+    ("constant.builtin", "constant.language"),
+    ("constructor", "entity.name.type"),
+    ("embedded", "source"),
+    ("escape", "constant.character.escape"),
+    ("function", "entity.name.function"),
+    ("function.builtin", "support.function"),
+    ("keyword", "keyword"),
+    ("label", "entity.name.label"),
+    ("number", "constant.numeric"),
+    ("operator", "keyword.operator"),
+    ("property", "variable.other.member"),
+    ("punctuation", "punctuation"),
+    ("string", "string"),
+    ("string.special", "string.regexp"),
+    ("tag", "entity.name.tag"),
+    ("type", "entity.name.type"),
+    ("type.builtin", "storage.type"),
+    ("variable", "variable"),
+    ("variable.builtin", "variable.language"),
+    ("variable.parameter", "variable.parameter"),
+];
+
+/// The grammar for a code block's language token, as the name it is
+/// cached under, the language and its highlight queries.
+#[cfg(feature = "tree-sitter")]
+fn grammar(lang: &str) -> Option<(&'static str, tree_sitter::Language, Vec<&'static str>)> {
+    use tree_sitter_bash as bash;
+    use tree_sitter_c as c;
+    use tree_sitter_go as go;
+    use tree_sitter_javascript as javascript;
+    use tree_sitter_json as json;
+    use tree_sitter_python as python;
+    use tree_sitter_rust as rust;
+    use tree_sitter_typescript as typescript;
+
+    let js = javascript::HIGHLIGHT_QUERY;
+    let jsx = javascript::JSX_HIGHLIGHT_QUERY;
+    let ts = typescript::HIGHLIGHTS_QUERY;
+    let (name, language, queries) = match lang {
+        "bash" | "sh" | "shell" | "zsh" => ("bash", bash::LANGUAGE, vec![bash::HIGHLIGHT_QUERY]),
+        "c" | "h" => ("c", c::LANGUAGE, vec![c::HIGHLIGHT_QUERY]),
+        "go" | "golang" => ("go", go::LANGUAGE, vec![go::HIGHLIGHTS_QUERY]),
+        "javascript" | "js" | "mjs" | "jsx" => ("javascript", javascript::LANGUAGE, vec![jsx, js]),
+        "json" => ("json", json::LANGUAGE, vec![json::HIGHLIGHTS_QUERY]),
+        "python" | "py" => ("python", python::LANGUAGE, vec![python::HIGHLIGHTS_QUERY]),
+        "rust" | "rs" => ("rust", rust::LANGUAGE, vec![rust::HIGHLIGHTS_QUERY]),
+        "typescript" | "ts" => ("typescript", typescript::LANGUAGE_TYPESCRIPT, vec![ts, js]),
+        "tsx" => ("tsx", typescript::LANGUAGE_TSX, vec![ts, jsx, js]),
+        _ => return None,
+    };
+    Some((name, language.into(), queries))
+}
+
+#[cfg(feature = "tree-sitter")]
+struct Grammar {
+    language: tree_sitter::Language,
+    query: tree_sitter::Query,
+}
+
+#[cfg(feature = "tree-sitter")]
+struct TreeSitterHighlighter {
+    parser: tree_sitter::Parser,
+    /// Grammars by name, once their queries are compiled; `None` if they
+    /// failed to.
+    grammars: std::collections::HashMap<&'static str, Option<Grammar>>,
+    theme: syntect::highlighting::Theme,
+}
+
+#[cfg(feature = "tree-sitter")]
+impl TreeSitterHighlighter {
+    fn new(theme: &Theme) -> Self {
+        Self {
+            parser: tree_sitter::Parser::new(),
+            grammars: std::collections::HashMap::new(),
+            theme: theme.syntect_theme(),
+        }
+    }
+}
+
+/// The theme's style for a capture name, through [`CAPTURE_SCOPES`].
+#[cfg(feature = "tree-sitter")]
+fn capture_style(highlighter: &syntect::highlighting::Highlighter, name: &str) -> Option<Style> {
+    let mut name = name;
+    loop {
+        if let Some(&(_, scope)) = CAPTURE_SCOPES.iter().find(|&&(n, _)| n == name) {
+            let scope = syntect::parsing::Scope::new(scope).ok()?;
+            return Some(to_style(highlighter.style_for_stack(&[scope])));
+        }
+        name = &name[..name.rfind('.')?];
+    }
+}
+
+#[cfg(feature = "tree-sitter")]
+impl Highlighter for TreeSitterHighlighter {
+    fn set_theme(&mut self, theme: &Theme) {
+        self.theme = theme.syntect_theme();
+    }
+
+    fn highlight(&mut self, lang: &str, lines: &[&str]) -> Option<HighlightedLines> {
+        use std::cmp::Reverse;
+        use tree_sitter::StreamingIterator;
+
+        let (name, language, queries) = grammar(lang)?;
+        let grammar = self
+            .grammars
+            .entry(name)
+            .or_insert_with(|| {
+                let query = tree_sitter::Query::new(&language, &queries.concat()).ok()?;
+                Some(Grammar { language, query })
+            })
+            .as_ref()?;
+        self.parser.set_language(&grammar.language).ok()?;
+        let code = lines.join("\n");
+        let tree = self.parser.parse(&code, None)?;
+
+        let highlighter = syntect::highlighting::Highlighter::new(&self.theme);
+        let default = to_style(highlighter.get_default());
+        let styles: Vec<Option<Style>> = grammar
+            .query
+            .capture_names()
+            .iter()
+            .map(|name| capture_style(&highlighter, name))
+            .collect();
+
+        let mut captures = Vec::new();
+        let mut cursor = tree_sitter::QueryCursor::new();
+        let mut matches = cursor.captures(&grammar.query, tree.root_node(), code.as_bytes());
+        while let Some((m, i)) = matches.next() {
+            let capture = m.captures[*i];
+            if let Some(style) = styles[capture.index as usize] {
+                captures.push((capture.node.byte_range(), m.pattern_index, style));
+            }
+        }
+        // A node takes the style of the first pattern that matches it, and
+        // is painted over the nodes around it
+        captures.sort_by_key(|(range, pattern, _)| (Reverse(range.len()), range.start, *pattern));
+        captures.dedup_by_key(|(range, ..)| range.clone());
+        let mut byte_styles = vec![default; code.len()];
+        for (range, _, style) in captures {
+            byte_styles[range].fill(style);
+        }
+
+        let mut offset = 0;
+        let lines = lines.iter().map(|line| {
+            let mut pieces: Vec<(Style, String)> = Vec::new();
+            for (i, c) in line.char_indices() {
+                let style = byte_styles[offset + i];
+                match pieces.last_mut() {
+                    Some((last, text)) if *last == style => text.push(c),
+                    _ => pieces.push((style, c.to_string())),
+                }
+            }
+            offset += line.len() + 1;
+            pieces
+        });
+        Some(lines.collect())
+    }
+
+    fn knows(&self, lang: &str) -> bool {
+        grammar(lang).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn guesses_languages_of_pasted_snippets() {
+        let all = |_: &str| true;
+        assert_eq!(
+            guess_language("#!/usr/bin/env python3\nx = 1", all),
+            Some("python")
+        );
+        assert_eq!(
+            guess_language("{\n  \"name\": \"ratride\"\n}", all),
+            Some("json")
+        );
+        let rust = "fn main() {\n    let mut x = 1;\n    println!(\"{x}\");\n}";
+        assert_eq!(guess_language(rust, all), Some("rust"));
+        let python = "class A:\n    def __init__(self):\n        self.x = None";
+        assert_eq!(guess_language(python, all), Some("python"));
+        let go = "func main() {\n\tx := 1\n\tfmt.Println(x)\n}";
+        assert_eq!(guess_language(go, all), Some("go"));
+        assert_eq!(guess_language("hello world", all), None);
+
+        // Only languages the highlighter knows are guessed
+        let sql = "SELECT name FROM users WHERE id = 1";
+        assert_eq!(guess_language(sql, all), Some("sql"));
+        assert_eq!(guess_language(sql, |lang| lang != "sql"), None);
+        assert_eq!(guess_language("#!/bin/sh\necho hi", |_| false), None);
+        assert!(!highlighter(HighlighterKind::None, &Theme::default()).knows("rust"));
+        #[cfg(feature = "tree-sitter")]
+        {
+            let tree_sitter = highlighter(HighlighterKind::TreeSitter, &Theme::default());
+            assert!(tree_sitter.knows("rust") && !tree_sitter.knows("sql"));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "tree-sitter")]
+    fn tree_sitter_queries_compile_and_color_keywords() {
+        let mut highlighter = TreeSitterHighlighter::new(&Theme::default());
+        for lang in [
+            "bash", "c", "go", "js", "json", "python", "rust", "ts", "tsx",
+        ] {
+            assert!(highlighter.highlight(lang, &["x"]).is_some(), "{lang}");
+        }
+        assert!(highlighter.highlight("cobol", &["x"]).is_none());
+
+        let lines = highlighter
+            .highlight("rust", &["fn main() {", "    let x = 1; // one", "}"])
+            .unwrap();
+        let style_of = |line: usize, text: &str| {
+            lines[line]
+                .iter()
+                .find(|(_, t)| t == text)
+                .map(|&(style, _)| style)
+        };
+        let comment = style_of(1, "// one").unwrap();
+        assert_ne!(style_of(0, "fn"), None);
+        assert_ne!(style_of(0, "fn"), Some(comment));
+        assert_eq!(
+            lines[1].iter().map(|(_, t)| t.as_str()).collect::<String>(),
+            "    let x = 1; // one"
+        );
+    }
+}
@@ -1,9 +1,48 @@
+pub mod action;
+#[cfg(feature = "effects")]
+pub mod bench;
+pub mod bigtext;
+pub mod cache;
+#[cfg(feature = "terminal")]
+pub mod chapters;
 pub mod color;
+#[cfg(feature = "terminal")]
+pub mod control;
+#[cfg(feature = "terminal")]
+pub mod crossfade;
+pub mod deck;
+#[cfg(feature = "terminal")]
+pub mod dither;
 pub mod export;
+#[cfg(feature = "figlet")]
 pub mod figlet;
+pub mod generate;
+pub mod highlight;
 pub mod markdown;
+pub mod math;
+#[cfg(feature = "terminal")]
+pub mod meta;
+#[cfg(feature = "terminal")]
+pub mod pack;
+#[cfg(feature = "terminal")]
+pub mod poll;
+pub mod print;
+#[cfg(feature = "terminal")]
+pub mod recovery;
+#[cfg(feature = "terminal")]
+pub mod remote;
 pub mod render;
 #[cfg(feature = "terminal")]
 pub mod serve;
+#[cfg(feature = "terminal")]
+pub mod serve_term;
+#[cfg(feature = "terminal")]
+pub mod session;
 pub mod theme;
+pub mod timer;
+#[cfg(feature = "terminal")]
+pub mod tmux;
+#[cfg(feature = "effects")]
 pub mod transition;
+#[cfg(feature = "terminal")]
+pub mod watch;
@@ -1,15 +1,22 @@
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::{Hash, Hasher};
 use std::io::{self, Write};
-use std::path::Path;
+use std::net::{IpAddr, TcpListener};
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
-use std::time::Instant;
+use std::sync::{LazyLock, Mutex, mpsc};
+use std::time::{Duration as StdDuration, Instant};
 
-use clap::Parser;
+use clap::{Args, Parser, Subcommand};
+use notify::Watcher;
 
 use base64::{Engine, engine::general_purpose::STANDARD};
 use crossterm::cursor::MoveTo;
 use crossterm::event::{
-    self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, MouseEventKind,
+    self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind,
+    KeyModifiers, MouseEventKind,
 };
 use ratatui::{
     DefaultTerminal, Frame,
@@ -17,15 +24,108 @@ use ratatui::{
     layout::{Constraint, Layout, Rect},
     widgets::StatefulWidget,
 };
-use ratatui_image::{StatefulImage, picker::Picker, protocol::StatefulProtocol};
-use ratride::markdown::{Frontmatter, Slide, parse_frontmatter, parse_slides};
-use ratride::render::{self, ImagePlacement};
+use ratatui_image::picker::{Picker, ProtocolType};
+use ratatui_image::{Resize, StatefulImage, protocol::StatefulProtocol};
+use ratride::action::{Action, Blank, Keymap};
+use ratride::bench;
+use ratride::chapters::{self, ChapterFormat, TimingLog};
+use ratride::control::{self, Control};
+use ratride::crossfade::{blend, fit_pair};
+use ratride::deck::DeckState;
+use ratride::dither::DitheredImage;
+use ratride::generate::Generator;
+use ratride::markdown::{
+    BigHeadings, DEFAULT_CELL_ASPECT, Dither, DitherColors, Frontmatter, LogoCorner, ParseWarning,
+    Slide, first_changed_slide, parse_dither, parse_dither_colors, parse_frontmatter, parse_slides,
+};
+use ratride::math;
+use ratride::meta;
+use ratride::poll::PollServer;
+use ratride::recovery::{self, Recovery};
+use ratride::render::{self, ActiveColumn, BigTextPlacement, ImagePlacement};
+use ratride::serve_term::Broadcast;
+use ratride::session::{self, Input, InputLog, Replay};
 use ratride::theme::{self, Theme};
+use ratride::timer::Timer;
+use ratride::transition::TransitionRegistry;
+use ratride::watch::{WatchChanges, WatchSet};
 use tachyonfx::{Duration, Effect, EffectRenderer};
 
 const FRAME_DURATION: std::time::Duration = std::time::Duration::from_millis(16); // ~60fps
 const LINE_DUR_MS: f32 = 400.0; // how long each line's animation takes
 const STAGGER_MS: f32 = 30.0; // delay before next line starts
+/// Image crossfades are drawn in steps: every blended frame is re-encoded for the image protocol.
+const CROSSFADE_STEPS: u32 = 4;
+/// Cell size in pixels crossfade frames are built for when the image
+/// backend doesn't know the font's (iTerm2).
+const FALLBACK_FONT_SIZE: (u16, u16) = (10, 20);
+/// Largest magnification of a zoomed image.
+const MAX_IMAGE_ZOOM: u32 = 8;
+/// Terminal size (columns, rows) below which slides aren't drawn, unless
+/// the frontmatter sets `min_size`.
+const DEFAULT_MIN_SIZE: (u16, u16) = (24, 8);
+
+/// Full terminal area as reported by crossterm.
+fn terminal_area() -> Rect {
+    let (w, h) = crossterm::terminal::size().unwrap_or((80, 24));
+    Rect::new(0, 0, w, h)
+}
+
+/// Letterbox `area` to the frontmatter `aspect` ratio, if one is set.
+fn slide_area(area: Rect, aspect: Option<f64>, cell_aspect: f64) -> Rect {
+    match aspect {
+        Some(aspect) => render::letterbox(area, aspect, 1.0 / cell_aspect),
+        None => area,
+    }
+}
+
+/// Width/height ratio of a terminal cell: the frontmatter's `cell_aspect`,
+/// else the font size the image picker measured, else the usual 1:2.
+fn cell_aspect(frontmatter: &Frontmatter, image_backend: &ImageBackend) -> f64 {
+    let measured = font_size(image_backend)
+        .map(|(w, h)| w as f64 / h as f64)
+        .filter(|ratio| ratio.is_finite() && *ratio > 0.0);
+    frontmatter
+        .cell_aspect
+        .or(measured)
+        .unwrap_or(DEFAULT_CELL_ASPECT)
+}
+
+/// Pixel size of a terminal cell, as the image picker measured it.
+fn font_size(image_backend: &ImageBackend) -> Option<(u16, u16)> {
+    let font_size = match image_backend {
+        ImageBackend::RatatuiImage {
+            picker: Some(picker),
+            ..
+        } => Some(picker.font_size()),
+        ImageBackend::Dithered { font_size, .. } => Some(*font_size),
+        _ => None,
+    };
+    font_size.filter(|&(w, h)| w > 0 && h > 0)
+}
+
+/// Name a key press the way `Action::from_key` expects.
+fn key_name(key: KeyEvent) -> Option<String> {
+    let name = match key.code {
+        KeyCode::Char(c) if key.modifiers.contains(KeyModifiers::CONTROL) => format!("Ctrl+{c}"),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::Left => "ArrowLeft".into(),
+        KeyCode::Right => "ArrowRight".into(),
+        KeyCode::Up => "ArrowUp".into(),
+        KeyCode::Down => "ArrowDown".into(),
+        KeyCode::Esc => "Escape".into(),
+        KeyCode::Enter => "Enter".into(),
+        KeyCode::Tab => "Tab".into(),
+        KeyCode::Backspace => "Backspace".into(),
+        KeyCode::PageUp => "PageUp".into(),
+        KeyCode::PageDown => "PageDown".into(),
+        KeyCode::Home => "Home".into(),
+        KeyCode::End => "End".into(),
+        KeyCode::F(n) => format!("F{n}"),
+        _ => return None,
+    };
+    Some(name)
+}
 
 /// Detect if the terminal supports iTerm2 inline image protocol.
 fn is_iterm2() -> bool {
@@ -42,6 +142,215 @@ fn is_iterm2() -> bool {
     false
 }
 
+/// Resolve `big_headings: auto` to a protocol the current terminal supports.
+fn detect_big_headings() -> BigHeadings {
+    let env = |key: &str| std::env::var(key).ok();
+    if env("KITTY_WINDOW_ID").is_some() || env("TERM").is_some_and(|t| t == "xterm-kitty") {
+        return BigHeadings::Kitty;
+    }
+    if env("XTERM_VERSION").is_some() || env("KONSOLE_VERSION").is_some() {
+        return BigHeadings::Dec;
+    }
+    BigHeadings::Off
+}
+
+/// SGR escape sequence for the RGB colors and bold modifier of `style`.
+fn sgr(style: ratatui::style::Style) -> String {
+    use ratatui::style::{Color, Modifier};
+    let mut out = String::from("\x1b[0m");
+    if style.add_modifier.contains(Modifier::BOLD) {
+        out.push_str("\x1b[1m");
+    }
+    if let Some(Color::Rgb(r, g, b)) = style.fg {
+        out.push_str(&format!("\x1b[38;2;{r};{g};{b}m"));
+    }
+    if let Some(Color::Rgb(r, g, b)) = style.bg {
+        out.push_str(&format!("\x1b[48;2;{r};{g};{b}m"));
+    }
+    out
+}
+
+type FigletKey = (String, Option<String>, Option<String>);
+
+/// Most FIGlet headings [`FIGLET_CACHE`] keeps; the least recently used go first.
+const FIGLET_CACHE_SIZE: usize = 256;
+
+/// FIGlet art by (text, font, color), with when each was last used.
+#[derive(Default)]
+struct FigletCache {
+    entries: HashMap<FigletKey, (String, u64)>,
+    uses: u64,
+}
+
+/// Shared by every slide in the process so `--watch` reloads and repeated
+/// headings don't spawn `figlet` again.
+static FIGLET_CACHE: LazyLock<Mutex<FigletCache>> = LazyLock::new(Default::default);
+
+/// Render FIGlet art, reusing earlier results for the same text, font, and
+/// color. Failures aren't kept, so a font installed meanwhile is picked up.
+fn render_figlet(text: &str, font: Option<&str>, color: Option<&str>) -> Result<String, String> {
+    let key = (
+        text.to_string(),
+        font.map(str::to_string),
+        color.map(str::to_string),
+    );
+    if let Ok(mut cache) = FIGLET_CACHE.lock() {
+        cache.uses += 1;
+        let uses = cache.uses;
+        if let Some((art, used)) = cache.entries.get_mut(&key) {
+            *used = uses;
+            return Ok(art.clone());
+        }
+    }
+    let art = render_figlet_uncached(text, font, color)?;
+    if let Ok(mut cache) = FIGLET_CACHE.lock() {
+        if cache.entries.len() >= FIGLET_CACHE_SIZE {
+            let oldest = cache.entries.iter().min_by_key(|(_, (_, used))| *used);
+            if let Some(oldest) = oldest.map(|(key, _)| key.clone()) {
+                cache.entries.remove(&oldest);
+            }
+        }
+        let uses = cache.uses;
+        cache.entries.insert(key, (art.clone(), uses));
+    }
+    Ok(art)
+}
+
+/// [`render_figlet`] for a deck in `base_dir`: a `.flf` font file is looked
+/// for next to the deck first, as images are, so packed decks find theirs.
+fn deck_figlet(
+    base_dir: &Path,
+) -> impl Fn(&str, Option<&str>, Option<&str>) -> Result<String, String> + use<> {
+    let base_dir = base_dir.to_path_buf();
+    move |text, font, color| {
+        let local = font
+            .filter(|font| ratride::export::is_font_file(font))
+            .map(|font| base_dir.join(font))
+            .filter(|path| path.is_file());
+        match local {
+            Some(path) => render_figlet(text, path.to_str(), color),
+            None => render_figlet(text, font, color),
+        }
+    }
+}
+
+/// Render FIGlet art via figrat (with color), the bundled fonts, or the
+/// `figlet` command, which says why a font it can't find failed.
+fn render_figlet_uncached(
+    text: &str,
+    font: Option<&str>,
+    color: Option<&str>,
+) -> Result<String, String> {
+    if let Some(color_spec) = color {
+        // Use figrat Rust API with color
+        if let Some(art) = ratride::figlet::render_figrat(text, font, color_spec) {
+            return Ok(art);
+        }
+    }
+    // Try built-in fonts first
+    if let Some(result) = ratride::figlet::render_builtin(text, font) {
+        return Ok(result);
+    }
+    // Fall back to external figlet command
+    let mut cmd = Command::new("figlet");
+    if let Some(font) = font {
+        cmd.args(["-f", font]);
+    }
+    let out = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .and_then(|mut child| {
+            if let Some(mut stdin) = child.stdin.take() {
+                let _ = stdin.write_all(text.as_bytes());
+            }
+            child.wait_with_output()
+        })
+        .map_err(|e| format!("not a bundled font, and `figlet` couldn't run: {e}"))?;
+    if !out.status.success() {
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        return Err(match stderr.trim() {
+            "" => format!("`figlet` failed ({})", out.status),
+            message => message.to_string(),
+        });
+    }
+    String::from_utf8(out.stdout).map_err(|e| format!("`figlet` wrote invalid UTF-8: {e}"))
+}
+
+/// Theme from the CLI flag, else the frontmatter, else the default. Either
+/// may name a `.toml` theme file: the flag's relative to the working
+/// directory, the frontmatter's relative to the deck in `base_dir`.
+fn resolve_theme(
+    name: Option<&str>,
+    frontmatter: &Frontmatter,
+    base_dir: &Path,
+) -> io::Result<Theme> {
+    match theme_source(name, frontmatter, base_dir) {
+        Some(ThemeSource::File(path)) => Theme::load(&path),
+        Some(ThemeSource::Builtin(theme)) => Ok(theme),
+        None => Ok(Theme::default()),
+    }
+}
+
+/// Where [`resolve_theme`] takes the theme from.
+enum ThemeSource {
+    File(PathBuf),
+    Builtin(Theme),
+}
+
+/// The theme file or built-in theme [`resolve_theme`] uses, `None` for the
+/// default.
+fn theme_source(
+    name: Option<&str>,
+    frontmatter: &Frontmatter,
+    base_dir: &Path,
+) -> Option<ThemeSource> {
+    let lookup = |name: &str, dir: &Path| {
+        if theme::is_theme_file(name) {
+            Some(ThemeSource::File(dir.join(name.trim())))
+        } else {
+            theme::theme_from_name(name).map(ThemeSource::Builtin)
+        }
+    };
+    name.and_then(|name| lookup(name, Path::new(".")))
+        .or_else(|| {
+            frontmatter
+                .theme
+                .as_deref()
+                .and_then(|name| lookup(name, base_dir))
+        })
+}
+
+/// Warnings for local images that don't exist relative to `base_dir`.
+fn missing_image_warnings(slides: &[Slide], base_dir: &Path) -> Vec<ParseWarning> {
+    slides
+        .iter()
+        .flat_map(|slide| &slide.images)
+        .flat_map(|img| img.paths().iter().map(move |path| (img, path)))
+        .filter(|(_, path)| {
+            !path.starts_with("http://")
+                && !path.starts_with("https://")
+                && !base_dir.join(path).exists()
+        })
+        .map(|(img, path)| ParseWarning {
+            line: img.source_line,
+            message: format!("missing image `{path}`"),
+        })
+        .collect()
+}
+
+/// Parse `file` and collect every warning, sorted by line.
+fn collect_warnings(file: &str) -> io::Result<Vec<ParseWarning>> {
+    let markdown = std::fs::read_to_string(file)?;
+    let base_dir = Path::new(file).parent().unwrap_or(Path::new("."));
+    let (frontmatter, body) = parse_frontmatter(&markdown);
+    let (slides, mut warnings) = parse_slides(body, &Theme::default(), &frontmatter, None, false);
+    warnings.extend(missing_image_warnings(&slides, base_dir));
+    warnings.sort_by_key(|w| w.line);
+    Ok(warnings)
+}
+
 enum ImageBackend {
     /// Write iTerm2 escape sequences directly to stdout (presenterm-style).
     /// Stores pre-encoded base64 data and decoded images for cropping.
@@ -51,18 +360,553 @@ enum ImageBackend {
     },
     /// Use ratatui-image for Kitty/Sixel/Halfblocks.
     RatatuiImage {
-        states: HashMap<String, StatefulProtocol>,
+        /// Images decoded by the loader, kept to encode at each size they're
+        /// drawn at without reading the file again.
+        decoded: HashMap<String, image::DynamicImage>,
+        /// Protocols by path and drawn size. Each is encoded once per size (for
+        /// kitty, transmitted once and re-placed by id), so an image shown at
+        /// several sizes isn't re-encoded whenever it alternates between them.
+        placed: HashMap<(String, u16, u16), StatefulProtocol>,
+        /// Kept to encode blended frames during image crossfades.
+        picker: Option<Picker>,
+    },
+    /// Half blocks in a reduced palette (`--dither-colors`), used instead of
+    /// ratatui-image's when the terminal has no image protocol.
+    Dithered {
+        images: HashMap<String, DitheredImage>,
+        colors: DitherColors,
+        dither: Dither,
+        /// Cell size the picker assumed, for fitting images like ratatui-image.
+        font_size: (u16, u16),
+    },
+}
+
+/// Images of the current slide fading in over the ones they replaced.
+struct ImageFade {
+    /// Sent to the loader with the fade, to match the sources it sends back.
+    id: u64,
+    /// When the loader's sources arrived and the fade began.
+    start: Option<Instant>,
+    /// (outgoing, incoming) images at the size the incoming one is drawn,
+    /// keyed by incoming path.
+    sources: HashMap<String, (image::RgbaImage, image::RgbaImage)>,
+    /// Step the blended frames were built for.
+    step: u32,
+    /// Blended frames encoded for the image backend, keyed by incoming path.
+    frames: HashMap<String, BlendedImage>,
+}
+
+/// An image frame encoded for the image backend: a crossfade step or a
+/// zoomed image.
+enum BlendedImage {
+    Protocol(Box<StatefulProtocol>),
+    Iterm2(usize, String),
+    Dithered(Box<DitheredImage>),
+}
+
+/// The current slide's images cropped to a region, which the image backend
+/// scales up to fill their place (`+`/`-` to zoom, `H`/`J`/`K`/`L` to pan).
+struct ImageZoom {
+    /// Magnification, a power of two.
+    level: u32,
+    /// Center of the region, as fractions of the image's width and height.
+    center: (f64, f64),
+    /// Decoded images by path, kept to crop again when the region moves.
+    sources: HashMap<String, image::DynamicImage>,
+    /// Zoomed images by slide image path, and the same encoded for the
+    /// image backend.
+    images: HashMap<String, image::DynamicImage>,
+    frames: HashMap<String, BlendedImage>,
+}
+
+impl ImageZoom {
+    /// The region of `img` being zoomed into.
+    fn crop(&self, img: &image::DynamicImage) -> image::DynamicImage {
+        let (w, h) = (img.width() as f64, img.height() as f64);
+        let scale = self.level as f64;
+        let (crop_w, crop_h) = ((w / scale).max(1.0), (h / scale).max(1.0));
+        let x = (self.center.0 * w - crop_w / 2.0).clamp(0.0, w - crop_w);
+        let y = (self.center.1 * h - crop_h / 2.0).clamp(0.0, h - crop_h);
+        img.crop_imm(x as u32, y as u32, crop_w as u32, crop_h as u32)
+    }
+}
+
+/// Encode `img` as PNG for an iTerm2 inline image, returning (byte size, base64).
+fn iterm2_payload(img: &image::DynamicImage) -> io::Result<(usize, String)> {
+    let mut buf = std::io::Cursor::new(Vec::new());
+    img.write_to(&mut buf, image::ImageFormat::Png)
+        .map_err(io::Error::other)?;
+    let bytes = buf.into_inner();
+    Ok((bytes.len(), STANDARD.encode(&bytes)))
+}
+
+/// A slide image read by [`ImageLoader`], or why it couldn't be loaded.
+struct LoadedImage {
+    path: String,
+    result: io::Result<ImageData>,
+    /// Time spent reading and decoding it.
+    took: StdDuration,
+}
+
+enum ImageData {
+    /// File bytes and the decoded image.
+    Decoded(Vec<u8>, image::DynamicImage),
+    /// Same bytes as the image already loaded from this path.
+    SameAs(String),
+}
+
+/// Both sides of each of a fade's crossfades, decoded and scaled by the
+/// [`ImageLoader`] and keyed by incoming path.
+struct FadeSources {
+    id: u64,
+    sources: HashMap<String, (image::RgbaImage, image::RgbaImage)>,
+}
+
+/// Reads and decodes slide images on a background thread so startup doesn't
+/// wait for the whole deck. Images near the current slide are loaded first.
+struct ImageLoader {
+    requests: mpsc::Sender<LoadRequest>,
+    loaded: mpsc::Receiver<LoadedImage>,
+    faded: mpsc::Receiver<FadeSources>,
+    /// Id of the last fade requested.
+    last_fade: u64,
+}
+
+enum LoadRequest {
+    /// Paths to move to the front of the queue.
+    Prioritize(Vec<String>),
+    /// Paths whose files changed, to read again first.
+    Reload(Vec<String>),
+    /// Images to decode for a crossfade, ahead of everything else, and the
+    /// pixel size of a cell they are drawn with.
+    Crossfade {
+        id: u64,
+        crossfades: Vec<render::ImageCrossfade>,
+        font_size: (u16, u16),
     },
 }
 
+impl ImageLoader {
+    /// Start loading `logo` and every image in `slides`, beginning with the
+    /// logo and the images around `page`.
+    fn spawn(slides: &[Slide], page: usize, logo: Option<&str>, base_dir: &Path) -> Self {
+        let mut queue: VecDeque<String> = VecDeque::new();
+        let all = slides.iter().flat_map(|slide| &slide.images);
+        for path in logo
+            .map(str::to_string)
+            .into_iter()
+            .chain(nearby_image_paths(slides, page))
+            .chain(all.flat_map(|img| img.paths().to_vec()))
+        {
+            if !queue.contains(&path) {
+                queue.push_back(path);
+            }
+        }
+        let base_dir = base_dir.to_path_buf();
+        let (requests, requests_rx) = mpsc::channel();
+        let (loaded_tx, loaded) = mpsc::channel();
+        let (faded_tx, faded) = mpsc::channel();
+        std::thread::spawn(move || {
+            // Content hash -> first path decoded with it
+            let mut decoded: HashMap<u64, String> = HashMap::new();
+            let mut pending = Vec::new();
+            loop {
+                pending.extend(requests_rx.try_iter());
+                // With nothing left to load, wait for reloads until the app goes away
+                if queue.is_empty() && pending.is_empty() {
+                    match requests_rx.recv() {
+                        Ok(request) => pending.push(request),
+                        Err(_) => break,
+                    }
+                }
+                // Later requests end up in front of earlier ones
+                for request in pending.drain(..) {
+                    match request {
+                        LoadRequest::Prioritize(paths) => {
+                            for path in paths.into_iter().rev() {
+                                if let Some(i) = queue.iter().position(|p| *p == path) {
+                                    let path = queue.remove(i).unwrap_or(path);
+                                    queue.push_front(path);
+                                }
+                            }
+                        }
+                        LoadRequest::Reload(paths) => {
+                            // Other paths can't share the old content anymore
+                            decoded.retain(|_, original| !paths.contains(original));
+                            for path in paths.into_iter().rev() {
+                                queue.retain(|p| *p != path);
+                                queue.push_front(path);
+                            }
+                        }
+                        LoadRequest::Crossfade {
+                            id,
+                            crossfades,
+                            font_size: (font_w, font_h),
+                        } => {
+                            let load = |path: &str| image::open(base_dir.join(path)).ok();
+                            let sources = crossfades
+                                .into_iter()
+                                .filter_map(|fade| {
+                                    let (cols, rows) = fade.cells;
+                                    let size = (
+                                        u32::from(cols) * u32::from(font_w),
+                                        u32::from(rows) * u32::from(font_h),
+                                    );
+                                    let pair = fit_pair(load(&fade.from)?, load(&fade.to)?, size);
+                                    Some((fade.to, pair))
+                                })
+                                .collect();
+                            let _ = faded_tx.send(FadeSources { id, sources });
+                        }
+                    }
+                }
+                let Some(path) = queue.pop_front() else {
+                    continue;
+                };
+                let start = Instant::now();
+                let result = std::fs::read(base_dir.join(&path)).and_then(|data| {
+                    let mut hasher = DefaultHasher::new();
+                    data.hash(&mut hasher);
+                    let hash = hasher.finish();
+                    if let Some(original) = decoded.get(&hash) {
+                        return Ok(ImageData::SameAs(original.clone()));
+                    }
+                    let img = image::load_from_memory(&data).map_err(io::Error::other)?;
+                    decoded.insert(hash, path.clone());
+                    Ok(ImageData::Decoded(data, img))
+                });
+                let took = start.elapsed();
+                if loaded_tx.send(LoadedImage { path, result, took }).is_err() {
+                    break;
+                }
+            }
+        });
+        Self {
+            requests,
+            loaded,
+            faded,
+            last_fade: 0,
+        }
+    }
+
+    /// Load the images around `page` next.
+    fn prioritize(&self, slides: &[Slide], page: usize) {
+        let _ = self
+            .requests
+            .send(LoadRequest::Prioritize(nearby_image_paths(slides, page)));
+    }
+
+    /// Read `paths` again, before anything else still queued.
+    fn reload(&self, paths: Vec<String>) {
+        let _ = self.requests.send(LoadRequest::Reload(paths));
+    }
+
+    /// Decode both sides of `crossfades` next, returning the id of the
+    /// [`FadeSources`] that will come back.
+    fn crossfade(&mut self, crossfades: Vec<render::ImageCrossfade>, font_size: (u16, u16)) -> u64 {
+        self.last_fade += 1;
+        let _ = self.requests.send(LoadRequest::Crossfade {
+            id: self.last_fade,
+            crossfades,
+            font_size,
+        });
+        self.last_fade
+    }
+}
+
+/// Image paths of slide `page`, then the next and previous slides.
+fn nearby_image_paths(slides: &[Slide], page: usize) -> Vec<String> {
+    [Some(page), page.checked_add(1), page.checked_sub(1)]
+        .into_iter()
+        .flatten()
+        .filter_map(|i| slides.get(i))
+        .flat_map(|slide| slide.images.iter().flat_map(|img| img.paths().to_vec()))
+        .collect()
+}
+
+/// Reloads the deck when its file changes, and images when theirs do (`--watch`).
+struct DeckWatch {
+    events: mpsc::Receiver<notify::Result<notify::Event>>,
+    /// Kept alive for as long as events are wanted.
+    watcher: notify::RecommendedWatcher,
+    /// Directories being watched, canonicalized.
+    dirs: HashSet<PathBuf>,
+    /// The deck, theme file and image files events are checked against.
+    files: WatchSet,
+    /// `--theme` from the command line, for finding the theme file.
+    theme_name: Option<String>,
+    /// Jump to the first changed slide on reload (`--follow-edits`).
+    follow_edits: bool,
+    /// Asks the reader thread to read the deck again.
+    reads: mpsc::Sender<()>,
+    /// The deck as the reader thread read it, off the UI thread since
+    /// `generate` commands and math toolchains may take a while.
+    decks: mpsc::Receiver<io::Result<ReadDeck>>,
+}
+
+/// A deck read again by [`DeckWatch`]'s reader thread.
+struct ReadDeck {
+    frontmatter: Frontmatter,
+    /// `--theme` from the command line, resolved again against the new
+    /// frontmatter.
+    theme: Theme,
+    /// After the frontmatter, with formulas rendered to images.
+    body: String,
+    math_warnings: Vec<ParseWarning>,
+}
+
+impl DeckWatch {
+    fn start(
+        path: &Path,
+        theme_name: Option<&str>,
+        follow_edits: bool,
+        mut generator: Option<Generator>,
+    ) -> io::Result<Self> {
+        let (reads, requests) = mpsc::channel::<()>();
+        let (deck_tx, decks) = mpsc::channel();
+        let deck_path = path.to_path_buf();
+        let theme_name = theme_name.map(str::to_string);
+        let reader_theme_name = theme_name.clone();
+        std::thread::spawn(move || {
+            let base_dir = deck_path.parent().unwrap_or(Path::new("."));
+            while requests.recv().is_ok() {
+                // Saves that came in meanwhile are covered by this read
+                requests.try_iter().for_each(drop);
+                let deck = read_deck(&deck_path, generator.as_mut()).and_then(|markdown| {
+                    let (frontmatter, body) = parse_frontmatter(&markdown);
+                    let theme =
+                        resolve_theme(reader_theme_name.as_deref(), &frontmatter, base_dir)?;
+                    let (body, math_warnings) = render_math(body, &frontmatter, &theme);
+                    Ok(ReadDeck {
+                        body: body.into_owned(),
+                        frontmatter,
+                        theme,
+                        math_warnings,
+                    })
+                });
+                if deck_tx.send(deck).is_err() {
+                    break;
+                }
+            }
+        });
+        let (tx, events) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(tx).map_err(io::Error::other)?;
+        let files = WatchSet::new(path).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{}: no such directory", path.display()),
+            )
+        })?;
+        let dirs = files.dirs();
+        for dir in &dirs {
+            watcher
+                .watch(dir, notify::RecursiveMode::NonRecursive)
+                .map_err(io::Error::other)?;
+        }
+        Ok(Self {
+            events,
+            watcher,
+            dirs,
+            files,
+            theme_name,
+            follow_edits,
+            reads,
+            decks,
+        })
+    }
+
+    /// Watch the deck's theme file and the local images among `images`
+    /// (relative to `base_dir`) instead of the files watched so far, and
+    /// only the directories they are in.
+    fn watch_files<'a>(
+        &mut self,
+        images: impl IntoIterator<Item = &'a str>,
+        frontmatter: &Frontmatter,
+        base_dir: &Path,
+    ) {
+        let theme = match theme_source(self.theme_name.as_deref(), frontmatter, base_dir) {
+            Some(ThemeSource::File(path)) => Some(path),
+            _ => None,
+        };
+        self.files.set_files(theme.as_deref(), images, base_dir);
+        let wanted = self.files.dirs();
+        for dir in self.dirs.difference(&wanted) {
+            let _ = self.watcher.unwatch(dir);
+        }
+        self.dirs.retain(|dir| wanted.contains(dir));
+        for dir in wanted {
+            if !self.dirs.contains(&dir)
+                && self
+                    .watcher
+                    .watch(&dir, notify::RecursiveMode::NonRecursive)
+                    .is_ok()
+            {
+                self.dirs.insert(dir);
+            }
+        }
+    }
+
+    /// What was written since the last call.
+    fn changes(&self) -> WatchChanges {
+        let events = self.events.try_iter().flatten();
+        let written = events.filter(|event| event.kind.is_modify() || event.kind.is_create());
+        let paths: Vec<PathBuf> = written.flat_map(|event| event.paths).collect();
+        self.files.changes(paths.iter().map(PathBuf::as_path))
+    }
+}
+
+/// Record decoded pixel sizes on the slide images (for centered layout) and
+/// shrink the placeholder lines of images constrained by `max_width_percent`.
+fn apply_image_dims(
+    slides: &mut [Slide],
+    dims: &HashMap<String, (u32, u32)>,
+    aspect: Option<f64>,
+    cell_aspect: f64,
+) {
+    for slide in slides.iter_mut() {
+        slide.cell_aspect = cell_aspect;
+        for img in &mut slide.images {
+            if let Some(&(w, h)) = dims.get(&img.path) {
+                img.pixel_width = w;
+                img.pixel_height = h;
+            }
+        }
+    }
+
+    let screen_w = slide_area(terminal_area(), aspect, cell_aspect).width;
+    let content_w = screen_w.saturating_sub(4) as f64; // approx content area width
+    for slide in slides.iter_mut() {
+        for i in 0..slide.images.len() {
+            let img = &mut slide.images[i];
+            if let Some(pct) = img.max_width_percent {
+                if let Some(ratio) = img.tallest_ratio(|path| dims.get(path).copied()) {
+                    let max_w = content_w * pct.clamp(0.0, 1.0);
+                    let new_h = (max_w * ratio * cell_aspect).ceil() as u16;
+                    let new_h = new_h.max(1).min(img.height);
+                    let to_remove = (img.height - new_h) as usize;
+                    if to_remove > 0 {
+                        let line = img.line_index;
+                        img.height = new_h;
+                        let start = line + new_h as usize;
+                        slide.content.lines.drain(start..start + to_remove);
+                        slide.shift_lines(line, -(to_remove as i32));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Unattended looping playback (`--kiosk`), on the clock `App::timer` runs on.
+struct Kiosk {
+    /// How long each slide stays up, or with `wpm` the least it does.
+    advance_ms: f64,
+    /// Reading speed to time each slide by its word count (`--wpm`).
+    wpm: Option<f64>,
+    /// Length of the "starting in" splash before playback begins.
+    countdown_ms: f64,
+    /// When the splash ends; `None` once playback has begun.
+    starts_at: Option<f64>,
+    /// When to move on to the next slide.
+    next_at: f64,
+}
+
+impl Kiosk {
+    fn new(advance_secs: f64, countdown_secs: f64, wpm: Option<f64>) -> Self {
+        Self {
+            advance_ms: advance_secs.max(1.0) * 1000.0,
+            wpm: wpm.map(|wpm| wpm.max(1.0)),
+            countdown_ms: countdown_secs.max(0.0) * 1000.0,
+            starts_at: None,
+            next_at: 0.0,
+        }
+    }
+
+    /// Show the splash from `now`, then start playback on a slide of `words`.
+    fn start(&mut self, now: f64, words: usize) {
+        self.starts_at = (self.countdown_ms > 0.0).then_some(now + self.countdown_ms);
+        self.schedule(now + self.countdown_ms, words);
+    }
+
+    /// Move on from a slide of `words` shown at `now` once it has been read.
+    fn schedule(&mut self, now: f64, words: usize) {
+        let reading_ms = self.wpm.map_or(0.0, |wpm| words as f64 / wpm * 60_000.0);
+        self.next_at = now + self.advance_ms.max(reading_ms);
+    }
+
+    /// Time left on the splash, `None` once playback has begun.
+    fn countdown_remaining(&self, now: f64) -> Option<f64> {
+        self.starts_at.map(|at| at - now).filter(|ms| *ms > 0.0)
+    }
+}
+
+/// Startup timings collected with `--profile-startup`, printed to stderr on exit.
+struct StartupProfile {
+    steps: Vec<(&'static str, StdDuration)>,
+    /// When the last step ended.
+    mark: Instant,
+    /// Images read and decoded on the loader thread, and the time that took.
+    images: usize,
+    image_decode: StdDuration,
+    /// Time spent drawing FIGlet headings before the first frame.
+    figlet: StdDuration,
+}
+
+impl StartupProfile {
+    fn new() -> Self {
+        Self {
+            steps: Vec::new(),
+            mark: Instant::now(),
+            images: 0,
+            image_decode: StdDuration::ZERO,
+            figlet: StdDuration::ZERO,
+        }
+    }
+
+    /// Record the time since the previous step as `name`.
+    fn step(&mut self, name: &'static str) {
+        self.steps.push((name, self.mark.elapsed()));
+        self.mark = Instant::now();
+    }
+
+    /// Move `part` of the last step's time into a step of its own.
+    fn split_last(&mut self, name: &'static str, part: StdDuration) {
+        if let Some((_, last)) = self.steps.last_mut() {
+            *last = last.saturating_sub(part);
+        }
+        self.steps.push((name, part));
+    }
+
+    fn report(&self) -> String {
+        let ms = |d: StdDuration| d.as_secs_f64() * 1000.0;
+        let mut out = String::from("startup profile:\n");
+        for (name, took) in &self.steps {
+            out.push_str(&format!("  {name:<14} {:>9.3} ms\n", ms(*took)));
+        }
+        let total: StdDuration = self.steps.iter().map(|(_, took)| *took).sum();
+        out.push_str(&format!("  {:<14} {:>9.3} ms\n", "total", ms(total)));
+        out.push_str(&format!(
+            "  {:<14} {:>9.3} ms ({} images, in the background)\n",
+            "image decode",
+            ms(self.image_decode),
+            self.images
+        ));
+        out
+    }
+}
+
 struct App {
     slides: Vec<Slide>,
-    current_page: usize,
-    scroll_offsets: Vec<u16>,
+    /// Wrapped slide content, kept between frames until the slides change.
+    layouts: render::LayoutCache,
+    /// Current page and per-slide scroll offsets.
+    deck: DeckState,
     quit: bool,
     image_backend: ImageBackend,
     /// Active transition effect.
     effect: Option<Effect>,
+    /// Transitions slides can name, built once.
+    transitions: TransitionRegistry,
     last_frame: Instant,
     /// Deferred image draws (collected during draw, flushed after ratatui render).
     pending_images: Vec<ImagePlacement>,
@@ -76,139 +920,172 @@ struct App {
     prev_buffer: Option<Buffer>,
     /// Set when iTerm2 image areas need clearing on next frame.
     needs_clear: bool,
+    /// Frontmatter `aspect` ratio the slide area is letterboxed to.
+    aspect: Option<f64>,
+    /// Width/height ratio of a terminal cell, see [`cell_aspect`].
+    cell_aspect: f64,
+    /// Resolved protocol for double-size headings.
+    big_headings: BigHeadings,
+    /// Double-size headings collected during draw, written after ratatui render.
+    pending_big_text: Vec<BigTextPlacement>,
+    /// Double-size headings currently on screen.
+    drawn_big_text: Vec<BigTextPlacement>,
+    /// Parse warnings shown at startup until the first key press.
+    warnings: Vec<ParseWarning>,
+    /// Directory image paths are resolved against.
+    base_dir: PathBuf,
+    /// Set by Ctrl-Z; the run loop suspends the process on the next frame.
+    suspend: bool,
+    /// Remote viewers following along (`ratride serve-term`).
+    broadcast: Option<Broadcast>,
+    /// Highlight a fixed row while scrolling long slides (toggled with `r`).
+    reading_ruler: bool,
+    /// Show a dimmed preview of the next slide beside the current one (toggled with `n`).
+    peek: bool,
+    /// Show the next slide and speaker notes beside the current one
+    /// (`--presenter`, toggled with `s`).
+    presenter_view: bool,
+    /// Show the speaker notes in a dimmed right margin (`--notes-margin`,
+    /// toggled with `N`); the presenter view replaces it.
+    notes_margin: bool,
+    /// When key hints were last shown in the status bar (startup or `?`).
+    hints_shown_at: Instant,
+    /// Focused column on two-column slides; the other is dimmed (cycled with `c`).
+    active_column: Option<ActiveColumn>,
+    /// Image placements of the slide being left, matched against the next
+    /// slide's on its first frame to decide whether to crossfade.
+    crossfade_from: Option<Vec<ImagePlacement>>,
+    /// When the slide's `animate: words` builds started, once its
+    /// transition ended.
+    words_shown_at: Option<f64>,
+    image_crossfade: Option<ImageFade>,
+    image_zoom: Option<ImageZoom>,
+    /// Slide markdown and frontmatter, kept to re-style the deck when cycling themes.
+    markdown: String,
+    frontmatter: Frontmatter,
+    /// Decoded image sizes, re-applied to re-parsed slides.
+    image_dims: HashMap<String, (u32, u32)>,
+    image_loader: ImageLoader,
+    /// Images with the same content as one loaded under another path share
+    /// its backend data, keyed by the loaded path.
+    image_aliases: HashMap<String, String>,
+    /// Show the deck outline in a left sidebar (toggled with `o`).
+    outline: bool,
+    /// Require a second `q` to quit (frontmatter `confirm_quit`).
+    confirm_quit: bool,
+    /// When the first `q` was pressed, while waiting for the second.
+    quit_requested_at: Option<Instant>,
+    /// Message for the status bar, and when it was posted.
+    notice: Option<(String, Instant)>,
+    /// Why images failed to read or decode, keyed by path.
+    image_errors: HashMap<String, String>,
+    /// Draw a box with the error where a failed image would be (frontmatter
+    /// `image_errors: false` leaves the space blank).
+    show_image_errors: bool,
+    /// Position of the current theme in `theme::THEME_NAMES` (cycled with `T`).
+    theme_index: Option<usize>,
+    /// Smallest terminal the slides are drawn in (frontmatter `min_size`).
+    min_size: (u16, u16),
+    /// Slides were parsed without figlet headings, for a compact slide area.
+    compact: bool,
+    /// Deck file being watched for edits (`--watch`).
+    watch: Option<DeckWatch>,
+    /// Image drawn in `logo_position` on every slide (frontmatter `logo`).
+    logo: Option<String>,
+    logo_position: LogoCorner,
+    /// Start of the clock `timer` runs on.
+    clock: Instant,
+    /// Elapsed talk time (paused with `t`, reset with `R`) and break countdown (`B`).
+    timer: Timer,
+    /// Break length in minutes (frontmatter `break_minutes`).
+    break_minutes: f64,
+    /// Set while the slide is hidden; any key brings it back.
+    blank: Blank,
+    /// Startup timings (`--profile-startup`); the first frame is recorded in `run`.
+    profile: Option<StartupProfile>,
+    /// Advance and loop on a timer (`--kiosk`).
+    kiosk: Option<Kiosk>,
+    /// Log of slide changes for chapter markers (`--timings`).
+    timings: Option<TimingLog>,
+    /// Log of the presenter's actions, for `--replay` (`--record-input`).
+    input_log: Option<InputLog>,
+    /// Actions of an earlier talk, taken again on their schedule (`--replay`).
+    replay: Option<Replay>,
+    /// External controllers' port (`--control`).
+    control: Option<Control>,
+    /// Voting page for the audience (`--poll-port`).
+    polls: Option<PollServer>,
+    /// Slide whose poll voting is open, and the votes last drawn on it.
+    shown_votes: Option<(usize, Vec<usize>)>,
+    /// Key bindings, with the user's overrides (`--keys`).
+    keymap: Keymap,
+    /// Deck file whose position is saved for [`recovery`] while presenting.
+    recovery_deck: Option<PathBuf>,
+    /// State last written to the recovery file, and when.
+    recovery_saved: Option<(Recovery, f64)>,
+    /// State from an earlier run, offered in the status bar until a key is pressed.
+    resume_offer: Option<Recovery>,
 }
 
 impl App {
-    fn new(markdown: &str, base_dir: &Path, theme: Theme, frontmatter: &Frontmatter) -> Self {
-        let figlet_fn = |text: &str, font: Option<&str>, color: Option<&str>| -> Option<String> {
-            if let Some(color_spec) = color {
-                // Use figrat Rust API with color
-                if let Some(art) = ratride::figlet::render_figrat(text, font, color_spec) {
-                    return Some(art);
-                }
-            }
-            // Try built-in fonts first
-            if let Some(result) = ratride::figlet::render_builtin(text, font) {
-                return Some(result);
-            }
-            // Fall back to external figlet command
-            let mut cmd = Command::new("figlet");
-            if let Some(font) = font {
-                cmd.args(["-f", font]);
-            }
-            cmd.stdin(Stdio::piped())
-                .stdout(Stdio::piped())
-                .stderr(Stdio::null())
-                .spawn()
-                .and_then(|mut child| {
-                    if let Some(mut stdin) = child.stdin.take() {
-                        let _ = stdin.write_all(text.as_bytes());
-                    }
-                    child.wait_with_output()
-                })
-                .ok()
-                .filter(|out| out.status.success())
-                .and_then(|out| String::from_utf8(out.stdout).ok())
-        };
-        let mut slides = parse_slides(markdown, &theme, frontmatter, Some(&figlet_fn), false);
-        let len = slides.len().max(1);
-
-        // Collect image pixel dimensions for centering.
-        let mut dims: HashMap<String, (u32, u32)> = HashMap::new();
+    fn new(
+        markdown: &str,
+        base_dir: &Path,
+        theme: Theme,
+        frontmatter: &Frontmatter,
+        mut profile: Option<StartupProfile>,
+    ) -> Self {
+        // FIGlet headings are drawn as their slides are first shown
+        let (slides, mut warnings) = parse_slides(markdown, &theme, frontmatter, None, false);
+        if let Some(profile) = &mut profile {
+            profile.step("parse");
+        }
+        warnings.extend(missing_image_warnings(&slides, base_dir));
+        warnings.sort_by_key(|w| w.line);
+        let deck = DeckState::new(slides.len(), frontmatter.remember_scroll.unwrap_or(false));
 
         let image_backend = if is_iterm2() {
-            let mut images: HashMap<String, (usize, String)> = HashMap::new();
-            let mut dyn_images: HashMap<String, image::DynamicImage> = HashMap::new();
-            for slide in &slides {
-                for img in &slide.images {
-                    if images.contains_key(&img.path) {
-                        continue;
-                    }
-                    let img_path = base_dir.join(&img.path);
-                    if let Ok(data) = std::fs::read(&img_path) {
-                        if let Ok((w, h)) = image::image_dimensions(&img_path) {
-                            dims.insert(img.path.clone(), (w, h));
-                        }
-                        // Decode image for potential cropping during scroll.
-                        if let Ok(dyn_img) = image::load_from_memory(&data) {
-                            dyn_images.insert(img.path.clone(), dyn_img);
-                        }
-                        let size = data.len();
-                        let b64 = STANDARD.encode(&data);
-                        images.insert(img.path.clone(), (size, b64));
-                    }
-                }
+            ImageBackend::Iterm2 {
+                images: HashMap::new(),
+                dyn_images: HashMap::new(),
             }
-            ImageBackend::Iterm2 { images, dyn_images }
         } else {
-            let mut states: HashMap<String, StatefulProtocol> = HashMap::new();
             let picker = Picker::from_query_stdio().ok();
-            if let Some(picker) = picker {
-                for slide in &slides {
-                    for img in &slide.images {
-                        if states.contains_key(&img.path) {
-                            continue;
-                        }
-                        let img_path = base_dir.join(&img.path);
-                        if let Ok(dyn_img) = image::ImageReader::open(&img_path).and_then(|r| {
-                            r.decode()
-                                .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
-                        }) {
-                            dims.insert(img.path.clone(), (dyn_img.width(), dyn_img.height()));
-                            let protocol = picker.new_resize_protocol(dyn_img);
-                            states.insert(img.path.clone(), protocol);
-                        }
-                    }
-                }
+            let halfblocks = picker
+                .as_ref()
+                .is_some_and(|picker| picker.protocol_type() == ProtocolType::Halfblocks);
+            let reduced = frontmatter.dither_colors.is_some() || frontmatter.dither.is_some();
+            match picker {
+                Some(picker) if halfblocks && reduced => ImageBackend::Dithered {
+                    images: HashMap::new(),
+                    colors: frontmatter.dither_colors.unwrap_or(DitherColors::Xterm256),
+                    dither: frontmatter.dither.unwrap_or_default(),
+                    font_size: picker.font_size(),
+                },
+                picker => ImageBackend::RatatuiImage {
+                    decoded: HashMap::new(),
+                    placed: HashMap::new(),
+                    picker,
+                },
             }
-            ImageBackend::RatatuiImage { states }
         };
-
-        // Populate pixel dimensions on SlideImage for centered layout.
-        for slide in &mut slides {
-            for img in &mut slide.images {
-                if let Some(&(w, h)) = dims.get(&img.path) {
-                    img.pixel_width = w;
-                    img.pixel_height = h;
-                }
-            }
-        }
-
-        // Shrink placeholder lines for images constrained by max_width_percent.
-        let (term_w, _) = crossterm::terminal::size().unwrap_or((80, 24));
-        let content_w = term_w.saturating_sub(4) as f64; // approx content area width
-        for slide in &mut slides {
-            let mut removed_total: usize = 0;
-            for img in &mut slide.images {
-                img.line_index -= removed_total;
-                if let Some(pct) = img.max_width_percent {
-                    if img.pixel_width > 0 && img.pixel_height > 0 {
-                        let max_w = content_w * pct.clamp(0.0, 1.0);
-                        let cell_aspect = 2.0_f64;
-                        let new_h = (max_w * img.pixel_height as f64
-                            / img.pixel_width as f64
-                            / cell_aspect)
-                            .ceil() as u16;
-                        let new_h = new_h.max(1).min(img.height);
-                        let to_remove = (img.height - new_h) as usize;
-                        if to_remove > 0 {
-                            let start = img.line_index + new_h as usize;
-                            slide.content.lines.drain(start..start + to_remove);
-                            removed_total += to_remove;
-                            img.height = new_h;
-                        }
-                    }
-                }
-            }
+        if let Some(profile) = &mut profile {
+            profile.step("picker query");
         }
+        let image_loader = ImageLoader::spawn(&slides, 0, frontmatter.logo.as_deref(), base_dir);
+        let cell_aspect = cell_aspect(frontmatter, &image_backend);
+
+        let theme_index = theme::THEME_NAMES
+            .iter()
+            .position(|name| theme::theme_from_name(name).as_ref() == Some(&theme));
 
         Self {
             slides,
-            current_page: 0,
-            scroll_offsets: vec![0; len],
+            deck,
             quit: false,
             image_backend,
             effect: None,
+            transitions: TransitionRegistry::default(),
             last_frame: Instant::now(),
             pending_images: Vec::new(),
             pending_hyperlinks: Vec::new(),
@@ -216,68 +1093,686 @@ impl App {
             mouse_pos: (0, 0),
             prev_buffer: None,
             needs_clear: false,
+            aspect: frontmatter.aspect,
+            cell_aspect,
+            big_headings: frontmatter.big_headings.unwrap_or_default(),
+            pending_big_text: Vec::new(),
+            drawn_big_text: Vec::new(),
+            warnings,
+            base_dir: base_dir.to_path_buf(),
+            suspend: false,
+            broadcast: None,
+            reading_ruler: frontmatter.reading_ruler.unwrap_or(false),
+            peek: false,
+            presenter_view: false,
+            notes_margin: false,
+            hints_shown_at: Instant::now(),
+            active_column: None,
+            crossfade_from: None,
+            words_shown_at: None,
+            image_crossfade: None,
+            image_zoom: None,
+            markdown: markdown.to_string(),
+            frontmatter: frontmatter.clone(),
+            image_dims: HashMap::new(),
+            image_loader,
+            image_aliases: HashMap::new(),
+            outline: false,
+            confirm_quit: frontmatter.confirm_quit.unwrap_or(false),
+            quit_requested_at: None,
+            notice: None,
+            image_errors: HashMap::new(),
+            show_image_errors: frontmatter.image_errors.unwrap_or(true),
+            theme_index,
+            min_size: frontmatter.min_size.unwrap_or(DEFAULT_MIN_SIZE),
+            compact: false,
+            layouts: render::LayoutCache::default(),
+            watch: None,
+            logo: frontmatter.logo.clone(),
+            logo_position: frontmatter.logo_position.unwrap_or_default(),
+            clock: Instant::now(),
+            timer: Timer::start(0.0),
+            break_minutes: frontmatter
+                .break_minutes
+                .unwrap_or(ratride::timer::DEFAULT_BREAK_MINUTES),
+            blank: Blank::default(),
+            profile,
+            recovery_deck: None,
+            recovery_saved: None,
+            resume_offer: None,
+            kiosk: None,
+            timings: None,
+            input_log: None,
+            replay: None,
+            control: None,
+            polls: None,
+            shown_votes: None,
+            keymap: Keymap::default(),
         }
     }
 
-    fn total_pages(&self) -> usize {
-        self.slides.len()
+    /// Switch the deck to the next built-in theme by re-parsing it; slides
+    /// with a `theme` directive keep their own.
+    fn cycle_theme(&mut self) {
+        let index = self
+            .theme_index
+            .map_or(0, |i| (i + 1) % theme::THEME_NAMES.len());
+        let Some(theme) = theme::theme_from_name(theme::THEME_NAMES[index]) else {
+            return;
+        };
+        if self.reparse(&theme) {
+            self.theme_index = Some(index);
+        }
     }
 
-    fn scroll_offset(&self) -> u16 {
-        self.scroll_offsets[self.current_page]
+    /// Parse the deck again with `theme`, staying on the current slide.
+    /// Returns false, keeping the slides, if the slide count would change.
+    fn reparse(&mut self, theme: &Theme) -> bool {
+        let (mut slides, _) = parse_slides(&self.markdown, theme, &self.frontmatter, None, false);
+        if slides.len() != self.slides.len() {
+            return false;
+        }
+        // Galleries stay on the image they were stepped to
+        for (slide, old) in slides.iter_mut().zip(&self.slides) {
+            for (img, old) in slide.images.iter_mut().zip(&old.images) {
+                if img.gallery == old.gallery {
+                    img.path.clone_from(&old.path);
+                }
+            }
+        }
+        apply_image_dims(&mut slides, &self.image_dims, self.aspect, self.cell_aspect);
+        self.slides = slides;
+        self.layouts.clear();
+        // Redraw the bars of the poll being shown; others are when visited
+        if let (Some(polls), Some((page, votes))) = (&self.polls, &self.shown_votes) {
+            self.slides[*page].show_votes(votes, Some(polls.url()));
+        }
+        self.effect = None;
+        self.needs_clear = true;
+        self.drawn_big_text.clear();
+        true
     }
 
-    fn scroll_offset_mut(&mut self) -> &mut u16 {
-        &mut self.scroll_offsets[self.current_page]
+    /// Draw the FIGlet headings of the slides about to be shown, the first
+    /// time they are. None are drawn while the slide area is compact, where
+    /// the art would be clipped.
+    fn render_figlets(&mut self) {
+        if self.compact {
+            return;
+        }
+        let start = Instant::now();
+        let page = self.deck.current();
+        let next = (self.peek || self.presenter_view).then_some(page + 1);
+        let figlet = deck_figlet(&self.base_dir);
+        for page in std::iter::once(page).chain(next) {
+            let Some(slide) = self.slides.get_mut(page) else {
+                continue;
+            };
+            if slide.pending_figlets.is_empty() {
+                continue;
+            }
+            self.warnings.extend(slide.render_figlets(&figlet));
+            self.layouts.forget(page);
+        }
+        if let Some(profile) = &mut self.profile {
+            profile.figlet += start.elapsed();
+        }
     }
 
-    /// Returns true when the current slide content exceeds the visible area.
-    fn can_scroll(&self) -> bool {
-        let (_, term_h) = crossterm::terminal::size().unwrap_or((80, 24));
-        // main_area height = term_h - 1 (status bar), content_area = main_area - 2 (margin)
-        let visible = term_h.saturating_sub(3) as usize;
-        let content_len = self.slides[self.current_page].content.lines.len();
-        content_len > visible
+    /// Re-parse the deck without figlet headings when the slide area turns
+    /// compact, and with them once it's large enough again.
+    fn fit_to_terminal(&mut self, area: Rect) {
+        let (area, _, _) = self.screen_areas(area);
+        let main_area = Rect {
+            height: area.height.saturating_sub(1),
+            ..area
+        };
+        let compact = render::is_compact(main_area);
+        if compact == self.compact {
+            return;
+        }
+        self.compact = compact;
+        let theme = self
+            .theme_index
+            .and_then(|i| theme::theme_from_name(theme::THEME_NAMES[i]))
+            .unwrap_or_default();
+        if !self.reparse(&theme) {
+            self.compact = !compact;
+        }
     }
 
-    fn max_scroll(&self) -> u16 {
-        let (_, term_h) = crossterm::terminal::size().unwrap_or((80, 24));
-        let visible = term_h.saturating_sub(3) as usize;
-        let slide = &self.slides[self.current_page];
-        let content_len = slide.content.lines.len();
-        let right_len = slide.right_content.as_ref().map_or(0, |r| r.lines.len());
-        content_len.max(right_len).saturating_sub(visible) as u16
+    /// Re-parse the deck after its file changed, staying on the current slide
+    /// or, with `--follow-edits`, jumping to the first one that changed.
+    fn reload_if_changed(&mut self) {
+        let Some(watch) = &self.watch else {
+            return;
+        };
+        let changes = watch.changes();
+        if changes.deck {
+            let _ = watch.reads.send(());
+        } else if !changes.images.is_empty() {
+            self.reload_changed_images(changes.images);
+            return;
+        }
+        // An unreadable file, or a theme file that doesn't parse, is usually
+        // mid-save; the next event brings the rest
+        let Some(Ok(ReadDeck {
+            mut frontmatter,
+            theme,
+            body,
+            math_warnings,
+        })) = watch.decks.try_iter().last()
+        else {
+            return;
+        };
+        if frontmatter.big_headings == Some(BigHeadings::Auto) {
+            frontmatter.big_headings = Some(detect_big_headings());
+        }
+        frontmatter.defines = self.frontmatter.defines.clone();
+        let (mut slides, mut warnings) = parse_slides(&body, &theme, &frontmatter, None, false);
+        if slides.is_empty() {
+            return;
+        }
+        if watch.follow_edits && !self.compact {
+            // Compare against slides whose headings are drawn the same way
+            let figlet = deck_figlet(&self.base_dir);
+            for (old, new) in self.slides.iter().zip(&mut slides) {
+                if old.pending_figlets.is_empty() {
+                    warnings.extend(new.render_figlets(&figlet));
+                }
+            }
+        }
+        warnings.extend(math_warnings);
+        warnings.extend(missing_image_warnings(&slides, &self.base_dir));
+        warnings.sort_by_key(|w| w.line);
+        let cell_aspect = cell_aspect(&frontmatter, &self.image_backend);
+        apply_image_dims(
+            &mut slides,
+            &self.image_dims,
+            frontmatter.aspect,
+            cell_aspect,
+        );
+        let target = if watch.follow_edits {
+            first_changed_slide(&self.slides, &slides)
+        } else {
+            None
+        };
+
+        self.slides = slides;
+        self.layouts.clear();
+        self.shown_votes = None;
+        self.image_zoom = None;
+        self.deck.resize(self.slides.len());
+        self.markdown = body.to_string();
+        self.aspect = frontmatter.aspect;
+        self.cell_aspect = cell_aspect;
+        self.min_size = frontmatter.min_size.unwrap_or(DEFAULT_MIN_SIZE);
+        self.big_headings = frontmatter.big_headings.unwrap_or_default();
+        self.logo = frontmatter.logo.clone();
+        self.logo_position = frontmatter.logo_position.unwrap_or_default();
+        self.frontmatter = frontmatter;
+        self.theme_index = theme::THEME_NAMES
+            .iter()
+            .position(|name| theme::theme_from_name(name).as_ref() == Some(&theme));
+        self.warnings = warnings;
+        self.reload_images();
+        self.watch_files();
+        self.effect = None;
+        self.image_crossfade = None;
+        self.needs_clear = true;
+        self.drawn_big_text.clear();
+        if let Some(page) = target {
+            self.goto_page(page);
+        }
     }
 
-    fn goto_page(&mut self, page: usize) {
-        if page < self.total_pages() && page != self.current_page {
-            if matches!(self.image_backend, ImageBackend::Iterm2 { .. })
-                && !self.pending_images.is_empty()
-            {
-                self.needs_clear = true;
+    /// Drop every loaded image and load them again, picking up edited files.
+    fn reload_images(&mut self) {
+        match &mut self.image_backend {
+            ImageBackend::Iterm2 { images, dyn_images } => {
+                images.clear();
+                dyn_images.clear();
             }
-            self.current_page = page;
-            self.effect = self.create_transition();
+            ImageBackend::RatatuiImage {
+                decoded, placed, ..
+            } => {
+                decoded.clear();
+                placed.clear();
+            }
+            ImageBackend::Dithered { images, .. } => images.clear(),
         }
+        self.image_aliases.clear();
+        self.image_errors.clear();
+        self.image_zoom = None;
+        self.image_loader = ImageLoader::spawn(
+            &self.slides,
+            self.deck.current(),
+            self.logo.as_deref(),
+            &self.base_dir,
+        );
     }
 
-    fn next_page(&mut self) {
-        let next = self.current_page + 1;
-        self.goto_page(next);
+    /// Drop the loaded `paths`, whose files changed, and load them again.
+    fn reload_changed_images(&mut self, mut paths: Vec<String>) {
+        // Aliases of a changed image showed its old content, which they still have
+        let aliases: Vec<String> = self
+            .image_aliases
+            .iter()
+            .filter(|(_, original)| paths.contains(original))
+            .map(|(alias, _)| alias.clone())
+            .collect();
+        paths.extend(aliases);
+        for path in &paths {
+            match &mut self.image_backend {
+                ImageBackend::Iterm2 { images, dyn_images } => {
+                    images.remove(path);
+                    dyn_images.remove(path);
+                }
+                ImageBackend::RatatuiImage {
+                    decoded, placed, ..
+                } => {
+                    decoded.remove(path);
+                    placed.retain(|(placed_path, _, _), _| placed_path != path);
+                }
+                ImageBackend::Dithered { images, .. } => {
+                    images.remove(path);
+                }
+            }
+            self.image_aliases.remove(path);
+            self.image_errors.remove(path);
+        }
+        self.image_zoom = None;
+        self.image_crossfade = None;
+        self.needs_clear = true;
+        self.image_loader.reload(paths);
     }
 
-    fn prev_page(&mut self) {
-        if self.current_page > 0 {
-            self.goto_page(self.current_page - 1);
-        }
+    /// Watch the deck's theme file and the files of its images and logo
+    /// (`--watch`).
+    fn watch_files(&mut self) {
+        let Some(watch) = &mut self.watch else {
+            return;
+        };
+        let images = self.slides.iter().flat_map(|slide| &slide.images);
+        let paths = images.flat_map(|img| img.paths().iter().map(String::as_str));
+        let paths = paths.chain(self.logo.as_deref());
+        watch.watch_files(paths, &self.frontmatter, &self.base_dir);
     }
 
-    fn create_transition(&self) -> Option<Effect> {
-        let slide = &self.slides[self.current_page];
+    /// Hand images finished by the loader to the image backend and re-lay
+    /// out the slides with their pixel sizes.
+    fn receive_images(&mut self) {
+        let mut received = false;
+        while let Ok(LoadedImage { path, result, took }) = self.image_loader.loaded.try_recv() {
+            received = true;
+            if let Some(profile) = &mut self.profile {
+                profile.images += 1;
+                profile.image_decode += took;
+            }
+            let (data, img) = match result {
+                Ok(ImageData::Decoded(data, img)) => (data, img),
+                Ok(ImageData::SameAs(original)) => {
+                    if let Some(&dims) = self.image_dims.get(&original) {
+                        self.image_dims.insert(path.clone(), dims);
+                    }
+                    self.image_aliases.insert(path, original);
+                    continue;
+                }
+                Err(e) => {
+                    self.image_errors.insert(path, e.to_string());
+                    continue;
+                }
+            };
+            self.image_dims
+                .insert(path.clone(), (img.width(), img.height()));
+            match &mut self.image_backend {
+                ImageBackend::Iterm2 { images, dyn_images } => {
+                    images.insert(path.clone(), (data.len(), STANDARD.encode(&data)));
+                    dyn_images.insert(path, img);
+                }
+                ImageBackend::RatatuiImage { decoded, .. } => {
+                    decoded.insert(path, img);
+                }
+                ImageBackend::Dithered {
+                    images,
+                    colors,
+                    dither,
+                    ..
+                } => {
+                    images.insert(path, DitheredImage::new(img, *colors, *dither));
+                }
+            }
+        }
+        while let Ok(FadeSources { id, sources }) = self.image_loader.faded.try_recv() {
+            let Some(fade) = &mut self.image_crossfade else {
+                continue;
+            };
+            if fade.id != id {
+                continue;
+            }
+            if sources.is_empty() {
+                self.image_crossfade = None;
+            } else {
+                fade.sources = sources;
+                fade.start = Some(Instant::now());
+            }
+        }
+        if received {
+            apply_image_dims(
+                &mut self.slides,
+                &self.image_dims,
+                self.aspect,
+                self.cell_aspect,
+            );
+            // Images sized to their files may have given lines back
+            self.layouts.clear();
+        }
+    }
+
+    /// Milliseconds on the clock `timer` runs on.
+    fn now_ms(&self) -> f64 {
+        self.clock.elapsed().as_secs_f64() * 1000.0
+    }
+
+    /// Start or end a break, showing the countdown to broadcast viewers too.
+    fn toggle_break(&mut self) {
+        let now = self.now_ms();
+        self.timer.toggle_break(now, self.break_minutes);
+        if let Some(broadcast) = &self.broadcast {
+            let remaining = self.timer.break_remaining_ms(now);
+            broadcast.set_break(
+                remaining
+                    .map(|ms| Instant::now() + std::time::Duration::from_secs_f64(ms / 1000.0)),
+            );
+        }
+    }
+
+    /// Hide the slide behind `action`'s color, or show it again when it
+    /// already is.
+    fn toggle_blank(&mut self, action: Action) {
+        self.blank.toggle(action);
+        self.blank_changed();
+    }
+
+    /// Redraw the whole screen after the slide was hidden or shown again.
+    fn blank_changed(&mut self) {
+        self.needs_clear = true;
+        self.drawn_big_text.clear();
+    }
+
+    /// Pick up the talk where `state` left it: same slide, fragment, scroll
+    /// and timer.
+    fn resume(&mut self, state: &Recovery) {
+        self.goto_page(state.page.min(self.total_pages() - 1));
+        // The slide may have lost fragments since the state was saved
+        let fragments = self.slides[self.deck.current()].fragments.len();
+        self.deck.set_step(state.step.min(fragments));
+        let (max, now) = (self.max_scroll(), self.now_ms());
+        self.deck.scroll_to(state.scroll, max, now);
+        self.timer = Timer::resume(now, state.elapsed_ms, state.timer_paused);
+    }
+
+    /// Write the current position to the recovery file when it has changed,
+    /// at most every [`recovery::SAVE_INTERVAL_MS`]; `force` skips the wait.
+    fn save_recovery(&mut self, force: bool) {
+        // Nothing is saved until an earlier talk has been resumed or declined
+        let (Some(deck), None) = (&self.recovery_deck, &self.resume_offer) else {
+            return;
+        };
+        let now = self.now_ms();
+        let state = Recovery {
+            page: self.deck.current(),
+            step: self.deck.step(),
+            scroll: self.deck.scroll_offset(),
+            elapsed_ms: self.timer.elapsed_ms(now),
+            timer_paused: self.timer.is_paused(),
+        };
+        if let Some((saved, at)) = &self.recovery_saved {
+            if *saved == state || (!force && now - at < recovery::SAVE_INTERVAL_MS) {
+                return;
+            }
+        }
+        // Best effort: a failed write only loses the ability to resume
+        let _ = recovery::save(deck, &state);
+        self.recovery_saved = Some((state, now));
+    }
+
+    /// Append `input` to the `--record-input` file. Suspending isn't
+    /// recorded, so a replay doesn't stop in the background.
+    fn record_input(&mut self, input: Input) {
+        if matches!(&input, Input::Action(name) if name == Action::Suspend.name()) {
+            return;
+        }
+        if let Some(log) = &mut self.input_log {
+            let _ = log.record(input);
+        }
+    }
+
+    /// Take the `--replay` actions whose time has come.
+    fn replay_input(&mut self) {
+        while let Some(input) = self.replay.as_mut().and_then(Replay::due) {
+            match input {
+                Input::Action(name) => {
+                    if let Some(action) = Action::from_name(&name) {
+                        let quit_requested = self.take_quit_request();
+                        self.perform(action, quit_requested);
+                    }
+                }
+                Input::Goto(page) => self.goto_page(page),
+            }
+        }
+    }
+
+    /// Log the current slide to the `--timings` file; a failed write only
+    /// costs a chapter marker.
+    fn record_timing(&mut self) {
+        let page = self.deck.current();
+        if let Some(log) = &mut self.timings {
+            let _ = log.record(page, self.slides[page].title.as_deref());
+        }
+    }
+
+    /// Open voting on the current slide's poll and redraw its bars when
+    /// votes came in since the last frame.
+    fn update_poll(&mut self) {
+        let Some(polls) = &self.polls else {
+            return;
+        };
+        let page = self.deck.current();
+        if self
+            .shown_votes
+            .as_ref()
+            .is_none_or(|(shown, _)| *shown != page)
+        {
+            polls.open(page, self.slides[page].poll.as_ref());
+            self.shown_votes = Some((page, Vec::new()));
+        }
+        let slide = &mut self.slides[page];
+        let Some(poll) = &slide.poll else {
+            return;
+        };
+        let votes = polls.votes(page, poll.options.len());
+        if self
+            .shown_votes
+            .as_ref()
+            .is_some_and(|(_, shown)| *shown == votes)
+        {
+            return;
+        }
+        slide.show_votes(&votes, Some(polls.url()));
+        self.layouts.forget(page);
+        self.shown_votes = Some((page, votes));
+    }
+
+    fn total_pages(&self) -> usize {
+        self.slides.len()
+    }
+
+    fn scroll_by(&mut self, lines: i32) {
+        let (max, now) = (self.max_scroll(), self.now_ms());
+        self.deck.scroll_by(lines, max, now);
+    }
+
+    /// Returns true when the current slide content exceeds the visible area.
+    fn can_scroll(&self) -> bool {
+        self.max_scroll() > 0
+    }
+
+    /// Letterboxed slide area within `area`, and the outline sidebar and
+    /// notes margin carved out of its sides when shown.
+    fn screen_areas(&self, area: Rect) -> (Rect, Option<Rect>, Option<Rect>) {
+        let area = slide_area(area, self.aspect, self.cell_aspect);
+        let (area, outline) = if self.outline {
+            let [outline, area] = render::outline_areas(area);
+            (area, Some(outline))
+        } else {
+            (area, None)
+        };
+        if self.notes_margin && !self.presenter_view {
+            let [area, notes] = render::notes_margin_areas(area);
+            (area, outline, Some(notes))
+        } else {
+            (area, outline, None)
+        }
+    }
+
+    fn max_scroll(&self) -> u16 {
+        // Slide content is drawn above the 1-row status bar
+        let (area, _, _) = self.screen_areas(terminal_area());
+        let mut main_area = Rect {
+            height: area.height.saturating_sub(1),
+            ..area
+        };
+        // The peek and presenter view narrow the slide to their left column
+        if self.peek || self.presenter_view {
+            [main_area, _] = render::next_slide_areas(main_area);
+        }
+        let page = self.deck.current();
+        render::max_scroll(
+            &self.slides[page],
+            self.layouts.slot(page, usize::MAX),
+            main_area,
+        )
+    }
+
+    fn goto_page(&mut self, page: usize) {
+        if self.deck.goto(page) {
+            self.on_page_change();
+        }
+    }
+
+    /// Follow up a page change: clear what the old slide left on screen,
+    /// start the transition and tell broadcast viewers.
+    fn on_page_change(&mut self) {
+        let page = self.deck.current();
+        if matches!(self.image_backend, ImageBackend::Iterm2 { .. })
+            && !self.pending_images.is_empty()
+        {
+            self.needs_clear = true;
+        }
+        if !self.drawn_big_text.is_empty() {
+            self.needs_clear = true;
+            self.drawn_big_text.clear();
+        }
+        self.crossfade_from = Some(self.pending_images.clone());
+        self.image_crossfade = None;
+        self.image_zoom = None;
+        self.image_loader.prioritize(&self.slides, page);
+        self.active_column = None;
+        self.words_shown_at = None;
+        self.effect = self.create_transition();
+        self.record_timing();
+        if let Some(broadcast) = &self.broadcast {
+            broadcast.set_page(page, self.deck.step());
+        }
+        // A page turned by hand gets the full interval too
+        self.schedule_kiosk();
+    }
+
+    /// In kiosk mode, end the splash and advance on schedule, looping back
+    /// to the first slide after the last.
+    fn advance_kiosk(&mut self) {
+        let now = self.now_ms();
+        let Some(kiosk) = &mut self.kiosk else {
+            return;
+        };
+        if kiosk.starts_at.is_some_and(|at| now >= at) {
+            kiosk.starts_at = None;
+            self.effect = self.create_transition();
+            return;
+        }
+        if kiosk.starts_at.is_some() || now < kiosk.next_at {
+            return;
+        }
+        let (page, step) = (self.deck.current(), self.deck.step());
+        self.next_page();
+        if (self.deck.current(), self.deck.step()) == (page, step) && self.deck.goto(0) {
+            self.on_page_change();
+        }
+    }
+
+    /// Follow up revealing or hiding a fragment of the current slide.
+    fn on_step_change(&mut self) {
+        // Images on hidden fragments stay on screen until cleared
+        if matches!(self.image_backend, ImageBackend::Iterm2 { .. })
+            && !self.pending_images.is_empty()
+        {
+            self.needs_clear = true;
+        }
+        if let Some(broadcast) = &self.broadcast {
+            broadcast.set_page(self.deck.current(), self.deck.step());
+        }
+        self.schedule_kiosk();
+    }
+
+    /// Give the slide (or newly revealed fragment) on screen its time in
+    /// kiosk mode.
+    fn schedule_kiosk(&mut self) {
+        let now = self.now_ms();
+        let words = self.current_words();
+        if let Some(kiosk) = &mut self.kiosk {
+            kiosk.schedule(now, words);
+        }
+    }
+
+    /// Words newly on screen, for `--wpm` timing.
+    fn current_words(&self) -> usize {
+        self.slides
+            .get(self.deck.current())
+            .map_or(0, |slide| slide.words_in_step(self.deck.step()))
+    }
+
+    fn next_page(&mut self) {
+        let step = self.deck.step();
+        if self.deck.next(&self.slides) {
+            self.on_page_change();
+        } else if self.deck.step() != step {
+            self.on_step_change();
+        }
+    }
+
+    fn prev_page(&mut self) {
+        let step = self.deck.step();
+        if self.deck.prev(&self.slides) {
+            self.on_page_change();
+        } else if self.deck.step() != step {
+            self.on_step_change();
+        }
+    }
+
+    fn goto_section(&mut self, forward: bool) {
+        if self.deck.goto_section(&self.slides, forward) {
+            self.on_page_change();
+        }
+    }
+
+    fn create_transition(&self) -> Option<Effect> {
+        let slide = &self.slides[self.deck.current()];
         let bg = slide.theme.bg;
         let prev_buf = self.prev_buffer.clone();
-        let (_, term_h) = crossterm::terminal::size().unwrap_or((80, 24));
+        let term_h = slide_area(terminal_area(), self.aspect, self.cell_aspect).height;
         ratride::transition::create_transition(
+            &self.transitions,
             &slide.transition,
             bg,
             prev_buf,
@@ -288,14 +1783,25 @@ impl App {
         )
     }
 
-    fn run(mut self, mut terminal: DefaultTerminal) -> io::Result<()> {
+    fn run(&mut self, mut terminal: DefaultTerminal) -> io::Result<()> {
         // Enable mouse capture for clickable hyperlinks
         crossterm::execute!(io::stdout(), EnableMouseCapture)?;
 
         terminal.draw(|_| {})?;
+        self.hints_shown_at = Instant::now();
         self.effect = self.create_transition();
+        self.record_timing();
+        let now = self.now_ms();
+        let words = self.current_words();
+        if let Some(kiosk) = &mut self.kiosk {
+            kiosk.start(now, words);
+        }
         self.last_frame = Instant::now();
         while !self.quit {
+            self.reload_if_changed();
+            let size = terminal.size()?;
+            self.fit_to_terminal(Rect::new(0, 0, size.width, size.height));
+            self.receive_images();
             self.pending_images.clear();
             if self.needs_clear {
                 terminal.clear()?;
@@ -305,8 +1811,24 @@ impl App {
             self.prev_buffer = Some(completed.buffer.clone());
             if self.effect.is_none() {
                 self.flush_iterm2_images()?;
+                self.flush_big_text(&mut terminal)?;
+            }
+            if let Some(profile) = &mut self.profile {
+                if !profile.steps.iter().any(|(name, _)| *name == "first frame") {
+                    profile.step("first frame");
+                    profile.split_last("figlet", profile.figlet);
+                }
             }
             self.handle_events()?;
+            self.advance_kiosk();
+            self.poll_control();
+            self.replay_input();
+            self.update_poll();
+            self.save_recovery(false);
+            if self.suspend {
+                self.suspend = false;
+                self.suspend_to_shell(&mut terminal)?;
+            }
             let elapsed = self.last_frame.elapsed();
             if elapsed < FRAME_DURATION {
                 std::thread::sleep(FRAME_DURATION - elapsed);
@@ -314,10 +1836,42 @@ impl App {
             self.last_frame = Instant::now();
         }
 
+        self.save_recovery(true);
+        // Marks where the last chapter ends
+        self.record_timing();
         crossterm::execute!(io::stdout(), DisableMouseCapture)?;
         Ok(())
     }
 
+    /// Restore the terminal and stop the process (as the shell does for Ctrl-Z).
+    /// Once resumed with `fg`, reinitialize the terminal and re-query the image
+    /// picker, since the font size or terminal may have changed meanwhile.
+    #[cfg(unix)]
+    fn suspend_to_shell(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        restore_terminal();
+        // SAFETY: raising a signal on our own process has no memory-safety preconditions.
+        unsafe {
+            libc::raise(libc::SIGTSTP);
+        }
+        *terminal = ratatui::init();
+        crossterm::execute!(io::stdout(), EnableMouseCapture)?;
+        if let ImageBackend::RatatuiImage { placed, picker, .. } = &mut self.image_backend {
+            *picker = Picker::from_query_stdio().ok();
+            placed.clear();
+        }
+        self.cell_aspect = cell_aspect(&self.frontmatter, &self.image_backend);
+        self.image_crossfade = None;
+        self.drawn_big_text.clear();
+        self.effect = None;
+        self.needs_clear = true;
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    fn suspend_to_shell(&mut self, _terminal: &mut DefaultTerminal) -> io::Result<()> {
+        Ok(())
+    }
+
     /// Write iTerm2 inline image escape sequences directly to stdout.
     fn flush_iterm2_images(&self) -> io::Result<()> {
         if let ImageBackend::Iterm2 {
@@ -329,11 +1883,20 @@ impl App {
             if pending.is_empty() {
                 return Ok(());
             }
+            let zoom = self.image_zoom.as_ref();
             let mut stdout = io::stdout();
             for img in pending {
-                let (size, b64) = if img.full_height > img.height {
+                let path = self.image_aliases.get(&img.path).unwrap_or(&img.path);
+                let blended = self
+                    .image_crossfade
+                    .as_ref()
+                    .and_then(|fade| fade.frames.get(&img.path));
+                let (size, b64) = if let Some(BlendedImage::Iterm2(size, b64)) = blended {
+                    (*size, b64.clone())
+                } else if img.full_height > img.height {
                     // Image partially off-screen: crop the source image to the visible portion.
-                    if let Some(dyn_img) = dyn_images.get(&img.path) {
+                    let zoomed = zoom.and_then(|zoom| zoom.images.get(&img.path));
+                    if let Some(dyn_img) = zoomed.or_else(|| dyn_images.get(path)) {
                         let pix_h = dyn_img.height();
                         let pix_w = dyn_img.width();
                         let crop_y = if img.clip_top {
@@ -344,21 +1907,17 @@ impl App {
                         };
                         let crop_h =
                             (pix_h as f64 * img.height as f64 / img.full_height as f64) as u32;
-                        let cropped = dyn_img.crop_imm(0, crop_y, pix_w, crop_h);
-                        let mut buf = std::io::Cursor::new(Vec::new());
-                        cropped
-                            .write_to(&mut buf, image::ImageFormat::Png)
-                            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
-                        let bytes = buf.into_inner();
-                        let size = bytes.len();
-                        let b64 = STANDARD.encode(&bytes);
-                        (size, b64)
-                    } else if let Some((size, b64)) = images.get(&img.path) {
+                        iterm2_payload(&dyn_img.crop_imm(0, crop_y, pix_w, crop_h))?
+                    } else if let Some((size, b64)) = images.get(path) {
                         (*size, b64.clone())
                     } else {
                         continue;
                     }
-                } else if let Some((size, b64)) = images.get(&img.path) {
+                } else if let Some(BlendedImage::Iterm2(size, b64)) =
+                    zoom.and_then(|zoom| zoom.frames.get(&img.path))
+                {
+                    (*size, b64.clone())
+                } else if let Some((size, b64)) = images.get(path) {
                     (*size, b64.clone())
                 } else {
                     continue;
@@ -376,6 +1935,43 @@ impl App {
         Ok(())
     }
 
+    /// Write double-size headings via terminal escapes when their placement changed.
+    /// Stale double-size cells (and DEC line attributes) are cleared first, so the
+    /// headings are re-emitted on the following frame.
+    fn flush_big_text(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        if self.pending_big_text == self.drawn_big_text {
+            return Ok(());
+        }
+        if !self.drawn_big_text.is_empty() {
+            terminal.clear()?;
+            self.drawn_big_text.clear();
+            return Ok(());
+        }
+        let mut stdout = io::stdout();
+        for p in &self.pending_big_text {
+            let style = sgr(p.style);
+            match self.big_headings {
+                BigHeadings::Kitty => {
+                    crossterm::execute!(stdout, MoveTo(p.x, p.y))?;
+                    write!(stdout, "{style}\x1b]66;s=2;{}\x07\x1b[0m", p.text)?;
+                }
+                BigHeadings::Dec => {
+                    // Line attributes halve the column count, so positions are halved too.
+                    for (dy, attr) in [(0, '3'), (1, '4')] {
+                        crossterm::execute!(stdout, MoveTo(0, p.y + dy))?;
+                        write!(stdout, "\x1b#{attr}")?;
+                        crossterm::execute!(stdout, MoveTo(p.x / 2, p.y + dy))?;
+                        write!(stdout, "{style}{}\x1b[0m", p.text)?;
+                    }
+                }
+                BigHeadings::Off | BigHeadings::Auto => {}
+            }
+        }
+        stdout.flush()?;
+        self.drawn_big_text = self.pending_big_text.clone();
+        Ok(())
+    }
+
     /// Find the hyperlink URL at the given screen position, if any.
     fn hyperlink_at(&self, x: u16, y: u16) -> Option<&str> {
         self.pending_hyperlinks
@@ -385,20 +1981,50 @@ impl App {
     }
 
     fn draw(&mut self, frame: &mut Frame) {
+        self.render_figlets();
         let area = frame.area();
+        let (max_scroll, now) = (self.max_scroll(), self.now_ms());
+        self.deck.settle(max_scroll, now);
 
-        // Fill entire screen with theme background (if enabled)
-        let slide = &self.slides[self.current_page];
-        if slide.bg_fill {
-            let slide_bg = slide.theme.bg;
-            let buf = frame.buffer_mut();
-            for y in area.y..area.y + area.height {
-                for x in area.x..area.x + area.width {
-                    if let Some(cell) = buf.cell_mut((x, y)) {
-                        cell.set_bg(slide_bg);
-                    }
-                }
-            }
+        // Fill entire screen with theme background (if enabled, or when letterboxing)
+        let stepped = self.slides[self.deck.current()].at_step(self.deck.step());
+        let slide = &*stepped;
+        let slot = self.layouts.slot(self.deck.current(), self.deck.step());
+        if let Some(remaining) = self.kiosk.as_ref().and_then(|k| k.countdown_remaining(now)) {
+            let title = self.slides[0].title.as_deref();
+            render::draw_kiosk_countdown(title, remaining, frame, area, &slide.theme);
+            return;
+        }
+        let (min_w, min_h) = self.min_size;
+        if area.width < min_w || area.height < min_h {
+            render::draw_too_small(self.min_size, frame, area, &slide.theme);
+            return;
+        }
+        if let Some(blank) = self.blank.shown() {
+            let color = match blank {
+                Action::WhiteScreen => ratatui::style::Color::Rgb(0xff, 0xff, 0xff),
+                _ => slide.theme.bg,
+            };
+            render::fill_background(frame, area, color);
+            return;
+        }
+        if slide.bg_fill || self.aspect.is_some() {
+            render::fill_background(frame, area, slide.theme.bg);
+        }
+        let (area, outline_area, notes_area) = self.screen_areas(area);
+        if let Some(outline_area) = outline_area {
+            let presenter = self.broadcast.is_some();
+            render::draw_outline(
+                &self.slides,
+                self.deck.current(),
+                presenter,
+                frame,
+                outline_area,
+                &slide.theme,
+            );
+        }
+        if let Some(notes_area) = notes_area {
+            render::draw_notes_margin(slide, frame, notes_area, &slide.theme);
         }
 
         let [main_area, status_area] =
@@ -406,14 +2032,79 @@ impl App {
 
         let slide_theme = slide.theme.clone();
         let slide_header = slide.header.clone();
-        let scroll = self.scroll_offset();
+        let scroll = self.deck.visible_scroll(now);
 
         // Draw slide content via core render functions
-        let (mut placements, hyperlinks) = render::draw_slide(slide, scroll, frame, main_area);
+        let (mut placements, hyperlinks) = if self.presenter_view {
+            let next = self.slides.get(self.deck.current() + 1);
+            let elapsed = self.timer.elapsed_ms(now);
+            render::draw_presenter_view(
+                slide,
+                slot,
+                next,
+                scroll,
+                elapsed,
+                frame,
+                main_area,
+                &slide_theme,
+            )
+        } else if self.peek {
+            let next = self.slides.get(self.deck.current() + 1);
+            render::draw_slide_with_peek(slide, slot, next, scroll, frame, main_area, &slide_theme)
+        } else {
+            render::draw_slide(slide, slot, scroll, frame, main_area)
+        };
+        if let Some(logo) = &self.logo {
+            placements.extend(render::logo_placement(logo, self.logo_position, main_area));
+        }
+        if let Some(active) = self.active_column {
+            render::dim_inactive_column(slide, active, frame, main_area, &slide_theme);
+        }
+        if !self.peek && !self.presenter_view {
+            let elapsed = self.words_shown_at.map_or(0.0, |at| now - at);
+            render::reveal_words(slide, slot, scroll, elapsed, frame, main_area);
+        }
+        if self.reading_ruler && self.can_scroll() {
+            render::draw_reading_ruler(frame, main_area, &slide_theme);
+        }
+
+        // Double-size headings (written after render; take bg from the drawn cells)
+        let mut big_text = if self.peek || self.presenter_view {
+            Vec::new()
+        } else {
+            render::big_heading_placements(slide, slot, scroll, main_area)
+        };
+        for p in &mut big_text {
+            if slide.ascii_only {
+                p.text = render::ascii_text(&p.text);
+            }
+            if let Some(cell) = frame.buffer_mut().cell((p.x, p.y)) {
+                if cell.bg != ratatui::style::Color::Reset {
+                    p.style = p.style.bg(cell.bg);
+                }
+            }
+        }
+        self.pending_big_text = big_text;
+
+        if let Some(prev) = self.crossfade_from.take() {
+            // Fade matching images into each other instead of the text buffer
+            let crossfades = render::image_crossfades(&prev, &placements);
+            if !crossfades.is_empty() {
+                self.effect = None;
+                self.start_crossfade(crossfades);
+            }
+        }
+        self.advance_crossfade();
 
         // Render images via native backend
         for placement in &placements {
-            self.draw_image(frame, placement);
+            match self.image_errors.get(&placement.path) {
+                Some(error) if self.show_image_errors => {
+                    render::draw_image_error(frame, placement, error, &slide_theme);
+                }
+                Some(_) => {}
+                None => self.draw_image(frame, placement),
+            }
         }
         self.pending_images.append(&mut placements);
         self.pending_hyperlinks = hyperlinks;
@@ -433,19 +2124,134 @@ impl App {
                 self.effect = None;
             }
         }
+        if self.effect.is_none() && self.words_shown_at.is_none() {
+            self.words_shown_at = Some(now);
+        }
 
         // Header (top-right overlay)
         let header_links = render::draw_header(&slide_header, frame, main_area, &slide_theme);
         self.pending_hyperlinks.extend(header_links);
 
         // Status bar
+        let show_hints =
+            self.hints_shown_at.elapsed().as_secs_f64() * 1000.0 < render::HINT_DURATION_MS;
         render::draw_status_bar(
-            self.current_page,
+            self.deck.current(),
+            self.total_pages(),
+            frame,
+            status_area,
+            &slide_theme,
+            show_hints,
+        );
+        if let Some(agenda) = self.frontmatter.agenda.filter(|_| !show_hints) {
+            render::draw_agenda(
+                &self.slides,
+                self.deck.current(),
+                self.broadcast.is_some(),
+                agenda,
+                frame,
+                status_area,
+                &slide_theme,
+            );
+        }
+
+        let now = self.now_ms();
+        render::draw_timer(
+            self.timer.elapsed_ms(now),
+            self.timer.is_paused(),
+            self.deck.current(),
             self.total_pages(),
             frame,
             status_area,
             &slide_theme,
         );
+        if let Some(remaining) = self.timer.break_remaining_ms(now) {
+            render::draw_break(remaining, frame, main_area, &slide_theme);
+        }
+
+        if self
+            .quit_requested_at
+            .is_some_and(|at| at.elapsed().as_secs_f64() * 1000.0 < render::QUIT_CONFIRM_MS)
+        {
+            render::draw_quit_prompt(frame, status_area, &slide_theme);
+        }
+        if let Some((text, _)) = self
+            .notice
+            .as_ref()
+            .filter(|(_, at)| at.elapsed().as_secs_f64() * 1000.0 < render::NOTICE_MS)
+        {
+            render::draw_notice(text, frame, status_area, &slide_theme);
+        }
+        if let Some(state) = &self.resume_offer {
+            render::draw_resume_prompt(
+                state.page,
+                self.total_pages(),
+                state.elapsed_ms,
+                frame,
+                status_area,
+                &slide_theme,
+            );
+        }
+
+        render::draw_warnings(&self.warnings, frame, main_area, &slide_theme);
+        if self.slides[self.deck.current()].ascii_only {
+            render::to_ascii(frame.buffer_mut(), &self.pending_images);
+        }
+    }
+
+    /// Have the loader decode both sides of each crossfade; the fade starts
+    /// when they arrive in [`Self::receive_images`].
+    fn start_crossfade(&mut self, crossfades: Vec<render::ImageCrossfade>) {
+        let font_size = font_size(&self.image_backend).unwrap_or(FALLBACK_FONT_SIZE);
+        self.image_crossfade = Some(ImageFade {
+            id: self.image_loader.crossfade(crossfades, font_size),
+            start: None,
+            sources: HashMap::new(),
+            step: u32::MAX,
+            frames: HashMap::new(),
+        });
+    }
+
+    /// Rebuild the blended frames when the crossfade reaches its next step,
+    /// and end it once the incoming images are fully shown.
+    fn advance_crossfade(&mut self) {
+        let Some(fade) = &mut self.image_crossfade else {
+            return;
+        };
+        let Some(start) = fade.start else {
+            return;
+        };
+        let t = start.elapsed().as_secs_f64() * 1000.0 / render::IMAGE_CROSSFADE_MS;
+        let step = (t * CROSSFADE_STEPS as f64) as u32;
+        if step >= CROSSFADE_STEPS {
+            self.image_crossfade = None;
+            return;
+        }
+        if step == fade.step {
+            return;
+        }
+        fade.step = step;
+        let alpha = step as f32 / CROSSFADE_STEPS as f32;
+        fade.frames = fade
+            .sources
+            .iter()
+            .filter_map(|(path, (from, to))| {
+                let blended = blend(from, to, alpha);
+                let frame = match &self.image_backend {
+                    ImageBackend::RatatuiImage { picker, .. } => BlendedImage::Protocol(Box::new(
+                        picker.as_ref()?.new_resize_protocol(blended),
+                    )),
+                    ImageBackend::Iterm2 { .. } => {
+                        let (size, b64) = iterm2_payload(&blended).ok()?;
+                        BlendedImage::Iterm2(size, b64)
+                    }
+                    ImageBackend::Dithered { colors, dither, .. } => BlendedImage::Dithered(
+                        Box::new(DitheredImage::new(blended, *colors, *dither)),
+                    ),
+                };
+                Some((path.clone(), frame))
+            })
+            .collect();
     }
 
     fn draw_image(&mut self, frame: &mut Frame, placement: &ImagePlacement) {
@@ -454,8 +2260,43 @@ impl App {
             ImageBackend::Iterm2 { .. } => {
                 // Deferred to flush_iterm2_images() — placement already stored
             }
-            ImageBackend::RatatuiImage { states } => {
-                if let Some(state) = states.get_mut(&placement.path) {
+            ImageBackend::RatatuiImage {
+                decoded,
+                placed,
+                picker,
+            } => {
+                let path = self
+                    .image_aliases
+                    .get(&placement.path)
+                    .unwrap_or(&placement.path);
+                let key = (path.clone(), placement.width, placement.full_height);
+                if !placed.contains_key(&key) {
+                    let state = decoded
+                        .get(path)
+                        .zip(picker.as_ref())
+                        .map(|(img, picker)| picker.new_resize_protocol(img.clone()));
+                    if let Some(state) = state {
+                        placed.insert(key.clone(), state);
+                    }
+                }
+                let blended = self
+                    .image_crossfade
+                    .as_mut()
+                    .and_then(|fade| fade.frames.get_mut(&placement.path));
+                let zoomed = self
+                    .image_zoom
+                    .as_mut()
+                    .and_then(|zoom| zoom.frames.get_mut(&placement.path));
+                // A zoomed region is smaller than the image it stands in for
+                let resize = match zoomed {
+                    Some(_) => Resize::Scale(None),
+                    None => Resize::default(),
+                };
+                let state = match blended.or(zoomed) {
+                    Some(BlendedImage::Protocol(state)) => Some(state.as_mut()),
+                    _ => placed.get_mut(&key),
+                };
+                if let Some(state) = state {
                     if placement.full_height > placement.height {
                         // Image partially off-screen: render at full size into temp buffer,
                         // then copy the visible portion to the frame.
@@ -466,7 +2307,9 @@ impl App {
                         };
                         let full_rect = Rect::new(0, 0, placement.width, placement.full_height);
                         let mut temp_buf = Buffer::empty(full_rect);
-                        StatefulImage::new().render(full_rect, &mut temp_buf, state);
+                        StatefulImage::new()
+                            .resize(resize)
+                            .render(full_rect, &mut temp_buf, state);
 
                         let buf = frame.buffer_mut();
                         for dy in 0..placement.height {
@@ -480,13 +2323,271 @@ impl App {
                             }
                         }
                     } else {
-                        StatefulImage::new().render(img_area, frame.buffer_mut(), state);
+                        let image = StatefulImage::new().resize(resize);
+                        image.render(img_area, frame.buffer_mut(), state);
+                    }
+                }
+            }
+            ImageBackend::Dithered {
+                images, font_size, ..
+            } => {
+                let path = self
+                    .image_aliases
+                    .get(&placement.path)
+                    .unwrap_or(&placement.path);
+                let blended = self
+                    .image_crossfade
+                    .as_mut()
+                    .and_then(|fade| fade.frames.get_mut(&placement.path));
+                let zoomed = self
+                    .image_zoom
+                    .as_mut()
+                    .and_then(|zoom| zoom.frames.get_mut(&placement.path));
+                let scale_up = zoomed.is_some();
+                let image = match blended.or(zoomed) {
+                    Some(BlendedImage::Dithered(image)) => Some(image.as_mut()),
+                    _ => images.get_mut(path),
+                };
+                if let Some(image) = image {
+                    let clip_rows = if placement.clip_top {
+                        placement.full_height - placement.height
+                    } else {
+                        0
+                    };
+                    let lines =
+                        image.lines(placement.width, placement.full_height, *font_size, scale_up);
+                    let visible = lines.iter().skip(clip_rows as usize);
+                    let buf = frame.buffer_mut();
+                    for (y, line) in (placement.y..).zip(visible.take(placement.height as usize)) {
+                        buf.set_line(placement.x, y, line, placement.width);
                     }
                 }
             }
         }
     }
 
+    /// Whether a quit is pending confirmation; cancels it either way.
+    fn take_quit_request(&mut self) -> bool {
+        self.quit_requested_at
+            .take()
+            .is_some_and(|at| at.elapsed().as_secs_f64() * 1000.0 < render::QUIT_CONFIRM_MS)
+    }
+
+    /// Carry out `action`; `quit_requested` confirms a quit.
+    fn perform(&mut self, action: Action, quit_requested: bool) {
+        self.record_input(Input::Action(action.name().to_string()));
+        match action {
+            Action::Suspend => self.suspend = true,
+            Action::Quit => {
+                if !self.confirm_quit || quit_requested {
+                    self.quit = true;
+                } else {
+                    self.quit_requested_at = Some(Instant::now());
+                }
+            }
+            Action::ToggleReadingRuler => self.reading_ruler = !self.reading_ruler,
+            Action::ShowHints => self.hints_shown_at = Instant::now(),
+            Action::CycleTheme => self.cycle_theme(),
+            Action::ToggleTimer => {
+                let now = self.now_ms();
+                self.timer.toggle_pause(now);
+            }
+            Action::ResetTimer => {
+                let now = self.now_ms();
+                self.timer.reset(now);
+            }
+            Action::ToggleBreak => self.toggle_break(),
+            Action::BlankScreen | Action::WhiteScreen => self.toggle_blank(action),
+            Action::NextSection => self.goto_section(true),
+            Action::PrevSection => self.goto_section(false),
+            Action::CycleColumn => self.active_column = ActiveColumn::cycle(self.active_column),
+            Action::ToggleOutline => {
+                self.outline = !self.outline;
+                self.needs_clear = true;
+                self.drawn_big_text.clear();
+            }
+            Action::TogglePeek => {
+                self.peek = !self.peek;
+                self.needs_clear = true;
+                self.drawn_big_text.clear();
+            }
+            Action::TogglePresenterView => {
+                self.presenter_view = !self.presenter_view;
+                self.needs_clear = true;
+                self.drawn_big_text.clear();
+            }
+            Action::ToggleNotesMargin => {
+                self.notes_margin = !self.notes_margin;
+                self.needs_clear = true;
+                self.drawn_big_text.clear();
+            }
+            Action::CopyLink => self.copy_link(),
+            Action::NextImage => self.step_gallery(true),
+            Action::PrevImage => self.step_gallery(false),
+            Action::ZoomIn => self.zoom_images(true),
+            Action::ZoomOut => self.zoom_images(false),
+            Action::PanImage(dx, dy) => self.pan_images(dx, dy),
+            Action::NextSlide => self.next_page(),
+            Action::PrevSlide => self.prev_page(),
+            Action::ScrollDown(lines) => self.scroll_by(lines as i32),
+            Action::ScrollUp(lines) => self.scroll_by(-(lines as i32)),
+            Action::ToggleMute | Action::ToggleVideo | Action::ToggleOverview => {}
+        }
+    }
+
+    /// Show the next (or previous) image of the current slide's galleries.
+    fn step_gallery(&mut self, forward: bool) {
+        let slide = &mut self.slides[self.deck.current()];
+        if !slide.step_galleries(forward) {
+            return;
+        }
+        for img in &mut slide.images {
+            let (w, h) = self.image_dims.get(&img.path).copied().unwrap_or_default();
+            img.pixel_width = w;
+            img.pixel_height = h;
+        }
+        // The new image may not cover all of the old one, and a zoom into
+        // the old one doesn't carry over
+        self.needs_clear = true;
+        self.image_zoom = None;
+    }
+
+    /// Zoom into the current slide's images, or back out; zooming out of
+    /// the smallest magnification shows them whole again.
+    fn zoom_images(&mut self, zoom_in: bool) {
+        if self.slides[self.deck.current()].images.is_empty() {
+            return;
+        }
+        let level = self.image_zoom.as_ref().map_or(1, |zoom| zoom.level);
+        let level = if zoom_in { level * 2 } else { level / 2 };
+        if level <= 1 {
+            self.image_zoom = None;
+            self.needs_clear = true;
+            return;
+        }
+        let zoom = self.image_zoom.get_or_insert_with(|| ImageZoom {
+            level,
+            center: (0.5, 0.5),
+            sources: HashMap::new(),
+            images: HashMap::new(),
+            frames: HashMap::new(),
+        });
+        zoom.level = level.min(MAX_IMAGE_ZOOM);
+        self.update_zoom();
+    }
+
+    /// Move the zoomed region a quarter of its size along `dx` and `dy`.
+    fn pan_images(&mut self, dx: i8, dy: i8) {
+        let Some(zoom) = &mut self.image_zoom else {
+            return;
+        };
+        let half = 0.5 / zoom.level as f64;
+        let step = |center: f64, d: i8| (center + d as f64 * half / 2.0).clamp(half, 1.0 - half);
+        zoom.center = (step(zoom.center.0, dx), step(zoom.center.1, dy));
+        self.update_zoom();
+    }
+
+    /// Crop and encode the current slide's images for the zoomed region.
+    fn update_zoom(&mut self) {
+        let Some(zoom) = &mut self.image_zoom else {
+            return;
+        };
+        let half = 0.5 / zoom.level as f64;
+        zoom.center.0 = zoom.center.0.clamp(half, 1.0 - half);
+        zoom.center.1 = zoom.center.1.clamp(half, 1.0 - half);
+        zoom.images.clear();
+        zoom.frames.clear();
+        for img in &self.slides[self.deck.current()].images {
+            let path = self.image_aliases.get(&img.path).unwrap_or(&img.path);
+            if !zoom.sources.contains_key(path) {
+                let source = match &self.image_backend {
+                    ImageBackend::Iterm2 { dyn_images, .. } => dyn_images.get(path).cloned(),
+                    ImageBackend::RatatuiImage { decoded, .. } => decoded.get(path).cloned(),
+                    ImageBackend::Dithered { images, .. } => {
+                        images.get(path).map(|image| image.image().clone())
+                    }
+                };
+                let Some(source) = source else {
+                    continue;
+                };
+                zoom.sources.insert(path.clone(), source);
+            }
+            let zoomed = zoom.crop(&zoom.sources[path]);
+            let frame = match &self.image_backend {
+                ImageBackend::RatatuiImage {
+                    picker: Some(picker),
+                    ..
+                } => BlendedImage::Protocol(Box::new(picker.new_resize_protocol(zoomed.clone()))),
+                ImageBackend::RatatuiImage { picker: None, .. } => continue,
+                ImageBackend::Iterm2 { .. } => match iterm2_payload(&zoomed) {
+                    Ok((size, b64)) => BlendedImage::Iterm2(size, b64),
+                    Err(_) => continue,
+                },
+                ImageBackend::Dithered { colors, dither, .. } => BlendedImage::Dithered(Box::new(
+                    DitheredImage::new(zoomed.clone(), *colors, *dither),
+                )),
+            };
+            zoom.images.insert(img.path.clone(), zoomed);
+            zoom.frames.insert(img.path.clone(), frame);
+        }
+        self.needs_clear = true;
+    }
+
+    /// Copy the current slide's link on the published deck (frontmatter
+    /// `web_url`) to the clipboard with OSC 52, which reaches the local
+    /// clipboard over ssh too. tmux drops it unless `set-clipboard` is `on`,
+    /// as `--tmux` sets it for its own server.
+    fn copy_link(&mut self) {
+        let text = match &self.frontmatter.web_url {
+            Some(base) => {
+                let base = base.split('#').next().unwrap_or_default();
+                let url = format!("{base}#/{}", self.deck.current() + 1);
+                let mut out = io::stdout();
+                let copied = write!(out, "\x1b]52;c;{}\x07", STANDARD.encode(&url))
+                    .and_then(|_| out.flush());
+                match copied {
+                    Ok(()) => format!("Copied {url}"),
+                    Err(e) => format!("Could not copy the link: {e}"),
+                }
+            }
+            None => "Set web_url in the frontmatter to copy slide links".to_string(),
+        };
+        self.notice = Some((text, Instant::now()));
+    }
+
+    /// Carry out what controllers asked for on the `--control` port and
+    /// tell them where the talk is.
+    fn poll_control(&mut self) {
+        while let Some(command) = self.control.as_ref().and_then(Control::poll) {
+            match command {
+                control::Command::Action(action) => {
+                    let quit_requested = self.take_quit_request();
+                    self.perform(action, quit_requested);
+                }
+                control::Command::Goto(page) => {
+                    self.record_input(Input::Goto(page));
+                    if self.deck.goto(page) {
+                        self.on_page_change();
+                    }
+                }
+            }
+        }
+        let now = self.now_ms();
+        let Some(control) = &mut self.control else {
+            return;
+        };
+        let page = self.deck.current();
+        let title = self.slides.get(page).and_then(|s| s.title.clone());
+        control.publish(&control::Status {
+            page,
+            total: self.slides.len(),
+            step: self.deck.step(),
+            title: title.unwrap_or_default(),
+            clock: ratride::timer::format_clock(self.timer.elapsed_ms(now)),
+            paused: self.timer.is_paused(),
+        });
+    }
+
     fn handle_events(&mut self) -> io::Result<()> {
         while event::poll(std::time::Duration::ZERO)? {
             match event::read()? {
@@ -494,32 +2595,32 @@ impl App {
                     if key.kind != KeyEventKind::Press {
                         continue;
                     }
-                    match key.code {
-                        KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
-                        KeyCode::Right | KeyCode::Char('l') | KeyCode::Char(' ') => {
-                            self.next_page()
-                        }
-                        KeyCode::Left | KeyCode::Char('h') => self.prev_page(),
-                        KeyCode::Char('j') | KeyCode::Down if self.can_scroll() => {
-                            *self.scroll_offset_mut() = self
-                                .scroll_offset()
-                                .saturating_add(1)
-                                .min(self.max_scroll());
-                        }
-                        KeyCode::Char('k') | KeyCode::Up if self.can_scroll() => {
-                            *self.scroll_offset_mut() = self.scroll_offset().saturating_sub(1);
-                        }
-                        KeyCode::Char('d') if self.can_scroll() => {
-                            *self.scroll_offset_mut() = self
-                                .scroll_offset()
-                                .saturating_add(10)
-                                .min(self.max_scroll());
-                        }
-                        KeyCode::Char('u') if self.can_scroll() => {
-                            *self.scroll_offset_mut() = self.scroll_offset().saturating_sub(10);
+                    // The first key press only dismisses startup warnings
+                    if !self.warnings.is_empty() {
+                        self.warnings.clear();
+                        continue;
+                    }
+                    // Then the next one answers the resume offer
+                    if let Some(state) = self.resume_offer.take() {
+                        if matches!(key.code, KeyCode::Char('y') | KeyCode::Enter) {
+                            self.resume(&state);
                         }
-                        _ => {}
+                        continue;
+                    }
+                    // Any key brings back a blanked slide and does nothing else
+                    let blanked = self.blank.shown().is_some();
+                    let action = key_name(key).and_then(|k| self.keymap.action(&k));
+                    let action = self.blank.press(action);
+                    if blanked {
+                        self.blank_changed();
+                        continue;
                     }
+                    // Any other key cancels a pending quit
+                    let quit_requested = self.take_quit_request();
+                    let Some(action) = action else {
+                        continue;
+                    };
+                    self.perform(action, quit_requested);
                 }
                 Event::Mouse(mouse) => {
                     match mouse.kind {
@@ -547,19 +2648,27 @@ impl App {
 
 /// Ratride - Markdown slide presenter for TUI & Web
 #[derive(Parser)]
-#[command(version)]
+#[command(
+    version,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
 struct Cli {
-    /// Path to the Markdown slide file
-    file: String,
+    #[command(subcommand)]
+    command: Option<Commands>,
 
-    /// Theme name [mocha (default), macchiato, frappe, latte]
-    #[arg(long, value_name = "NAME")]
-    theme: Option<String>,
+    /// Path or http(s) URL of the Markdown slide file (several decks with --export/--serve)
+    #[arg(required = true)]
+    files: Vec<String>,
 
     /// Export slides as a static HTML directory to the given path
     #[arg(long, value_name = "DIR")]
     export: Option<String>,
 
+    /// Write slide titles and speaker notes to a Markdown speaking script (`-` for stdout)
+    #[arg(long, value_name = "FILE")]
+    script: Option<String>,
+
     /// Start dev server with live reload
     #[arg(long)]
     serve: bool,
@@ -567,11 +2676,775 @@ struct Cli {
     /// Port for dev server
     #[arg(long, default_value_t = 3000)]
     port: u16,
+
+    #[command(flatten)]
+    present: PresentArgs,
+
+    #[command(flatten)]
+    terminal: TerminalArgs,
+
+    /// Print chapter markers for a recording from a --timings log
+    #[arg(long, value_name = "FILE")]
+    chapters: Option<String>,
+
+    /// Format of --chapters: `0:00 Title` lines or an FFmpeg metadata file
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        default_value = "youtube",
+        value_parser = ["youtube", "ffmetadata"]
+    )]
+    chapter_format: String,
+
+    /// Present in a new tmux session: the slides in one pane, the presenter
+    /// view in the other
+    #[arg(long)]
+    tmux: bool,
+}
+
+/// Options for presenting in this terminal that `serve-term` takes too, so
+/// `--tmux` can pass all of them on to the `serve-term` it starts.
+#[derive(Args)]
+struct PresentArgs {
+    /// Theme name [mocha (default), macchiato, frappe, latte, high-contrast] or a .toml theme file
+    #[arg(long, value_name = "NAME")]
+    theme: Option<String>,
+
+    /// Start in the presenter view: current and next slide, speaker notes and timer
+    #[arg(long)]
+    presenter: bool,
+
+    /// Show the speaker notes in a dimmed margin right of the slide, for
+    /// presenting from a single terminal
+    #[arg(long)]
+    notes_margin: bool,
+
+    /// Accept commands from external controllers on this localhost port
+    #[arg(long, value_name = "PORT")]
+    control: Option<u16>,
+
+    /// Log when each slide comes up to this JSON Lines file, for --chapters
+    #[arg(long, value_name = "FILE")]
+    timings: Option<String>,
+
+    /// Log every navigation action with its time to this JSON Lines file, for --replay
+    #[arg(long, value_name = "FILE")]
+    record_input: Option<String>,
+
+    /// Take the actions of a --record-input log again, with their original timing
+    #[arg(long, value_name = "FILE")]
+    replay: Option<String>,
+
+    /// Take audience votes on `<!-- poll -->` slides from a web page on this port
+    #[arg(long, value_name = "PORT")]
+    poll_port: Option<u16>,
+
+    /// Key bindings file [default: ~/.config/ratride/keys.toml]
+    #[arg(long, value_name = "FILE")]
+    keys: Option<String>,
+
+    /// Run the commands of `generate` directives and show their output
+    #[arg(long)]
+    generate: bool,
+
+    /// Like --generate, but also run the commands whose output is cached,
+    /// replacing what was cached
+    #[arg(long)]
+    regenerate: bool,
+
+    /// Set NAME for `<!-- if: NAME -->` guards (repeatable)
+    #[arg(long, value_name = "NAME")]
+    define: Vec<String>,
+
+    /// When the terminal can only show images as half blocks, reduce them
+    /// to this many colors, for terminals without true color
+    #[arg(long, value_name = "N", value_parser = ["16", "256"])]
+    dither_colors: Option<String>,
+
+    /// How images reduced to fewer colors are dithered: error diffusion
+    /// (default), an ordered pattern, or none
+    #[arg(long, value_name = "MODE", value_parser = ["diffusion", "ordered", "none"])]
+    dither: Option<String>,
+}
+
+impl PresentArgs {
+    /// These options as `serve-term` flags for `--tmux`, with paths made
+    /// absolute for the tmux session. `--presenter` is left out, as
+    /// [`ratride::tmux::present`] always passes it.
+    fn tmux_args(&self) -> io::Result<Vec<String>> {
+        let mut args = Vec::new();
+        if let Some(theme) = &self.theme {
+            let theme = if theme::is_theme_file(theme) {
+                std::path::absolute(theme)?.to_string_lossy().into_owned()
+            } else {
+                theme.clone()
+            };
+            args.extend(["--theme".to_string(), theme]);
+        }
+        if self.notes_margin {
+            args.push("--notes-margin".to_string());
+        }
+        if let Some(port) = self.control {
+            args.extend(["--control".to_string(), port.to_string()]);
+        }
+        if let Some(timings) = &self.timings {
+            let log = std::path::absolute(timings)?;
+            args.extend(["--timings".to_string(), log.to_string_lossy().into_owned()]);
+        }
+        if let Some(record_input) = &self.record_input {
+            let log = std::path::absolute(record_input)?;
+            let log = log.to_string_lossy().into_owned();
+            args.extend(["--record-input".to_string(), log]);
+        }
+        if let Some(replay) = &self.replay {
+            let log = Path::new(replay).canonicalize()?;
+            args.extend(["--replay".to_string(), log.to_string_lossy().into_owned()]);
+        }
+        if let Some(port) = self.poll_port {
+            args.extend(["--poll-port".to_string(), port.to_string()]);
+        }
+        if let Some(keys) = &self.keys {
+            let keys = Path::new(keys).canonicalize()?;
+            args.extend(["--keys".to_string(), keys.to_string_lossy().into_owned()]);
+        }
+        if self.regenerate {
+            args.push("--regenerate".to_string());
+        } else if self.generate {
+            args.push("--generate".to_string());
+        }
+        for name in &self.define {
+            args.extend(["--define".to_string(), name.clone()]);
+        }
+        if let Some(colors) = &self.dither_colors {
+            args.extend(["--dither-colors".to_string(), colors.clone()]);
+        }
+        if let Some(dither) = &self.dither {
+            args.extend(["--dither".to_string(), dither.clone()]);
+        }
+        Ok(args)
+    }
+}
+
+/// Options only for presenting in this terminal without broadcasting.
+#[derive(Args)]
+struct TerminalArgs {
+    /// Reload the deck when its file changes while presenting in the terminal,
+    /// and its images when theirs do
+    #[arg(long)]
+    watch: bool,
+
+    /// With --watch, jump to the first slide that changed on each reload
+    #[arg(long, requires = "watch")]
+    follow_edits: bool,
+
+    /// Print how long each startup step took (file read, parse, FIGlet,
+    /// terminal queries, first frame, image decoding) to stderr on exit
+    #[arg(long)]
+    profile_startup: bool,
+
+    /// Loop through the deck unattended, advancing every --advance seconds
+    #[arg(long)]
+    kiosk: bool,
+
+    /// With --kiosk, seconds each slide stays up (the least it does with --wpm)
+    #[arg(long, value_name = "SECS", default_value_t = 10.0, requires = "kiosk")]
+    advance: f64,
+
+    /// With --kiosk, keep each slide up as long as reading its words takes at
+    /// this many words per minute
+    #[arg(long, value_name = "WPM", requires = "kiosk")]
+    wpm: Option<f64>,
+
+    /// With --kiosk, seconds of "starting in" splash with the deck title before
+    /// playback begins (0 to skip)
+    #[arg(long, value_name = "SECS", default_value_t = 5.0, requires = "kiosk")]
+    countdown: f64,
+}
+
+impl TerminalArgs {
+    /// The first of these options given, which `--tmux` has no way to pass on.
+    fn first_given(&self) -> Option<&'static str> {
+        [
+            (self.watch, "--watch"),
+            (self.profile_startup, "--profile-startup"),
+            (self.kiosk, "--kiosk"),
+        ]
+        .into_iter()
+        .find_map(|(given, flag)| given.then_some(flag))
+    }
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Report unknown directives, bad frontmatter values and missing images
+    Check {
+        /// Path to the Markdown slide file
+        file: String,
+    },
+    /// Render slides to stdout with ANSI colors, without the interactive UI
+    Print {
+        /// Path to the Markdown slide file
+        file: String,
+
+        /// Only print this slide (1-based)
+        #[arg(long, value_name = "N")]
+        slide: Option<usize>,
+
+        /// Set NAME for `<!-- if: NAME -->` guards (repeatable)
+        #[arg(long, value_name = "NAME")]
+        define: Vec<String>,
+
+        /// Theme name [mocha (default), macchiato, frappe, latte, high-contrast] or a .toml theme file
+        #[arg(long, value_name = "NAME")]
+        theme: Option<String>,
+    },
+    /// List titles, sections, notes, images, word counts and estimated
+    /// durations of the slides
+    Meta {
+        /// Path to the Markdown slide file
+        file: String,
+
+        /// Print everything as JSON instead of a chapter list
+        #[arg(long)]
+        json: bool,
+
+        /// Speaking rate durations are estimated at, in words per minute
+        #[arg(long, value_name = "WPM", default_value_t = meta::DEFAULT_WPM)]
+        wpm: f64,
+
+        /// Set NAME for `<!-- if: NAME -->` guards (repeatable)
+        #[arg(long, value_name = "NAME")]
+        define: Vec<String>,
+    },
+    /// Present in this terminal while streaming the current slide to viewers
+    /// who connect with `telnet <host> <port>`
+    ServeTerm {
+        /// Path to the Markdown slide file
+        file: String,
+
+        /// Port viewers connect to
+        #[arg(long, default_value_t = 2323)]
+        port: u16,
+
+        /// Address to listen on; 0.0.0.0 lets viewers on other machines connect
+        #[arg(long, value_name = "ADDR", default_value = "127.0.0.1")]
+        bind: IpAddr,
+
+        /// Open a tmux pane beside this one that follows the broadcast (used by --tmux)
+        #[arg(long, hide = true)]
+        tmux_viewer: bool,
+
+        #[command(flatten)]
+        present: PresentArgs,
+    },
+    /// Show a serve-term presentation running at HOST:PORT in this terminal
+    Follow {
+        /// Address of the presenter's serve-term, e.g. 127.0.0.1:2323
+        address: String,
+    },
+    /// Time parsing, per-slide rendering and transition frames for a deck
+    Bench {
+        /// Path to the Markdown slide file
+        file: String,
+
+        /// Columns to render at
+        #[arg(long, default_value_t = 80)]
+        width: u16,
+
+        /// Rows to render at
+        #[arg(long, default_value_t = 24)]
+        height: u16,
+
+        /// Draws averaged per slide
+        #[arg(long, default_value_t = 100)]
+        iterations: u32,
+
+        /// Theme name [mocha (default), macchiato, frappe, latte, high-contrast] or a .toml theme file
+        #[arg(long, value_name = "NAME")]
+        theme: Option<String>,
+    },
+    /// Bundle a deck with its images, media, logo and web font into one
+    /// `.ratride` file that can be presented directly
+    Pack {
+        /// Path to the Markdown slide file
+        file: String,
+
+        /// Archive to write [default: the deck's name with `.ratride`]
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<String>,
+    },
+    /// Render a sample slide in every built-in theme
+    Themes,
+    /// List the bundled FIGlet fonts usable in `<!-- figlet:font -->`
+    Fonts,
+    /// Inspect built-in themes
+    Theme {
+        #[command(subcommand)]
+        command: ThemeCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum ThemeCommand {
+    /// Print a theme as TOML, as a starting point for a custom theme
+    Dump {
+        /// Theme name [mocha, macchiato, frappe, latte, high-contrast]
+        name: String,
+    },
+}
+
+/// `path` itself, or for an http(s) URL the downloaded copy of the deck, or
+/// for a `.ratride` archive the unpacked one.
+fn local_deck_path(path: &str) -> io::Result<PathBuf> {
+    if ratride::remote::is_url(path) {
+        ratride::remote::fetch_deck(path)
+    } else if ratride::pack::is_archive(path) {
+        ratride::pack::unpack(Path::new(path))
+    } else {
+        Ok(PathBuf::from(path))
+    }
+}
+
+/// Present `path` in the terminal, optionally broadcasting it to viewers on
+/// `broadcast`, or else with the `terminal` options. With `--watch`, reload
+/// the deck or its images whenever their files change (see [`DeckWatch`]).
+fn present(
+    path: &str,
+    args: &PresentArgs,
+    broadcast: Option<TcpListener>,
+    terminal: Option<&TerminalArgs>,
+) -> io::Result<()> {
+    let mut profile = terminal
+        .is_some_and(|t| t.profile_startup)
+        .then(StartupProfile::new);
+    let mut generator = generator(args.generate, args.regenerate);
+    if generator.is_some() && (ratride::remote::is_url(path) || ratride::pack::is_archive(path)) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "--generate only runs the commands of local decks",
+        ));
+    }
+    let path = local_deck_path(path)?;
+    let base_dir = path.parent().unwrap_or(Path::new("."));
+    let markdown = read_deck(&path, generator.as_mut())?;
+    if let Some(profile) = &mut profile {
+        profile.step("read file");
+    }
+
+    let (mut frontmatter, body) = parse_frontmatter(&markdown);
+    frontmatter.defines = args.define.clone();
+    let dither_colors = args.dither_colors.as_deref().and_then(parse_dither_colors);
+    frontmatter.dither_colors = dither_colors.or(frontmatter.dither_colors);
+    let dither = args.dither.as_deref().and_then(parse_dither);
+    frontmatter.dither = dither.or(frontmatter.dither);
+    if let Some(profile) = &mut profile {
+        profile.step("frontmatter");
+    }
+    if frontmatter.big_headings == Some(BigHeadings::Auto) {
+        frontmatter.big_headings = Some(detect_big_headings());
+        if let Some(profile) = &mut profile {
+            profile.step("heading query");
+        }
+    }
+
+    let theme = resolve_theme(args.theme.as_deref(), &frontmatter, base_dir)?;
+    let (body, math_warnings) = render_math(body, &frontmatter, &theme);
+    let body = body.as_ref();
+
+    let broadcast = match broadcast {
+        Some(listener) => {
+            let figlet = deck_figlet(base_dir);
+            let (slides, _) = parse_slides(body, &theme, &frontmatter, Some(&figlet), false);
+            Some(Broadcast::start(slides, listener))
+        }
+        None => None,
+    };
+    if let (Some(profile), Some(_)) = (&mut profile, &broadcast) {
+        profile.step("broadcast");
+    }
+
+    let mut app = App::new(body, base_dir, theme, &frontmatter, profile);
+    if !math_warnings.is_empty() {
+        app.warnings.extend(math_warnings);
+        app.warnings.sort_by_key(|w| w.line);
+    }
+    // Presenter-only slides are shown here only while broadcasting, when
+    // this terminal is the presenter's rather than the audience's.
+    app.deck.set_presenter(broadcast.is_some());
+    app.deck.skip_hidden(&app.slides);
+    app.broadcast = broadcast;
+    app.presenter_view = args.presenter;
+    app.notes_margin = args.notes_margin;
+    app.kiosk = terminal
+        .filter(|t| t.kiosk)
+        .map(|t| Kiosk::new(t.advance, t.countdown, t.wpm));
+    app.keymap = load_keymap(args.keys.as_deref())?;
+    if let Some(port) = args.control {
+        app.control = Some(Control::start(port)?);
+    }
+    if let Some(timings) = &args.timings {
+        app.timings = Some(TimingLog::create(Path::new(timings))?);
+    }
+    if let Some(record_input) = &args.record_input {
+        app.input_log = Some(InputLog::create(Path::new(record_input))?);
+    }
+    if let Some(replay) = &args.replay {
+        let events = session::parse_log(&std::fs::read_to_string(replay)?)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{replay}: {e}")))?;
+        app.replay = Some(Replay::new(events));
+    }
+    if let Some(port) = args.poll_port {
+        app.polls = Some(PollServer::start(port)?);
+    }
+    if let Some(terminal) = terminal.filter(|t| t.watch) {
+        app.watch = Some(DeckWatch::start(
+            &path,
+            args.theme.as_deref(),
+            terminal.follow_edits,
+            generator,
+        )?);
+        app.watch_files();
+    }
+    // Nobody is there to answer the offer in kiosk mode, and recorded and
+    // replayed talks start from the first slide
+    if app.kiosk.is_none() && app.input_log.is_none() && app.replay.is_none() {
+        app.resume_offer = recovery::load(&path).filter(Recovery::is_resumable);
+    }
+    app.recovery_deck = Some(path.clone());
+    let terminal = ratatui::init();
+    install_panic_hook();
+    let result = app.run(terminal);
+    restore_terminal();
+    if let Some(profile) = &app.profile {
+        eprint!("{}", profile.report());
+    }
+    result
+}
+
+/// Run `write` on the locked stdout. Output cut short because the reader
+/// went away, as in `ratride meta talk.md | head`, is no error.
+fn write_stdout(write: impl FnOnce(&mut io::StdoutLock) -> io::Result<()>) -> io::Result<()> {
+    match write(&mut io::stdout().lock()) {
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        result => result,
+    }
+}
+
+/// What runs `generate` directives with `--generate`, replacing their cached
+/// output with `--regenerate`.
+fn generator(generate: bool, regenerate: bool) -> Option<Generator> {
+    (generate || regenerate).then(|| Generator::new(regenerate))
+}
+
+/// The deck at `path`, with `generate` directives run by `generator` if any.
+fn read_deck(path: &Path, generator: Option<&mut Generator>) -> io::Result<String> {
+    let markdown = std::fs::read_to_string(path)?;
+    let Some(generator) = generator else {
+        return Ok(markdown);
+    };
+    let dir = path.parent().unwrap_or(Path::new("."));
+    Ok(generator.expand(&markdown, dir).into_owned())
+}
+
+/// With frontmatter `math: latex` or `math: typst`, swap the display
+/// formulas in `body` for images of them in the theme's text color.
+fn render_math<'a>(
+    body: &'a str,
+    frontmatter: &Frontmatter,
+    theme: &Theme,
+) -> (Cow<'a, str>, Vec<ParseWarning>) {
+    let color = match theme.fg {
+        ratatui::style::Color::Rgb(r, g, b) => (r, g, b),
+        _ => (255, 255, 255),
+    };
+    let renderer = frontmatter.math.unwrap_or_default();
+    if renderer == math::MathRenderer::Unicode || !body.contains("$$") {
+        return (Cow::Borrowed(body), Vec::new());
+    }
+    let cache_dir = match ratride::cache::cache_dir("math") {
+        Ok(dir) => dir,
+        Err(e) => {
+            let warning = ParseWarning {
+                line: frontmatter.body_line,
+                message: format!("could not render math as images: {e}"),
+            };
+            return (Cow::Borrowed(body), vec![warning]);
+        }
+    };
+    math::render_images(body, renderer, color, &cache_dir, frontmatter.body_line)
+}
+
+/// Key bindings from `path`, or from the user's config file if there is one.
+fn load_keymap(path: Option<&str>) -> io::Result<Keymap> {
+    let path = match path {
+        Some(path) => PathBuf::from(path),
+        None => {
+            let config = std::env::var_os("XDG_CONFIG_HOME")
+                .map(PathBuf::from)
+                .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")));
+            match config.map(|dir| dir.join("ratride").join("keys.toml")) {
+                Some(path) if path.exists() => path,
+                _ => return Ok(Keymap::default()),
+            }
+        }
+    };
+    let text = std::fs::read_to_string(&path)?;
+    Keymap::from_toml(&text).map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}: {e}", path.display()),
+        )
+    })
+}
+
+/// Leave the alternate screen and raw mode, and stop mouse reporting.
+fn restore_terminal() {
+    let _ = crossterm::execute!(io::stdout(), DisableMouseCapture);
+    ratatui::restore();
+}
+
+/// Restore the terminal before a panic message is printed. `ratatui::init`
+/// already restores raw mode and the alternate screen, but not mouse capture.
+fn install_panic_hook() {
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        restore_terminal();
+        hook(info);
+    }));
+}
+
+/// `ratride print`: render the deck (or one slide) to stdout at the terminal's size.
+fn print(
+    file: &str,
+    slide: Option<usize>,
+    theme_name: Option<&str>,
+    defines: &[String],
+) -> io::Result<()> {
+    let path = local_deck_path(file)?;
+    let markdown = std::fs::read_to_string(&path)?;
+    let (mut frontmatter, body) = parse_frontmatter(&markdown);
+    frontmatter.defines = defines.to_vec();
+    let base_dir = path.parent().unwrap_or(Path::new("."));
+    let theme = resolve_theme(theme_name, &frontmatter, base_dir)?;
+    let figlet = deck_figlet(base_dir);
+    let (slides, _) = parse_slides(body, &theme, &frontmatter, Some(&figlet), false);
+    let slides = match slide {
+        Some(n) => {
+            let Some(s) = n.checked_sub(1).and_then(|i| slides.get(i)) else {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("slide {n} out of range (1-{})", slides.len()),
+                ));
+            };
+            std::slice::from_ref(s)
+        }
+        None => &slides,
+    };
+    let area = terminal_area();
+    ratride::print::print_slides(slides, area.width, area.height, &mut io::stdout().lock())
+}
+
+/// Slide shown by `ratride themes`, touching the styles a theme colors.
+const THEME_SAMPLE: &str = r#"# Heading
+
+Body text with **bold**, *italic*, `inline code` and a [link](https://example.com).
+
+- List item
+  - Nested item
+
+> Block quote
+
+```rust
+fn main() {
+    println!("hello");
+}
+```
+"#;
+
+/// `ratride themes`: print the sample slide once per built-in theme.
+fn print_themes() -> io::Result<()> {
+    let frontmatter = Frontmatter {
+        bg_fill: Some(true),
+        ..Frontmatter::default()
+    };
+    let width = terminal_area().width;
+    let mut out = io::stdout().lock();
+    for (i, name) in theme::THEME_NAMES.iter().enumerate() {
+        let Some(theme) = theme::theme_from_name(name) else {
+            continue;
+        };
+        let (slides, _) = parse_slides(
+            THEME_SAMPLE,
+            &theme,
+            &frontmatter,
+            Some(&render_figlet),
+            false,
+        );
+        if i > 0 {
+            writeln!(out)?;
+        }
+        writeln!(out, "{name}")?;
+        ratride::print::print_slides(&slides, width, 0, &mut out)?;
+    }
+    Ok(())
+}
+
+/// `ratride bench`: time parsing, rendering and transitions of `file`.
+fn bench(
+    file: &str,
+    width: u16,
+    height: u16,
+    iterations: u32,
+    theme_name: Option<&str>,
+) -> io::Result<()> {
+    let ms = |d: std::time::Duration| d.as_secs_f64() * 1000.0;
+    let path = local_deck_path(file)?;
+    let markdown = std::fs::read_to_string(&path)?;
+    let start = Instant::now();
+    let (frontmatter, body) = parse_frontmatter(&markdown);
+    let base_dir = path.parent().unwrap_or(Path::new("."));
+    let theme = resolve_theme(theme_name, &frontmatter, base_dir)?;
+    let figlet = deck_figlet(base_dir);
+    let (slides, _) = parse_slides(body, &theme, &frontmatter, Some(&figlet), false);
+    let parse_ms = ms(start.elapsed());
+    println!("parse: {parse_ms:.3} ms ({} slides)", slides.len());
+    println!("slide  render ms  transition");
+    for (i, slide) in slides.iter().enumerate() {
+        let render = bench::time_render(slide, width, height, iterations);
+        let transition = i
+            .checked_sub(1)
+            .and_then(|prev| {
+                bench::time_transition(&slides[prev], slide, width, height, LINE_DUR_MS, STAGGER_MS)
+            })
+            .map_or("-".to_string(), |(frames, per_frame)| {
+                let name = slide.transition.name().unwrap_or_default();
+                format!("{:.3} ms/frame x {frames} ({name})", ms(per_frame))
+            });
+        println!("{:>5}  {:>9.3}  {transition}", i + 1, ms(render));
+    }
+    Ok(())
+}
+
+/// Print chapters for a recording of the deck at `path` from the timing
+/// log at `log`, titling slides the log has no title for from the deck.
+fn print_chapters(path: &str, log: &str, format: &str) -> io::Result<()> {
+    let format = ChapterFormat::from_name(format).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("unknown chapter format `{format}`"),
+        )
+    })?;
+    let timings = chapters::parse_log(&std::fs::read_to_string(log)?)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{log}: {e}")))?;
+    let markdown = std::fs::read_to_string(local_deck_path(path)?)?;
+    let (frontmatter, body) = parse_frontmatter(&markdown);
+    let (slides, _) = parse_slides(body, &Theme::default(), &frontmatter, None, false);
+    let chapters = chapters::chapters(&timings, &slides);
+    print!("{}", chapters::format_chapters(&chapters, format));
+    Ok(())
 }
 
 fn main() -> io::Result<()> {
     let cli = Cli::parse();
 
+    match &cli.command {
+        Some(Commands::Check { file }) => {
+            let warnings = collect_warnings(file)?;
+            write_stdout(|out| {
+                for w in &warnings {
+                    writeln!(out, "{}:{}: {}", file, w.line, w.message)?;
+                }
+                Ok(())
+            })?;
+            if !warnings.is_empty() {
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+        Some(Commands::Print {
+            file,
+            slide,
+            theme,
+            define,
+        }) => {
+            return print(file, *slide, theme.as_deref(), define);
+        }
+        Some(Commands::Meta {
+            file,
+            json,
+            wpm,
+            define,
+        }) => {
+            let markdown = std::fs::read_to_string(local_deck_path(file)?)?;
+            let (mut frontmatter, body) = parse_frontmatter(&markdown);
+            frontmatter.defines = define.clone();
+            let (slides, _) = parse_slides(body, &Theme::default(), &frontmatter, None, false);
+            let deck = meta::deck_meta(&slides, *wpm);
+            return write_stdout(|out| {
+                if *json {
+                    writeln!(out, "{}", meta::to_json(&deck))
+                } else {
+                    write!(out, "{}", meta::summary(&deck))
+                }
+            });
+        }
+        Some(Commands::ServeTerm {
+            file,
+            port,
+            bind,
+            tmux_viewer,
+            present: args,
+        }) => {
+            let listener = TcpListener::bind((*bind, *port))?;
+            if *tmux_viewer {
+                ratride::tmux::open_viewer(&std::env::current_exe()?, listener.local_addr()?)?;
+            }
+            return present(file, args, Some(listener), None);
+        }
+        Some(Commands::Follow { address }) => {
+            return ratride::serve_term::follow(address);
+        }
+        Some(Commands::Bench {
+            file,
+            width,
+            height,
+            iterations,
+            theme,
+        }) => {
+            return bench(file, *width, *height, *iterations, theme.as_deref());
+        }
+        Some(Commands::Pack { file, output }) => {
+            let deck = Path::new(file);
+            let output = match output {
+                Some(output) => PathBuf::from(output),
+                None => deck.with_extension("ratride"),
+            };
+            return ratride::pack::pack(deck, &output);
+        }
+        Some(Commands::Themes) => {
+            return print_themes();
+        }
+        Some(Commands::Fonts) => {
+            for font in ratride::figlet::builtin_fonts() {
+                println!("{font}");
+            }
+            return Ok(());
+        }
+        Some(Commands::Theme {
+            command: ThemeCommand::Dump { name },
+        }) => {
+            let theme = theme::theme_from_name(name).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("unknown theme `{name}`"),
+                )
+            })?;
+            print!("{}", theme.to_toml());
+            return Ok(());
+        }
+        None => {}
+    }
+
     if cli.serve {
         let out_dir = cli.export.clone().unwrap_or_else(|| {
             std::env::temp_dir()
@@ -579,33 +3452,54 @@ fn main() -> io::Result<()> {
                 .to_string_lossy()
                 .to_string()
         });
-        return ratride::serve::serve(&cli.file, &out_dir, cli.theme.as_deref(), cli.port);
+        return ratride::serve::serve(&cli.files, &out_dir, cli.present.theme.as_deref(), cli.port);
     }
 
-    if let Some(out_dir) = &cli.export {
-        return ratride::export::export(&cli.file, out_dir, cli.theme.as_deref());
+    if let Some(out) = &cli.script {
+        let [path] = cli.files.as_slice() else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "--script takes a single file",
+            ));
+        };
+        return ratride::export::export_script(&local_deck_path(path)?, out);
     }
 
-    let path = &cli.file;
-    let base_dir = Path::new(path).parent().unwrap_or(Path::new("."));
-    let markdown = std::fs::read_to_string(path)?;
-
-    let (frontmatter, body) = parse_frontmatter(&markdown);
+    if let Some(log) = &cli.chapters {
+        let [path] = cli.files.as_slice() else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "--chapters takes a single file",
+            ));
+        };
+        return print_chapters(path, log, &cli.chapter_format);
+    }
 
-    let theme = cli
-        .theme
-        .as_deref()
-        .and_then(theme::theme_from_name)
-        .or_else(|| {
-            frontmatter
-                .theme
-                .as_deref()
-                .and_then(theme::theme_from_name)
-        })
-        .unwrap_or_default();
+    if let Some(out_dir) = &cli.export {
+        return ratride::export::export(
+            &cli.files,
+            out_dir,
+            cli.present.theme.as_deref(),
+            generator(cli.present.generate, cli.present.regenerate).as_mut(),
+            &cli.present.define,
+        );
+    }
 
-    let terminal = ratatui::init();
-    let result = App::new(body, base_dir, theme, &frontmatter).run(terminal);
-    ratatui::restore();
-    result
+    let [path] = cli.files.as_slice() else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "presenting in the terminal takes a single file; use --export or --serve for several decks",
+        ));
+    };
+    if cli.tmux {
+        if let Some(flag) = cli.terminal.first_given() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{flag} is not supported with --tmux"),
+            ));
+        }
+        let args = cli.present.tmux_args()?;
+        return ratride::tmux::present(&std::env::current_exe()?, path, &args);
+    }
+    present(path, &cli.present, None, Some(&cli.terminal))
 }
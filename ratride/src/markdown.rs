@@ -1,12 +1,40 @@
-use crate::theme::Theme;
-use pulldown_cmark::{CodeBlockKind, Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+use crate::bigtext;
+use crate::color::blend_color;
+use crate::highlight::{self, Highlighter, HighlighterKind, guess_language, parse_highlighter};
+use crate::math::{self, MathRenderer};
+use crate::theme::{DiffColors, DivStyle, Theme};
+use pulldown_cmark::{
+    BlockQuoteKind, CodeBlockKind, Event, HeadingLevel, Options, Parser, Tag, TagEnd,
+    TextMergeWithOffset,
+};
+use ratatui::layout::Alignment;
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span, Text};
-use syntect::parsing::SyntaxSet;
+use std::borrow::Cow;
+use std::ops::Range;
+use unicode_width::UnicodeWidthStr;
 
 /// Default line-height multiplier when not specified in frontmatter or directives.
 pub const DEFAULT_LINE_HEIGHT: f64 = 1.2;
 
+/// Width/height ratio of a terminal cell when neither the frontmatter nor
+/// the terminal says otherwise: cells are about twice as tall as wide.
+pub const DEFAULT_CELL_ASPECT: f64 = 0.5;
+
+/// A non-fatal problem found while parsing a deck.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseWarning {
+    /// 1-based line number in the source file.
+    pub line: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
 /// A single header item, optionally linking to a URL.
 #[derive(Clone, Debug)]
 pub struct HeaderItem {
@@ -47,6 +75,116 @@ pub enum FigletWebMode {
     Original,
 }
 
+/// Terminal protocol used to draw H1 headings at double size.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum BigHeadings {
+    /// Render headings at normal size (default).
+    #[default]
+    Off,
+    /// Use whichever protocol the terminal supports. Frontends resolve this to
+    /// a concrete mode before parsing; left unresolved it behaves like `Off`.
+    Auto,
+    /// Kitty text-sizing protocol (`OSC 66`).
+    Kitty,
+    /// DEC double-width/double-height line attributes (DECDHL).
+    Dec,
+}
+
+impl BigHeadings {
+    /// Whether headings should reserve space and record double-size metadata.
+    pub fn is_active(self) -> bool {
+        matches!(self, BigHeadings::Kitty | BigHeadings::Dec)
+    }
+}
+
+fn parse_big_headings(value: &str) -> BigHeadings {
+    match value {
+        "" | "true" | "auto" => BigHeadings::Auto,
+        "kitty" => BigHeadings::Kitty,
+        "dec" | "decdhl" => BigHeadings::Dec,
+        _ => BigHeadings::Off,
+    }
+}
+
+/// Palette images are reduced to on terminals that can only draw them as
+/// half blocks (frontmatter `dither_colors: 16`).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DitherColors {
+    /// The 16 ANSI colors.
+    Ansi16,
+    /// The 240 colors of the xterm color cube and gray ramp.
+    Xterm256,
+}
+
+/// How the colors lost to a [`DitherColors`] palette are made up for
+/// (frontmatter `dither: ordered`).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Dither {
+    /// Nearest color only, in flat bands.
+    None,
+    /// A fixed 4x4 Bayer pattern.
+    Ordered,
+    /// Floyd-Steinberg error diffusion (default).
+    #[default]
+    Diffusion,
+}
+
+/// Parse `--dither-colors` or frontmatter `dither_colors`.
+pub fn parse_dither_colors(value: &str) -> Option<DitherColors> {
+    match value {
+        "16" => Some(DitherColors::Ansi16),
+        "256" => Some(DitherColors::Xterm256),
+        _ => None,
+    }
+}
+
+/// Parse `--dither` or frontmatter `dither`.
+pub fn parse_dither(value: &str) -> Option<Dither> {
+    match value {
+        "none" => Some(Dither::None),
+        "ordered" | "bayer" => Some(Dither::Ordered),
+        "diffusion" | "floyd-steinberg" => Some(Dither::Diffusion),
+        _ => None,
+    }
+}
+
+/// Corner of the slide the frontmatter `logo` is drawn in.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum LogoCorner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+}
+
+fn parse_logo_corner(value: &str) -> Option<LogoCorner> {
+    match value {
+        "top-left" => Some(LogoCorner::TopLeft),
+        "top-right" => Some(LogoCorner::TopRight),
+        "bottom-left" => Some(LogoCorner::BottomLeft),
+        "bottom-right" => Some(LogoCorner::BottomRight),
+        _ => None,
+    }
+}
+
+/// Section progress drawn in the status bar (frontmatter `agenda`).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Agenda {
+    /// A dot per section.
+    Dots,
+    /// Each section's title, shortened.
+    Labels,
+}
+
+fn parse_agenda(value: &str) -> Option<Agenda> {
+    match value {
+        "dots" => Some(Agenda::Dots),
+        "labels" => Some(Agenda::Labels),
+        _ => None,
+    }
+}
+
 /// File-wide defaults parsed from YAML frontmatter (`--- ... ---`).
 #[derive(Clone, Debug, Default)]
 pub struct Frontmatter {
@@ -58,13 +196,96 @@ pub struct Frontmatter {
     /// `Some(None)` = default figlet font, `Some(Some("slant"))` = named font.
     pub figlet: Option<Option<String>>,
     pub bg_fill: Option<bool>,
+    /// Render all body text bold (frontmatter `bold_all: true`).
+    pub bold_all: Option<bool>,
+    /// Curly quotes, dashes and ellipses (frontmatter `smart_punctuation: false`
+    /// keeps the text as typed).
+    pub smart_punctuation: Option<bool>,
+    /// Number the lines of every code block (frontmatter `line_numbers: true`);
+    /// single blocks opt in with ```` ```rust {numbers} ```` or out with
+    /// `{no-numbers}`.
+    pub line_numbers: Option<bool>,
+    /// Capitalize the words of H1 headings (frontmatter `title_case: true`).
+    pub title_case: Option<bool>,
+    /// Start with the reading ruler on (frontmatter `reading_ruler: true`).
+    pub reading_ruler: Option<bool>,
+    /// Keep each slide's scroll position when returning to it, instead of
+    /// starting at the top (frontmatter `remember_scroll: true`).
+    pub remember_scroll: Option<bool>,
+    /// Ask for a second `q` before quitting (frontmatter `confirm_quit: true`).
+    pub confirm_quit: Option<bool>,
+    /// Length of a break started with `B`, in minutes (frontmatter `break_minutes: 15`).
+    pub break_minutes: Option<f64>,
+    /// Show a box with the path and error where an image failed to load
+    /// (frontmatter `image_errors: false` to leave the space blank).
+    pub image_errors: Option<bool>,
     /// Color argument for figrat. When set, `figrat --color "<value>"` is used
     /// instead of `figlet`.
     pub figlet_color: Option<String>,
     /// Header items displayed at top-right, overlaying the content area.
     pub header: Option<Vec<HeaderItem>>,
+    /// Image drawn small in a corner of every slide (frontmatter `logo: logo.png`).
+    pub logo: Option<String>,
+    /// Corner for `logo` (frontmatter `logo_position: top-left`; bottom-right by default).
+    pub logo_position: Option<LogoCorner>,
+    /// Section progress in the status bar (frontmatter `agenda: dots` or
+    /// `agenda: labels`).
+    pub agenda: Option<Agenda>,
+    /// How display formulas are drawn (frontmatter `math: unicode`, `latex`
+    /// or `typst`). Without it, `$` is plain text.
+    pub math: Option<MathRenderer>,
     /// How figlet headings are rendered on the web.
     pub figlet_web: Option<FigletWebMode>,
+    /// Target width/height ratio of the slide area (e.g. `16:9` → 1.777…).
+    /// The slide is letterboxed to this ratio, centered in the screen.
+    pub aspect: Option<f64>,
+    /// Width/height ratio of a terminal cell (frontmatter `cell_aspect: 0.5`),
+    /// for sizing images on terminals whose font the image picker can't
+    /// measure or gets wrong.
+    pub cell_aspect: Option<f64>,
+    /// Palette of half-block images (frontmatter `dither_colors: 256`);
+    /// full color when neither this nor `dither` is set.
+    pub dither_colors: Option<DitherColors>,
+    /// Dithering of half-block images in a reduced palette.
+    pub dither: Option<Dither>,
+    /// Draw box-drawing characters, bullets and quote bars as ASCII, and
+    /// math as its TeX source, for fonts without them (frontmatter
+    /// `ascii_only: true`).
+    pub ascii_only: Option<bool>,
+    /// Smallest terminal, in columns and rows, the deck is drawn in
+    /// (frontmatter `min_size: 60x18`); smaller ones get a notice instead.
+    pub min_size: Option<(u16, u16)>,
+    /// Syntax highlighting backend for code blocks (frontmatter
+    /// `highlighter: tree-sitter`).
+    pub highlighter: Option<HighlighterKind>,
+    /// Guess the language of code blocks without one (frontmatter
+    /// `detect_language: true`).
+    pub detect_language: Option<bool>,
+    /// Draw H1 headings at double size on supporting terminals.
+    pub big_headings: Option<BigHeadings>,
+    /// Webfont file (path or URL) used for text on the web.
+    pub web_font: Option<String>,
+    /// CSS snippet injected into the hosting page on the web.
+    pub web_css: Option<String>,
+    /// Where the deck is published on the web; slide links append `#/<n>`.
+    pub web_url: Option<String>,
+    /// Origins besides the player's own whose pages may drive an embedded
+    /// deck with `postMessage` (frontmatter `web_embed_origins:
+    /// https://blog.example.com`, several separated by spaces).
+    pub web_embed_origins: Vec<String>,
+    /// Names set with `--define` for `<!-- if: NAME -->` guards (not read
+    /// from the YAML).
+    pub defines: Vec<String>,
+    /// Transitions the player registered beyond the built-ins (see
+    /// `TransitionRegistry::names`; not read from the YAML). `parse_slides`
+    /// warns about any other name, which falls back to `slide`.
+    pub transitions: Vec<String>,
+    /// Line of the `transition` key, for that warning.
+    pub transition_line: usize,
+    /// Number of source lines before the body (the frontmatter block).
+    pub body_line: usize,
+    /// Problems found in the frontmatter.
+    pub warnings: Vec<ParseWarning>,
 }
 
 fn parse_figlet_web_mode(value: &str) -> FigletWebMode {
@@ -76,6 +297,22 @@ fn parse_figlet_web_mode(value: &str) -> FigletWebMode {
     }
 }
 
+/// Parse an aspect ratio like `16:9`, `4/3` or `1.6` into a width/height ratio.
+fn parse_aspect(value: &str) -> Option<f64> {
+    let ratio = match value.split_once([':', '/']) {
+        Some((w, h)) => w.trim().parse::<f64>().ok()? / h.trim().parse::<f64>().ok()?,
+        None => value.parse::<f64>().ok()?,
+    };
+    (ratio.is_finite() && ratio > 0.0).then_some(ratio)
+}
+
+/// Parse a terminal size like `80x24` (or `80×24`) into columns and rows.
+pub fn parse_terminal_size(value: &str) -> Option<(u16, u16)> {
+    let (w, h) = value.split_once(['x', '×'])?;
+    let (w, h) = (w.trim().parse().ok()?, h.trim().parse().ok()?);
+    (w > 0 && h > 0).then_some((w, h))
+}
+
 /// Extract YAML frontmatter from the beginning of a markdown string.
 ///
 /// Returns the parsed `Frontmatter` and the remaining markdown body (with the
@@ -118,12 +355,110 @@ pub fn parse_frontmatter(input: &str) -> (Frontmatter, &str) {
         None => return (Frontmatter::default(), input),
     };
 
-    let mut fm = Frontmatter::default();
+    let mut fm = Frontmatter {
+        body_line: input[..input.len() - body.len()].matches('\n').count(),
+        ..Default::default()
+    };
+    let yaml_line = input[..input.len() - after_open.len()]
+        .matches('\n')
+        .count()
+        + 1;
+
+    match serde_yaml::from_str::<serde_yaml::Value>(yaml_block) {
+        Ok(serde_yaml::Value::Mapping(map)) => {
+            for (key, value) in &map {
+                let Some(key) = key.as_str() else {
+                    continue;
+                };
+                let line = yaml_key_line(yaml_block, key).map_or(yaml_line, |i| yaml_line + i);
+                match (key, value) {
+                    ("header", serde_yaml::Value::Sequence(items)) => {
+                        let mut header = Vec::new();
+                        for item in items {
+                            match yaml_scalar(item, None) {
+                                Some(item) => header.push(parse_header_item(&item)),
+                                None => fm.warnings.push(nested_yaml_warning(key, line)),
+                            }
+                        }
+                        header.retain(|item| !item.text.is_empty());
+                        if !header.is_empty() {
+                            fm.header = Some(header);
+                        }
+                    }
+                    _ => match yaml_scalar(value, yaml_value_text(yaml_block, key)) {
+                        Some(value) => apply_frontmatter_key(&mut fm, key, value.trim(), line),
+                        None => fm.warnings.push(nested_yaml_warning(key, line)),
+                    },
+                }
+            }
+        }
+        Ok(serde_yaml::Value::Null) => {}
+        Ok(_) => fm.warnings.push(ParseWarning {
+            line: yaml_line,
+            message: "frontmatter is not a key/value mapping".to_string(),
+        }),
+        Err(err) => {
+            // Older decks may contain values that aren't valid YAML
+            // (e.g. unquoted `- [text](url)` header items), so keep reading them.
+            let line = err
+                .location()
+                .map_or(yaml_line, |loc| yaml_line + loc.line().saturating_sub(1));
+            fm.warnings.push(ParseWarning {
+                line,
+                message: "frontmatter is not valid YAML; parsed line by line".to_string(),
+            });
+            parse_frontmatter_lines(&mut fm, yaml_block, yaml_line);
+        }
+    }
+
+    (fm, body)
+}
+
+/// Scalar YAML value as a string (`null` becomes empty), or `None` for a
+/// nested list or mapping. Unquoted numbers are taken as written in `raw`
+/// when it's known, so that `008000` isn't read as `8000`: each key parses
+/// its own type.
+fn yaml_scalar(value: &serde_yaml::Value, raw: Option<&str>) -> Option<String> {
+    match value {
+        serde_yaml::Value::Null => Some(String::new()),
+        serde_yaml::Value::Bool(b) => Some(b.to_string()),
+        serde_yaml::Value::Number(n) => Some(raw.map_or_else(|| n.to_string(), str::to_string)),
+        serde_yaml::Value::String(s) => Some(s.clone()),
+        _ => None,
+    }
+}
+
+/// Source text of the value of the top-level `key` in a YAML block, when
+/// it's on the key's own line, without a trailing comment.
+fn yaml_value_text<'a>(yaml: &'a str, key: &str) -> Option<&'a str> {
+    let line = yaml.lines().nth(yaml_key_line(yaml, key)?)?;
+    let value = line[key.len()..].trim_start().strip_prefix(':')?;
+    let value = value.split(" #").next().unwrap_or(value).trim();
+    (!value.is_empty()).then_some(value)
+}
+
+fn nested_yaml_warning(key: &str, line: usize) -> ParseWarning {
+    ParseWarning {
+        line,
+        message: format!("invalid value for `{key}`: nested YAML is not supported"),
+    }
+}
+
+/// 0-based index of the top-level line defining `key` in a YAML block.
+fn yaml_key_line(yaml: &str, key: &str) -> Option<usize> {
+    yaml.lines().position(|line| {
+        line.strip_prefix(key)
+            .is_some_and(|rest| rest.trim_start().starts_with(':'))
+    })
+}
+
+/// Line-based fallback for frontmatter that isn't valid YAML.
+fn parse_frontmatter_lines(fm: &mut Frontmatter, yaml_block: &str, yaml_line: usize) {
     // Track whether we're collecting YAML list items for `header`
     let mut in_header_list = false;
     let mut header_items: Vec<HeaderItem> = Vec::new();
 
-    for line in yaml_block.lines() {
+    for (i, line) in yaml_block.lines().enumerate() {
         let trimmed = line.trim();
         if trimmed.is_empty() || trimmed.starts_with('#') {
             continue;
@@ -149,67 +484,12 @@ pub fn parse_frontmatter(input: &str) -> (Frontmatter, &str) {
         if let Some((key, value)) = trimmed.split_once(':') {
             let key = key.trim();
             let value = value.trim();
-            match key {
-                "theme" => {
-                    fm.theme = Some(value.to_string());
-                }
-                "layout" => {
-                    fm.layout = Some(match value {
-                        "center" => SlideLayout::Center,
-                        "two-column" => SlideLayout::TwoColumn,
-                        _ => SlideLayout::Default,
-                    });
-                }
-                "transition" => {
-                    fm.transition = Some(parse_transition_kind(value));
-                }
-                "image_max_width" => {
-                    let value = value.trim_end_matches('%');
-                    if let Ok(pct) = value.parse::<f64>() {
-                        fm.image_max_width = Some(pct / 100.0);
-                    }
-                }
-                "line_height" => {
-                    if let Ok(lh) = value.parse::<f64>() {
-                        fm.line_height = Some(lh);
-                    }
-                }
-                "figlet" => {
-                    if value.is_empty() || value == "true" {
-                        fm.figlet = Some(None);
-                    } else if value != "false" {
-                        fm.figlet = Some(Some(value.to_string()));
-                    }
-                }
-                "bg_fill" => {
-                    fm.bg_fill = Some(value == "true");
-                }
-                "figlet_web" => {
-                    fm.figlet_web = Some(parse_figlet_web_mode(value));
-                }
-                "figlet_color" => {
-                    if !value.is_empty() {
-                        fm.figlet_color = Some(value.to_string());
-                    }
-                }
-                "header" => {
-                    if value.is_empty() {
-                        // Empty value means YAML list follows on next lines
-                        in_header_list = true;
-                        header_items.clear();
-                    } else {
-                        // Inline pipe-separated format: header: item1 | item2
-                        let items: Vec<HeaderItem> = value
-                            .split('|')
-                            .map(|s| parse_header_item(s))
-                            .filter(|item| !item.text.is_empty())
-                            .collect();
-                        if !items.is_empty() {
-                            fm.header = Some(items);
-                        }
-                    }
-                }
-                _ => {}
+            if key == "header" && value.is_empty() {
+                // Empty value means YAML list follows on next lines
+                in_header_list = true;
+                header_items.clear();
+            } else {
+                apply_frontmatter_key(fm, key, value.trim_matches(['"', '\'']), yaml_line + i);
             }
         }
     }
@@ -218,8 +498,201 @@ pub fn parse_frontmatter(input: &str) -> (Frontmatter, &str) {
     if in_header_list && !header_items.is_empty() {
         fm.header = Some(header_items);
     }
+}
 
-    (fm, body)
+/// Apply one scalar frontmatter setting defined on source line `line`.
+fn apply_frontmatter_key(fm: &mut Frontmatter, key: &str, value: &str, line: usize) {
+    let warning = |message: String| ParseWarning { line, message };
+    match key {
+        "theme" => {
+            if !crate::theme::is_theme_file(value) && crate::theme::theme_from_name(value).is_none()
+            {
+                fm.warnings
+                    .push(warning(format!("unknown theme `{value}`")));
+            }
+            fm.theme = Some(value.to_string());
+        }
+        "layout" => {
+            fm.layout = Some(match value {
+                "center" => SlideLayout::Center,
+                "two-column" => SlideLayout::TwoColumn,
+                "default" => SlideLayout::Default,
+                _ => {
+                    fm.warnings
+                        .push(warning(format!("unknown layout `{value}`")));
+                    SlideLayout::Default
+                }
+            });
+        }
+        "transition" => {
+            fm.transition = Some(parse_transition_kind(value));
+            fm.transition_line = line;
+        }
+        "image_max_width" => match value.trim_end_matches('%').parse::<f64>() {
+            Ok(pct) => fm.image_max_width = Some(pct / 100.0),
+            Err(_) => fm.warnings.push(warning(format!(
+                "invalid value for `image_max_width`: `{value}`"
+            ))),
+        },
+        "cell_aspect" => match value.parse::<f64>() {
+            Ok(ratio) if ratio.is_finite() && ratio > 0.0 => fm.cell_aspect = Some(ratio),
+            _ => fm.warnings.push(warning(format!(
+                "invalid value for `cell_aspect`: `{value}`"
+            ))),
+        },
+        "dither_colors" => match parse_dither_colors(value) {
+            Some(colors) => fm.dither_colors = Some(colors),
+            None => fm.warnings.push(warning(format!(
+                "invalid value for `dither_colors`: `{value}`"
+            ))),
+        },
+        "dither" => match parse_dither(value) {
+            Some(dither) => fm.dither = Some(dither),
+            None => fm
+                .warnings
+                .push(warning(format!("invalid value for `dither`: `{value}`"))),
+        },
+        "highlighter" => match parse_highlighter(value) {
+            Some(kind) if kind.is_available() => fm.highlighter = Some(kind),
+            Some(_) => fm.warnings.push(warning(format!(
+                "`highlighter: {value}` needs ratride built with the `{value}` feature"
+            ))),
+            None => fm.warnings.push(warning(format!(
+                "invalid value for `highlighter`: `{value}`"
+            ))),
+        },
+        "min_size" => match parse_terminal_size(value) {
+            Some(size) => fm.min_size = Some(size),
+            None => fm
+                .warnings
+                .push(warning(format!("invalid value for `min_size`: `{value}`"))),
+        },
+        "line_height" => match value.parse::<f64>() {
+            Ok(lh) => fm.line_height = Some(lh),
+            Err(_) => fm.warnings.push(warning(format!(
+                "invalid value for `line_height`: `{value}`"
+            ))),
+        },
+        "figlet" => {
+            if value.is_empty() || value == "true" {
+                fm.figlet = Some(None);
+            } else if value != "false" {
+                fm.figlet = Some(Some(value.to_string()));
+            }
+        }
+        "bg_fill" => {
+            fm.bg_fill = Some(value == "true");
+        }
+        "bold_all" => {
+            fm.bold_all = Some(value == "true");
+        }
+        "smart_punctuation" => {
+            fm.smart_punctuation = Some(value == "true");
+        }
+        "title_case" => {
+            fm.title_case = Some(value == "true");
+        }
+        "line_numbers" => {
+            fm.line_numbers = Some(value == "true");
+        }
+        "reading_ruler" => {
+            fm.reading_ruler = Some(value == "true");
+        }
+        "remember_scroll" => {
+            fm.remember_scroll = Some(value == "true");
+        }
+        "confirm_quit" => {
+            fm.confirm_quit = Some(value == "true");
+        }
+        "detect_language" => {
+            fm.detect_language = Some(value == "true");
+        }
+        "ascii_only" => {
+            fm.ascii_only = Some(value == "true");
+        }
+        "break_minutes" => match value.parse::<f64>() {
+            Ok(minutes) if minutes > 0.0 => fm.break_minutes = Some(minutes),
+            _ => fm.warnings.push(warning(format!(
+                "invalid value for `break_minutes`: `{value}`"
+            ))),
+        },
+        "image_errors" => {
+            fm.image_errors = Some(value == "true");
+        }
+        "figlet_web" => {
+            fm.figlet_web = Some(parse_figlet_web_mode(value));
+        }
+        "big_headings" => {
+            fm.big_headings = Some(parse_big_headings(value));
+        }
+        "aspect" => {
+            fm.aspect = parse_aspect(value);
+            if fm.aspect.is_none() {
+                fm.warnings
+                    .push(warning(format!("invalid value for `aspect`: `{value}`")));
+            }
+        }
+        "web_font" if !value.is_empty() => {
+            fm.web_font = Some(value.to_string());
+        }
+        "web_css" if !value.is_empty() => {
+            fm.web_css = Some(value.to_string());
+        }
+        "web_url" if !value.is_empty() => {
+            fm.web_url = Some(value.to_string());
+        }
+        "web_embed_origins" => {
+            fm.web_embed_origins = value
+                .split([' ', ','])
+                .filter(|origin| !origin.is_empty())
+                .map(|origin| origin.trim_end_matches('/').to_string())
+                .collect();
+        }
+        "figlet_color" if !value.is_empty() => {
+            fm.figlet_color = Some(value.to_string());
+        }
+        "logo" if !value.is_empty() => {
+            fm.logo = Some(value.to_string());
+        }
+        "figlet_color" | "web_font" | "web_css" | "web_url" | "logo" => {}
+        "logo_position" => {
+            fm.logo_position = parse_logo_corner(value);
+            if fm.logo_position.is_none() {
+                fm.warnings.push(warning(format!(
+                    "invalid value for `logo_position`: `{value}`"
+                )));
+            }
+        }
+        "agenda" => {
+            fm.agenda = parse_agenda(value);
+            if fm.agenda.is_none() {
+                fm.warnings
+                    .push(warning(format!("invalid value for `agenda`: `{value}`")));
+            }
+        }
+        "math" => {
+            fm.math = MathRenderer::from_name(value);
+            if fm.math.is_none() {
+                fm.warnings
+                    .push(warning(format!("invalid value for `math`: `{value}`")));
+            }
+        }
+        "header" => {
+            // Inline pipe-separated format: header: item1 | item2
+            let items: Vec<HeaderItem> = value
+                .split('|')
+                .map(parse_header_item)
+                .filter(|item| !item.text.is_empty())
+                .collect();
+            if !items.is_empty() {
+                fm.header = Some(items);
+            }
+        }
+        _ => {
+            fm.warnings
+                .push(warning(format!("unknown frontmatter key `{key}`")));
+        }
+    }
 }
 
 #[derive(Clone, Debug, Default)]
@@ -252,6 +725,27 @@ pub enum TransitionKind {
     LinesCross,
     LinesRgb,
     SlideRgb,
+    /// A transition registered by name outside the built-in set.
+    Custom(String),
+}
+
+impl TransitionKind {
+    /// Registry name of the transition, or `None` for no transition.
+    pub fn name(&self) -> Option<&str> {
+        Some(match self {
+            TransitionKind::None => return None,
+            TransitionKind::Slide(_) => "slide",
+            TransitionKind::Fade => "fade",
+            TransitionKind::Dissolve => "dissolve",
+            TransitionKind::Coalesce => "coalesce",
+            TransitionKind::SweepIn => "sweep-in",
+            TransitionKind::Lines => "lines",
+            TransitionKind::LinesCross => "lines-cross",
+            TransitionKind::LinesRgb => "lines-rgb",
+            TransitionKind::SlideRgb => "slide-rgb",
+            TransitionKind::Custom(name) => name,
+        })
+    }
 }
 
 /// Semantic element for a11y overlay in web builds.
@@ -285,10 +779,38 @@ pub struct FigletHeadingMeta {
     pub figlet_color: Option<String>,
 }
 
+/// A FIGlet heading the parser left as a line of plain text because it had
+/// no renderer, drawn as art by [`Slide::render_figlets`] once the slide is
+/// shown.
+#[derive(Clone, Debug)]
+pub struct PendingFiglet {
+    /// Line index in `content.lines` of the heading's text.
+    pub line_index: usize,
+    text: String,
+    font: Option<String>,
+    color: Option<String>,
+    style: Style,
+    /// Line of the heading in the source, for warnings.
+    source_line: usize,
+}
+
+/// An H1 heading that the terminal frontend redraws at double size.
+/// The line after `line_index` is reserved for the heading's lower half.
+#[derive(Clone, Debug)]
+pub struct BigHeadingMeta {
+    /// Line index in `content.lines` where the heading starts.
+    pub line_index: usize,
+    /// Plain text of the heading line (including any `# ` prefix).
+    pub text: String,
+    pub style: Style,
+}
+
 /// Image reference found in a slide.
 #[derive(Clone, Debug)]
 pub struct SlideImage {
     pub path: String,
+    /// 1-based source line of the image reference.
+    pub source_line: usize,
     /// Line index in content where placeholder starts.
     pub line_index: usize,
     /// Number of placeholder lines reserved.
@@ -298,6 +820,295 @@ pub struct SlideImage {
     pub pixel_height: u32,
     /// Max display width as percentage of content area (0.0–1.0).
     pub max_width_percent: Option<f64>,
+    /// Every image of a `<!-- gallery: ... -->`, of which `path` is the one
+    /// shown; empty for a single image.
+    pub gallery: Vec<String>,
+}
+
+impl SlideImage {
+    /// Every image this placement can show.
+    pub fn paths(&self) -> &[String] {
+        if self.gallery.is_empty() {
+            std::slice::from_ref(&self.path)
+        } else {
+            &self.gallery
+        }
+    }
+
+    /// Height over width of the tallest of [`paths`](Self::paths), once
+    /// `dims` knows all their pixel sizes, so a gallery's placeholder fits
+    /// whichever image it shows.
+    pub fn tallest_ratio(&self, mut dims: impl FnMut(&str) -> Option<(u32, u32)>) -> Option<f64> {
+        let mut tallest = 0.0_f64;
+        for path in self.paths() {
+            let (w, h) = dims(path).filter(|&(w, h)| w > 0 && h > 0)?;
+            tallest = tallest.max(h as f64 / w as f64);
+        }
+        Some(tallest)
+    }
+}
+
+/// A `<!-- video: path -->` embed (web only; the terminal shows a placeholder).
+#[derive(Clone, Debug)]
+pub struct SlideVideo {
+    pub path: String,
+    /// Line index in content where placeholder starts.
+    pub line_index: usize,
+    /// Number of placeholder lines reserved.
+    pub height: u16,
+}
+
+/// A Markdown table, kept as cells so it can be laid out again when the
+/// screen is too narrow for it.
+#[derive(Clone, Debug)]
+pub struct SlideTable {
+    /// Line index in content of its top border.
+    pub line_index: usize,
+    /// Alignment of each column.
+    pub alignments: Vec<Alignment>,
+    /// Cells by row, header row first, as styled spans.
+    pub rows: Vec<Vec<Vec<Span<'static>>>>,
+    pub border_style: Style,
+}
+
+impl SlideTable {
+    /// Number of content lines the table takes, at any width.
+    pub fn height(&self) -> usize {
+        self.rows.len() + 3
+    }
+
+    fn natural_widths(&self) -> Vec<usize> {
+        let mut widths = vec![1; self.alignments.len()];
+        for row in &self.rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.iter().map(Span::width).sum());
+            }
+        }
+        widths
+    }
+
+    /// Width of the table drawn without truncating any cell.
+    pub fn width(&self) -> usize {
+        self.natural_widths().iter().map(|w| w + 3).sum::<usize>() + 1
+    }
+
+    /// The table as box-drawn lines at most `max_width` columns wide where
+    /// possible: the widest columns are narrowed first, and cells that no
+    /// longer fit end in `…`.
+    pub fn lines(&self, max_width: usize) -> Vec<Line<'static>> {
+        let mut widths = self.natural_widths();
+        let budget = max_width.saturating_sub(3 * widths.len() + 1);
+        while widths.iter().sum::<usize>() > budget {
+            let Some(widest) = widths.iter_mut().filter(|w| **w > 1).max() else {
+                break;
+            };
+            *widest -= 1;
+        }
+
+        let rule = |left: &str, mid: &str, right: &str| {
+            let bars: Vec<String> = widths.iter().map(|w| "─".repeat(w + 2)).collect();
+            Line::from(Span::styled(
+                format!("{left}{}{right}", bars.join(mid)),
+                self.border_style,
+            ))
+        };
+        let mut lines = vec![rule("┌", "┬", "┐")];
+        for (i, row) in self.rows.iter().enumerate() {
+            let mut spans = Vec::new();
+            for (col, (&width, &alignment)) in widths.iter().zip(&self.alignments).enumerate() {
+                let cell = row.get(col).map(Vec::as_slice).unwrap_or_default();
+                let cell = fit_cell(cell, width);
+                let gap = width - cell.iter().map(Span::width).sum::<usize>();
+                let before = match alignment {
+                    Alignment::Left => 0,
+                    Alignment::Center => gap / 2,
+                    Alignment::Right => gap,
+                };
+                spans.push(Span::styled("│ ", self.border_style));
+                spans.push(Span::raw(" ".repeat(before)));
+                spans.extend(cell);
+                spans.push(Span::raw(" ".repeat(gap - before + 1)));
+            }
+            spans.push(Span::styled("│", self.border_style));
+            lines.push(Line::from(spans));
+            if i == 0 {
+                lines.push(rule("├", "┼", "┤"));
+            }
+        }
+        lines.push(rule("└", "┴", "┘"));
+        lines
+    }
+}
+
+/// `cell` cut to `width` columns, ending in `…` when anything was cut.
+fn fit_cell(cell: &[Span<'static>], width: usize) -> Vec<Span<'static>> {
+    if cell.iter().map(Span::width).sum::<usize>() <= width {
+        return cell.to_vec();
+    }
+    let mut room = width - 1;
+    let mut out = Vec::new();
+    for span in cell {
+        let mut text = String::new();
+        for c in span.content.chars() {
+            let w = unicode_width::UnicodeWidthChar::width(c).unwrap_or(0);
+            if w > room {
+                room = 0;
+                break;
+            }
+            room -= w;
+            text.push(c);
+        }
+        out.push(Span::styled(text, span.style));
+        if room == 0 {
+            break;
+        }
+    }
+    let style = cell.last().map(|s| s.style).unwrap_or_default();
+    out.push(Span::styled("…", style));
+    out
+}
+
+/// Rows a `<!-- bigtext -->` heading takes when no size is given.
+const DEFAULT_BIGTEXT_ROWS: usize = 4;
+
+/// A heading in block letters (`<!-- bigtext: ROWS -->`), drawn again by
+/// the renderer as large as the screen width allows.
+#[derive(Clone, Debug)]
+pub struct SlideBigText {
+    /// Line index in content of its first row.
+    pub line_index: usize,
+    pub text: String,
+    /// Number of content lines it takes, at any width.
+    pub rows: usize,
+    pub style: Style,
+}
+
+impl SlideBigText {
+    /// The heading as `rows` lines at most `max_width` columns wide, in
+    /// plain text when block letters don't fit.
+    pub fn lines(&self, max_width: usize) -> Vec<Line<'static>> {
+        let letters = bigtext::render(&self.text, self.rows, max_width);
+        let mut lines: Vec<Line<'static>> = match letters {
+            Some(rows) => rows
+                .into_iter()
+                .map(|row| Line::from(Span::styled(row, self.style)))
+                .collect(),
+            None => vec![Line::from(Span::styled(self.text.clone(), self.style))],
+        };
+        lines.resize(self.rows, Line::default());
+        lines
+    }
+}
+
+/// Lines of a code block picked out with ```` ```rust {highlight: 1-2|4} ````,
+/// kept when there are several groups to step through.
+#[derive(Clone, Debug)]
+pub struct SlideCodeHighlight {
+    /// Line index in content of the first line of code.
+    pub line_index: usize,
+    /// Number of lines of code.
+    pub height: usize,
+    /// Lines highlighted by each group, counting from 0.
+    pub groups: Vec<Vec<usize>>,
+    /// Pause step that moves on to the second group.
+    pub first_step: usize,
+    pub bg: Color,
+    pub highlight_bg: Color,
+}
+
+impl SlideCodeHighlight {
+    /// Give the lines of `group` (the last one when past it) the brighter
+    /// background and the rest of the block the plain one.
+    fn apply(&self, lines: &mut [Line<'static>], group: usize) {
+        let highlighted = &self.groups[group.min(self.groups.len() - 1)];
+        let code = lines.iter_mut().skip(self.line_index).take(self.height);
+        for (i, line) in code.enumerate() {
+            let bg = match highlighted.contains(&i) {
+                true => self.highlight_bg,
+                false => self.bg,
+            };
+            line.style = line.style.bg(bg);
+            for span in &mut line.spans {
+                span.style = span.style.bg(bg);
+            }
+        }
+    }
+}
+
+/// The line-number column of a ```` ```rust {numbers} ```` block, which the
+/// renderer repeats blank on the rows it wraps numbered lines onto.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SlideCodeGutter {
+    /// Column of a two-column slide the block is in: 0 for `content`, 1
+    /// for `right_content`.
+    pub column: usize,
+    /// Line index in that column of the first line of code.
+    pub line_index: usize,
+    /// Number of lines of code.
+    pub height: usize,
+    /// Characters the numbers and their separator take after the padding.
+    pub width: usize,
+}
+
+/// Cells the bar of an option with every vote spans.
+const POLL_BAR_WIDTH: usize = 30;
+
+/// A `<!-- poll: "Question?" options=A,B,C -->`: the question, then a bar
+/// per option that the presenter fills in as the audience votes.
+#[derive(Clone, Debug)]
+pub struct SlidePoll {
+    /// Line index in content of the first option's bar.
+    pub line_index: usize,
+    pub question: String,
+    pub options: Vec<String>,
+    pub bar_color: Color,
+    pub track_color: Color,
+}
+
+impl SlidePoll {
+    /// Number of content lines the bars take: one per option and one
+    /// saying where to vote.
+    pub fn height(&self) -> usize {
+        self.options.len() + 1
+    }
+
+    /// The bars for `votes` (by option; missing ones count as none) and
+    /// then a line pointing the audience to `url`, blank without one.
+    pub fn lines(&self, votes: &[usize], url: Option<&str>) -> Vec<Line<'static>> {
+        let total: usize = votes.iter().sum();
+        let label_width = self.options.iter().map(|o| o.width()).max().unwrap_or(0);
+        let mut lines: Vec<Line<'static>> = self
+            .options
+            .iter()
+            .enumerate()
+            .map(|(i, option)| {
+                let count = votes.get(i).copied().unwrap_or(0);
+                let filled = (count * POLL_BAR_WIDTH).checked_div(total).unwrap_or(0);
+                let tally = match (count * 100).checked_div(total) {
+                    Some(percent) => format!(" {count} ({percent}%)"),
+                    None => format!(" {count}"),
+                };
+                let pad = " ".repeat(label_width - option.width());
+                Line::from(vec![
+                    Span::raw(format!("{option}{pad}  ")),
+                    Span::styled("█".repeat(filled), Style::default().fg(self.bar_color)),
+                    Span::styled(
+                        "░".repeat(POLL_BAR_WIDTH - filled),
+                        Style::default().fg(self.track_color),
+                    ),
+                    Span::raw(tally),
+                ])
+            })
+            .collect();
+        lines.push(match url {
+            Some(url) => Line::from(Span::styled(
+                format!("Vote at {url}"),
+                Style::default().fg(self.track_color),
+            )),
+            None => Line::default(),
+        });
+        lines
+    }
 }
 
 #[derive(Clone)]
@@ -316,49 +1127,546 @@ pub struct Slide {
     pub theme: Theme,
     /// Line-height multiplier for web rendering (default 1.2).
     pub line_height: f64,
+    /// Width/height ratio of a terminal cell, for placing images.
+    pub cell_aspect: f64,
+    /// Draw box-drawing characters, bullets and other symbols as ASCII
+    /// (frontmatter `ascii_only`).
+    pub ascii_only: bool,
     /// Whether to fill entire screen with theme bg color.
     pub bg_fill: bool,
     /// Header items displayed at top-right, overlaying the content area.
     pub header: Vec<HeaderItem>,
     /// Figlet heading metadata for web image rendering.
     pub figlet_headings: Vec<FigletHeadingMeta>,
+    /// FIGlet headings still shown as plain text.
+    pub pending_figlets: Vec<PendingFiglet>,
+    /// Headings drawn at double size by the terminal frontend.
+    pub big_headings: Vec<BigHeadingMeta>,
+    /// Audio clip played on slide entry (web only).
+    pub audio: Option<String>,
+    /// Embedded videos.
+    pub videos: Vec<SlideVideo>,
+    /// Plain text of the slide's first heading.
+    pub title: Option<String>,
+    /// Shown only on the presenter's screen (`<!-- presenter-only -->`);
+    /// audience displays skip it when navigating.
+    pub presenter_only: bool,
+    /// Speaker notes (`<!-- notes: ... -->` or a fenced `notes` block), shown
+    /// only in the presenter view.
+    pub notes: Option<String>,
+    /// Line indices where each `<!-- pause -->` hides the rest of the slide
+    /// until the presenter steps past it.
+    pub fragments: Vec<usize>,
+    /// Line ranges whose words appear one by one once the slide is shown
+    /// (`<!-- animate: words -->` before a heading or paragraph).
+    pub word_builds: Vec<Range<usize>>,
+    /// Tables, laid out again by the renderer when wider than the screen.
+    pub tables: Vec<SlideTable>,
+    /// Block-letter headings, scaled again by the renderer to the screen.
+    pub big_texts: Vec<SlideBigText>,
+    /// Code blocks stepping through groups of highlighted lines.
+    pub code_highlights: Vec<SlideCodeHighlight>,
+    /// Line-number columns of code blocks.
+    pub code_gutters: Vec<SlideCodeGutter>,
+    /// The slide's poll, whose bars the presenter redraws as votes come in.
+    pub poll: Option<SlidePoll>,
+}
+
+impl Slide {
+    /// The slide as shown after `step` pause steps: lines from the next
+    /// hidden fragment on are blanked, keeping the layout, and whatever sits
+    /// on them (images, links, headings) is dropped.
+    pub fn at_step(&self, step: usize) -> Cow<'_, Slide> {
+        let Some(&hidden) = self.fragments.get(step) else {
+            return Cow::Borrowed(self);
+        };
+        let mut slide = self.clone();
+        for highlight in &slide.code_highlights {
+            let group = step.saturating_sub(highlight.first_step);
+            highlight.apply(&mut slide.content.lines, group);
+        }
+        for line in slide.content.lines.iter_mut().skip(hidden) {
+            *line = Line::raw(" ".repeat(line.width()));
+        }
+        slide.images.retain(|img| img.line_index < hidden);
+        slide.videos.retain(|video| video.line_index < hidden);
+        slide.big_headings.retain(|h| h.line_index < hidden);
+        slide.word_builds.retain(|build| build.start < hidden);
+        slide.tables.retain(|table| table.line_index < hidden);
+        slide.big_texts.retain(|text| text.line_index < hidden);
+        slide.poll = slide.poll.filter(|poll| poll.line_index < hidden);
+        slide.figlet_headings.retain(|h| h.line_index < hidden);
+        slide.pending_figlets.retain(|f| f.line_index < hidden);
+        slide.semantics.retain(|elem| match elem {
+            SemanticElement::Heading { line_index, .. }
+            | SemanticElement::Link { line_index, .. } => *line_index < hidden,
+        });
+        Cow::Owned(slide)
+    }
+
+    /// Move everything anchored below content line `after` by `delta`
+    /// lines, after lines were inserted into or removed from the content.
+    pub fn shift_lines(&mut self, after: usize, delta: i32) {
+        let shift = |line: &mut usize| {
+            if *line > after {
+                *line = (*line as i32 + delta).max(0) as usize;
+            }
+        };
+        for line in &mut self.fragments {
+            shift(line);
+        }
+        for build in &mut self.word_builds {
+            shift(&mut build.start);
+            shift(&mut build.end);
+        }
+        for elem in &mut self.semantics {
+            match elem {
+                SemanticElement::Heading { line_index, .. }
+                | SemanticElement::Link { line_index, .. } => shift(line_index),
+            }
+        }
+        let anchors = self.images.iter_mut().map(|img| &mut img.line_index);
+        anchors
+            .chain(self.videos.iter_mut().map(|video| &mut video.line_index))
+            .chain(self.big_headings.iter_mut().map(|h| &mut h.line_index))
+            .chain(self.figlet_headings.iter_mut().map(|h| &mut h.line_index))
+            .chain(self.pending_figlets.iter_mut().map(|f| &mut f.line_index))
+            .chain(self.tables.iter_mut().map(|table| &mut table.line_index))
+            .chain(self.big_texts.iter_mut().map(|text| &mut text.line_index))
+            .chain(self.code_highlights.iter_mut().map(|h| &mut h.line_index))
+            .chain(self.poll.iter_mut().map(|poll| &mut poll.line_index))
+            .chain(
+                (self.code_gutters.iter_mut())
+                    .filter(|gutter| gutter.column == 0)
+                    .map(|gutter| &mut gutter.line_index),
+            )
+            .for_each(shift);
+    }
+
+    /// Draw the slide's pending FIGlet headings with `figlet`, returning
+    /// warnings for fonts it couldn't use.
+    pub fn render_figlets(&mut self, figlet: &FigletFn) -> Vec<ParseWarning> {
+        let mut warnings = Vec::new();
+        // Last first, so the lines each one adds don't move the rest
+        for pending in std::mem::take(&mut self.pending_figlets).into_iter().rev() {
+            let font = pending.font.as_deref();
+            let color = pending.color.as_deref();
+            let (art, warning) = figlet_art(figlet, &pending.text, font, color);
+            warnings.extend(warning.map(|message| ParseWarning {
+                line: pending.source_line,
+                message,
+            }));
+            let line_index = pending.line_index;
+            let Ok(art) = art else {
+                continue;
+            };
+            let lines = figlet_lines(&art, pending.style, color.is_some());
+            if lines.is_empty() || line_index >= self.content.lines.len() {
+                continue;
+            }
+            let delta = lines.len() as i32 - 1;
+            self.content
+                .lines
+                .splice(line_index..=line_index, lines.iter().cloned());
+            self.shift_lines(line_index, delta);
+            self.figlet_headings.push(FigletHeadingMeta {
+                line_index,
+                line_count: lines.len(),
+                styled_lines: lines,
+                figlet_color: pending.color,
+            });
+        }
+        self.figlet_headings.sort_by_key(|h| h.line_index);
+        warnings.reverse();
+        warnings
+    }
+
+    /// Words revealed by pause step `step` (the whole slide when it has no
+    /// pauses), for timing how long it takes to read. Tokens without a
+    /// letter or digit, such as bullets and FIGlet art, don't count.
+    pub fn words_in_step(&self, step: usize) -> usize {
+        let start = match step {
+            0 => 0,
+            _ => self.fragments.get(step - 1).copied().unwrap_or(usize::MAX),
+        };
+        let end = self.fragments.get(step).copied().unwrap_or(usize::MAX);
+        let right = self.right_content.iter().filter(|_| step == 0);
+        let lines = self.content.lines.iter().take(end).skip(start);
+        lines
+            .chain(right.flat_map(|text| &text.lines))
+            .map(|line| {
+                let text: String = line.spans.iter().map(|span| &*span.content).collect();
+                text.split_whitespace()
+                    .filter(|word| word.chars().any(char::is_alphanumeric))
+                    .count()
+            })
+            .sum()
+    }
+
+    /// Redraw the poll's bars for `votes`, pointing the audience to `url`.
+    pub fn show_votes(&mut self, votes: &[usize], url: Option<&str>) {
+        let Some(poll) = &self.poll else {
+            return;
+        };
+        let start = poll.line_index;
+        let end = (start + poll.height()).min(self.content.lines.len());
+        let lines = poll.lines(votes, url);
+        self.content.lines.splice(start..end, lines);
+    }
+
+    /// Show the next image of each gallery on the slide, or the previous one
+    /// going backward, wrapping around. `false` when it has no gallery.
+    pub fn step_galleries(&mut self, forward: bool) -> bool {
+        let mut stepped = false;
+        for img in &mut self.images {
+            let Some(i) = img.gallery.iter().position(|path| *path == img.path) else {
+                continue;
+            };
+            let len = img.gallery.len();
+            let next = if forward { i + 1 } else { i + len - 1 };
+            img.path = img.gallery[next % len].clone();
+            stepped = true;
+        }
+        stepped
+    }
+
+    /// Whether the slide opens a section, i.e. has a level-1 heading.
+    pub fn starts_section(&self) -> bool {
+        self.semantics
+            .iter()
+            .any(|elem| matches!(elem, SemanticElement::Heading { level: 1, .. }))
+    }
+}
+
+/// Index of the next section's first slide after `from`, or going backward
+/// the start of the section containing `from` (the previous one when `from`
+/// starts a section), falling back to the first slide.
+pub fn adjacent_section(
+    slides: &[Slide],
+    from: usize,
+    forward: bool,
+    presenter: bool,
+) -> Option<usize> {
+    let visible = |i: &usize| presenter || !slides[*i].presenter_only;
+    let boundary = |i: &usize| visible(i) && slides[*i].starts_section();
+    if forward {
+        (from + 1..slides.len()).find(boundary)
+    } else {
+        (0..from.min(slides.len()))
+            .rev()
+            .find(boundary)
+            .or_else(|| (0..from.min(slides.len())).find(visible))
+    }
+}
+
+/// Index of the next (or, going backward, previous) slide from `from` that
+/// is shown, skipping presenter-only slides unless `presenter` is set.
+pub fn adjacent_slide(
+    slides: &[Slide],
+    from: usize,
+    forward: bool,
+    presenter: bool,
+) -> Option<usize> {
+    let visible = |i: &usize| presenter || !slides[*i].presenter_only;
+    if forward {
+        (from + 1..slides.len()).find(visible)
+    } else {
+        (0..from.min(slides.len())).rev().find(visible)
+    }
+}
+
+/// Index of the first slide whose text or images differ between `old` and
+/// `new`, the first added slide, or the last one when slides were removed
+/// from the end. `None` when nothing changed.
+pub fn first_changed_slide(old: &[Slide], new: &[Slide]) -> Option<usize> {
+    let same = |a: &Slide, b: &Slide| {
+        a.content == b.content
+            && a.right_content == b.right_content
+            && a.images
+                .iter()
+                .map(|img| &img.path)
+                .eq(b.images.iter().map(|img| &img.path))
+    };
+    match old.iter().zip(new).position(|(a, b)| !same(a, b)) {
+        Some(i) => Some(i),
+        None if old.len() == new.len() => None,
+        None => Some(old.len().min(new.len().saturating_sub(1))),
+    }
 }
 
 const IMAGE_PLACEHOLDER_HEIGHT: u16 = 15;
 
-/// Parse markdown into slides split by `---` (horizontal rule).
-/// Figlet rendering callback: `(text, font_name, color) -> Option<ascii_art>`.
+/// Figlet rendering callback: `(text, font_name, color) -> Result<ascii_art, why_not>`.
 /// When `color` is `Some(...)`, the renderer should use `figrat --color` instead
 /// of plain `figlet`.
-pub type FigletFn = dyn Fn(&str, Option<&str>, Option<&str>) -> Option<String>;
+pub type FigletFn = dyn Fn(&str, Option<&str>, Option<&str>) -> Result<String, String>;
 
+/// Parse markdown into slides split by `---` (horizontal rule).
+/// Also returns warnings for the frontmatter and for unrecognized directives.
 pub fn parse_slides(
     input: &str,
     theme: &Theme,
     frontmatter: &Frontmatter,
     figlet_fn: Option<&FigletFn>,
     is_mobile: bool,
-) -> Vec<Slide> {
+) -> (Vec<Slide>, Vec<ParseWarning>) {
     let mut options = Options::empty();
     options.insert(Options::ENABLE_STRIKETHROUGH);
     options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_GFM);
+    // Decks that don't ask for math keep their dollar signs as typed
+    if frontmatter.math.is_some() {
+        options.insert(Options::ENABLE_MATH);
+    }
+    if frontmatter.smart_punctuation != Some(false) {
+        options.insert(Options::ENABLE_SMART_PUNCTUATION);
+    }
 
+    let (selected, mut guard_warnings) =
+        select_conditional(input, &frontmatter.defines, frontmatter.body_line);
+    let expanded = expand_fenced_divs(&selected);
+    let input = expanded.as_ref();
     let parser = Parser::new_ext(input, options);
     let mut converter = MdConverter::new(theme.clone(), frontmatter, figlet_fn, is_mobile);
-    for (event, range) in parser.into_offset_iter() {
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(input.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+    // Brackets come out as separate text events; merge them so inline
+    // `[[badge:...]]` arrives in one piece
+    for (event, range) in TextMergeWithOffset::new(parser.into_offset_iter()) {
+        converter.source_line =
+            frontmatter.body_line + line_starts.partition_point(|&start| start <= range.start);
         if matches!(event, Event::Rule) {
-            if input[range].contains('-') {
-                // Only dash-based rules (`---`) act as slide separators
+            if is_slide_separator(&input[range]) {
                 converter.process(event);
             } else {
                 // `___` / `***` render as a visible horizontal rule
                 converter.process_horizontal_rule();
             }
         } else {
-            converter.process(event);
+            converter.process(event);
+        }
+    }
+    let mut warnings = frontmatter.warnings.clone();
+    let default_transition = frontmatter.transition.as_ref();
+    if let Some(message) =
+        default_transition.and_then(|kind| unknown_transition(kind, &frontmatter.transitions))
+    {
+        warnings.push(ParseWarning {
+            line: frontmatter.transition_line,
+            message,
+        });
+    }
+    warnings.append(&mut guard_warnings);
+    warnings.append(&mut converter.warnings);
+    (converter.finish_slides(), warnings)
+}
+
+/// Whether the text of a thematic break separates slides. Only dash rules
+/// (`---`, `- - -`) do; `***` and `___` draw a rule on the slide.
+fn is_slide_separator(rule: &str) -> bool {
+    rule.contains('-')
+}
+
+/// Byte offsets of the rules that separate slides in `markdown`, found as
+/// [`parse_slides`] finds them, for the preprocessors that work on lines.
+pub(crate) fn slide_separators(markdown: &str) -> Vec<usize> {
+    Parser::new_ext(markdown, Options::ENABLE_TABLES | Options::ENABLE_GFM)
+        .into_offset_iter()
+        .filter(|(event, range)| {
+            matches!(event, Event::Rule) && is_slide_separator(&markdown[range.clone()])
+        })
+        .map(|(_, range)| range.start)
+        .collect()
+}
+
+/// Tracks fenced code blocks for the preprocessors that scan the deck line
+/// by line, so they leave code alone. As in CommonMark, a block only closes
+/// on a run of its opening character at least as long as the one that
+/// opened it, so a ```` block can show ``` lines.
+#[derive(Default)]
+pub(crate) struct CodeFences {
+    /// Character and length of the open block's fence.
+    open: Option<(char, usize)>,
+}
+
+impl CodeFences {
+    /// Whether the (trimmed) line opens, closes or sits inside a code block.
+    pub(crate) fn in_code(&mut self, trimmed: &str) -> bool {
+        let run = |c: char| trimmed.chars().take_while(|&x| x == c).count();
+        match self.open {
+            Some((c, len)) => {
+                let n = run(c);
+                if n >= len && trimmed[n..].trim().is_empty() {
+                    self.open = None;
+                }
+                true
+            }
+            None => {
+                self.open = ['`', '~']
+                    .into_iter()
+                    .map(|c| (c, run(c)))
+                    .find(|&(_, n)| n >= 3);
+                self.open.is_some()
+            }
+        }
+    }
+}
+
+/// Rewrite Pandoc-style fenced divs (`::: {.warning}` … `:::`, or
+/// `::: warning`) into `<!-- div: warning -->` … `<!-- /div -->` comments,
+/// which pulldown-cmark passes through. Code blocks are left alone and every
+/// line keeps its number.
+fn expand_fenced_divs(input: &str) -> Cow<'_, str> {
+    if !input.contains(":::") {
+        return Cow::Borrowed(input);
+    }
+    let mut out = String::with_capacity(input.len());
+    let mut fences = CodeFences::default();
+    let mut depth = 0;
+    for line in input.split_inclusive('\n') {
+        let trimmed = line.trim();
+        let newline = &line[line.trim_end_matches(['\r', '\n']).len()..];
+        if fences.in_code(trimmed) {
+            out.push_str(line);
+        } else if let Some(classes) = fenced_div_classes(trimmed) {
+            depth += 1;
+            if classes.is_empty() {
+                out.push_str("<!-- div -->");
+            } else {
+                out.push_str(&format!("<!-- div: {} -->", classes.join(" ")));
+            }
+            out.push_str(newline);
+        } else if depth > 0 && trimmed.len() >= 3 && trimmed.chars().all(|c| c == ':') {
+            depth -= 1;
+            out.push_str("<!-- /div -->");
+            out.push_str(newline);
+        } else {
+            out.push_str(line);
+        }
+    }
+    Cow::Owned(out)
+}
+
+/// Classes of a fenced div opening line (possibly none, as in
+/// `::: {#id}`), `None` for other lines.
+fn fenced_div_classes(line: &str) -> Option<Vec<&str>> {
+    let rest = line.strip_prefix(":::")?.trim_start_matches(':').trim();
+    if rest.is_empty() {
+        return None;
+    }
+    let classes: Vec<&str> = match rest.strip_prefix('{') {
+        Some(attrs) => attrs
+            .strip_suffix('}')?
+            .split_whitespace()
+            .filter_map(|attr| attr.strip_prefix('.'))
+            .collect(),
+        None => rest.split_whitespace().take(1).collect(),
+    };
+    Some(classes)
+}
+
+/// A line of an `<!-- if: ... -->` guard.
+enum Guard<'a> {
+    If(&'a str),
+    Else,
+    EndIf,
+}
+
+fn parse_guard(line: &str) -> Option<Guard<'_>> {
+    let inner = line.strip_prefix("<!--")?.strip_suffix("-->")?.trim();
+    match inner {
+        "else" => Some(Guard::Else),
+        "endif" => Some(Guard::EndIf),
+        _ => inner.strip_prefix("if:").map(|cond| Guard::If(cond.trim())),
+    }
+}
+
+/// Whether a guard condition holds: a name passed with `--define`,
+/// `env(NAME)` for a non-empty environment variable, or either negated with
+/// `!`. `None` when the condition can't be parsed.
+fn condition_holds(cond: &str, defines: &[String]) -> Option<bool> {
+    if let Some(cond) = cond.strip_prefix('!') {
+        return condition_holds(cond.trim(), defines).map(|holds| !holds);
+    }
+    if let Some(name) = cond.strip_prefix("env(").and_then(|c| c.strip_suffix(')')) {
+        let name = name.trim();
+        return (!name.is_empty())
+            .then(|| std::env::var_os(name).is_some_and(|value| !value.is_empty()));
+    }
+    let valid = !cond.is_empty()
+        && cond
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '-');
+    valid.then(|| defines.iter().any(|name| name == cond))
+}
+
+/// Blank out the lines of `<!-- if: cond -->` … `<!-- else -->` …
+/// `<!-- endif -->` branches not taken, and the guard lines themselves, so
+/// one deck can hold several versions of a talk. Every line keeps its
+/// number; guards nest, and code blocks are left alone. `body_line` offsets
+/// the line numbers of the warnings.
+pub fn select_conditional<'a>(
+    input: &'a str,
+    defines: &[String],
+    body_line: usize,
+) -> (Cow<'a, str>, Vec<ParseWarning>) {
+    let mut warnings = Vec::new();
+    if !input.contains("<!-- if:") {
+        return (Cow::Borrowed(input), warnings);
+    }
+    let mut out = String::with_capacity(input.len());
+    let mut fences = CodeFences::default();
+    // Whether each open guard's current branch is taken, and its line
+    let mut open: Vec<(bool, usize)> = Vec::new();
+    for (i, line) in input.split_inclusive('\n').enumerate() {
+        let trimmed = line.trim();
+        let newline = &line[line.trim_end_matches(['\r', '\n']).len()..];
+        let line_no = body_line + i + 1;
+        let mut warn = |message: &str| {
+            warnings.push(ParseWarning {
+                line: line_no,
+                message: message.to_string(),
+            })
+        };
+        let guard = if fences.in_code(trimmed) {
+            None
+        } else {
+            parse_guard(trimmed)
+        };
+        match guard {
+            Some(Guard::If(cond)) => {
+                let taken = condition_holds(cond, defines).unwrap_or_else(|| {
+                    warn(&format!("invalid condition `{cond}`"));
+                    false
+                });
+                open.push((taken, line_no));
+            }
+            Some(Guard::Else) => match open.last_mut() {
+                Some((taken, _)) => *taken = !*taken,
+                None => warn("`else` without `if`"),
+            },
+            Some(Guard::EndIf) => {
+                if open.pop().is_none() {
+                    warn("`endif` without `if`");
+                }
+            }
+            None => {
+                if open.iter().all(|(taken, _)| *taken) {
+                    out.push_str(line);
+                    continue;
+                }
+            }
         }
+        out.push_str(newline);
+    }
+    for (_, line) in open {
+        warnings.push(ParseWarning {
+            line,
+            message: "`if` without `endif`".to_string(),
+        });
     }
-    converter.finish_slides()
+    (Cow::Owned(out), warnings)
 }
 
 enum CommentDirective {
@@ -367,11 +1675,31 @@ enum CommentDirective {
     Figlet(Option<String>),
     FigletWeb(FigletWebMode),
     FigletColor(String),
+    /// Draw the next heading in block letters this many rows tall.
+    BigText(usize),
     ImageMaxWidth(f64),
     LineHeight(f64),
     Theme(Theme),
     BgFill(bool),
     Header(Vec<HeaderItem>),
+    Audio(String),
+    Video(String),
+    /// Images shown one at a time in a single placement.
+    Gallery(Vec<String>),
+    PresenterOnly,
+    Spec,
+    Badge(String, Option<Color>),
+    /// A poll's question and options.
+    Poll(String, Vec<String>),
+    Notes(String),
+    Pause,
+    /// `<!-- animate: words -->`: reveal the next heading or paragraph word
+    /// by word.
+    AnimateWords,
+    /// Start of a fenced div with these classes; see [`expand_fenced_divs`].
+    Div(Vec<String>),
+    DivEnd,
+    Style(Style),
 }
 
 fn parse_transition_kind(s: &str) -> TransitionKind {
@@ -394,7 +1722,71 @@ fn parse_transition_kind(s: &str) -> TransitionKind {
         "lines-cross" => TransitionKind::LinesCross,
         "lines-rgb" => TransitionKind::LinesRgb,
         "slide-rgb" => TransitionKind::SlideRgb,
-        _ => TransitionKind::Slide(SlideDirection::default()),
+        "none" | "off" => TransitionKind::None,
+        "" => TransitionKind::Slide(SlideDirection::default()),
+        name => TransitionKind::Custom(name.to_string()),
+    }
+}
+
+/// Warning for a transition that is neither built in nor in `registered`.
+fn unknown_transition(kind: &TransitionKind, registered: &[String]) -> Option<String> {
+    match kind {
+        TransitionKind::Custom(name) if !registered.contains(name) => {
+            Some(format!("unknown transition `{name}`, using `slide`"))
+        }
+        _ => None,
+    }
+}
+
+/// Names accepted by `parse_comment`, used to tell bad values from unknown directives.
+const DIRECTIVES: &[&str] = &[
+    "layout",
+    "transition",
+    "figlet",
+    "figlet_web",
+    "figlet_color",
+    "bigtext",
+    "image_max_width",
+    "line_height",
+    "theme",
+    "bg_fill",
+    "header",
+    "audio",
+    "video",
+    "gallery",
+    "presenter_only",
+    "spec",
+    "badge",
+    "notes",
+    "pause",
+    "poll",
+    "div",
+    "style",
+    "animate",
+];
+
+/// Warning for a comment shaped like a directive (`<!-- name -->` or
+/// `<!-- name: value -->`) that `parse_comment` did not accept.
+fn directive_warning(html: &str) -> Option<String> {
+    let inner = html
+        .trim()
+        .strip_prefix("<!--")?
+        .strip_suffix("-->")?
+        .trim();
+    let name = inner.split_once(':').map_or(inner, |(name, _)| name).trim();
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_lowercase() || c == '_') {
+        return None;
+    }
+    if name == "generate" {
+        // Expanded before parsing, and only when asked to
+        Some("`generate` runs its command only with --generate".to_string())
+    } else if name == "cwd" {
+        // Read by `generate::expand`, and harmless without it
+        None
+    } else if DIRECTIVES.contains(&name) {
+        Some(format!("invalid value for `{name}` directive"))
+    } else {
+        Some(format!("unknown directive `{name}`"))
     }
 }
 
@@ -429,6 +1821,15 @@ fn parse_comment(html: &str) -> Option<CommentDirective> {
             return Some(CommentDirective::FigletColor(value.to_string()));
         }
     }
+    if inner == "bigtext" {
+        return Some(CommentDirective::BigText(DEFAULT_BIGTEXT_ROWS));
+    }
+    if let Some(value) = inner.strip_prefix("bigtext:") {
+        // Block letters need at least 4 rows
+        if let Ok(rows @ 4..) = value.trim().parse::<usize>() {
+            return Some(CommentDirective::BigText(rows));
+        }
+    }
     if let Some(value) = inner.strip_prefix("image_max_width:") {
         let value = value.trim().trim_end_matches('%');
         if let Ok(pct) = value.parse::<f64>() {
@@ -454,16 +1855,218 @@ fn parse_comment(html: &str) -> Option<CommentDirective> {
     if let Some(value) = inner.strip_prefix("header:") {
         let items: Vec<HeaderItem> = value
             .split('|')
-            .map(|s| parse_header_item(s))
+            .map(parse_header_item)
             .filter(|item| !item.text.is_empty())
             .collect();
-        if !items.is_empty() {
-            return Some(CommentDirective::Header(items));
+        // An empty `<!-- header: -->` clears the header for this slide
+        return Some(CommentDirective::Header(items));
+    }
+    if inner == "presenter-only" || inner == "presenter_only" {
+        return Some(CommentDirective::PresenterOnly);
+    }
+    if let Some(value) = inner.strip_prefix("badge:") {
+        if let Some((text, color)) = parse_badge(value) {
+            return Some(CommentDirective::Badge(text, color));
+        }
+    }
+    if let Some(value) = inner.strip_prefix("poll:") {
+        if let Some((question, options)) = parse_poll(value) {
+            return Some(CommentDirective::Poll(question, options));
+        }
+    }
+    if let Some(value) = inner.strip_prefix("notes:") {
+        let notes: Vec<&str> = value.lines().map(str::trim).collect();
+        let notes = notes.join("\n").trim().to_string();
+        if !notes.is_empty() {
+            return Some(CommentDirective::Notes(notes));
+        }
+    }
+    if inner == "pause" {
+        return Some(CommentDirective::Pause);
+    }
+    if inner == "div" {
+        return Some(CommentDirective::Div(Vec::new()));
+    }
+    if let Some(value) = inner.strip_prefix("div:") {
+        let classes: Vec<String> = value.split_whitespace().map(str::to_string).collect();
+        return Some(CommentDirective::Div(classes));
+    }
+    if inner == "/div" {
+        return Some(CommentDirective::DivEnd);
+    }
+    if let Some("words") = inner.strip_prefix("animate:").map(str::trim) {
+        return Some(CommentDirective::AnimateWords);
+    }
+    if let Some(value) = inner.strip_prefix("style:") {
+        if let Some(style) = parse_style(value) {
+            return Some(CommentDirective::Style(style));
+        }
+    }
+    if inner == "spec" {
+        return Some(CommentDirective::Spec);
+    }
+    if let Some(value) = inner.strip_prefix("audio:") {
+        let value = value.trim();
+        if !value.is_empty() {
+            return Some(CommentDirective::Audio(value.to_string()));
+        }
+    }
+    if let Some(value) = inner.strip_prefix("video:") {
+        let value = value.trim();
+        if !value.is_empty() {
+            return Some(CommentDirective::Video(value.to_string()));
+        }
+    }
+    if let Some(value) = inner.strip_prefix("gallery:") {
+        let paths: Vec<String> = value.split_whitespace().map(str::to_string).collect();
+        if !paths.is_empty() {
+            return Some(CommentDirective::Gallery(paths));
         }
     }
     None
 }
 
+/// The language and `{...}` options of a fenced code block's info string,
+/// as in ```` ```rust {numbers highlight: 3-5,8} ````: flags, and `key: value`
+/// (or `key=value`) pairs. Flags have an empty value.
+fn parse_code_info(info: &str) -> (Option<String>, Vec<(&str, &str)>) {
+    let (lang, options) = match info.split_once('{') {
+        Some((lang, rest)) => (lang, rest.split('}').next().unwrap_or("")),
+        None => (info, ""),
+    };
+    let lang = lang.split(',').next().unwrap_or("").trim();
+    let mut tokens = options.split_whitespace();
+    let mut pairs = Vec::new();
+    while let Some(token) = tokens.next() {
+        // At the first `:` or `=`, so values may contain either
+        let pair = token.split_once([':', '=']);
+        pairs.push(match pair {
+            Some((key, "")) => (key, tokens.next().unwrap_or("")),
+            Some((key, value)) => (key, value),
+            None => (token.trim_matches(','), ""),
+        });
+    }
+    ((!lang.is_empty()).then(|| lang.to_string()), pairs)
+}
+
+/// Split a line of a diff code block into its `+`, `-` or ` ` marker and
+/// the code after it, with the color of added and removed lines. Other
+/// lines, such as `@@` hunk headers and the `---`/`+++` file headers, have
+/// no marker.
+fn split_diff_line<'a>(line: &'a str, colors: &DiffColors) -> (&'a str, &'a str, Option<Color>) {
+    let is_file_header = |prefix: &str| {
+        line.strip_prefix(prefix)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(' '))
+    };
+    if is_file_header("---") || is_file_header("+++") {
+        return ("", line, None);
+    }
+    let color = match line.chars().next() {
+        Some('+') => Some(colors.added),
+        Some('-') => Some(colors.removed),
+        Some(' ') => None,
+        _ => return ("", line, None),
+    };
+    (&line[..1], &line[1..], color)
+}
+
+/// Groups of a `highlight:` code block option, as in `1-2|4,6`: lines 1
+/// and 2, then lines 4 and 6, counting from 0. `None` when a part isn't a
+/// line number or range.
+fn parse_highlight_groups(value: &str) -> Option<Vec<Vec<usize>>> {
+    value
+        .split('|')
+        .map(|group| {
+            let mut lines = Vec::new();
+            for part in group.split(',').filter(|part| !part.is_empty()) {
+                let (start, end) = part.split_once('-').unwrap_or((part, part));
+                let start: usize = start.trim().parse().ok()?;
+                let end: usize = end.trim().parse().ok()?;
+                if start == 0 || end < start {
+                    return None;
+                }
+                lines.extend(start - 1..end);
+            }
+            Some(lines)
+        })
+        .collect()
+}
+
+/// Parse the value of `<!-- style: fg=#fff bg=#222 bold -->`: colors and
+/// text modifiers. `None` when any part is not recognized.
+fn parse_style(value: &str) -> Option<Style> {
+    let mut style = Style::default();
+    for part in value.split_whitespace() {
+        style = match part.split_once('=') {
+            Some(("fg", color)) => style.fg(parse_color(color)?),
+            Some(("bg", color)) => style.bg(parse_color(color)?),
+            Some(_) => return None,
+            None => style.add_modifier(match part {
+                "bold" => Modifier::BOLD,
+                "dim" => Modifier::DIM,
+                "italic" => Modifier::ITALIC,
+                "underline" => Modifier::UNDERLINED,
+                "strikethrough" => Modifier::CROSSED_OUT,
+                "reverse" => Modifier::REVERSED,
+                _ => return None,
+            }),
+        };
+    }
+    (style != Style::default()).then_some(style)
+}
+
+/// A color name or `#rrggbb`, also accepting the short `#rgb` form.
+fn parse_color(value: &str) -> Option<Color> {
+    match value.strip_prefix('#') {
+        Some(hex) if hex.len() == 3 => {
+            let long: String = hex.chars().flat_map(|c| [c, c]).collect();
+            format!("#{long}").parse().ok()
+        }
+        _ => value.parse().ok(),
+    }
+}
+
+/// Parse the value of `<!-- badge: "text" color=green -->`; the quotes are
+/// optional. `None` when the text is empty or the color is not recognized.
+fn parse_badge(value: &str) -> Option<(String, Option<Color>)> {
+    let value = value.trim();
+    let (text, rest) = match value.strip_prefix('"') {
+        Some(quoted) => quoted.split_once('"')?,
+        None => match value.find("color=") {
+            Some(i) => (value[..i].trim_end(), &value[i..]),
+            None => (value, ""),
+        },
+    };
+    let color = match rest
+        .split_whitespace()
+        .find_map(|w| w.strip_prefix("color="))
+    {
+        Some(name) => Some(name.parse::<Color>().ok()?),
+        None => None,
+    };
+    (!text.is_empty()).then(|| (text.to_string(), color))
+}
+
+/// Parse the value of `<!-- poll: "Question?" options=A,B,C -->`; options
+/// may contain spaces but not commas. `None` without a question or with
+/// fewer than two options.
+fn parse_poll(value: &str) -> Option<(String, Vec<String>)> {
+    let value = value.trim();
+    let (question, rest) = match value.strip_prefix('"') {
+        Some(quoted) => quoted.split_once('"')?,
+        None => value.split_at(value.find("options=")?),
+    };
+    let options: Vec<String> = rest
+        .trim()
+        .strip_prefix("options=")?
+        .split(',')
+        .map(|option| option.trim().to_string())
+        .filter(|option| !option.is_empty())
+        .collect();
+    let question = question.trim();
+    (!question.is_empty() && options.len() >= 2).then(|| (question.to_string(), options))
+}
+
 /// Parse a single line containing ANSI true-color escape codes (`\x1b[38;2;R;G;Bm`
 /// and `\x1b[0m`) into a ratatui `Line` with per-segment colors.
 fn parse_ansi_line(input: &str, base_style: Style) -> Line<'static> {
@@ -522,14 +2125,21 @@ struct MdConverter<'a> {
     list_stack: Vec<ListKind>,
     in_code_block: bool,
     in_blockquote: bool,
+    // Open fenced divs and the line each starts on
+    open_divs: Vec<(DivStyle, usize)>,
     in_image: bool,
     pending_layout: Option<SlideLayout>,
     pending_transition: Option<TransitionKind>,
     pending_figlet: Option<Option<String>>,
+    // Rows of the next heading in block letters, and the finished ones
+    pending_bigtext: Option<usize>,
+    big_texts: Vec<SlideBigText>,
     in_heading: bool,
     heading_text_buf: String,
     images: Vec<SlideImage>,
+    videos: Vec<SlideVideo>,
     figlet_headings: Vec<FigletHeadingMeta>,
+    pending_figlets: Vec<PendingFiglet>,
     pending_image_max_width: Option<f64>,
     // Semantic elements for a11y
     semantics: Vec<SemanticElement>,
@@ -544,18 +2154,32 @@ struct MdConverter<'a> {
     link_start_col: usize,
     // Syntax highlighting
     code_block_lang: Option<String>,
+    // Whether the code block being read gets line numbers, and the default
+    code_block_numbers: bool,
+    line_numbers: bool,
+    // Highlight groups of the code block being read, and the blocks that
+    // step through theirs
+    code_block_highlight: Vec<Vec<usize>>,
+    code_highlights: Vec<SlideCodeHighlight>,
+    code_gutters: Vec<SlideCodeGutter>,
     code_block_buf: String,
-    syntax_set: SyntaxSet,
-    syntect_theme: syntect::highlighting::Theme,
+    highlighter: Box<dyn Highlighter>,
     // Frontmatter defaults
     default_layout: Option<SlideLayout>,
     default_transition: Option<TransitionKind>,
+    /// Transition names registered beyond the built-ins.
+    transitions: Vec<String>,
     default_image_max_width: Option<f64>,
     default_line_height: Option<f64>,
+    cell_aspect: f64,
+    ascii_only: bool,
+    detect_language: bool,
     pending_line_height: Option<f64>,
     default_figlet: Option<Option<String>>,
     default_bg_fill: Option<bool>,
     pending_bg_fill: Option<bool>,
+    // Frontmatter `bold_all`: body text is bold
+    bold_all: bool,
     // External figlet renderer
     figlet_fn: Option<&'a FigletFn>,
     // Default theme for resetting after each slide
@@ -568,6 +2192,42 @@ struct MdConverter<'a> {
     // Header
     default_header: Option<Vec<HeaderItem>>,
     pending_header: Option<Vec<HeaderItem>>,
+    pending_audio: Option<String>,
+    pending_presenter_only: bool,
+    // `<!-- spec -->`: the next paragraph is aligned as `key: value` lines,
+    // starting at `in_spec`
+    pending_spec: bool,
+    in_spec: Option<usize>,
+    // Badge directives have started a row in the current HTML block
+    badge_row: bool,
+    // A comment spanning several lines of an HTML block, until its `-->`
+    html_buf: String,
+    // Speaker notes for the current slide; `in_notes` while in a `notes` fence
+    notes: Vec<String>,
+    in_notes: bool,
+    // Line indices of `<!-- pause -->` on the current slide, and the line
+    // just past the last top-level list
+    fragments: Vec<usize>,
+    list_end: usize,
+    // `animate: words` waiting for its block, the start of the block being
+    // read, and the finished ones
+    pending_word_build: bool,
+    word_build_start: Option<usize>,
+    word_builds: Vec<Range<usize>>,
+    // The table being read, the row being read, and the finished tables
+    table: Option<SlideTable>,
+    table_row: Vec<Vec<Span<'static>>>,
+    tables: Vec<SlideTable>,
+    poll: Option<SlidePoll>,
+    // Capitalize H1 words
+    title_case: bool,
+    // Double-size headings
+    big_headings_mode: BigHeadings,
+    in_big_heading: bool,
+    big_headings: Vec<BigHeadingMeta>,
+    // Diagnostics
+    source_line: usize,
+    warnings: Vec<ParseWarning>,
 }
 
 #[derive(Clone)]
@@ -583,8 +2243,10 @@ impl<'a> MdConverter<'a> {
         figlet_fn: Option<&'a FigletFn>,
         _is_mobile: bool,
     ) -> Self {
-        let base_style = Style::default().fg(theme.fg);
-        let syntect_theme = theme.syntect_theme();
+        let bold_all = frontmatter.bold_all.unwrap_or(false);
+        let base_style = body_style(&theme, bold_all);
+        let highlighter =
+            highlight::highlighter(frontmatter.highlighter.unwrap_or_default(), &theme);
         let default_theme = theme.clone();
         Self {
             theme,
@@ -595,14 +2257,19 @@ impl<'a> MdConverter<'a> {
             list_stack: Vec::new(),
             in_code_block: false,
             in_blockquote: false,
+            open_divs: Vec::new(),
             in_image: false,
             pending_layout: None,
             pending_transition: None,
             pending_figlet: None,
+            pending_bigtext: None,
+            big_texts: Vec::new(),
             in_heading: false,
             heading_text_buf: String::new(),
             images: Vec::new(),
+            videos: Vec::new(),
             figlet_headings: Vec::new(),
+            pending_figlets: Vec::new(),
             pending_image_max_width: None,
             semantics: Vec::new(),
             semantic_heading_level: 0,
@@ -615,17 +2282,26 @@ impl<'a> MdConverter<'a> {
             link_start_line: 0,
             link_start_col: 0,
             code_block_lang: None,
+            code_block_numbers: false,
+            line_numbers: frontmatter.line_numbers.unwrap_or(false),
+            code_block_highlight: Vec::new(),
+            code_highlights: Vec::new(),
+            code_gutters: Vec::new(),
             code_block_buf: String::new(),
-            syntax_set: SyntaxSet::load_defaults_newlines(),
-            syntect_theme,
+            highlighter,
             default_layout: frontmatter.layout.clone(),
             default_transition: frontmatter.transition.clone(),
+            transitions: frontmatter.transitions.clone(),
             default_image_max_width: frontmatter.image_max_width,
             default_line_height: frontmatter.line_height,
+            cell_aspect: frontmatter.cell_aspect.unwrap_or(DEFAULT_CELL_ASPECT),
+            ascii_only: frontmatter.ascii_only.unwrap_or(false),
+            detect_language: frontmatter.detect_language.unwrap_or(false),
             pending_line_height: None,
             default_figlet: frontmatter.figlet.clone(),
             default_bg_fill: frontmatter.bg_fill,
             pending_bg_fill: None,
+            bold_all,
             figlet_fn,
             default_theme,
             default_figlet_web: frontmatter.figlet_web.clone().unwrap_or_default(),
@@ -634,6 +2310,29 @@ impl<'a> MdConverter<'a> {
             pending_figlet_color: None,
             default_header: frontmatter.header.clone(),
             pending_header: None,
+            pending_audio: None,
+            pending_presenter_only: false,
+            pending_spec: false,
+            in_spec: None,
+            badge_row: false,
+            html_buf: String::new(),
+            notes: Vec::new(),
+            in_notes: false,
+            fragments: Vec::new(),
+            list_end: 0,
+            pending_word_build: false,
+            word_build_start: None,
+            word_builds: Vec::new(),
+            table: None,
+            table_row: Vec::new(),
+            tables: Vec::new(),
+            poll: None,
+            title_case: frontmatter.title_case.unwrap_or(false),
+            big_headings_mode: frontmatter.big_headings.unwrap_or_default(),
+            in_big_heading: false,
+            big_headings: Vec::new(),
+            source_line: 1,
+            warnings: Vec::new(),
         }
     }
 
@@ -641,6 +2340,16 @@ impl<'a> MdConverter<'a> {
         self.style_stack.last().copied().unwrap_or_default()
     }
 
+    /// How the formula `tex` is shown: as written when the deck is
+    /// `ascii_only`, otherwise in Unicode.
+    fn formula(&self, tex: &str) -> String {
+        if self.ascii_only {
+            tex.trim().to_string()
+        } else {
+            math::to_unicode(tex)
+        }
+    }
+
     fn push_style(&mut self, modifier: impl FnOnce(Style) -> Style) {
         let base = self.current_style();
         self.style_stack.push(modifier(base));
@@ -669,7 +2378,110 @@ impl<'a> MdConverter<'a> {
         }
     }
 
+    /// Draw the innermost open div's border, background and icon on the
+    /// lines it covered.
+    fn close_div(&mut self) {
+        let Some((style, start)) = self.open_divs.pop() else {
+            return;
+        };
+        if !self.current_spans.is_empty() {
+            self.flush_line();
+        }
+        while self.lines.len() > start
+            && self
+                .lines
+                .last()
+                .is_some_and(|l| l.spans.is_empty() && l.style.bg.is_none())
+        {
+            self.lines.pop();
+        }
+        let base = style
+            .bg
+            .map_or(Style::default(), |bg| Style::default().bg(bg));
+        let border = base.fg(style.border.unwrap_or(self.theme.block_quote_prefix));
+        let mut icon = style.icon;
+        for (index, line) in self.lines.iter_mut().enumerate().skip(start) {
+            let mut prefix = vec![Span::styled("▌ ", border)];
+            if let Some(icon) = icon.take() {
+                prefix.push(Span::styled(
+                    format!("{icon} "),
+                    border.add_modifier(Modifier::BOLD),
+                ));
+            }
+            let width: usize = prefix.iter().map(Span::width).sum();
+            line.spans.splice(0..0, prefix);
+            if line.style.bg.is_none() {
+                line.style = line.style.patch(base);
+            }
+            for elem in &mut self.semantics {
+                if let SemanticElement::Link {
+                    line_index,
+                    start_col,
+                    end_col,
+                    ..
+                } = elem
+                {
+                    if *line_index == index {
+                        *start_col += width;
+                        *end_col += width;
+                    }
+                }
+            }
+        }
+        self.lines.push(Line::default());
+    }
+
+    /// Start a GitHub-style alert (`> [!NOTE]`): a box like a fenced div's,
+    /// in the theme's color for the kind, headed by an icon and title line.
+    fn open_alert(&mut self, kind: BlockQuoteKind) {
+        if !self.current_spans.is_empty() {
+            self.flush_line();
+        }
+        let alerts = &self.theme.alerts;
+        let (color, icon, title) = match kind {
+            BlockQuoteKind::Note => (alerts.note, "ℹ", "Note"),
+            BlockQuoteKind::Tip => (alerts.tip, "✓", "Tip"),
+            BlockQuoteKind::Important => (alerts.important, "✱", "Important"),
+            BlockQuoteKind::Warning => (alerts.warning, "⚠", "Warning"),
+            BlockQuoteKind::Caution => (alerts.caution, "✗", "Caution"),
+        };
+        let style = DivStyle {
+            bg: Some(self.theme.surface),
+            border: Some(color),
+            icon: None,
+        };
+        self.open_divs.push((style, self.lines.len()));
+        self.lines.push(Line::from(Span::styled(
+            format!("{icon} {title}"),
+            Style::default().fg(color).add_modifier(Modifier::BOLD),
+        )));
+    }
+
+    /// Note where the block an `animate: words` directive applies to starts.
+    fn start_word_build(&mut self) {
+        if std::mem::take(&mut self.pending_word_build) {
+            self.word_build_start = Some(self.lines.len());
+        }
+    }
+
+    fn end_word_build(&mut self) {
+        if let Some(start) = self.word_build_start.take() {
+            self.word_builds.push(start..self.lines.len());
+        }
+    }
+
+    fn end_table_row(&mut self) {
+        let row = std::mem::take(&mut self.table_row);
+        if let Some(table) = &mut self.table {
+            table.rows.push(row);
+        }
+    }
+
     fn flush_slide(&mut self) {
+        // A div left open at the end of a slide closes with it
+        while !self.open_divs.is_empty() {
+            self.close_div();
+        }
         if !self.current_spans.is_empty() {
             self.flush_line();
         }
@@ -683,7 +2495,30 @@ impl<'a> MdConverter<'a> {
         }
         let lines = std::mem::take(&mut self.lines);
         let images = std::mem::take(&mut self.images);
+        let videos = std::mem::take(&mut self.videos);
+        let big_headings = std::mem::take(&mut self.big_headings);
+        let mut fragments = std::mem::take(&mut self.fragments);
+        // A pause with nothing after it has nothing to reveal
+        fragments.retain(|&line| line < lines.len());
+        fragments.dedup();
+        let mut word_builds = std::mem::take(&mut self.word_builds);
+        word_builds.retain(|build| build.start < lines.len());
+        let tables = std::mem::take(&mut self.tables);
+        let big_texts = std::mem::take(&mut self.big_texts);
+        let mut code_highlights = std::mem::take(&mut self.code_highlights);
+        let code_gutters = std::mem::take(&mut self.code_gutters);
+        // Each group after the first is a step of its own, taken before any
+        // pause below the block
+        for highlight in &mut code_highlights {
+            let end = (highlight.line_index + highlight.height + 2).min(lines.len());
+            highlight.first_step = fragments.partition_point(|&line| line < end);
+            let steps = std::iter::repeat_n(end, highlight.groups.len() - 1);
+            fragments.splice(highlight.first_step..highlight.first_step, steps);
+        }
+        let poll = self.poll.take();
+        self.pending_word_build = false;
         self.pending_figlet = None;
+        self.pending_bigtext = None;
         self.pending_figlet_web = None;
         self.pending_figlet_color = None;
         let transition = self
@@ -699,8 +2534,9 @@ impl<'a> MdConverter<'a> {
                 .unwrap_or_default();
             let semantics = std::mem::take(&mut self.semantics);
             let figlet_headings = std::mem::take(&mut self.figlet_headings);
+            let pending_figlets = std::mem::take(&mut self.pending_figlets);
             let mut slide = match layout {
-                SlideLayout::TwoColumn => split_two_column(lines),
+                SlideLayout::TwoColumn => split_two_column(lines, code_gutters),
                 _ => Slide {
                     layout,
                     content: Text::from(lines),
@@ -710,21 +2546,43 @@ impl<'a> MdConverter<'a> {
                     semantics: Vec::new(),
                     theme: Theme::default(),
                     line_height: DEFAULT_LINE_HEIGHT,
+                    cell_aspect: DEFAULT_CELL_ASPECT,
+                    ascii_only: false,
                     bg_fill: false,
                     header: Vec::new(),
                     figlet_headings: Vec::new(),
+                    pending_figlets: Vec::new(),
+                    big_headings: Vec::new(),
+                    audio: None,
+                    videos: Vec::new(),
+                    title: None,
+                    presenter_only: false,
+                    notes: None,
+                    fragments: Vec::new(),
+                    word_builds: Vec::new(),
+                    tables: Vec::new(),
+                    big_texts: Vec::new(),
+                    code_highlights: Vec::new(),
+                    code_gutters,
+                    poll: None,
                 },
             };
             slide.images = images;
+            slide.videos = videos;
             slide.transition = transition;
+            slide.title = first_heading(&semantics);
             slide.semantics = semantics;
             slide.figlet_headings = figlet_headings;
+            slide.pending_figlets = pending_figlets;
+            slide.big_headings = big_headings;
             slide.theme = self.theme.clone();
             slide.line_height = self
                 .pending_line_height
                 .take()
                 .or(self.default_line_height)
                 .unwrap_or(DEFAULT_LINE_HEIGHT);
+            slide.cell_aspect = self.cell_aspect;
+            slide.ascii_only = self.ascii_only;
             slide.bg_fill = self
                 .pending_bg_fill
                 .take()
@@ -735,14 +2593,137 @@ impl<'a> MdConverter<'a> {
                 .take()
                 .or_else(|| self.default_header.clone())
                 .unwrap_or_default();
+            slide.audio = self.pending_audio.take();
+            slide.presenter_only = std::mem::take(&mut self.pending_presenter_only);
+            let notes = std::mem::take(&mut self.notes);
+            slide.notes = (!notes.is_empty()).then(|| notes.join("\n\n"));
+            slide.fragments = fragments;
+            slide.word_builds = word_builds;
+            slide.tables = tables;
+            slide.big_texts = big_texts;
+            slide.code_highlights = code_highlights;
+            slide.poll = poll;
+            // Column splitting invalidates line indices, so two-column slides
+            // keep their headings at normal size and show all at once.
+            if matches!(slide.layout, SlideLayout::TwoColumn) {
+                slide.big_headings.clear();
+                slide.fragments.clear();
+                slide.word_builds.clear();
+                slide.tables.clear();
+                slide.big_texts.clear();
+                slide.code_highlights.clear();
+                slide.poll = None;
+                // Right-column headings stay plain text
+                let left = slide.content.lines.len();
+                slide.pending_figlets.retain(|f| f.line_index < left);
+            }
             self.slides.push(slide);
         }
         // Reset theme to default for next slide
-        self.syntect_theme = self.default_theme.syntect_theme();
-        self.style_stack[0] = Style::default().fg(self.default_theme.fg);
+        self.highlighter.set_theme(&self.default_theme);
+        self.style_stack[0] = body_style(&self.default_theme, self.bold_all);
         self.theme = self.default_theme.clone();
     }
 
+    /// Push text in the current style, turning inline `[[badge:text|color]]`
+    /// into badges.
+    fn push_text(&mut self, mut text: &str) {
+        while let Some((before, rest)) = text.split_once("[[badge:") {
+            let Some((inner, after)) = rest.split_once("]]") else {
+                break;
+            };
+            let (label, color) = match inner.split_once('|') {
+                Some((label, color)) => (label, color.trim().parse::<Color>().ok()),
+                None => (inner, None),
+            };
+            if !before.is_empty() {
+                let style = self.current_style();
+                self.current_spans
+                    .push(Span::styled(before.to_string(), style));
+            }
+            self.push_badge(label.trim(), color);
+            text = after;
+        }
+        if !text.is_empty() {
+            self.current_spans
+                .push(Span::styled(text.to_string(), self.current_style()));
+        }
+    }
+
+    /// A pill-shaped label: `text` in `color` (inline code color by default)
+    /// on the surface background, between half-block caps.
+    fn push_badge(&mut self, text: &str, color: Option<Color>) {
+        let surface = self.theme.surface;
+        let style = Style::default()
+            .fg(color.unwrap_or(self.theme.inline_code_fg))
+            .bg(surface)
+            .add_modifier(Modifier::BOLD);
+        if self.current_spans.last().is_some_and(|s| s.content == "▌") {
+            self.current_spans.push(Span::raw(" "));
+        }
+        self.current_spans.extend([
+            Span::styled("▐", Style::default().fg(surface)),
+            Span::styled(text.to_string(), style),
+            Span::styled("▌", Style::default().fg(surface)),
+        ]);
+    }
+
+    /// Lay out a poll with no votes yet; a slide has at most one.
+    fn push_poll(&mut self, question: String, options: Vec<String>) {
+        if self.poll.is_some() {
+            self.warnings.push(ParseWarning {
+                line: self.source_line,
+                message: "only the first poll on a slide collects votes".to_string(),
+            });
+            return;
+        }
+        if !self.current_spans.is_empty() {
+            self.flush_line();
+        }
+        self.lines.push(Line::from(Span::styled(
+            question.clone(),
+            Style::default()
+                .fg(self.theme.h3)
+                .add_modifier(Modifier::BOLD),
+        )));
+        self.lines.push(Line::default());
+        let poll = SlidePoll {
+            line_index: self.lines.len(),
+            question,
+            options,
+            bar_color: self.theme.link,
+            track_color: self.theme.list_bullet,
+        };
+        self.lines.extend(poll.lines(&[], None));
+        self.poll = Some(poll);
+    }
+
+    /// Reserve placeholder lines for an image showing `path`, one of
+    /// `gallery` when it isn't empty.
+    fn push_image(&mut self, path: String, gallery: Vec<String>) {
+        if !self.current_spans.is_empty() {
+            self.flush_line();
+        }
+        let line_index = self.lines.len();
+        self.images.push(SlideImage {
+            path,
+            source_line: self.source_line,
+            line_index,
+            height: IMAGE_PLACEHOLDER_HEIGHT,
+            pixel_width: 0,
+            pixel_height: 0,
+            max_width_percent: self
+                .pending_image_max_width
+                .take()
+                .or(self.default_image_max_width),
+            gallery,
+        });
+        // Insert placeholder lines
+        for _ in 0..IMAGE_PLACEHOLDER_HEIGHT {
+            self.lines.push(Line::default());
+        }
+    }
+
     fn list_indent(&self) -> String {
         "  ".repeat(self.list_stack.len().saturating_sub(1))
     }
@@ -752,36 +2733,35 @@ impl<'a> MdConverter<'a> {
             // --- Images ---
             Event::Start(Tag::Image { dest_url, .. }) => {
                 self.in_image = true;
-                if !self.current_spans.is_empty() {
-                    self.flush_line();
-                }
-                let line_index = self.lines.len();
-                self.images.push(SlideImage {
-                    path: dest_url.to_string(),
-                    line_index,
-                    height: IMAGE_PLACEHOLDER_HEIGHT,
-                    pixel_width: 0,
-                    pixel_height: 0,
-                    max_width_percent: self
-                        .pending_image_max_width
-                        .take()
-                        .or(self.default_image_max_width),
-                });
-                // Insert placeholder lines
-                for _ in 0..IMAGE_PLACEHOLDER_HEIGHT {
-                    self.lines.push(Line::default());
-                }
+                self.push_image(dest_url.to_string(), Vec::new());
             }
             Event::End(TagEnd::Image) => {
                 self.in_image = false;
             }
 
             // --- HTML comments (directives) ---
+            Event::Html(html)
+                if !self.html_buf.is_empty()
+                    || (html.trim_start().starts_with("<!--") && !html.contains("-->")) =>
+            {
+                // Gather a multi-line comment and handle it once it closes
+                self.html_buf.push_str(&html);
+                if self.html_buf.contains("-->") {
+                    let html = std::mem::take(&mut self.html_buf);
+                    self.process(Event::Html(html.into()));
+                }
+            }
             Event::Html(html) | Event::InlineHtml(html) => match parse_comment(&html) {
                 Some(CommentDirective::Layout(layout)) => {
                     self.pending_layout = Some(layout);
                 }
                 Some(CommentDirective::Transition(transition)) => {
+                    if let Some(message) = unknown_transition(&transition, &self.transitions) {
+                        self.warnings.push(ParseWarning {
+                            line: self.source_line,
+                            message,
+                        });
+                    }
                     self.pending_transition = Some(transition);
                 }
                 Some(CommentDirective::Figlet(font)) => {
@@ -793,6 +2773,9 @@ impl<'a> MdConverter<'a> {
                 Some(CommentDirective::FigletColor(color)) => {
                     self.pending_figlet_color = Some(color);
                 }
+                Some(CommentDirective::BigText(rows)) => {
+                    self.pending_bigtext = Some(rows);
+                }
                 Some(CommentDirective::ImageMaxWidth(pct)) => {
                     self.pending_image_max_width = Some(pct);
                 }
@@ -800,8 +2783,8 @@ impl<'a> MdConverter<'a> {
                     self.pending_line_height = Some(lh);
                 }
                 Some(CommentDirective::Theme(t)) => {
-                    self.syntect_theme = t.syntect_theme();
-                    self.style_stack[0] = Style::default().fg(t.fg);
+                    self.highlighter.set_theme(&t);
+                    self.style_stack[0] = body_style(&t, self.bold_all);
                     self.theme = t;
                 }
                 Some(CommentDirective::BgFill(v)) => {
@@ -810,11 +2793,79 @@ impl<'a> MdConverter<'a> {
                 Some(CommentDirective::Header(items)) => {
                     self.pending_header = Some(items);
                 }
-                None => {}
+                Some(CommentDirective::Audio(path)) => {
+                    self.pending_audio = Some(path);
+                }
+                Some(CommentDirective::PresenterOnly) => {
+                    self.pending_presenter_only = true;
+                }
+                Some(CommentDirective::Spec) => {
+                    self.pending_spec = true;
+                }
+                Some(CommentDirective::Badge(text, color)) => {
+                    self.push_badge(&text, color);
+                    self.badge_row = true;
+                }
+                Some(CommentDirective::Poll(question, options)) => {
+                    self.push_poll(question, options);
+                }
+                Some(CommentDirective::Notes(notes)) => {
+                    self.notes.push(notes);
+                }
+                Some(CommentDirective::Div(classes)) => {
+                    if !self.current_spans.is_empty() {
+                        self.flush_line();
+                    }
+                    let style = self.theme.div_style(&classes);
+                    self.open_divs.push((style, self.lines.len()));
+                }
+                Some(CommentDirective::DivEnd) => self.close_div(),
+                Some(CommentDirective::Style(style)) => {
+                    self.style_stack[0] = self.style_stack[0].patch(style);
+                }
+                Some(CommentDirective::AnimateWords) => {
+                    self.pending_word_build = true;
+                }
+                Some(CommentDirective::Pause) => {
+                    if !self.current_spans.is_empty() {
+                        self.flush_line();
+                    }
+                    self.fragments.push(self.lines.len());
+                }
+                Some(CommentDirective::Gallery(paths)) => {
+                    self.push_image(paths[0].clone(), paths);
+                }
+                Some(CommentDirective::Video(path)) => {
+                    if !self.current_spans.is_empty() {
+                        self.flush_line();
+                    }
+                    self.videos.push(SlideVideo {
+                        path: path.clone(),
+                        line_index: self.lines.len(),
+                        height: IMAGE_PLACEHOLDER_HEIGHT,
+                    });
+                    // Placeholder shown by the terminal; the web overlays a <video> on it
+                    self.lines.push(Line::from(Span::styled(
+                        format!("▶ {path}"),
+                        Style::default().fg(self.theme.block_quote_prefix),
+                    )));
+                    for _ in 1..IMAGE_PLACEHOLDER_HEIGHT {
+                        self.lines.push(Line::default());
+                    }
+                }
+                None => {
+                    if let Some(message) = directive_warning(&html) {
+                        self.warnings.push(ParseWarning {
+                            line: self.source_line,
+                            message,
+                        });
+                    }
+                }
             },
 
             // --- Headings ---
             Event::Start(Tag::Heading { level, .. }) => {
+                self.start_word_build();
                 let style = match level {
                     HeadingLevel::H1 => Style::default()
                         .fg(self.theme.h1)
@@ -847,8 +2898,13 @@ impl<'a> MdConverter<'a> {
                     .pending_figlet_web
                     .clone()
                     .unwrap_or(self.default_figlet_web.clone());
-                let use_figlet = has_figlet && figlet_web != FigletWebMode::Original;
-                if use_figlet {
+                let use_bigtext = self.pending_bigtext.is_some();
+                let use_figlet =
+                    !use_bigtext && has_figlet && figlet_web != FigletWebMode::Original;
+                if use_bigtext {
+                    self.in_heading = true;
+                    self.heading_text_buf.clear();
+                } else if use_figlet {
                     // Apply default figlet if no per-slide directive
                     if self.pending_figlet.is_none() {
                         self.pending_figlet = self.default_figlet.clone();
@@ -864,6 +2920,13 @@ impl<'a> MdConverter<'a> {
                     self.current_spans
                         .push(Span::styled("# ", self.current_style()));
                 }
+                // Double-size text is written past the buffer, where a word
+                // build can't hide it
+                self.in_big_heading = !use_figlet
+                    && !use_bigtext
+                    && level == HeadingLevel::H1
+                    && self.big_headings_mode.is_active()
+                    && self.word_build_start.is_none();
             }
             Event::End(TagEnd::Heading(_)) => {
                 // Emit semantic heading
@@ -879,19 +2942,54 @@ impl<'a> MdConverter<'a> {
                     self.in_heading = false;
                     let style = self.current_style();
                     self.current_spans.clear();
-                    self.render_figlet_heading(&self.heading_text_buf.clone(), style);
+                    let text = self.heading_text_buf.clone();
+                    match self.pending_bigtext.take() {
+                        Some(rows) => self.push_big_text(text, rows, style),
+                        None => self.render_figlet_heading(&text, style),
+                    }
                     self.lines.push(Line::default());
                 } else {
-                    self.flush_line();
+                    if self.in_big_heading {
+                        self.in_big_heading = false;
+                        let text: String = self
+                            .current_spans
+                            .iter()
+                            .map(|s| s.content.as_ref())
+                            .collect();
+                        self.big_headings.push(BigHeadingMeta {
+                            line_index: self.lines.len(),
+                            text,
+                            style: self.current_style(),
+                        });
+                        // Reserve the row covered by the lower half of the heading
+                        self.flush_line();
+                        self.lines.push(Line::default());
+                    } else {
+                        self.flush_line();
+                    }
                     self.lines.push(Line::default());
                 }
+                self.end_word_build();
                 self.pop_style();
             }
 
             // --- Paragraph ---
-            Event::Start(Tag::Paragraph) => {}
+            Event::Start(Tag::Paragraph) if self.pending_spec => {
+                self.pending_spec = false;
+                self.start_word_build();
+                self.in_spec = Some(self.lines.len());
+            }
+            Event::Start(Tag::Paragraph) => self.start_word_build(),
             Event::End(TagEnd::Paragraph) => {
                 self.flush_line();
+                if let Some(start) = self.in_spec.take() {
+                    let key_style = Style::default()
+                        .fg(self.theme.h2)
+                        .add_modifier(Modifier::BOLD);
+                    let spec = align_spec(self.lines.split_off(start), key_style);
+                    self.lines.extend(spec);
+                }
+                self.end_word_build();
                 // Suppress blank line between list items (loose lists wrap items in paragraphs)
                 if self.list_stack.is_empty() {
                     self.lines.push(Line::default());
@@ -923,16 +3021,65 @@ impl<'a> MdConverter<'a> {
                     .push(Span::styled(format!(" {code} "), style));
             }
 
+            // --- Math ---
+            Event::InlineMath(tex) => {
+                let style = self.current_style().add_modifier(Modifier::ITALIC);
+                let text = self.formula(&tex).replace('\n', " ");
+                self.current_spans.push(Span::styled(text, style));
+            }
+            Event::DisplayMath(tex) => {
+                if !self.current_spans.is_empty() {
+                    self.flush_line();
+                }
+                // Rows go on lines of their own; the paragraph ends the last
+                let style = self.current_style().add_modifier(Modifier::ITALIC);
+                for (i, row) in self.formula(&tex).lines().enumerate() {
+                    if i > 0 {
+                        self.flush_line();
+                    }
+                    self.current_spans
+                        .push(Span::styled(format!("  {row}"), style));
+                }
+            }
+
             // --- Code Block ---
+            Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(lang))) if lang.trim() == "notes" => {
+                self.in_notes = true;
+                self.code_block_buf.clear();
+            }
+            Event::End(TagEnd::CodeBlock) if self.in_notes => {
+                self.in_notes = false;
+                let notes = std::mem::take(&mut self.code_block_buf);
+                self.notes.push(notes.trim_end().to_string());
+            }
             Event::Start(Tag::CodeBlock(kind)) => {
                 self.in_code_block = true;
                 self.code_block_buf.clear();
-                self.code_block_lang = match kind {
-                    CodeBlockKind::Fenced(lang) => {
-                        let lang = lang.split(',').next().unwrap_or("").trim().to_string();
-                        if lang.is_empty() { None } else { Some(lang) }
-                    }
-                    CodeBlockKind::Indented => None,
+                let (lang, options) = match &kind {
+                    CodeBlockKind::Fenced(info) => parse_code_info(info),
+                    CodeBlockKind::Indented => (None, Vec::new()),
+                };
+                // An untagged block named `{file: main.py}` is in the
+                // language of its extension (or name, as in `Makefile`)
+                let file = options.iter().find(|&&(key, _)| key == "file");
+                self.code_block_lang = lang.or_else(|| {
+                    let path = std::path::Path::new(file?.1);
+                    let hint = path.extension().or(path.file_name())?;
+                    Some(hint.to_string_lossy().into_owned())
+                });
+                let flag = |name| options.iter().any(|&(key, _)| key == name);
+                self.code_block_numbers =
+                    !flag("no-numbers") && (self.line_numbers || flag("numbers"));
+                let highlight = options.iter().find(|&&(key, _)| key == "highlight");
+                self.code_block_highlight = match highlight {
+                    Some(&(_, value)) => parse_highlight_groups(value).unwrap_or_else(|| {
+                        self.warnings.push(ParseWarning {
+                            line: self.source_line,
+                            message: format!("invalid code highlight `{value}`"),
+                        });
+                        Vec::new()
+                    }),
+                    None => Vec::new(),
                 };
                 self.flush_line();
                 // Replace preceding blank line (from paragraph end) with bg-colored padding,
@@ -961,6 +3108,18 @@ impl<'a> MdConverter<'a> {
                 if !self.current_spans.is_empty() {
                     self.flush_line();
                 }
+                // A pause between items ends the list in Markdown; carry on
+                // without the gap a new list would leave
+                if self.list_stack.is_empty()
+                    && self.list_end == self.lines.len()
+                    && self.fragments.last() == Some(&self.lines.len())
+                    && self.lines.last().is_some_and(|l| l.spans.is_empty())
+                {
+                    self.lines.pop();
+                    if let Some(line) = self.fragments.last_mut() {
+                        *line -= 1;
+                    }
+                }
                 let kind = match start {
                     Some(n) => ListKind::Ordered(n),
                     None => ListKind::Unordered,
@@ -971,6 +3130,7 @@ impl<'a> MdConverter<'a> {
                 self.list_stack.pop();
                 if self.list_stack.is_empty() {
                     self.lines.push(Line::default());
+                    self.list_end = self.lines.len();
                 }
             }
 
@@ -992,21 +3152,63 @@ impl<'a> MdConverter<'a> {
                     Style::default().fg(self.theme.list_bullet),
                 ));
             }
-            Event::End(TagEnd::Item) => {
-                if !self.current_spans.is_empty() {
-                    self.flush_line();
-                }
+            Event::End(TagEnd::Item) if !self.current_spans.is_empty() => {
+                self.flush_line();
             }
 
             // --- Blockquote ---
-            Event::Start(Tag::BlockQuote(_)) => {
+            Event::Start(Tag::BlockQuote(Some(kind))) => self.open_alert(kind),
+            Event::End(TagEnd::BlockQuote(Some(_))) => self.close_div(),
+            Event::Start(Tag::BlockQuote(None)) => {
                 self.in_blockquote = true;
             }
-            Event::End(TagEnd::BlockQuote(_)) => {
+            Event::End(TagEnd::BlockQuote(None)) => {
                 self.in_blockquote = false;
                 self.lines.push(Line::default());
             }
 
+            // --- Tables ---
+            Event::Start(Tag::Table(alignments)) => {
+                if !self.current_spans.is_empty() {
+                    self.flush_line();
+                }
+                let alignments = alignments
+                    .iter()
+                    .map(|alignment| match alignment {
+                        pulldown_cmark::Alignment::Center => Alignment::Center,
+                        pulldown_cmark::Alignment::Right => Alignment::Right,
+                        _ => Alignment::Left,
+                    })
+                    .collect();
+                self.table = Some(SlideTable {
+                    line_index: 0,
+                    alignments,
+                    rows: Vec::new(),
+                    border_style: Style::default().fg(self.theme.block_quote_prefix),
+                });
+            }
+            Event::Start(Tag::TableHead) => {
+                let color = self.theme.h2;
+                self.push_style(|s| s.fg(color).add_modifier(Modifier::BOLD));
+            }
+            Event::End(TagEnd::TableCell) => {
+                let cell = std::mem::take(&mut self.current_spans);
+                self.table_row.push(cell);
+            }
+            Event::End(TagEnd::TableHead) => {
+                self.pop_style();
+                self.end_table_row();
+            }
+            Event::End(TagEnd::TableRow) => self.end_table_row(),
+            Event::End(TagEnd::Table) => {
+                if let Some(mut table) = self.table.take() {
+                    table.line_index = self.lines.len();
+                    self.lines.extend(table.lines(usize::MAX));
+                    self.lines.push(Line::default());
+                    self.tables.push(table);
+                }
+            }
+
             // --- Horizontal Rule = Slide separator ---
             Event::Rule => {
                 self.flush_slide();
@@ -1025,19 +3227,31 @@ impl<'a> MdConverter<'a> {
             }
             Event::End(TagEnd::Link) => {
                 let end_col: usize = self.link_start_col + Span::raw(&self.link_text_buf).width();
-                self.semantics.push(SemanticElement::Link {
+                let link = SemanticElement::Link {
                     url: std::mem::take(&mut self.link_url),
                     text: std::mem::take(&mut self.link_text_buf),
                     line_index: self.link_start_line,
                     start_col: self.link_start_col,
                     end_col,
-                });
+                };
+                // A table cell moves whenever the table is laid out again
+                if self.table.is_none() {
+                    self.semantics.push(link);
+                }
                 self.in_link = false;
                 self.pop_style();
             }
 
             // --- Text ---
             Event::Text(text) => {
+                let text = if self.title_case
+                    && self.in_semantic_heading
+                    && self.semantic_heading_level == 1
+                {
+                    title_case(&text, &self.semantic_heading_buf).into()
+                } else {
+                    text
+                };
                 // Accumulate into semantic buffers
                 if self.in_semantic_heading {
                     self.semantic_heading_buf.push_str(&text);
@@ -1050,16 +3264,31 @@ impl<'a> MdConverter<'a> {
                     self.heading_text_buf.push_str(&text);
                 } else if self.in_image {
                     // Skip alt text of images
-                } else if self.in_code_block {
+                } else if self.in_code_block || self.in_notes {
                     self.code_block_buf.push_str(&text);
                 } else {
-                    self.current_spans
-                        .push(Span::styled(text.to_string(), self.current_style()));
+                    self.push_text(&text);
+                }
+            }
+
+            // Badges in a block of their own share one row
+            Event::Start(Tag::HtmlBlock) => {
+                self.badge_row = false;
+            }
+            Event::End(TagEnd::HtmlBlock) if self.badge_row => {
+                self.badge_row = false;
+                if !self.current_spans.is_empty() {
+                    self.flush_line();
+                    self.lines.push(Line::default());
                 }
             }
 
             Event::SoftBreak => {
-                self.current_spans.push(Span::raw(" "));
+                if self.in_spec.is_some() {
+                    self.flush_line();
+                } else {
+                    self.current_spans.push(Span::raw(" "));
+                }
             }
             Event::HardBreak => {
                 self.flush_line();
@@ -1083,70 +3312,116 @@ impl<'a> MdConverter<'a> {
     fn flush_code_block(&mut self) {
         let buf = std::mem::take(&mut self.code_block_buf);
         let lang = self.code_block_lang.take();
+        // ```diff colors added and removed lines; ```diff-rust also
+        // highlights the Rust after each line's marker
+        let diff_lang = lang.as_deref().and_then(|l| l.strip_prefix("diff-"));
+        let diff = lang.as_deref() == Some("diff") || diff_lang.is_some();
+        let code = buf.trim_end_matches('\n');
+        let lang = match diff {
+            true => diff_lang.map(str::to_string),
+            false => lang.or_else(|| {
+                let guess = (self.detect_language)
+                    .then(|| guess_language(code, |lang| self.highlighter.knows(lang)));
+                guess.flatten().map(str::to_string)
+            }),
+        };
         let bg = self.theme.surface;
-        let code = buf.trim_end_matches('\n');
+        // Right-aligned numbers in a dimmed column, recorded in
+        // `Slide::code_gutters` so `rewrap_bg_lines` in render.rs keeps them
+        // clear of wrapped code
+        let numbers = std::mem::take(&mut self.code_block_numbers);
+        let first_line = self.lines.len();
+        let number_width = code.split('\n').count().to_string().len();
+        let number_style = Style::default().fg(self.theme.block_quote_prefix).bg(bg);
+        let gutter = |i: usize| {
+            let number = format!("{:\u{00a0}>number_width$}\u{00a0}│\u{00a0}", i + 1);
+            numbers.then(|| Span::styled(number, number_style))
+        };
 
-        let syntax = lang.as_deref().and_then(|l| {
-            self.syntax_set.find_syntax_by_token(l).or_else(|| {
-                // Fallback: map common tokens missing from syntect defaults
-                let fallback = match l {
-                    "jsx" | "tsx" | "ts" | "typescript" => Some("js"),
-                    _ => None,
-                };
-                fallback.and_then(|f| self.syntax_set.find_syntax_by_token(f))
+        let split: Vec<_> = code
+            .split('\n')
+            .map(|line| match diff {
+                true => split_diff_line(line, &self.theme.diff),
+                false => ("", line, None),
             })
-        });
+            .collect();
+        let code_lines: Vec<&str> = split.iter().map(|&(_, line, _)| line).collect();
+        let highlighted = lang
+            .as_deref()
+            .and_then(|l| self.highlighter.highlight(l, &code_lines));
 
-        if let Some(syntax) = syntax {
-            let mut h = syntect::easy::HighlightLines::new(syntax, &self.syntect_theme);
-            for line in code.split('\n') {
-                let regions = h.highlight_line(line, &self.syntax_set).unwrap_or_default();
+        if let Some(highlighted) = highlighted {
+            for (i, (&(marker, _, color), pieces)) in split.iter().zip(highlighted).enumerate() {
+                let bg = color.map_or(bg, |color| blend_color(bg, color, 0.15));
                 let mut spans: Vec<Span<'static>> =
                     vec![Span::styled("\u{00a0}\u{00a0}", Style::default().bg(bg))];
-                for (syn_style, text) in regions {
-                    let fg_color = Color::Rgb(
-                        syn_style.foreground.r,
-                        syn_style.foreground.g,
-                        syn_style.foreground.b,
-                    );
-                    let mut style = Style::default().fg(fg_color).bg(bg);
-                    if syn_style
-                        .font_style
-                        .contains(syntect::highlighting::FontStyle::BOLD)
-                    {
-                        style = style.add_modifier(Modifier::BOLD);
-                    }
-                    if syn_style
-                        .font_style
-                        .contains(syntect::highlighting::FontStyle::ITALIC)
-                    {
-                        style = style.add_modifier(Modifier::ITALIC);
-                    }
-                    if syn_style
-                        .font_style
-                        .contains(syntect::highlighting::FontStyle::UNDERLINE)
-                    {
-                        style = style.add_modifier(Modifier::UNDERLINED);
-                    }
+                spans.extend(gutter(i));
+                if !marker.is_empty() {
+                    let style = Style::default().fg(color.unwrap_or(self.theme.fg)).bg(bg);
+                    spans.push(Span::styled(marker.replace(' ', "\u{00a0}"), style));
+                }
+                for (style, text) in pieces {
                     // Use NBSP so word-wrapper falls back to character-based
                     // wrapping, matching wrapped_line_height calculation.
-                    spans.push(Span::styled(text.replace(' ', "\u{00a0}"), style));
+                    spans.push(Span::styled(text.replace(' ', "\u{00a0}"), style.bg(bg)));
                 }
                 self.lines
                     .push(Line::from(spans).style(Style::default().bg(bg)));
             }
         } else {
             // Fallback: uniform style (no language or unknown language)
-            let style = Style::default().fg(self.theme.fg).bg(bg);
-            for line in code.split('\n') {
+            for (i, line) in code.split('\n').enumerate() {
+                let color = match diff {
+                    true => split_diff_line(line, &self.theme.diff).2,
+                    false => None,
+                };
+                let bg = color.map_or(bg, |color| blend_color(bg, color, 0.15));
+                let style = Style::default().fg(color.unwrap_or(self.theme.fg)).bg(bg);
+                let mut spans = vec![Span::styled("\u{00a0}\u{00a0}", style)];
+                spans.extend(gutter(i));
                 // Use NBSP so word-wrapper falls back to character-based wrapping
-                let text = format!("\u{00a0}\u{00a0}{}", line.replace(' ', "\u{00a0}"));
-                self.lines.push(
-                    Line::from(vec![Span::styled(text, style)])
-                        .style(Style::default().bg(bg)),
-                );
+                spans.push(Span::styled(line.replace(' ', "\u{00a0}"), style));
+                self.lines
+                    .push(Line::from(spans).style(Style::default().bg(bg)));
+            }
+        }
+
+        // The block shows its last group; earlier ones are pause steps that
+        // `Slide::at_step` restyles the block for
+        let groups = std::mem::take(&mut self.code_block_highlight);
+        if !groups.is_empty() {
+            let highlight = SlideCodeHighlight {
+                line_index: first_line,
+                height: self.lines.len() - first_line,
+                groups,
+                first_step: 0,
+                bg,
+                highlight_bg: blend_color(bg, self.theme.fg, 0.15),
+            };
+            highlight.apply(&mut self.lines, usize::MAX);
+            if highlight.groups.len() > 1 {
+                self.code_highlights.push(highlight);
             }
         }
+        if numbers {
+            self.code_gutters.push(SlideCodeGutter {
+                column: 0,
+                line_index: first_line,
+                height: self.lines.len() - first_line,
+                width: number_width + 3,
+            });
+        }
+    }
+
+    fn push_big_text(&mut self, text: String, rows: usize, style: Style) {
+        let big_text = SlideBigText {
+            line_index: self.lines.len(),
+            text,
+            rows,
+            style: style.remove_modifier(Modifier::UNDERLINED),
+        };
+        self.lines.extend(big_text.lines(usize::MAX));
+        self.big_texts.push(big_text);
     }
 
     fn render_figlet_heading(&mut self, text: &str, style: Style) {
@@ -1156,8 +3431,28 @@ impl<'a> MdConverter<'a> {
             .pending_figlet_color
             .as_deref()
             .or(self.default_figlet_color.as_deref());
-        let has_color = color.is_some();
-        let art = self.figlet_fn.and_then(|f| f(text, font, color));
+        let art = match self.figlet_fn {
+            Some(figlet) => {
+                let (art, warning) = figlet_art(figlet, text, font, color);
+                self.warnings.extend(warning.map(|message| ParseWarning {
+                    line: self.source_line,
+                    message,
+                }));
+                art.ok()
+            }
+            None => {
+                // Left for Slide::render_figlets once the slide is shown
+                self.pending_figlets.push(PendingFiglet {
+                    line_index: self.lines.len(),
+                    text: text.to_string(),
+                    font: font.map(str::to_string),
+                    color: color.map(str::to_string),
+                    style,
+                    source_line: self.source_line,
+                });
+                None
+            }
+        };
 
         let Some(art) = art else {
             self.current_spans
@@ -1165,29 +3460,9 @@ impl<'a> MdConverter<'a> {
             self.flush_line();
             return;
         };
-        // Trim trailing all-whitespace lines
-        let art_lines: Vec<&str> = art.split('\n').collect();
-        let end = art_lines
-            .iter()
-            .rposition(|l| l.chars().any(|c| !c.is_whitespace()))
-            .map_or(0, |i| i + 1);
-
         let line_index = self.lines.len();
-        let mut styled_lines = Vec::new();
-        if has_color {
-            // Parse ANSI escape codes into colored Spans
-            for line in &art_lines[..end] {
-                let l = parse_ansi_line(line, style);
-                styled_lines.push(l.clone());
-                self.lines.push(l);
-            }
-        } else {
-            for line in &art_lines[..end] {
-                let l = Line::from(Span::styled(line.to_string(), style));
-                styled_lines.push(l.clone());
-                self.lines.push(l);
-            }
-        }
+        let styled_lines = figlet_lines(&art, style, color.is_some());
+        self.lines.extend(styled_lines.iter().cloned());
         let line_count = styled_lines.len();
         if line_count > 0 {
             self.figlet_headings.push(FigletHeadingMeta {
@@ -1218,6 +3493,7 @@ impl<'a> MdConverter<'a> {
                 right_content: None,
                 images: std::mem::take(&mut self.images),
                 transition,
+                title: first_heading(&self.semantics),
                 semantics: std::mem::take(&mut self.semantics),
                 theme: self.theme.clone(),
                 line_height: self
@@ -1225,6 +3501,8 @@ impl<'a> MdConverter<'a> {
                     .take()
                     .or(self.default_line_height)
                     .unwrap_or(1.2),
+                cell_aspect: self.cell_aspect,
+                ascii_only: self.ascii_only,
                 bg_fill: self
                     .pending_bg_fill
                     .take()
@@ -1236,14 +3514,65 @@ impl<'a> MdConverter<'a> {
                     .or_else(|| self.default_header.clone())
                     .unwrap_or_default(),
                 figlet_headings: std::mem::take(&mut self.figlet_headings),
+                pending_figlets: std::mem::take(&mut self.pending_figlets),
+                big_headings: std::mem::take(&mut self.big_headings),
+                audio: self.pending_audio.take(),
+                videos: std::mem::take(&mut self.videos),
+                presenter_only: std::mem::take(&mut self.pending_presenter_only),
+                notes: (!self.notes.is_empty()).then(|| self.notes.join("\n\n")),
+                fragments: std::mem::take(&mut self.fragments),
+                word_builds: std::mem::take(&mut self.word_builds),
+                tables: std::mem::take(&mut self.tables),
+                big_texts: std::mem::take(&mut self.big_texts),
+                code_highlights: std::mem::take(&mut self.code_highlights),
+                code_gutters: std::mem::take(&mut self.code_gutters),
+                poll: self.poll.take(),
             });
         }
         self.slides
     }
 }
 
-/// Split lines at `|||` marker into left/right columns for TwoColumn layout.
-fn split_two_column(lines: Vec<Line<'static>>) -> Slide {
+/// FIGlet art for `text`, in the default font when `font` can't be used,
+/// with a warning saying why.
+fn figlet_art(
+    figlet: &FigletFn,
+    text: &str,
+    font: Option<&str>,
+    color: Option<&str>,
+) -> (Result<String, String>, Option<String>) {
+    match (figlet(text, font, color), font) {
+        (Err(e), Some(name)) => (
+            figlet(text, None, color),
+            Some(format!("figlet font `{name}`: {e}; using the default font")),
+        ),
+        (art, _) => (art, None),
+    }
+}
+
+/// FIGlet `art` as lines in `style`, without its trailing blank lines. Art
+/// drawn with a color has ANSI escapes, parsed into colored spans.
+fn figlet_lines(art: &str, style: Style, colored: bool) -> Vec<Line<'static>> {
+    let art_lines: Vec<&str> = art.split('\n').collect();
+    let end = art_lines
+        .iter()
+        .rposition(|l| l.chars().any(|c| !c.is_whitespace()))
+        .map_or(0, |i| i + 1);
+    art_lines[..end]
+        .iter()
+        .map(|line| {
+            if colored {
+                parse_ansi_line(line, style)
+            } else {
+                Line::from(Span::styled(line.to_string(), style))
+            }
+        })
+        .collect()
+}
+
+/// Split lines at `|||` marker into left/right columns for TwoColumn layout,
+/// moving `code_gutters` into the column their block ends up in.
+fn split_two_column(lines: Vec<Line<'static>>, mut code_gutters: Vec<SlideCodeGutter>) -> Slide {
     let sep_idx = lines.iter().position(|line| {
         let text: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
         text.trim() == "|||"
@@ -1261,8 +3590,16 @@ fn split_two_column(lines: Vec<Line<'static>>) -> Slide {
                 right.pop();
             }
             // Trim leading blanks from right
+            let mut right_start = idx + 1;
             while right.first().is_some_and(|l| l.spans.is_empty()) {
                 right.remove(0);
+                right_start += 1;
+            }
+            for gutter in &mut code_gutters {
+                if gutter.line_index > idx {
+                    gutter.column = 1;
+                    gutter.line_index -= right_start;
+                }
             }
             Slide {
                 layout: SlideLayout::TwoColumn,
@@ -1273,9 +3610,25 @@ fn split_two_column(lines: Vec<Line<'static>>) -> Slide {
                 semantics: Vec::new(),
                 theme: Theme::default(),
                 line_height: 1.2,
+                cell_aspect: DEFAULT_CELL_ASPECT,
+                ascii_only: false,
                 bg_fill: false,
                 header: Vec::new(),
                 figlet_headings: Vec::new(),
+                pending_figlets: Vec::new(),
+                big_headings: Vec::new(),
+                audio: None,
+                videos: Vec::new(),
+                title: None,
+                presenter_only: false,
+                notes: None,
+                fragments: Vec::new(),
+                word_builds: Vec::new(),
+                tables: Vec::new(),
+                big_texts: Vec::new(),
+                code_highlights: Vec::new(),
+                code_gutters,
+                poll: None,
             }
         }
         None => Slide {
@@ -1287,13 +3640,126 @@ fn split_two_column(lines: Vec<Line<'static>>) -> Slide {
             semantics: Vec::new(),
             theme: Theme::default(),
             line_height: 1.2,
+            cell_aspect: DEFAULT_CELL_ASPECT,
+            ascii_only: false,
             bg_fill: false,
             header: Vec::new(),
             figlet_headings: Vec::new(),
+            pending_figlets: Vec::new(),
+            big_headings: Vec::new(),
+            audio: None,
+            videos: Vec::new(),
+            title: None,
+            presenter_only: false,
+            notes: None,
+            fragments: Vec::new(),
+            word_builds: Vec::new(),
+            tables: Vec::new(),
+            big_texts: Vec::new(),
+            code_highlights: Vec::new(),
+            code_gutters,
+            poll: None,
         },
     }
 }
 
+/// Lay out `key: value` lines as two columns: keys right-aligned in
+/// `key_style`, values lined up after them. Lines without a key continue the
+/// value above.
+fn align_spec(lines: Vec<Line<'static>>, key_style: Style) -> Vec<Line<'static>> {
+    let rows: Vec<(Option<String>, Vec<Span<'static>>)> = lines
+        .into_iter()
+        .map(|line| split_spec_key(line.spans))
+        .collect();
+    let key_width = rows
+        .iter()
+        .filter_map(|(key, _)| key.as_deref().map(UnicodeWidthStr::width))
+        .max()
+        .unwrap_or(0);
+    rows.into_iter()
+        .map(|(key, value)| {
+            let key = key.unwrap_or_default();
+            let pad = " ".repeat(key_width - key.width());
+            let mut spans = vec![
+                Span::raw(pad),
+                Span::styled(key, key_style),
+                Span::raw("  "),
+            ];
+            spans.extend(value);
+            Line::from(spans)
+        })
+        .collect()
+}
+
+/// Split spans at the first `:` into the key text and the value spans.
+fn split_spec_key(spans: Vec<Span<'static>>) -> (Option<String>, Vec<Span<'static>>) {
+    let Some(at) = spans.iter().position(|s| s.content.contains(':')) else {
+        return (None, spans);
+    };
+    let mut key: String = spans[..at].iter().map(|s| s.content.as_ref()).collect();
+    let mut value = spans[at..].to_vec();
+    let (before, after) = value[0].content.split_once(':').unwrap_or_default();
+    key.push_str(before);
+    value[0].content = after.trim_start().to_string().into();
+    if value[0].content.is_empty() {
+        value.remove(0);
+    }
+    (Some(key.trim().to_string()), value)
+}
+
+/// Base style for body text in `theme`.
+fn body_style(theme: &Theme, bold_all: bool) -> Style {
+    let style = Style::default().fg(theme.fg);
+    if bold_all {
+        style.add_modifier(Modifier::BOLD)
+    } else {
+        style
+    }
+}
+
+/// Words that stay lowercase in a title unless they start it (or follow a
+/// colon).
+const MINOR_WORDS: &[&str] = &[
+    "a", "an", "and", "as", "at", "but", "by", "for", "from", "in", "into", "nor", "of", "on",
+    "or", "per", "the", "to", "via", "vs", "with",
+];
+
+/// Title-case `text`, which follows `before` in the same heading. Words that
+/// already have a capital (`iPhone`, `CLI`) are left as written.
+fn title_case(text: &str, before: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut mid_word = before.ends_with(|c: char| !c.is_whitespace());
+    let mut opens = before.trim_end().is_empty() || before.trim_end().ends_with(':');
+    for word in text.split_inclusive(char::is_whitespace) {
+        let bare = word.trim_end();
+        let core = bare.trim_matches(|c: char| !c.is_alphanumeric());
+        let keep = mid_word
+            || core.chars().any(char::is_uppercase)
+            || (!opens && MINOR_WORDS.contains(&core));
+        match word.char_indices().find(|(_, c)| c.is_alphabetic()) {
+            Some((i, c)) if !keep => {
+                out.push_str(&word[..i]);
+                out.extend(c.to_uppercase());
+                out.push_str(&word[i + c.len_utf8()..]);
+            }
+            _ => out.push_str(word),
+        }
+        if !bare.is_empty() {
+            opens = bare.ends_with(':');
+        }
+        mid_word = false;
+    }
+    out
+}
+
+/// Text of the first heading among a slide's semantic elements.
+fn first_heading(semantics: &[SemanticElement]) -> Option<String> {
+    semantics.iter().find_map(|elem| match elem {
+        SemanticElement::Heading { text, .. } => Some(text.clone()),
+        _ => None,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1303,7 +3769,7 @@ mod tests {
 
     fn parse(md: &str) -> Vec<Slide> {
         let fm = Frontmatter::default();
-        parse_slides(md, &test_theme(), &fm, None, false)
+        parse_slides(md, &test_theme(), &fm, None, false).0
     }
 
     /// Helper: collect (text, has_bg) for each line in a slide.
@@ -1320,6 +3786,103 @@ mod tests {
             .collect()
     }
 
+    #[test]
+    fn transition_none_overrides_deck_default() {
+        let fm = Frontmatter {
+            transition: Some(TransitionKind::Fade),
+            ..Frontmatter::default()
+        };
+        let md = "one\n\n---\n\n<!-- transition: none -->\n\ntwo\n";
+        let slides = parse_slides(md, &test_theme(), &fm, None, false).0;
+        assert!(matches!(slides[0].transition, TransitionKind::Fade));
+        assert!(matches!(slides[1].transition, TransitionKind::None));
+    }
+
+    #[test]
+    fn presenter_only_slides_are_skipped_for_the_audience() {
+        let md = "one\n\n---\n\n<!-- presenter-only -->\n\ncrib\n\n---\n\nthree\n";
+        let slides = parse(md);
+        assert!(slides[1].presenter_only);
+        assert!(!slides[2].presenter_only);
+        assert_eq!(adjacent_slide(&slides, 0, true, false), Some(2));
+        assert_eq!(adjacent_slide(&slides, 2, false, false), Some(0));
+        assert_eq!(adjacent_slide(&slides, 0, true, true), Some(1));
+        assert_eq!(adjacent_slide(&slides, 2, true, false), None);
+    }
+
+    #[test]
+    fn section_navigation_jumps_between_h1_slides() {
+        let md = "# A\n\n---\n\na2\n\n---\n\n# B\n\n---\n\nb2\n\n---\n\nb3\n";
+        let slides = parse(md);
+        assert!(slides[0].starts_section());
+        assert!(!slides[1].starts_section());
+        assert_eq!(adjacent_section(&slides, 0, true, false), Some(2));
+        assert_eq!(adjacent_section(&slides, 2, true, false), None);
+        assert_eq!(adjacent_section(&slides, 4, false, false), Some(2));
+        assert_eq!(adjacent_section(&slides, 2, false, false), Some(0));
+        assert_eq!(adjacent_section(&slides, 0, false, false), None);
+    }
+
+    #[test]
+    fn first_changed_slide_finds_the_edit() {
+        let old = parse("one\n\n---\n\ntwo\n\n---\n\nthree\n");
+        assert_eq!(first_changed_slide(&old, &old), None);
+        let edited = parse("one\n\n---\n\ntwo!\n\n---\n\nthree\n");
+        assert_eq!(first_changed_slide(&old, &edited), Some(1));
+        let appended = parse("one\n\n---\n\ntwo\n\n---\n\nthree\n\n---\n\nfour\n");
+        assert_eq!(first_changed_slide(&old, &appended), Some(3));
+        let truncated = parse("one\n\n---\n\ntwo\n");
+        assert_eq!(first_changed_slide(&old, &truncated), Some(1));
+    }
+
+    #[test]
+    fn missing_figlet_font_falls_back_with_warning() {
+        let figlet = |text: &str, font: Option<&str>, _: Option<&str>| match font {
+            None => Ok(format!("<{text}>")),
+            Some(_) => Err("figlet: nope.flf: Unable to open font file".to_string()),
+        };
+        let md = "<!-- figlet:nope -->\n\n# Title\n";
+        let (slides, warnings) = parse_slides(
+            md,
+            &test_theme(),
+            &Frontmatter::default(),
+            Some(&figlet),
+            false,
+        );
+        let info = line_info(&slides[0]);
+        assert!(info.iter().any(|(t, _)| t == "<Title>"), "{info:?}");
+        assert_eq!(
+            warnings[0].message,
+            "figlet font `nope`: figlet: nope.flf: Unable to open font file; using the default font"
+        );
+        assert_eq!(warnings.len(), 1);
+    }
+
+    #[test]
+    fn figlet_headings_without_a_renderer_are_drawn_later() {
+        let figlet = |text: &str, font: Option<&str>, _: Option<&str>| match font {
+            None => Ok(format!("<{text}>\n[{text}]\n\n")),
+            Some(_) => Err("no such font".to_string()),
+        };
+        let md = "<!-- figlet:nope -->\n\n# Title\n\n<!-- pause -->\n\nafter\n";
+        let mut slide = parse(md).remove(0);
+        let text = |slide: &Slide| -> Vec<String> {
+            line_info(slide).into_iter().map(|(t, _)| t).collect()
+        };
+        assert_eq!(text(&slide)[0], "Title");
+        assert_eq!(slide.pending_figlets.len(), 1);
+        let after = slide.fragments[0];
+
+        let warnings = slide.render_figlets(&figlet);
+        assert_eq!(text(&slide)[..2], ["<Title>", "[Title]"]);
+        assert_eq!(slide.fragments[0], after + 1);
+        assert_eq!(slide.figlet_headings[0].line_count, 2);
+        assert!(slide.pending_figlets.is_empty());
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].line, 3);
+        assert!(warnings[0].message.contains("no such font"));
+    }
+
     #[test]
     fn single_code_block() {
         let md = "```\nhello\n```\n";
@@ -1337,6 +3900,91 @@ mod tests {
         assert!(info[1].1, "content should have bg");
     }
 
+    #[test]
+    fn code_blocks_can_number_their_lines() {
+        let code: Vec<String> = (1..=10).map(|i| format!("x = {i}")).collect();
+        let code = code.join("\n");
+        let md = format!("```rust {{numbers}}\n{code}\n```\n\n```\nplain\n```\n");
+        let info = line_info(&parse(&md)[0]);
+        let text = |i: usize| info[i].0.replace('\u{a0}', " ");
+        assert_eq!(text(1), "   1 │ x = 1");
+        assert_eq!(text(10), "  10 │ x = 10");
+        assert_eq!(text(14), "  plain");
+
+        let fm = Frontmatter {
+            line_numbers: Some(true),
+            ..Frontmatter::default()
+        };
+        let md = "```\none\n```\n\n```sh {no-numbers}\n$ two\n```\n";
+        let (slides, _) = parse_slides(md, &test_theme(), &fm, None, false);
+        let info = line_info(&slides[0]);
+        assert_eq!(info[1].0.replace('\u{a0}', " "), "  1 │ one");
+        assert_eq!(info[5].0.replace('\u{a0}', " "), "  $ two");
+        let gutter = |column, line_index| SlideCodeGutter {
+            column,
+            line_index,
+            height: 1,
+            width: 4,
+        };
+        assert_eq!(slides[0].code_gutters, [gutter(0, 1)]);
+
+        // Blocks in the right column are counted from its first line
+        let md = "<!-- layout: two-column -->\n\n```\nleft\n```\n\n|||\n\n```\nright\n```\n";
+        let (slides, _) = parse_slides(md, &test_theme(), &fm, None, false);
+        assert_eq!(slides[0].code_gutters, [gutter(0, 1), gutter(1, 0)]);
+        let right = slides[0].right_content.as_ref().unwrap();
+        assert_eq!(
+            right.lines[0].to_string().replace('\u{a0}', " "),
+            "  1 │ right"
+        );
+    }
+
+    #[test]
+    fn code_highlight_groups_are_pause_steps() {
+        let md = "<!-- pause -->\n\n```rust {highlight: 1|2-3}\na\nb\nc\n```\n\n<!-- pause -->\n\nafter\n";
+        let slide = &parse(md)[0];
+        assert_eq!(slide.fragments.len(), 3);
+        let shown = |step: usize, text: &str| {
+            let lines = slide.at_step(step).content.lines.clone();
+            let line = lines.into_iter().find(|l| l.to_string().contains(text));
+            line.map(|l| l.style.bg == Some(slide.code_highlights[0].highlight_bg))
+        };
+        assert_eq!(shown(1, "a"), Some(true));
+        assert_eq!(shown(1, "b"), Some(false));
+        assert_eq!(shown(2, "a"), Some(false));
+        assert_eq!(shown(2, "c"), Some(true));
+        assert_eq!(shown(2, "after"), None);
+        assert_eq!(shown(3, "b"), Some(true));
+
+        let slide = &parse("```\na\nb\n```\n\n```{highlight: 2}\nc\n```\n")[0];
+        assert!(slide.fragments.is_empty());
+        assert!(slide.code_highlights.is_empty());
+    }
+
+    #[test]
+    fn diff_blocks_color_added_and_removed_lines() {
+        let theme = test_theme();
+        let md = "```diff\n-old\n+new\n same\n```\n\n```diff-rust\n+let x = 1;\n```\n\n```diff\n--- a/x.rs\n+++ b/x.rs\n---- gone\n```\n";
+        let lines = &parse(md)[0].content.lines;
+        let fg = |line: &Line| line.spans.last().and_then(|span| span.style.fg);
+        assert_eq!(fg(&lines[1]), Some(theme.diff.removed));
+        assert_eq!(fg(&lines[2]), Some(theme.diff.added));
+        assert_eq!(fg(&lines[3]), Some(theme.fg));
+        assert_ne!(lines[2].style.bg, lines[3].style.bg);
+        // File headers are no added or removed lines
+        assert_eq!(fg(&lines[11]), Some(theme.fg));
+        assert_eq!(fg(&lines[12]), Some(theme.fg));
+        assert_eq!(lines[12].style.bg, lines[3].style.bg);
+        assert_eq!(fg(&lines[13]), Some(theme.diff.removed));
+
+        let rust = &lines[7];
+        assert_eq!(rust.spans[1].content, "+");
+        assert_eq!(rust.spans[1].style.fg, Some(theme.diff.added));
+        let added = Some(theme.diff.added);
+        assert!(rust.spans[2..].iter().any(|span| span.style.fg != added));
+        assert_eq!(rust.style.bg, lines[2].style.bg);
+    }
+
     #[test]
     fn consecutive_code_blocks_have_gap() {
         let md = "```\nfirst\n```\n\n```\nsecond\n```\n";
@@ -1444,4 +4092,575 @@ mod tests {
             gap2
         );
     }
+
+    #[test]
+    fn frontmatter_numbers_keep_their_digits() {
+        let (fm, _) = parse_frontmatter("---\nfiglet_color: 008000 # green\n---\n");
+        assert_eq!(fm.figlet_color.as_deref(), Some("008000"));
+        assert!(fm.warnings.is_empty());
+
+        let (fm, _) = parse_frontmatter("---\nlogo:\n  path: a.png\n---\n");
+        assert_eq!(fm.warnings.len(), 1);
+        assert!(fm.warnings[0].message.contains("nested YAML"));
+        assert_eq!(fm.warnings[0].line, 2);
+    }
+
+    #[test]
+    fn frontmatter_aspect_ratio() {
+        let (fm, _) = parse_frontmatter("---\naspect: 16:9\n---\n# Hi\n");
+        assert!((fm.aspect.unwrap() - 16.0 / 9.0).abs() < 1e-9);
+
+        let (fm, _) = parse_frontmatter("---\naspect: 4/3\n---\n");
+        assert!((fm.aspect.unwrap() - 4.0 / 3.0).abs() < 1e-9);
+
+        let (fm, _) = parse_frontmatter("---\naspect: 0:9\n---\n");
+        assert!(fm.aspect.is_none());
+
+        let (fm, _) = parse_frontmatter("---\ncell_aspect: 0.45\n---\n");
+        assert_eq!(fm.cell_aspect, Some(0.45));
+        for value in ["0", "inf", "NaN", "-0.5"] {
+            let (fm, _) = parse_frontmatter(&format!("---\ncell_aspect: {value}\n---\n"));
+            assert_eq!((fm.cell_aspect, fm.warnings.len()), (None, 1));
+        }
+
+        let (fm, _) = parse_frontmatter("---\ndither_colors: 16\ndither: ordered\n---\n");
+        assert_eq!(fm.dither_colors, Some(DitherColors::Ansi16));
+        assert_eq!(fm.dither, Some(Dither::Ordered));
+
+        let (fm, _) = parse_frontmatter("---\nmin_size: 60x18\n---\n");
+        assert_eq!(fm.min_size, Some((60, 18)));
+        let (fm, _) = parse_frontmatter("---\nmin_size: 60\n---\n");
+        assert_eq!((fm.min_size, fm.warnings.len()), (None, 1));
+    }
+
+    #[test]
+    fn frontmatter_ascii_only_reaches_every_slide() {
+        let md = "---\nascii_only: true\n---\n# A\n\n---\n\nleft\n\n|||\n\nright\n";
+        let (fm, body) = parse_frontmatter(md);
+        let (slides, _) = parse_slides(body, &test_theme(), &fm, None, false);
+        assert_eq!(slides.len(), 2);
+        assert!(slides.iter().all(|slide| slide.ascii_only));
+    }
+
+    #[test]
+    fn frontmatter_highlighter_none_leaves_code_plain() {
+        let md = "---\nhighlighter: none\n---\n```rust\nfn main() {}\n```\n";
+        let (fm, body) = parse_frontmatter(md);
+        assert_eq!(fm.highlighter, Some(HighlighterKind::None));
+        let (slides, _) = parse_slides(body, &test_theme(), &fm, None, false);
+        let code = &slides[0].content.lines[0];
+        let fg = test_theme().fg;
+        assert!(code.spans.iter().all(|span| span.style.fg == Some(fg)));
+
+        let (fm, _) = parse_frontmatter("---\nhighlighter: prism\n---\n");
+        assert_eq!(fm.highlighter, None);
+        assert_eq!(fm.warnings.len(), 1);
+    }
+
+    #[test]
+    fn untagged_code_is_highlighted_from_file_hints_and_guesses() {
+        let colors = |md: &str| {
+            let (fm, body) = parse_frontmatter(md);
+            let (slides, _) = parse_slides(body, &test_theme(), &fm, None, false);
+            let line = &slides[0].content.lines[1];
+            let mut colors: Vec<_> = line.spans.iter().map(|span| span.style.fg).collect();
+            colors.dedup();
+            colors.len()
+        };
+        let code = "```{}\nfn main() { let mut x = 1; println!(\"{x}\"); }\n```\n";
+        assert_eq!(colors(&code.replace("{}", "")), 1);
+        assert!(colors(&code.replace("{}", "{file=src/main.rs}")) > 1);
+        let untagged = code.replace("{}", "");
+        assert!(colors(&format!("---\ndetect_language: true\n---\n{untagged}")) > 1);
+
+        // Options split at their first `:` or `=`
+        let (_, options) = parse_code_info("{file=C:\\main.rs title: a=b}");
+        assert_eq!(options, [("file", "C:\\main.rs"), ("title", "a=b")]);
+    }
+
+    #[test]
+    fn frontmatter_yaml_values() {
+        let md = "---\ntheme: 'latte'\nheader:\n  - \"[Docs](https://example.com)\"\n  - v1\nfiglet:\n---\n";
+        let (fm, _) = parse_frontmatter(md);
+        assert_eq!(fm.theme.as_deref(), Some("latte"));
+        let header = fm.header.unwrap();
+        assert_eq!(header.len(), 2);
+        assert_eq!(header[0].url.as_deref(), Some("https://example.com"));
+        assert_eq!(fm.figlet, Some(None));
+        assert!(fm.warnings.is_empty());
+
+        // Unquoted link items aren't valid YAML; fall back to line parsing
+        let (fm, _) = parse_frontmatter("---\nheader:\n  - [Docs](https://example.com)\n---\n");
+        assert_eq!(fm.header.unwrap()[0].text, "Docs");
+        assert_eq!(fm.warnings.len(), 1);
+    }
+
+    #[test]
+    fn frontmatter_logo() {
+        let (fm, _) =
+            parse_frontmatter("---\nlogo: brand/logo.png\nlogo_position: top-left\n---\n");
+        assert_eq!(fm.logo.as_deref(), Some("brand/logo.png"));
+        assert_eq!(fm.logo_position, Some(LogoCorner::TopLeft));
+        let (fm, _) = parse_frontmatter("---\nlogo: logo.png\nlogo_position: middle\n---\n");
+        assert_eq!(fm.logo_position, None);
+        assert_eq!(fm.warnings.len(), 1);
+    }
+
+    #[test]
+    fn frontmatter_embed_origins() {
+        let md = "---\nweb_embed_origins: https://blog.example.com/ https://b.example:8080\n---\n";
+        let (fm, _) = parse_frontmatter(md);
+        assert_eq!(
+            fm.web_embed_origins,
+            ["https://blog.example.com", "https://b.example:8080"]
+        );
+        assert!(fm.warnings.is_empty());
+    }
+
+    #[test]
+    fn frontmatter_agenda() {
+        let (fm, _) = parse_frontmatter("---\nagenda: labels\n---\n");
+        assert_eq!(fm.agenda, Some(Agenda::Labels));
+        let (fm, _) = parse_frontmatter("---\nagenda: bars\n---\n");
+        assert_eq!(fm.agenda, None);
+        assert_eq!(fm.warnings.len(), 1);
+    }
+
+    #[test]
+    fn bold_all_makes_body_bold() {
+        let (fm, body) = parse_frontmatter("---\nbold_all: true\n---\nplain text\n");
+        let (slides, _) = parse_slides(body, &test_theme(), &fm, None, false);
+        let span = &slides[0].content.lines[0].spans[0];
+        assert_eq!(span.content, "plain text");
+        assert!(span.style.add_modifier.contains(Modifier::BOLD));
+    }
+
+    #[test]
+    fn big_headings_reserve_lower_half() {
+        let fm = Frontmatter {
+            big_headings: Some(BigHeadings::Kitty),
+            ..Default::default()
+        };
+        let (slides, _) = parse_slides("# Title\n\nbody\n", &test_theme(), &fm, None, false);
+        let slide = &slides[0];
+        assert_eq!(slide.big_headings.len(), 1);
+        assert_eq!(slide.big_headings[0].text, "# Title");
+        let info = line_info(slide);
+        // heading, reserved lower half, gap, body
+        assert_eq!(info[1].0, "");
+        assert_eq!(info[2].0, "");
+        assert_eq!(info[3].0, "body");
+
+        // Unresolved `auto` behaves like off
+        let fm = Frontmatter {
+            big_headings: Some(BigHeadings::Auto),
+            ..Default::default()
+        };
+        let (slides, _) = parse_slides("# Title\n", &test_theme(), &fm, None, false);
+        assert!(slides[0].big_headings.is_empty());
+    }
+
+    #[test]
+    fn parse_warnings_have_source_lines() {
+        let md = "---\ntheme: nope\ncolour: red\n---\n# Hi\n\n<!-- transiton: fade -->\n<!-- line_height: tall -->\n<!-- just a note -->\n";
+        let (fm, body) = parse_frontmatter(md);
+        let (_, warnings) = parse_slides(body, &test_theme(), &fm, None, false);
+        let got: Vec<String> = warnings.iter().map(|w| w.to_string()).collect();
+        assert_eq!(
+            got,
+            [
+                "line 2: unknown theme `nope`",
+                "line 3: unknown frontmatter key `colour`",
+                "line 7: unknown directive `transiton`",
+                "line 8: invalid value for `line_height` directive",
+            ]
+        );
+        let (fm, _) = parse_frontmatter("---\ntheme: brand.toml\n---\n# Hi\n");
+        assert_eq!(fm.theme.as_deref(), Some("brand.toml"));
+        assert!(fm.warnings.is_empty());
+    }
+
+    #[test]
+    fn slide_title_is_first_heading() {
+        let slides = parse("intro\n\n## First *one*\n\n# Second\n\n---\n\nno heading\n");
+        assert_eq!(slides[0].title.as_deref(), Some("First one"));
+        assert_eq!(slides[1].title, None);
+    }
+
+    #[test]
+    fn video_directive_reserves_placeholder() {
+        let slides = parse("intro\n\n<!-- video: demo.webm -->\n\nafter\n");
+        let slide = &slides[0];
+        assert_eq!(slide.videos.len(), 1);
+        let video = &slide.videos[0];
+        assert_eq!(video.path, "demo.webm");
+        let info = line_info(slide);
+        assert_eq!(info[video.line_index].0, "▶ demo.webm");
+        assert_eq!(info[video.line_index + video.height as usize].0, "after");
+    }
+
+    #[test]
+    fn gallery_steps_through_images_in_one_placement() {
+        let mut slides = parse("intro\n\n<!-- gallery: a.png b.png c.png -->\n\nafter\n");
+        let slide = &mut slides[0];
+        assert_eq!(slide.images.len(), 1);
+        let img = &slide.images[0];
+        assert_eq!(img.path, "a.png");
+        assert_eq!(img.paths(), ["a.png", "b.png", "c.png"]);
+        let info = line_info(slide);
+        assert_eq!(info[img.line_index + img.height as usize].0, "after");
+        assert!(slide.step_galleries(false));
+        assert_eq!(slide.images[0].path, "c.png");
+        slide.step_galleries(true);
+        slide.step_galleries(true);
+        assert_eq!(slide.images[0].path, "b.png");
+        assert!(!parse("one\n\n![x](x.png)\n")[0].step_galleries(true));
+
+        // Placeholders are sized for the tallest image, once all are known
+        let img = &slides[0].images[0];
+        let dims = std::collections::HashMap::from([("a.png", (200, 100)), ("b.png", (100, 100))]);
+        assert_eq!(img.tallest_ratio(|path| dims.get(path).copied()), None);
+        let dims = std::collections::HashMap::from([
+            ("a.png", (200, 100)),
+            ("b.png", (100, 100)),
+            ("c.png", (100, 50)),
+        ]);
+        assert_eq!(img.tallest_ratio(|path| dims.get(path).copied()), Some(1.0));
+    }
+
+    #[test]
+    fn spec_block_aligns_keys() {
+        let slides =
+            parse("<!-- spec -->\nLanguage: **Rust**\nLicense: MIT\n  or Apache-2.0\n\nafter\n");
+        let info = line_info(&slides[0]);
+        let text: Vec<&str> = info.iter().map(|(t, _)| t.as_str()).collect();
+        assert_eq!(
+            text,
+            [
+                "Language  Rust",
+                " License  MIT",
+                "          or Apache-2.0",
+                "",
+                "after"
+            ]
+        );
+    }
+
+    #[test]
+    fn badges_render_as_pills() {
+        let md = "<!-- badge: \"v1.2.0\" color=green -->\n\nStatus [[badge:stable|red]] [[badge:beta]]\n\n<!-- badge: x color=nope -->\n";
+        let (slides, warnings) =
+            parse_slides(md, &test_theme(), &Frontmatter::default(), None, false);
+        let info = line_info(&slides[0]);
+        assert_eq!(info[0].0, "▐v1.2.0▌");
+        assert_eq!(info[2].0, "Status ▐stable▌ ▐beta▌");
+        let badge = &slides[0].content.lines[2].spans[2];
+        assert_eq!(badge.style.fg, Some(Color::Red));
+        assert_eq!(badge.style.bg, Some(test_theme().surface));
+        assert_eq!(warnings.len(), 1);
+    }
+
+    #[test]
+    fn only_badge_rows_add_a_line_after_html_blocks() {
+        let slides = parse("- one\n  <div>x</div>\n- two\n");
+        let info = line_info(&slides[0]);
+        let text: Vec<&str> = info.iter().map(|(t, _)| t.as_str()).collect();
+        assert_eq!(text, ["• one", "• two"]);
+    }
+
+    #[test]
+    fn notes_are_kept_off_the_slide() {
+        let md = "# Intro\n\n<!-- notes: Say hi.\n  Mention the demo. -->\n\nbody\n\n```notes\nAsk questions\n```\n\n---\n\nnext\n";
+        let slides = parse(md);
+        let info = line_info(&slides[0]);
+        let text: Vec<&str> = info.iter().map(|(t, _)| t.as_str()).collect();
+        assert_eq!(text, ["# Intro", "", "body"]);
+        assert_eq!(
+            slides[0].notes.as_deref(),
+            Some("Say hi.\nMention the demo.\n\nAsk questions")
+        );
+        assert_eq!(slides[1].notes, None);
+    }
+
+    #[test]
+    fn pauses_split_slides_into_steps() {
+        let slides = parse(
+            "intro
+
+<!-- pause -->
+
+- one
+<!-- pause -->
+- two
+",
+        );
+        let slide = &slides[0];
+        let text = |s: &Slide| -> Vec<String> {
+            line_info(s)
+                .into_iter()
+                .map(|(t, _)| t.trim_end().to_string())
+                .collect()
+        };
+        assert_eq!(text(slide), ["intro", "", "• one", "• two"]);
+        assert_eq!(slide.fragments, [2, 3]);
+        assert_eq!(text(&slide.at_step(0)), ["intro", "", "", ""]);
+        assert_eq!(text(&slide.at_step(1)), ["intro", "", "• one", ""]);
+        assert_eq!(text(&slide.at_step(2)), text(slide));
+        let words: Vec<usize> = (0..3).map(|step| slide.words_in_step(step)).collect();
+        assert_eq!(words, [1, 1, 1]);
+    }
+
+    #[test]
+    fn shifting_lines_moves_what_sits_below() {
+        let md = "# Top\n\nintro\n\n<!-- pause -->\n\n| a |\n|---|\n| 1 |\n\n[link](https://example.com)\n";
+        let mut slide = parse(md).remove(0);
+        let link_line = |slide: &Slide| {
+            slide.semantics.iter().find_map(|elem| match elem {
+                SemanticElement::Link { line_index, .. } => Some(*line_index),
+                _ => None,
+            })
+        };
+        let (fragment, table, link) = (
+            slide.fragments[0],
+            slide.tables[0].line_index,
+            link_line(&slide).unwrap(),
+        );
+        slide.shift_lines(1, 3);
+        assert_eq!(slide.fragments[0], fragment + 3);
+        assert_eq!(slide.tables[0].line_index, table + 3);
+        assert_eq!(link_line(&slide), Some(link + 3));
+        // The heading on line 0 is above the change
+        assert!(matches!(
+            slide.semantics[0],
+            SemanticElement::Heading { line_index: 0, .. }
+        ));
+        slide.shift_lines(1, -3);
+        assert_eq!(slide.tables[0].line_index, table);
+    }
+
+    #[test]
+    fn code_fences_close_on_a_run_as_long_as_the_opening() {
+        let mut fences = CodeFences::default();
+        let lines = [
+            "text", "````md", "```", "::: x", "````", "~~~", "```", "~~~~", "after",
+        ];
+        let in_code = lines.map(|line| fences.in_code(line));
+        assert_eq!(
+            in_code,
+            [false, true, true, true, true, true, true, true, false]
+        );
+        // Closing fences take no info string
+        let in_code = ["```", "``` rust", "```", "text"].map(|line| fences.in_code(line));
+        assert_eq!(in_code, [true, true, true, false]);
+    }
+
+    #[test]
+    fn fenced_divs_take_theme_styles() {
+        let md = "::: {.warning}\nMind the [gap](https://example.com).\n\nTwice.\n:::\n\n````\n```\n:::\n````\n";
+        let slides = parse(md);
+        let info = line_info(&slides[0]);
+        let text: Vec<&str> = info.iter().map(|(t, _)| t.as_str()).collect();
+        assert_eq!(text[..4], ["▌ ⚠ Mind the gap.", "▌ ", "▌ Twice.", ""]);
+        // Fences inside code blocks stay as they are
+        assert!(text.iter().any(|t| t.trim_start_matches('\u{a0}') == ":::"));
+        let line = &slides[0].content.lines[0];
+        assert_eq!(line.style.bg, Some(test_theme().surface));
+        assert_eq!(
+            line.spans[0].style.fg,
+            Some(test_theme().block_quote_prefix)
+        );
+        let link = slides[0].semantics.iter().find_map(|elem| match elem {
+            SemanticElement::Link { start_col, .. } => Some(*start_col),
+            _ => None,
+        });
+        assert_eq!(link, Some(13));
+    }
+
+    #[test]
+    fn github_alerts_become_callout_boxes() {
+        let md = "> [!WARNING]\n> Mind the gap.\n\n> plain quote\n";
+        let slides = parse(md);
+        let info = line_info(&slides[0]);
+        let text: Vec<&str> = info.iter().map(|(t, _)| t.as_str()).collect();
+        assert_eq!(
+            text[..4],
+            ["▌ ⚠ Warning", "▌ Mind the gap.", "", "│ plain quote"]
+        );
+        let title = &slides[0].content.lines[0];
+        assert_eq!(title.style.bg, Some(test_theme().surface));
+        assert_eq!(title.spans[1].style.fg, Some(test_theme().alerts.warning));
+    }
+
+    #[test]
+    fn polls_draw_a_bar_per_option() {
+        let md = "<!-- poll: \"Tabs or spaces?\" options=Tabs, Two spaces -->\n\n---\n\n\
+                  <!-- poll: options=A -->\n\nNo poll\n";
+        let (mut slides, warnings) =
+            parse_slides(md, &test_theme(), &Frontmatter::default(), None, false);
+        let poll = slides[0].poll.clone().unwrap();
+        assert_eq!(poll.options, ["Tabs", "Two spaces"]);
+        assert_eq!(poll.line_index, 2);
+        slides[0].show_votes(&[1, 3], Some("http://10.0.0.2:8080/"));
+        let text: Vec<String> = line_info(&slides[0]).into_iter().map(|(t, _)| t).collect();
+        assert_eq!(text.len(), 5);
+        assert_eq!(text[0], "Tabs or spaces?");
+        assert!(text[2].starts_with("Tabs        ███████░░░"));
+        assert!(text[3].ends_with("░░░░ 3 (75%)"));
+        assert_eq!(text[4], "Vote at http://10.0.0.2:8080/");
+        assert!(slides[1].poll.is_none());
+        assert_eq!(warnings.len(), 1);
+    }
+
+    #[test]
+    fn math_is_shown_as_unicode() {
+        let md = "Energy $E = mc^2$ costs $5.\n\n$$\n\\alpha \\\\ \\frac{1}{n}\n$$\n\nafter\n";
+        let fm = Frontmatter {
+            math: Some(MathRenderer::Unicode),
+            ..Frontmatter::default()
+        };
+        let (slides, _) = parse_slides(md, &test_theme(), &fm, None, false);
+        let text: Vec<String> = line_info(&slides[0]).into_iter().map(|(t, _)| t).collect();
+        assert_eq!(
+            text,
+            ["Energy E = mc² costs $5.", "", "  α", "  1/n", "", "after"]
+        );
+        // Without `math`, dollar signs are just dollar signs
+        let text = line_info(&parse("From $5 to $10.\n")[0]);
+        assert_eq!(text[0].0, "From $5 to $10.");
+        let (fm, _) = parse_frontmatter("---\nmath: typst\n---\n");
+        assert_eq!(fm.math, Some(MathRenderer::Typst));
+    }
+
+    #[test]
+    fn style_directive_layers_over_the_theme() {
+        let md = "before\n\n<!-- style: fg=#fff bg=blue bold -->\n\n# Title\n\nafter\n\n---\n\nnext\n\n<!-- style: fg=nope -->\n";
+        let (slides, warnings) =
+            parse_slides(md, &test_theme(), &Frontmatter::default(), None, false);
+        let span = |slide: usize, line: usize| slides[slide].content.lines[line].spans[0].style;
+        assert_eq!(span(0, 0).fg, Some(test_theme().fg));
+        let after = span(0, 4);
+        assert_eq!(after.fg, Some(Color::Rgb(255, 255, 255)));
+        assert_eq!(after.bg, Some(Color::Blue));
+        assert!(after.add_modifier.contains(Modifier::BOLD));
+        // Headings keep their theme color
+        assert_eq!(span(0, 2).fg, Some(test_theme().h1));
+        assert_eq!(span(1, 0).fg, Some(test_theme().fg));
+        assert_eq!(warnings.len(), 1);
+    }
+
+    #[test]
+    fn typography_follows_the_frontmatter() {
+        let md = "# a tour of the CLI: the *basics*\n\n## the rest\n\n\"Quotes\" -- and so on...\n";
+        let text = |frontmatter: &Frontmatter| {
+            let (slides, _) = parse_slides(md, &test_theme(), frontmatter, None, false);
+            let lines = &slides[0].content.lines;
+            [0, 2, 4].map(|i| lines[i].to_string())
+        };
+        let fm = Frontmatter {
+            title_case: Some(true),
+            ..Frontmatter::default()
+        };
+        assert_eq!(
+            text(&fm),
+            [
+                "# A Tour of the CLI: The Basics",
+                "# the rest",
+                "\u{201c}Quotes\u{201d} \u{2013} and so on\u{2026}",
+            ]
+        );
+        let fm = Frontmatter {
+            smart_punctuation: Some(false),
+            ..Frontmatter::default()
+        };
+        assert_eq!(text(&fm)[2], "\"Quotes\" -- and so on...");
+        assert_eq!(text(&fm)[0], "# a tour of the CLI: the basics");
+    }
+
+    #[test]
+    fn animate_words_marks_the_next_block() {
+        let md = "<!-- animate: words -->\n# Big idea\n\nplain\n\n<!-- animate: words -->\nsome words\nhere\n\n<!-- animate: letters -->\n";
+        let (slides, warnings) =
+            parse_slides(md, &test_theme(), &Frontmatter::default(), None, false);
+        let lines = &slides[0].content.lines;
+        let builds: Vec<String> = slides[0]
+            .word_builds
+            .iter()
+            .map(|build| lines[build.start].to_string())
+            .collect();
+        assert_eq!(builds, ["# Big idea", "some words here"]);
+        assert_eq!(warnings.len(), 1);
+    }
+
+    #[test]
+    fn tables_are_box_drawn_and_narrowed_to_fit() {
+        let md = "# Top\n\n| Name | Score |\n|:--|--:|\n| Ada | 9 |\n| Grace Hopper | 10 |\n";
+        let (slides, _) = parse_slides(md, &test_theme(), &Frontmatter::default(), None, false);
+        let table = &slides[0].tables[0];
+        let lines = &slides[0].content.lines;
+        let drawn: Vec<String> = lines[table.line_index..table.line_index + table.height()]
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+        assert_eq!(
+            drawn,
+            [
+                "┌──────────────┬───────┐",
+                "│ Name         │ Score │",
+                "├──────────────┼───────┤",
+                "│ Ada          │     9 │",
+                "│ Grace Hopper │    10 │",
+                "└──────────────┴───────┘",
+            ]
+        );
+        let narrow: Vec<String> = table.lines(18).iter().map(|l| l.to_string()).collect();
+        assert_eq!(narrow[4], "│ Grace… │    10 │");
+        assert!(narrow.iter().all(|line| line.chars().count() == 18));
+    }
+
+    #[test]
+    fn bigtext_headings_are_block_letters_scaled_to_the_width() {
+        let md = "<!-- bigtext: 8 -->\n# Hi\n\ntext\n\n<!-- bigtext: 2 -->\n## Small\n";
+        let (slides, warnings) =
+            parse_slides(md, &test_theme(), &Frontmatter::default(), None, false);
+        assert_eq!(warnings.len(), 1);
+        let big_text = &slides[0].big_texts[0];
+        assert_eq!(big_text.line_index, 0);
+        assert_eq!(slides[0].title.as_deref(), Some("Hi"));
+        // Seven rows of doubled pixels, then one to spare
+        let lines = &slides[0].content.lines;
+        assert_eq!(lines[0].to_string(), "██      ██    ██████  ");
+        assert_eq!(lines[7].to_string(), "");
+        assert_eq!(lines[9].to_string(), "text");
+        let narrow: Vec<String> = big_text.lines(12).iter().map(|l| l.to_string()).collect();
+        assert_eq!(narrow.len(), 8);
+        assert_eq!(narrow[0], "█   █  ▀█▀ ");
+        assert_eq!(big_text.lines(5)[0].to_string(), "Hi");
+    }
+
+    #[test]
+    fn if_guards_pick_a_version_of_the_talk() {
+        let md = "# Intro\n\n<!-- if: long -->\n\n---\n\n# Deep dive\n\n<!-- if: !demo -->\nno demo\n<!-- else -->\ndemo\n<!-- endif -->\n<!-- endif -->\n\n---\n\n# End\n<!-- if: bad cond -->\n";
+        let titles = |defines: &[&str]| {
+            let frontmatter = Frontmatter {
+                defines: defines.iter().map(|d| d.to_string()).collect(),
+                ..Frontmatter::default()
+            };
+            let (slides, warnings) = parse_slides(md, &test_theme(), &frontmatter, None, false);
+            let text: Vec<String> = slides
+                .iter()
+                .flat_map(|slide| &slide.content.lines)
+                .map(|line| line.to_string())
+                .filter(|line| !line.is_empty())
+                .collect();
+            (text, warnings)
+        };
+        let (short, warnings) = titles(&[]);
+        assert_eq!(short, ["# Intro", "# End"]);
+        assert_eq!(
+            warnings.iter().map(|w| w.line).collect::<Vec<_>>(),
+            [19, 19]
+        );
+        let (long, _) = titles(&["long", "demo"]);
+        assert_eq!(long, ["# Intro", "# Deep dive", "demo", "# End"]);
+    }
 }
@@ -0,0 +1,544 @@
+//! Math in slides: `$...$` inline and `$$...$$` display formulas, written in
+//! LaTeX. They are shown as a Unicode approximation (`\alpha^2` → `α²`), or
+//! with frontmatter `math: latex` / `math: typst` display formulas are
+//! rendered to images by a local toolchain before the deck is parsed.
+
+use std::borrow::Cow;
+use std::io;
+use std::iter::Peekable;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::str::Chars;
+
+use crate::cache;
+use crate::markdown::{CodeFences, ParseWarning};
+
+/// How display formulas are drawn (frontmatter `math`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MathRenderer {
+    /// Unicode text, everywhere.
+    #[default]
+    Unicode,
+    /// Images from `latex` and `dvipng`.
+    Latex,
+    /// Images from `typst`; formulas are then written in Typst's syntax.
+    Typst,
+}
+
+impl MathRenderer {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "unicode" => Some(MathRenderer::Unicode),
+            "latex" => Some(MathRenderer::Latex),
+            "typst" => Some(MathRenderer::Typst),
+            _ => None,
+        }
+    }
+}
+
+/// Width in pixels of a formula image that spans the whole slide; narrower
+/// ones get a proportional `image_max_width`.
+const FULL_WIDTH_PX: f64 = 1600.0;
+
+fn symbol(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "alpha" => "α",
+        "beta" => "β",
+        "gamma" => "γ",
+        "delta" => "δ",
+        "epsilon" => "ϵ",
+        "varepsilon" => "ε",
+        "zeta" => "ζ",
+        "eta" => "η",
+        "theta" => "θ",
+        "vartheta" => "ϑ",
+        "iota" => "ι",
+        "kappa" => "κ",
+        "lambda" => "λ",
+        "mu" => "μ",
+        "nu" => "ν",
+        "xi" => "ξ",
+        "pi" => "π",
+        "varpi" => "ϖ",
+        "rho" => "ρ",
+        "varrho" => "ϱ",
+        "sigma" => "σ",
+        "varsigma" => "ς",
+        "tau" => "τ",
+        "upsilon" => "υ",
+        "phi" => "ϕ",
+        "varphi" => "φ",
+        "chi" => "χ",
+        "psi" => "ψ",
+        "omega" => "ω",
+        "Gamma" => "Γ",
+        "Delta" => "Δ",
+        "Theta" => "Θ",
+        "Lambda" => "Λ",
+        "Xi" => "Ξ",
+        "Pi" => "Π",
+        "Sigma" => "Σ",
+        "Upsilon" => "Υ",
+        "Phi" => "Φ",
+        "Psi" => "Ψ",
+        "Omega" => "Ω",
+        "sum" => "∑",
+        "prod" => "∏",
+        "coprod" => "∐",
+        "int" => "∫",
+        "iint" => "∬",
+        "iiint" => "∭",
+        "oint" => "∮",
+        "infty" => "∞",
+        "partial" => "∂",
+        "nabla" => "∇",
+        "pm" => "±",
+        "mp" => "∓",
+        "times" => "×",
+        "div" => "÷",
+        "cdot" => "⋅",
+        "ast" => "∗",
+        "star" => "⋆",
+        "circ" => "∘",
+        "bullet" => "∙",
+        "le" | "leq" => "≤",
+        "ge" | "geq" => "≥",
+        "ne" | "neq" => "≠",
+        "approx" => "≈",
+        "equiv" => "≡",
+        "sim" => "∼",
+        "simeq" => "≃",
+        "cong" => "≅",
+        "propto" => "∝",
+        "ll" => "≪",
+        "gg" => "≫",
+        "in" => "∈",
+        "notin" => "∉",
+        "ni" => "∋",
+        "subset" => "⊂",
+        "supset" => "⊃",
+        "subseteq" => "⊆",
+        "supseteq" => "⊇",
+        "cup" => "∪",
+        "cap" => "∩",
+        "setminus" => "∖",
+        "emptyset" | "varnothing" => "∅",
+        "forall" => "∀",
+        "exists" => "∃",
+        "nexists" => "∄",
+        "neg" | "lnot" => "¬",
+        "land" | "wedge" => "∧",
+        "lor" | "vee" => "∨",
+        "oplus" => "⊕",
+        "otimes" => "⊗",
+        "to" | "rightarrow" => "→",
+        "gets" | "leftarrow" => "←",
+        "leftrightarrow" => "↔",
+        "Rightarrow" => "⇒",
+        "Leftarrow" => "⇐",
+        "Leftrightarrow" => "⇔",
+        "implies" => "⟹",
+        "iff" => "⟺",
+        "mapsto" => "↦",
+        "uparrow" => "↑",
+        "downarrow" => "↓",
+        "cdots" => "⋯",
+        "ldots" | "dots" => "…",
+        "vdots" => "⋮",
+        "ddots" => "⋱",
+        "prime" => "′",
+        "angle" => "∠",
+        "perp" => "⊥",
+        "parallel" => "∥",
+        "mid" => "∣",
+        "langle" => "⟨",
+        "rangle" => "⟩",
+        "lceil" => "⌈",
+        "rceil" => "⌉",
+        "lfloor" => "⌊",
+        "rfloor" => "⌋",
+        "hbar" => "ℏ",
+        "ell" => "ℓ",
+        "Re" => "ℜ",
+        "Im" => "ℑ",
+        "aleph" => "ℵ",
+        "degree" => "°",
+        "quad" => "  ",
+        "qquad" => "    ",
+        _ => return None,
+    })
+}
+
+/// Commands that print their own name, upright in TeX.
+const OPERATOR_NAMES: &[&str] = &[
+    "sin", "cos", "tan", "cot", "sec", "csc", "arcsin", "arccos", "arctan", "sinh", "cosh", "tanh",
+    "log", "ln", "lg", "exp", "lim", "max", "min", "sup", "inf", "det", "gcd", "arg", "deg", "dim",
+    "ker", "Pr", "mod",
+];
+
+fn superscript(c: char) -> Option<char> {
+    let i = "0123456789+-=()niabcdefghjklmoprstuvwxyzT".find(c)?;
+    "⁰¹²³⁴⁵⁶⁷⁸⁹⁺⁻⁼⁽⁾ⁿⁱᵃᵇᶜᵈᵉᶠᵍʰʲᵏˡᵐᵒᵖʳˢᵗᵘᵛʷˣʸᶻᵀ".chars().nth(i)
+}
+
+fn subscript(c: char) -> Option<char> {
+    let i = "0123456789+-=()aehijklmnoprstuvx".find(c)?;
+    "₀₁₂₃₄₅₆₇₈₉₊₋₌₍₎ₐₑₕᵢⱼₖₗₘₙₒₚᵣₛₜᵤᵥₓ".chars().nth(i)
+}
+
+/// `\mathbb` letters, double-struck.
+fn double_struck(c: char) -> char {
+    match c {
+        'C' => 'ℂ',
+        'H' => 'ℍ',
+        'N' => 'ℕ',
+        'P' => 'ℙ',
+        'Q' => 'ℚ',
+        'R' => 'ℝ',
+        'Z' => 'ℤ',
+        'A'..='Z' => char::from_u32(0x1D538 + (c as u32 - 'A' as u32)).unwrap_or(c),
+        _ => c,
+    }
+}
+
+/// A LaTeX formula as Unicode text: Greek letters and operators become
+/// their symbols, simple super- and subscripts raised or lowered characters,
+/// and fractions `a/b`. Commands it doesn't know are kept as written. Rows
+/// separated by `\\` come out as lines.
+pub fn to_unicode(tex: &str) -> String {
+    let mut chars = tex.chars().peekable();
+    let out = convert(&mut chars, false);
+    let rows: Vec<String> = out
+        .lines()
+        .map(tidy_spaces)
+        .filter(|row| !row.is_empty())
+        .collect();
+    rows.join("\n")
+}
+
+/// Collapse runs of spaces, and drop those just inside brackets.
+fn tidy_spaces(row: &str) -> String {
+    let words: Vec<&str> = row.split_whitespace().collect();
+    let row = words.join(" ");
+    let chars: Vec<char> = row.chars().collect();
+    let mut out = String::with_capacity(row.len());
+    for (i, &c) in chars.iter().enumerate() {
+        let after_open = i > 0 && matches!(chars[i - 1], '(' | '[' | '⟨');
+        let before_close = matches!(chars.get(i + 1), Some(')' | ']' | '⟩'));
+        if c != ' ' || !(after_open || before_close) {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Convert up to the end of the input or, inside a group, its closing brace.
+fn convert(chars: &mut Peekable<Chars>, in_group: bool) -> String {
+    let mut out = String::new();
+    while let Some(c) = chars.next() {
+        match c {
+            '}' if in_group => break,
+            '{' => out.push_str(&convert(chars, true)),
+            '\\' => out.push_str(&command(chars)),
+            '^' | '_' => {
+                let arg = argument(chars);
+                out.push_str(&script(&arg, c == '^'));
+            }
+            '&' | '~' => out.push(' '),
+            '\'' => out.push('′'),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// The next argument: a braced group, a command or a single character.
+fn argument(chars: &mut Peekable<Chars>) -> String {
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+    match chars.next() {
+        Some('{') => convert(chars, true),
+        Some('\\') => command(chars),
+        Some(c) => c.to_string(),
+        None => String::new(),
+    }
+}
+
+fn script(arg: &str, up: bool) -> String {
+    let map = if up { superscript } else { subscript };
+    if let Some(mapped) = arg.chars().map(map).collect::<Option<String>>() {
+        return mapped;
+    }
+    let mark = if up { '^' } else { '_' };
+    if arg.chars().count() == 1 {
+        format!("{mark}{arg}")
+    } else {
+        format!("{mark}({arg})")
+    }
+}
+
+/// Wrap `arg` in parentheses when it is more than one term.
+fn term(arg: &str) -> String {
+    if arg.contains([' ', '+', '-', '−', '/', '⋅', '×']) {
+        format!("({arg})")
+    } else {
+        arg.to_string()
+    }
+}
+
+/// Convert the command after a backslash.
+fn command(chars: &mut Peekable<Chars>) -> String {
+    let mut name = String::new();
+    while let Some(c) = chars.next_if(|c| c.is_ascii_alphabetic()) {
+        name.push(c);
+    }
+    if name.is_empty() {
+        return match chars.next() {
+            Some(',' | ':' | ';' | ' ') => " ".to_string(),
+            Some('!') | None => String::new(),
+            Some('\\') => "\n".to_string(),
+            Some(c) => c.to_string(),
+        };
+    }
+    if let Some(symbol) = symbol(&name) {
+        return symbol.to_string();
+    }
+    if OPERATOR_NAMES.contains(&name.as_str()) {
+        return name;
+    }
+    match name.as_str() {
+        "frac" | "dfrac" | "tfrac" => {
+            let (num, den) = (argument(chars), argument(chars));
+            format!("{}/{}", term(&num), term(&den))
+        }
+        "sqrt" => {
+            let mut index = String::new();
+            if chars.next_if_eq(&'[').is_some() {
+                while let Some(c) = chars.next_if(|c| *c != ']') {
+                    index.push(c);
+                }
+                chars.next();
+            }
+            let root = match index.trim() {
+                "3" => "∛",
+                "4" => "∜",
+                _ => "√",
+            };
+            format!("{root}{}", term(&argument(chars)))
+        }
+        "mathbb" => argument(chars).chars().map(double_struck).collect(),
+        "hat" => format!("{}\u{302}", argument(chars)),
+        "bar" | "overline" => format!("{}\u{304}", argument(chars)),
+        "vec" => format!("{}\u{20d7}", argument(chars)),
+        "dot" => format!("{}\u{307}", argument(chars)),
+        "ddot" => format!("{}\u{308}", argument(chars)),
+        "tilde" => format!("{}\u{303}", argument(chars)),
+        "text" | "textrm" | "mathrm" | "mathit" | "mathbf" | "mathsf" | "mathtt" | "mathcal"
+        | "operatorname" | "mbox" | "boldsymbol" => argument(chars),
+        // Sizing and delimiter hints draw nothing themselves
+        "left" | "right" | "big" | "Big" | "bigg" | "Bigg" | "bigl" | "bigr" | "Bigl" | "Bigr"
+        | "displaystyle" | "textstyle" | "limits" | "nolimits" => {
+            chars.next_if_eq(&'.');
+            String::new()
+        }
+        _ => format!("\\{name}"),
+    }
+}
+
+/// Replace each `$$ ... $$` block that stands on lines of its own in
+/// `markdown` with an image of the formula drawn by `renderer` in `color`,
+/// cached in `cache_dir`. A block that fails to render stays as it is, to be
+/// shown as Unicode, with a warning; `body_line` is the number of lines
+/// before `markdown` in its file. Lines are kept where they were.
+pub fn render_images<'a>(
+    markdown: &'a str,
+    renderer: MathRenderer,
+    color: (u8, u8, u8),
+    cache_dir: &Path,
+    body_line: usize,
+) -> (Cow<'a, str>, Vec<ParseWarning>) {
+    let mut warnings = Vec::new();
+    if renderer == MathRenderer::Unicode || !markdown.contains("$$") {
+        return (Cow::Borrowed(markdown), warnings);
+    }
+    let lines: Vec<&str> = markdown.split_inclusive('\n').collect();
+    let mut out = String::with_capacity(markdown.len());
+    let mut fences = CodeFences::default();
+    let mut missing_tool = false;
+    let mut i = 0;
+    while i < lines.len() {
+        let trimmed = lines[i].trim();
+        if !fences.in_code(trimmed) && trimmed.starts_with("$$") && !missing_tool {
+            let end = (i..lines.len()).find(|&j| {
+                let t = lines[j].trim();
+                t.ends_with("$$") && (j > i || t.len() >= 4)
+            });
+            if let Some(end) = end {
+                let block: String = lines[i..=end].concat();
+                let tex = block.trim().trim_start_matches("$$").trim_end_matches("$$");
+                match render_image(tex.trim(), renderer, color, cache_dir) {
+                    Ok((path, width)) => {
+                        let percent = (width as f64 / FULL_WIDTH_PX * 100.0).clamp(10.0, 100.0);
+                        // The directive needs a line of its own, or its HTML
+                        // block would swallow the image: the block's first
+                        // line, or the blank line before a one-line block
+                        let mut padding = end - i;
+                        if padding == 0 && i > 0 && lines[i - 1].trim().is_empty() {
+                            out.truncate(out.len() - lines[i - 1].len());
+                            padding = 1;
+                        }
+                        out.push_str(&format!("<!-- image_max_width: {percent:.0}% -->\n"));
+                        out.push_str(&format!("![formula](<{}>)\n", path.display()));
+                        out.push_str(&"\n".repeat(padding.saturating_sub(1)));
+                        i = end + 1;
+                        continue;
+                    }
+                    Err(e) => {
+                        missing_tool = e.kind() == io::ErrorKind::NotFound;
+                        warnings.push(ParseWarning {
+                            line: body_line + i + 1,
+                            message: format!("could not render math as an image: {e}"),
+                        });
+                    }
+                }
+            }
+        }
+        out.push_str(lines[i]);
+        i += 1;
+    }
+    (Cow::Owned(out), warnings)
+}
+
+/// Where the image of a formula is cached.
+fn cache_path(tex: &str, renderer: MathRenderer, color: (u8, u8, u8), cache_dir: &Path) -> PathBuf {
+    let (r, g, b) = color;
+    let key = format!("{}\0{r},{g},{b}\0{tex}", renderer as u8);
+    cache_dir.join(format!("{:016x}.png", cache::stable_hash(key.as_bytes())))
+}
+
+/// Render one formula to a PNG in `cache_dir`, unless an earlier run did;
+/// the path and pixel width of the image.
+fn render_image(
+    tex: &str,
+    renderer: MathRenderer,
+    (r, g, b): (u8, u8, u8),
+    cache_dir: &Path,
+) -> io::Result<(PathBuf, u32)> {
+    let png = cache_path(tex, renderer, (r, g, b), cache_dir);
+    let stem = png.file_stem().unwrap_or_default().to_string_lossy();
+    if !png.exists() {
+        std::fs::create_dir_all(cache_dir)?;
+        match renderer {
+            MathRenderer::Latex => {
+                let source = format!(
+                    "\\documentclass{{article}}\\usepackage{{amsmath,amssymb,xcolor}}\
+                     \\pagestyle{{empty}}\\begin{{document}}\\color[RGB]{{{r},{g},{b}}}\
+                     \\[{tex}\\]\\end{{document}}\n"
+                );
+                std::fs::write(cache_dir.join(format!("{stem}.tex")), source)?;
+                run(Command::new("latex")
+                    .args(["-interaction=nonstopmode", "-halt-on-error"])
+                    .arg(format!("{stem}.tex"))
+                    .current_dir(cache_dir))?;
+                run(Command::new("dvipng")
+                    .args(["-T", "tight", "-D", "300", "-bg", "Transparent", "-o"])
+                    .arg(&png)
+                    .arg(format!("{stem}.dvi"))
+                    .current_dir(cache_dir))?;
+            }
+            MathRenderer::Typst => {
+                let source = format!(
+                    "#set page(width: auto, height: auto, margin: 4pt, fill: none)\n\
+                     #set text(size: 24pt, fill: rgb({r}, {g}, {b}))\n$ {tex} $\n"
+                );
+                let typ = cache_dir.join(format!("{stem}.typ"));
+                std::fs::write(&typ, source)?;
+                run(Command::new("typst")
+                    .args(["compile", "--format", "png", "--ppi", "144"])
+                    .arg(&typ)
+                    .arg(&png))?;
+            }
+            MathRenderer::Unicode => unreachable!("Unicode math is not rendered to images"),
+        }
+    }
+    Ok((png.clone(), png_width(&png)?))
+}
+
+/// Run a toolchain command, turning a failed exit into an error.
+fn run(command: &mut Command) -> io::Result<()> {
+    let program = command.get_program().to_string_lossy().into_owned();
+    let output = command.output().map_err(|e| {
+        let message = format!("`{program}`: {e}");
+        io::Error::new(e.kind(), message)
+    })?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!(
+            "`{program}` failed ({})",
+            output.status
+        )))
+    }
+}
+
+/// Width of a PNG, from its header.
+fn png_width(path: &Path) -> io::Result<u32> {
+    let data = std::fs::read(path)?;
+    match data.get(16..20) {
+        Some(bytes) if data.starts_with(b"\x89PNG") => {
+            Ok(u32::from_be_bytes(bytes.try_into().unwrap()))
+        }
+        _ => Err(io::Error::other(format!("{} is not a PNG", path.display()))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formulas_become_unicode() {
+        assert_eq!(to_unicode(r"\alpha^2 + \beta_{i}"), "α² + βᵢ");
+        assert_eq!(to_unicode(r"\frac{a+b}{2} \leq \sqrt{x}"), "(a+b)/2 ≤ √x");
+        assert_eq!(to_unicode(r"\sum_{n=1}^{\infty} x^{n+1}"), "∑ₙ₌₁^∞ xⁿ⁺¹");
+        assert_eq!(to_unicode(r"f: \mathbb{R} \to \mathbb{R}"), "f: ℝ → ℝ");
+        assert_eq!(to_unicode(r"\left( \sin x \right)^{q}"), "(sin x)^q");
+        assert_eq!(to_unicode(r"a &= 1 \\ b &= 2"), "a = 1\nb = 2");
+        assert_eq!(to_unicode(r"\unknown{x}"), r"\unknownx");
+    }
+
+    #[test]
+    fn rendered_formulas_become_images() {
+        let md = "Euler:\n\n$$\ne^{i\\pi} + 1 = 0\n$$\n\n```\n$$ x $$\n```\n\n$$ y $$\n";
+        let dir = std::env::temp_dir().join(format!("ratride math {}", std::process::id()));
+        let (out, _) = render_images(md, MathRenderer::Unicode, (0, 0, 0), &dir, 0);
+        assert!(matches!(out, Cow::Borrowed(_)));
+        // Cached images are used without running the toolchain
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut header = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+        header.extend(800u32.to_be_bytes());
+        let png = |tex| cache_path(tex, MathRenderer::Typst, (1, 2, 3), &dir);
+        for tex in ["e^{i\\pi} + 1 = 0", "y"] {
+            std::fs::write(png(tex), &header).unwrap();
+        }
+        let (out, warnings) = render_images(md, MathRenderer::Typst, (1, 2, 3), &dir, 0);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(warnings.is_empty());
+        assert_eq!(out.lines().nth(2), Some("<!-- image_max_width: 50% -->"));
+        assert_eq!(out.lines().count(), md.lines().count());
+        assert!(out.contains("```\n$$ x $$\n```\n"));
+
+        // Paths with spaces in them stay whole
+        let (slides, _) = crate::markdown::parse_slides(
+            &out,
+            &crate::theme::Theme::default(),
+            &Default::default(),
+            None,
+            false,
+        );
+        let images = &slides[0].images;
+        assert_eq!(images.len(), 2);
+        assert_eq!(images[0].path, png("e^{i\\pi} + 1 = 0").to_string_lossy());
+        assert_eq!(images[1].path, png("y").to_string_lossy());
+        assert_eq!(images[1].max_width_percent, Some(0.5));
+    }
+}
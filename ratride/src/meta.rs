@@ -0,0 +1,169 @@
+//! Deck metadata for other tools (`ratride meta`): titles, sections, notes,
+//! images, word counts and an estimate of how long each slide takes to
+//! present, as JSON or a chapter list.
+
+use serde::Serialize;
+
+use crate::markdown::Slide;
+use crate::timer::format_clock;
+
+/// Speaking rate, in words per minute, durations are estimated at by default.
+pub const DEFAULT_WPM: f64 = 130.0;
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct DeckMeta {
+    /// Title of the first slide.
+    pub title: Option<String>,
+    pub words: usize,
+    /// Estimated length of the whole talk.
+    pub duration_secs: u64,
+    pub sections: Vec<SectionMeta>,
+    pub slides: Vec<SlideMeta>,
+}
+
+/// A section: a slide with an H1 and the slides up to the next one.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SectionMeta {
+    pub title: String,
+    /// Number of its first slide, 1-based.
+    pub first_slide: usize,
+    pub slide_count: usize,
+    pub start_secs: u64,
+    pub duration_secs: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SlideMeta {
+    /// 1-based, as `goto N` and `print --slide N` take it.
+    pub number: usize,
+    pub title: Option<String>,
+    /// Index into `sections`, for slides after the first section starts.
+    pub section: Option<usize>,
+    /// Pause steps the slide is revealed in.
+    pub steps: usize,
+    /// Words on the slide, not counting its notes.
+    pub words: usize,
+    pub notes: Option<String>,
+    pub images: Vec<String>,
+    pub presenter_only: bool,
+    pub start_secs: u64,
+    /// Time to say the slide's words and its notes at the speaking rate.
+    pub duration_secs: u64,
+}
+
+/// Metadata of `slides`, with durations estimated at `wpm` words per minute.
+pub fn deck_meta(slides: &[Slide], wpm: f64) -> DeckMeta {
+    let mut sections: Vec<SectionMeta> = Vec::new();
+    let mut metas = Vec::new();
+    let mut start_secs = 0;
+    for (i, slide) in slides.iter().enumerate() {
+        let words = (0..=slide.fragments.len())
+            .map(|step| slide.words_in_step(step))
+            .sum();
+        let notes_words = slide
+            .notes
+            .as_deref()
+            .map_or(0, |notes| notes.split_whitespace().count());
+        let duration_secs = ((words + notes_words) as f64 / wpm.max(1.0) * 60.0).round() as u64;
+        if slide.starts_section() {
+            sections.push(SectionMeta {
+                title: slide.title.clone().unwrap_or_default(),
+                first_slide: i + 1,
+                slide_count: 0,
+                start_secs,
+                duration_secs: 0,
+            });
+        }
+        if let Some(section) = sections.last_mut() {
+            section.slide_count += 1;
+            section.duration_secs += duration_secs;
+        }
+        metas.push(SlideMeta {
+            number: i + 1,
+            title: slide.title.clone(),
+            section: sections.len().checked_sub(1),
+            steps: slide.fragments.len() + 1,
+            words,
+            notes: slide.notes.clone(),
+            images: slide
+                .images
+                .iter()
+                .flat_map(|img| img.paths().to_vec())
+                .collect(),
+            presenter_only: slide.presenter_only,
+            start_secs,
+            duration_secs,
+        });
+        start_secs += duration_secs;
+    }
+    DeckMeta {
+        title: slides.first().and_then(|slide| slide.title.clone()),
+        words: metas.iter().map(|slide| slide.words).sum(),
+        duration_secs: start_secs,
+        sections,
+        slides: metas,
+    }
+}
+
+/// `meta` as pretty-printed JSON.
+pub fn to_json(meta: &DeckMeta) -> String {
+    serde_json::to_string_pretty(meta).expect("deck metadata serializes")
+}
+
+/// `meta` as a chapter list: each slide's estimated start time and title,
+/// slides within a section indented under it.
+pub fn summary(meta: &DeckMeta) -> String {
+    let mut out = format!(
+        "{}: {} slides, {} words, about {}\n\n",
+        meta.title.as_deref().unwrap_or("Untitled"),
+        meta.slides.len(),
+        meta.words,
+        format_clock(meta.duration_secs as f64 * 1000.0),
+    );
+    for slide in &meta.slides {
+        let opens_section = slide
+            .section
+            .is_some_and(|k| meta.sections[k].first_slide == slide.number);
+        let indent = if slide.section.is_some() && !opens_section {
+            "  "
+        } else {
+            ""
+        };
+        let title = slide
+            .title
+            .clone()
+            .unwrap_or_else(|| format!("Slide {}", slide.number));
+        out.push_str(&format!(
+            "{:>7}  {indent}{title}\n",
+            format_clock(slide.start_secs as f64 * 1000.0)
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::markdown::{Frontmatter, parse_slides};
+    use crate::theme::Theme;
+
+    #[test]
+    fn slides_are_timed_and_grouped_into_sections() {
+        let md = "Welcome\n\n---\n\n# Part one\n\none two three\n\n<!-- notes: say four more words -->\n\n---\n\n## Detail\n\n<!-- pause -->\n\nmore\n\n![chart](chart.png)\n\n<!-- gallery: a.png b.png -->\n";
+        let (slides, _) = parse_slides(md, &Theme::default(), &Frontmatter::default(), None, false);
+        let meta = deck_meta(&slides, 60.0);
+        assert_eq!(meta.sections.len(), 1);
+        assert_eq!(meta.sections[0].first_slide, 2);
+        assert_eq!(meta.sections[0].slide_count, 2);
+        let slide = &meta.slides[1];
+        assert_eq!(
+            (slide.section, slide.words, slide.start_secs),
+            (Some(0), 5, 1)
+        );
+        assert_eq!(slide.duration_secs, 9);
+        assert_eq!(meta.slides[2].images, ["chart.png", "a.png", "b.png"]);
+        assert_eq!(meta.slides[2].steps, 2);
+        assert_eq!(meta.duration_secs, 12);
+        assert!(to_json(&meta).contains("\"first_slide\": 2"));
+    }
+}
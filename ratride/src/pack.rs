@@ -0,0 +1,293 @@
+//! Single-file deck archives (`ratride pack`): the markdown and every local
+//! file it uses, in a plain tar archive that can be presented directly.
+
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use crate::cache;
+use crate::export::{extract_asset_paths, is_font_file, rewrite_asset_paths};
+use crate::markdown::parse_frontmatter;
+use crate::theme::is_theme_file;
+
+/// File name the deck's markdown is stored under.
+const DECK_NAME: &str = "slides.md";
+
+const BLOCK: usize = 512;
+
+pub fn is_archive(path: &str) -> bool {
+    path.ends_with(".ratride")
+}
+
+/// Pack the deck at `deck` with its images, media, logo, web font and
+/// stylesheet, theme file and FIGlet font files into the archive `out`.
+///
+/// Assets outside the deck's directory are stored under `assets/`, with a
+/// hash of their path in front of names that are already taken, and the
+/// markdown is rewritten to point there.
+pub fn pack(deck: &Path, out: &Path) -> io::Result<()> {
+    let markdown = fs::read_to_string(deck)?;
+    let base_dir = deck.parent().unwrap_or(Path::new("."));
+
+    let (frontmatter, _) = parse_frontmatter(&markdown);
+    let mut paths = extract_asset_paths(&markdown);
+    let local = |path: &&String| !path.contains("://");
+    let theme = frontmatter.theme.filter(|theme| is_theme_file(theme));
+    let font = frontmatter.figlet.flatten();
+    let font = font.filter(|font| is_font_file(font));
+    for path in [
+        &frontmatter.logo,
+        &frontmatter.web_font,
+        &frontmatter.web_css,
+        &theme,
+        &font,
+    ]
+    .into_iter()
+    .flatten()
+    .filter(local)
+    {
+        if !paths.contains(path) {
+            paths.push(path.clone());
+        }
+    }
+
+    let mut entries = Vec::new();
+    let mut rewrites = Vec::new();
+    for path in paths {
+        let src = base_dir.join(&path);
+        let data = match fs::read(&src) {
+            Ok(data) => data,
+            Err(e) => {
+                eprintln!("warning: {}: {e}", src.display());
+                continue;
+            }
+        };
+        let file_name = Path::new(&path)
+            .file_name()
+            .map(|f| f.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.replace('/', "_"));
+        let mut name = if is_contained(Path::new(&path)) {
+            path.trim_start_matches("./").to_string()
+        } else {
+            format!("assets/{file_name}")
+        };
+        let packed = entries.iter().find(|(n, _)| *n == name);
+        match packed.map(|(_, packed)| *packed == data) {
+            // The same file under another spelling of its path
+            Some(true) => {}
+            // Another file of the same name, from another directory
+            Some(false) => {
+                let hash = cache::stable_hash(path.as_bytes());
+                name = format!("assets/{hash:016x}-{file_name}");
+                entries.push((name.clone(), data));
+            }
+            None => entries.push((name.clone(), data)),
+        }
+        if name != path.trim_start_matches("./") {
+            rewrites.push((path, name));
+        }
+    }
+    let markdown = rewrite_asset_paths(&markdown, &rewrites);
+    entries.insert(0, (DECK_NAME.to_string(), markdown.into_bytes()));
+
+    let mut archive = Vec::new();
+    for (name, data) in &entries {
+        archive.extend(header(name, data.len())?);
+        archive.extend(data);
+        archive.resize(archive.len().next_multiple_of(BLOCK), 0);
+    }
+    // Two zero blocks end a tar archive
+    archive.resize(archive.len() + 2 * BLOCK, 0);
+    fs::write(out, archive)
+}
+
+/// Unpack the archive at `path` into a per-archive directory under the
+/// user's cache directory, returning the local path of the markdown file.
+///
+/// The directory is cleared first, so files dropped from the archive don't
+/// linger.
+pub fn unpack(path: &Path) -> io::Result<PathBuf> {
+    let archive = fs::read(path)?;
+    let invalid = |message: &str| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}: {message}", path.display()),
+        )
+    };
+
+    let path_bytes = path.canonicalize()?.into_os_string();
+    let key = cache::stable_hash(path_bytes.as_encoded_bytes());
+    let dir = cache::cache_dir("pack")?.join(format!("{key:016x}"));
+    if dir.exists() {
+        fs::remove_dir_all(&dir)?;
+    }
+    fs::create_dir_all(&dir)?;
+
+    let mut offset = 0;
+    while let Some(block) = archive.get(offset..offset + BLOCK) {
+        if block.iter().all(|&b| b == 0) {
+            break;
+        }
+        let (name, size, regular) = parse_header(block).ok_or_else(|| invalid("not an archive"))?;
+        let start = offset + BLOCK;
+        let data = archive
+            .get(start..start + size)
+            .ok_or_else(|| invalid("archive is truncated"))?;
+        offset = start + size.next_multiple_of(BLOCK);
+        if !regular {
+            continue;
+        }
+        // Only unpack paths that stay inside the cache directory
+        if !is_contained(Path::new(&name)) {
+            return Err(invalid(&format!("unsafe path `{name}`")));
+        }
+        let local = dir.join(&name);
+        if let Some(parent) = local.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&local, data)?;
+    }
+
+    let deck = dir.join(DECK_NAME);
+    if !deck.exists() {
+        return Err(invalid(&format!("no {DECK_NAME} in archive")));
+    }
+    Ok(deck)
+}
+
+/// Whether `path` is relative and never leaves its directory.
+fn is_contained(path: &Path) -> bool {
+    path.components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+}
+
+/// A ustar header for a regular file. Names longer than 100 bytes are split
+/// at a `/` into the 155-byte prefix field.
+fn header(name: &str, size: usize) -> io::Result<[u8; BLOCK]> {
+    let (prefix, name) = if name.len() <= 100 {
+        ("", name)
+    } else {
+        name.char_indices()
+            .filter(|&(i, c)| c == '/' && i <= 155 && name.len() - i - 1 <= 100)
+            .map(|(i, _)| (&name[..i], &name[i + 1..]))
+            .next()
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("path too long for an archive: {name}"),
+                )
+            })?
+    };
+    let mut block = [0u8; BLOCK];
+    let mut put = |at: usize, bytes: &[u8]| block[at..at + bytes.len()].copy_from_slice(bytes);
+    put(0, name.as_bytes());
+    put(100, b"0000644\0");
+    put(108, b"0000000\0");
+    put(116, b"0000000\0");
+    put(124, format!("{size:011o}\0").as_bytes());
+    put(136, b"00000000000\0");
+    put(156, b"0");
+    put(257, b"ustar\0");
+    put(263, b"00");
+    put(345, prefix.as_bytes());
+    // The checksum is taken with its own field filled with spaces
+    block[148..156].fill(b' ');
+    let sum: u32 = block.iter().map(|&b| b as u32).sum();
+    block[148..156].copy_from_slice(format!("{sum:06o}\0 ").as_bytes());
+    Ok(block)
+}
+
+/// Name, size and whether it's a regular file, from a ustar header block.
+fn parse_header(block: &[u8]) -> Option<(String, usize, bool)> {
+    let field = |range: std::ops::Range<usize>| {
+        let bytes = &block[range];
+        let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+        std::str::from_utf8(&bytes[..end]).ok()
+    };
+    let octal = |range| usize::from_str_radix(field(range)?.trim(), 8).ok();
+
+    let checksum = octal(148..156)?;
+    let sum: usize = block
+        .iter()
+        .enumerate()
+        .map(|(i, &b)| if (148..156).contains(&i) { b' ' } else { b } as usize)
+        .sum();
+    if sum != checksum {
+        return None;
+    }
+    let name = field(0..100)?;
+    let name = match field(345..500).filter(|p| !p.is_empty()) {
+        Some(prefix) => format!("{prefix}/{name}"),
+        None => name.to_string(),
+    };
+    let size = octal(124..136)?;
+    let regular = matches!(block[156], b'0' | 0);
+    Some((name, size, regular))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packed_decks_unpack_with_their_assets() {
+        let _home = cache::TempHome::new("pack");
+        let dir = std::env::temp_dir().join(format!("ratride-pack-test-{}", std::process::id()));
+        let shared = dir.join("shared");
+        fs::create_dir_all(dir.join("deck/img")).unwrap();
+        fs::create_dir_all(&shared).unwrap();
+        fs::write(dir.join("deck/img/a.png"), b"a").unwrap();
+        fs::write(shared.join("logo.png"), b"logo").unwrap();
+        fs::write(shared.join("dark.toml"), b"theme").unwrap();
+        fs::write(dir.join("deck/big.flf"), b"font").unwrap();
+        let deck = dir.join("deck/talk.md");
+        fs::write(
+            &deck,
+            "---\nlogo: ../shared/logo.png\ntheme: ../shared/dark.toml\n---\n<!-- figlet: big.flf -->\n# One\n\n![a](img/a.png)\n",
+        )
+        .unwrap();
+
+        let archive = dir.join("talk.ratride");
+        pack(&deck, &archive).unwrap();
+        let unpacked = unpack(&archive).unwrap();
+        let markdown = fs::read_to_string(&unpacked).unwrap();
+        assert!(markdown.contains("logo: assets/logo.png"));
+        let root = unpacked.parent().unwrap();
+        assert_eq!(fs::read(root.join("img/a.png")).unwrap(), b"a");
+        assert_eq!(fs::read(root.join("assets/logo.png")).unwrap(), b"logo");
+        assert!(markdown.contains("theme: assets/dark.toml"));
+        assert_eq!(fs::read(root.join("assets/dark.toml")).unwrap(), b"theme");
+        assert_eq!(fs::read(root.join("big.flf")).unwrap(), b"font");
+        assert!(root.starts_with(cache::cache_dir("pack").unwrap()));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn same_named_assets_from_different_directories_are_both_packed() {
+        let _home = cache::TempHome::new("pack-names");
+        let dir = std::env::temp_dir().join(format!("ratride-pack-names-{}", std::process::id()));
+        for (sub, data) in [("a", "first"), ("b", "second")] {
+            fs::create_dir_all(dir.join(sub)).unwrap();
+            fs::write(dir.join(sub).join("logo.png"), data).unwrap();
+        }
+        fs::create_dir_all(dir.join("deck")).unwrap();
+        let deck = dir.join("deck/talk.md");
+        let md = "![a](../a/logo.png)\n\n---\n\n![b](../b/logo.png)\n\n![a](../a/logo.png)\n";
+        fs::write(&deck, md).unwrap();
+
+        let archive = dir.join("talk.ratride");
+        pack(&deck, &archive).unwrap();
+        let unpacked = unpack(&archive).unwrap();
+        let root = unpacked.parent().unwrap();
+        let markdown = fs::read_to_string(&unpacked).unwrap();
+        let paths = extract_asset_paths(&markdown);
+        assert_eq!(paths.len(), 2);
+        assert_eq!(paths[0], "assets/logo.png");
+        assert_eq!(fs::read(root.join(&paths[0])).unwrap(), b"first");
+        assert_eq!(fs::read(root.join(&paths[1])).unwrap(), b"second");
+        assert_eq!(markdown.matches(paths[0].as_str()).count(), 2);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
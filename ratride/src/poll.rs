@@ -0,0 +1,227 @@
+//! Audience polls, opened with `--poll-port PORT`: a small web page on the
+//! local network where people vote on the poll of the slide being presented
+//! (`<!-- poll: "Question?" options=A,B,C -->`). The presenter reads the
+//! tally every frame and redraws the slide's bars.
+
+use std::collections::HashMap;
+use std::io::{self, Read};
+use std::net::{IpAddr, Ipv4Addr, UdpSocket};
+use std::sync::{Arc, Mutex};
+
+use crate::markdown::SlidePoll;
+
+/// How often the voting page reloads, in seconds, to follow the presenter
+/// from poll to poll.
+const REFRESH_SECS: u32 = 5;
+
+/// Why a vote was not counted.
+#[derive(Debug, PartialEq)]
+enum Rejected {
+    /// The page voted from shows a poll that is no longer open.
+    Closed,
+    /// The open poll has no such option.
+    NoOption,
+}
+
+#[derive(Default)]
+struct Ballots {
+    /// The poll on screen: its slide, question and options.
+    open: Option<(usize, String, Vec<String>)>,
+    /// Each voter's choice, by slide. Voting again changes the choice.
+    choices: HashMap<usize, HashMap<IpAddr, usize>>,
+}
+
+impl Ballots {
+    /// Count `voter`'s vote for `option` of the poll on slide `poll`, as
+    /// long as that poll is still the open one.
+    fn vote(&mut self, voter: IpAddr, poll: usize, option: usize) -> Result<(), Rejected> {
+        let Some((page, _, options)) = self.open.as_ref().filter(|open| open.0 == poll) else {
+            return Err(Rejected::Closed);
+        };
+        if option >= options.len() {
+            return Err(Rejected::NoOption);
+        }
+        self.choices.entry(*page).or_default().insert(voter, option);
+        Ok(())
+    }
+
+    fn tally(&self, page: usize, options: usize) -> Vec<usize> {
+        let mut votes = vec![0; options];
+        let choices = self
+            .choices
+            .get(&page)
+            .into_iter()
+            .flat_map(HashMap::values);
+        for &choice in choices {
+            if let Some(count) = votes.get_mut(choice) {
+                *count += 1;
+            }
+        }
+        votes
+    }
+
+    /// The voting page as `voter` sees it.
+    fn page(&self, voter: Option<IpAddr>) -> String {
+        let body = match &self.open {
+            Some((page, question, options)) => {
+                let chosen = voter
+                    .and_then(|voter| self.choices.get(page)?.get(&voter))
+                    .copied();
+                let buttons: String = options
+                    .iter()
+                    .enumerate()
+                    .map(|(i, option)| {
+                        let mark = if chosen == Some(i) { " ✓" } else { "" };
+                        format!(
+                            "<button name=\"option\" value=\"{i}\">{}{mark}</button>\n",
+                            escape_html(option)
+                        )
+                    })
+                    .collect();
+                // The poll goes with the vote, so a page left open from the
+                // last poll can't vote in the next one
+                format!(
+                    "<h1>{}</h1>\n<form method=\"post\" action=\"/vote\">\n\
+                     <input type=\"hidden\" name=\"poll\" value=\"{page}\">\n{buttons}</form>",
+                    escape_html(question)
+                )
+            }
+            None => "<p>No poll is open right now.</p>".to_string(),
+        };
+        format!(
+            "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\">\
+             <meta name=\"viewport\" content=\"width=device-width\">\
+             <meta http-equiv=\"refresh\" content=\"{REFRESH_SECS}\">\
+             <title>Poll</title><style>body{{font-family:sans-serif;margin:2em}}\
+             button{{display:block;width:100%;margin:.5em 0;padding:1em;font-size:1.2em}}\
+             </style></head><body>\n{body}\n</body></html>\n"
+        )
+    }
+}
+
+/// Handle to a running poll server.
+pub struct PollServer {
+    ballots: Arc<Mutex<Ballots>>,
+    url: String,
+}
+
+impl PollServer {
+    /// Serve the voting page on `port` of every interface.
+    pub fn start(port: u16) -> io::Result<Self> {
+        let server = tiny_http::Server::http(("0.0.0.0", port)).map_err(io::Error::other)?;
+        let ballots = Arc::new(Mutex::new(Ballots::default()));
+        let ballots_s = Arc::clone(&ballots);
+        std::thread::spawn(move || {
+            for request in server.incoming_requests() {
+                handle_request(request, &ballots_s);
+            }
+        });
+        Ok(Self {
+            ballots,
+            url: format!("http://{}:{port}/", local_ip()),
+        })
+    }
+
+    /// Where the audience votes.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Take votes for `poll` on slide `page`, or stop taking them with `None`.
+    pub fn open(&self, page: usize, poll: Option<&SlidePoll>) {
+        self.ballots.lock().unwrap().open =
+            poll.map(|poll| (page, poll.question.clone(), poll.options.clone()));
+    }
+
+    /// Votes so far for each of the `options` of the poll on slide `page`.
+    pub fn votes(&self, page: usize, options: usize) -> Vec<usize> {
+        self.ballots.lock().unwrap().tally(page, options)
+    }
+}
+
+fn handle_request(mut request: tiny_http::Request, ballots: &Mutex<Ballots>) {
+    let voter = request.remote_addr().map(|addr| addr.ip());
+    let response = match (request.method(), request.url()) {
+        (tiny_http::Method::Post, "/vote") => {
+            let mut body = String::new();
+            let _ = request.as_reader().take(64).read_to_string(&mut body);
+            let field = |name: &str| {
+                body.split('&')
+                    .find_map(|pair| pair.strip_prefix(name)?.strip_prefix('='))
+                    .and_then(|n| n.trim().parse().ok())
+            };
+            let vote = match (voter, field("poll"), field("option")) {
+                (Some(voter), Some(poll), Some(option)) => {
+                    ballots.lock().unwrap().vote(voter, poll, option)
+                }
+                _ => Err(Rejected::NoOption),
+            };
+            match vote {
+                // Back to the page, so reloading it doesn't vote again
+                Ok(()) => {
+                    let location = tiny_http::Header::from_bytes("Location", "/").unwrap();
+                    tiny_http::Response::from_string("")
+                        .with_status_code(303)
+                        .with_header(location)
+                }
+                Err(Rejected::Closed) => {
+                    tiny_http::Response::from_string("That poll has closed.").with_status_code(409)
+                }
+                Err(Rejected::NoOption) => {
+                    tiny_http::Response::from_string("Bad Request").with_status_code(400)
+                }
+            }
+        }
+        (tiny_http::Method::Get, "/") => {
+            let html = ballots.lock().unwrap().page(voter);
+            let content_type =
+                tiny_http::Header::from_bytes("Content-Type", "text/html; charset=utf-8").unwrap();
+            tiny_http::Response::from_string(html).with_header(content_type)
+        }
+        _ => tiny_http::Response::from_string("Not Found").with_status_code(404),
+    };
+    let _ = request.respond(response);
+}
+
+/// This machine's address on the network the default route goes through;
+/// connecting a UDP socket only picks the route, nothing is sent.
+fn local_ip() -> IpAddr {
+    UdpSocket::bind(("0.0.0.0", 0))
+        .and_then(|socket| {
+            socket.connect(("192.0.2.1", 80))?;
+            socket.local_addr()
+        })
+        .map_or(IpAddr::V4(Ipv4Addr::LOCALHOST), |addr| addr.ip())
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_voter_counts_once() {
+        let mut ballots = Ballots::default();
+        let (alice, bob) = ([10, 0, 0, 2].into(), [10, 0, 0, 3].into());
+        assert_eq!(ballots.vote(alice, 4, 0), Err(Rejected::Closed));
+        let options = vec!["Rust".to_string(), "<Go>".to_string()];
+        ballots.open = Some((4, "Which?".to_string(), options));
+        assert_eq!(ballots.vote(alice, 4, 0), Ok(()));
+        assert_eq!(ballots.vote(bob, 4, 0), Ok(()));
+        assert_eq!(ballots.vote(bob, 4, 1), Ok(()));
+        assert_eq!(ballots.vote(bob, 4, 2), Err(Rejected::NoOption));
+        // A page still showing an earlier poll
+        assert_eq!(ballots.vote(bob, 2, 0), Err(Rejected::Closed));
+        assert_eq!(ballots.tally(4, 2), [1, 1]);
+        assert_eq!(ballots.tally(5, 2), [0, 0]);
+        let page = ballots.page(Some(bob));
+        assert!(page.contains("name=\"poll\" value=\"4\""));
+        assert!(page.contains("value=\"1\">&lt;Go&gt; ✓</button>"));
+    }
+}
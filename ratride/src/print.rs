@@ -0,0 +1,160 @@
+//! Non-interactive rendering of slides as ANSI-colored text.
+
+use std::fmt::Write as _;
+use std::io::{self, Write};
+
+use ratatui::Terminal;
+use ratatui::backend::TestBackend;
+use ratatui::buffer::Buffer;
+use ratatui::style::{Color, Modifier};
+use unicode_width::UnicodeWidthStr;
+
+use crate::markdown::Slide;
+use crate::render::{self, ImagePlacement, LayoutSlot};
+
+/// Render `slides` at `width` columns and write them to `out`, one after
+/// another. Each slide is drawn at least `height` rows tall (taller if its
+/// content needs it) with trailing blank rows trimmed.
+pub fn print_slides(
+    slides: &[Slide],
+    width: u16,
+    height: u16,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    for (i, slide) in slides.iter().enumerate() {
+        if i > 0 {
+            writeln!(out)?;
+        }
+        out.write_all(render_slide(slide, width, height).as_bytes())?;
+    }
+    Ok(())
+}
+
+/// Render a single slide to an ANSI string, one `\n`-terminated line per row.
+pub fn render_slide(slide: &Slide, width: u16, height: u16) -> String {
+    // Tall enough that nothing needs scrolling: content plus the 1-row margins.
+    let inner_w = width.saturating_sub(4);
+    let content_h = render::wrapped_content_height(&slide.content, inner_w).max(
+        slide
+            .right_content
+            .as_ref()
+            .map_or(0, |right| render::wrapped_content_height(right, inner_w)),
+    );
+    let rows = (content_h + 2).min(u16::MAX as usize) as u16;
+
+    let (buffer, _) = render_slide_buffer(slide, width, rows.max(height));
+    buffer_to_ansi(&buffer)
+}
+
+/// Draw the top of a slide into an offscreen `width`×`height` buffer, returning
+/// it along with the image placements that belong on top of it.
+pub fn render_slide_buffer(
+    slide: &Slide,
+    width: u16,
+    height: u16,
+) -> (Buffer, Vec<ImagePlacement>) {
+    let mut placements = Vec::new();
+    let Ok(mut terminal) = Terminal::new(TestBackend::new(width, height));
+    let Ok(completed) = terminal.draw(|frame| {
+        let area = frame.area();
+        if slide.bg_fill {
+            render::fill_background(frame, area, slide.theme.bg);
+        }
+        (placements, _) = render::draw_slide(slide, LayoutSlot::NONE, 0, frame, area);
+        render::draw_header(&slide.header, frame, area, &slide.theme);
+    });
+    (completed.buffer.clone(), placements)
+}
+
+/// Convert a buffer to text with SGR escape sequences, dropping trailing
+/// blank cells on each row and trailing blank rows.
+fn buffer_to_ansi(buf: &Buffer) -> String {
+    let area = buf.area;
+    let is_blank = |x: u16, y: u16| {
+        let cell = &buf[(x, y)];
+        cell.symbol() == " " && cell.bg == Color::Reset
+    };
+    let last_row = (area.y..area.y + area.height)
+        .rev()
+        .find(|&y| (area.x..area.x + area.width).any(|x| !is_blank(x, y)));
+    let Some(last_row) = last_row else {
+        return String::new();
+    };
+
+    let mut out = String::new();
+    for y in area.y..=last_row {
+        let end = (area.x..area.x + area.width)
+            .rev()
+            .find(|&x| !is_blank(x, y))
+            .map_or(area.x, |x| x + 1);
+        let plain = (Color::Reset, Color::Reset, Modifier::empty());
+        let mut style = plain;
+        let mut skip = 0;
+        for x in area.x..end {
+            // Cells covered by a preceding wide character
+            if skip > 0 {
+                skip -= 1;
+                continue;
+            }
+            let cell = &buf[(x, y)];
+            let cell_style = (cell.fg, cell.bg, cell.modifier);
+            if cell_style != style {
+                push_sgr(&mut out, cell.fg, cell.bg, cell.modifier);
+                style = cell_style;
+            }
+            out.push_str(cell.symbol());
+            skip = cell.symbol().width().saturating_sub(1);
+        }
+        if style != plain {
+            out.push_str("\x1b[0m");
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Append an SGR sequence that resets attributes and then applies the given ones.
+fn push_sgr(out: &mut String, fg: Color, bg: Color, modifiers: Modifier) {
+    out.push_str("\x1b[0");
+    for (modifier, code) in [
+        (Modifier::BOLD, 1),
+        (Modifier::DIM, 2),
+        (Modifier::ITALIC, 3),
+        (Modifier::UNDERLINED, 4),
+        (Modifier::REVERSED, 7),
+        (Modifier::CROSSED_OUT, 9),
+    ] {
+        if modifiers.contains(modifier) {
+            let _ = write!(out, ";{code}");
+        }
+    }
+    push_color(out, fg, 30);
+    push_color(out, bg, 40);
+    out.push('m');
+}
+
+/// Append the SGR parameters for `color`; `base` is 30 for foreground, 40 for background.
+fn push_color(out: &mut String, color: Color, base: u8) {
+    let named = |n: u8| if n < 8 { base + n } else { base + 60 + n - 8 };
+    let _ = match color {
+        Color::Reset => return,
+        Color::Black => write!(out, ";{}", named(0)),
+        Color::Red => write!(out, ";{}", named(1)),
+        Color::Green => write!(out, ";{}", named(2)),
+        Color::Yellow => write!(out, ";{}", named(3)),
+        Color::Blue => write!(out, ";{}", named(4)),
+        Color::Magenta => write!(out, ";{}", named(5)),
+        Color::Cyan => write!(out, ";{}", named(6)),
+        Color::Gray => write!(out, ";{}", named(7)),
+        Color::DarkGray => write!(out, ";{}", named(8)),
+        Color::LightRed => write!(out, ";{}", named(9)),
+        Color::LightGreen => write!(out, ";{}", named(10)),
+        Color::LightYellow => write!(out, ";{}", named(11)),
+        Color::LightBlue => write!(out, ";{}", named(12)),
+        Color::LightMagenta => write!(out, ";{}", named(13)),
+        Color::LightCyan => write!(out, ";{}", named(14)),
+        Color::White => write!(out, ";{}", named(15)),
+        Color::Indexed(i) => write!(out, ";{};5;{i}", base + 8),
+        Color::Rgb(r, g, b) => write!(out, ";{};2;{r};{g};{b}", base + 8),
+    };
+}
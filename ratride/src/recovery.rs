@@ -0,0 +1,90 @@
+//! Where a talk was, saved while presenting so it can resume after a crash
+//! or an accidental quit.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::cache;
+
+/// How often the presenter saves its position while it changes.
+pub const SAVE_INTERVAL_MS: f64 = 2000.0;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Recovery {
+    /// Current slide, 0-based.
+    pub page: usize,
+    /// Fragments of it revealed so far.
+    #[serde(default)]
+    pub step: usize,
+    /// Scroll offset of the current slide.
+    pub scroll: u16,
+    /// Elapsed talk time on the presenter timer.
+    pub elapsed_ms: f64,
+    pub timer_paused: bool,
+}
+
+impl Recovery {
+    /// Whether there is anything worth offering to resume.
+    pub fn is_resumable(&self) -> bool {
+        self.page > 0 || self.step > 0 || self.scroll > 0 || self.elapsed_ms >= 1000.0
+    }
+}
+
+/// Recovery file for the deck at `deck` in the user's state directory,
+/// named by a hash of the deck's canonical path.
+pub fn recovery_path(deck: &Path) -> io::Result<PathBuf> {
+    let deck = fs::canonicalize(deck).unwrap_or_else(|_| deck.to_path_buf());
+    let hash = cache::stable_hash(deck.as_os_str().as_encoded_bytes());
+    let dir = cache::state_dir()?.join("recovery");
+    cache::create_private_dir(&dir)?;
+    Ok(dir.join(format!("{hash:016x}.toml")))
+}
+
+/// The state last saved for the deck at `deck`, if any.
+pub fn load(deck: &Path) -> Option<Recovery> {
+    let text = fs::read_to_string(recovery_path(deck).ok()?).ok()?;
+    toml::from_str(&text).ok()
+}
+
+/// Save `state` for the deck at `deck`. Written atomically, so a crash
+/// mid-write leaves the previous state intact.
+pub fn save(deck: &Path, state: &Recovery) -> io::Result<()> {
+    let text = toml::to_string(state).map_err(io::Error::other)?;
+    cache::write_atomic(&recovery_path(deck)?, text.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saved_state_loads_back() {
+        let _home = cache::TempHome::new("recovery");
+        let deck =
+            std::env::temp_dir().join(format!("ratride-recovery-test-{}.md", std::process::id()));
+        let state = Recovery {
+            page: 3,
+            step: 1,
+            scroll: 2,
+            elapsed_ms: 61_500.0,
+            timer_paused: true,
+        };
+        save(&deck, &state).unwrap();
+        assert_eq!(load(&deck), Some(state));
+        let _ = fs::remove_file(recovery_path(&deck).unwrap());
+        assert_eq!(load(&deck), None);
+        assert!(
+            !Recovery {
+                page: 0,
+                step: 0,
+                scroll: 0,
+                elapsed_ms: 200.0,
+                timer_paused: false
+            }
+            .is_resumable()
+        );
+    }
+}
@@ -0,0 +1,180 @@
+//! Presenting decks straight from an http(s) URL.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::cache;
+use crate::export::extract_asset_paths;
+
+pub fn is_url(path: &str) -> bool {
+    path.starts_with("http://") || path.starts_with("https://")
+}
+
+/// What the server said identifies the version of a file we have cached,
+/// sent back with the next request so an unchanged file isn't sent again.
+#[derive(Default, Serialize, Deserialize)]
+struct Validator {
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+/// Download the deck at `url` and its relative assets into a per-URL cache
+/// directory, returning the local path of the markdown file.
+///
+/// Files already in the cache are revalidated with the `ETag` or
+/// `Last-Modified` the server sent for them and only downloaded again when
+/// they changed. An asset that can't be fetched keeps its cached copy.
+pub fn fetch_deck(url: &str) -> io::Result<PathBuf> {
+    let root = cache::cache_dir("url")?;
+    let key = format!("{:016x}", cache::stable_hash(url.as_bytes()));
+    let dir = root.join(&key);
+    fs::create_dir_all(&dir)?;
+    let validators_path = root.join(format!("{key}.toml"));
+    let mut validators: BTreeMap<String, Validator> = fs::read_to_string(&validators_path)
+        .ok()
+        .and_then(|text| toml::from_str(&text).ok())
+        .unwrap_or_default();
+
+    let name = deck_name(url);
+    let deck_path = dir.join(name);
+    fetch(
+        url,
+        &deck_path,
+        validators.entry(name.to_string()).or_default(),
+    )?;
+    let markdown = fs::read_to_string(&deck_path)?;
+
+    for asset in extract_asset_paths(&markdown) {
+        // Only cache paths that stay inside the cache directory
+        let rel = Path::new(&asset);
+        if !rel
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+        {
+            continue;
+        }
+        let local = dir.join(rel);
+        let validator = validators.entry(asset.clone()).or_default();
+        if let Err(e) = fetch(&resolve(url, &asset), &local, validator) {
+            eprintln!("warning: {asset}: {e}");
+        }
+    }
+
+    let text = toml::to_string(&validators).map_err(io::Error::other)?;
+    cache::write_atomic(&validators_path, text.as_bytes())?;
+    Ok(deck_path)
+}
+
+/// File name the deck at `url` is cached under: the last segment of its
+/// path, without the query string or fragment.
+fn deck_name(url: &str) -> &str {
+    let url = url.split(['?', '#']).next().unwrap_or(url);
+    let after_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+    after_scheme
+        .split_once('/')
+        .and_then(|(_, path)| path.rsplit('/').next())
+        .filter(|s| !s.is_empty())
+        .unwrap_or("slides.md")
+}
+
+/// Download `url` to `local`, unless `local` exists and the server answers
+/// `validator` with 304 Not Modified. `validator` is updated from the
+/// response.
+fn fetch(url: &str, local: &Path, validator: &mut Validator) -> io::Result<()> {
+    let mut request = ureq::get(url);
+    if local.exists() {
+        if let Some(etag) = &validator.etag {
+            request = request.header("If-None-Match", etag);
+        }
+        if let Some(last_modified) = &validator.last_modified {
+            request = request.header("If-Modified-Since", last_modified);
+        }
+    }
+    let mut res = request
+        .call()
+        .map_err(|e| io::Error::other(format!("{url}: {e}")))?;
+    if res.status() == 304 {
+        return Ok(());
+    }
+    let header = |name: &str| {
+        res.headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+    };
+    *validator = Validator {
+        etag: header("etag"),
+        last_modified: header("last-modified"),
+    };
+    let data = res
+        .body_mut()
+        .read_to_vec()
+        .map_err(|e| io::Error::other(format!("{url}: {e}")))?;
+    if let Some(parent) = local.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    cache::write_atomic(local, &data)
+}
+
+/// Resolve `path` relative to the document at `base`.
+fn resolve(base: &str, path: &str) -> String {
+    let path = path.strip_prefix("./").unwrap_or(path);
+    let base = base.split(['?', '#']).next().unwrap_or(base);
+    let dir = base.rfind('/').map_or(base, |i| &base[..=i]);
+    format!("{dir}{path}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_strings_stay_out_of_cached_names() {
+        assert_eq!(
+            deck_name("https://example.com/talks/deck.md?token=abc"),
+            "deck.md"
+        );
+        assert_eq!(deck_name("https://example.com/talks/#intro"), "slides.md");
+        assert_eq!(deck_name("https://example.com"), "slides.md");
+        assert_eq!(
+            resolve("https://example.com/talks/deck.md?from=a/b", "./img/a.png"),
+            "https://example.com/talks/img/a.png"
+        );
+    }
+
+    #[test]
+    fn unchanged_files_are_not_downloaded_again() {
+        let _home = cache::TempHome::new("remote");
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/deck.md", server.server_addr().to_ip().unwrap());
+        let handle = std::thread::spawn(move || {
+            let mut bodies_sent = 0;
+            for _ in 0..2 {
+                let request = server.recv().unwrap();
+                let fresh = request
+                    .headers()
+                    .iter()
+                    .any(|h| h.field.equiv("If-None-Match") && h.value == "\"v1\"");
+                let etag = tiny_http::Header::from_bytes("ETag", "\"v1\"").unwrap();
+                if fresh {
+                    let _ = request.respond(tiny_http::Response::empty(304).with_header(etag));
+                } else {
+                    bodies_sent += 1;
+                    let response = tiny_http::Response::from_string("# Hi\n").with_header(etag);
+                    let _ = request.respond(response);
+                }
+            }
+            bodies_sent
+        });
+
+        let first = fetch_deck(&url).unwrap();
+        let second = fetch_deck(&url).unwrap();
+        assert_eq!(first, second);
+        assert_eq!(fs::read_to_string(&second).unwrap(), "# Hi\n");
+        assert_eq!(handle.join().unwrap(), 1);
+    }
+}
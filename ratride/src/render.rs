@@ -1,12 +1,20 @@
-use crate::markdown::{HeaderItem, SemanticElement, Slide, SlideLayout};
+use crate::color::blend_color;
+use crate::markdown::{
+    Agenda, HeaderItem, LogoCorner, ParseWarning, SemanticElement, Slide, SlideLayout,
+};
 use crate::theme::Theme;
 use ratatui::{
     Frame,
+    buffer::Buffer,
     layout::{Alignment, Constraint, Flex, Layout, Margin, Rect},
     text::{Span, Text},
-    widgets::{Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState, Wrap},
+    widgets::{
+        Block, Borders, Clear, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState, Wrap,
+    },
 };
-use unicode_width::UnicodeWidthChar;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 /// Position where an image should be rendered.
 /// Terminal backend uses this to draw images after ratatui render.
@@ -23,6 +31,47 @@ pub struct ImagePlacement {
     pub full_height: u16,
 }
 
+/// Box the frontmatter `logo` is fitted into, in cells.
+const LOGO_WIDTH: u16 = 12;
+const LOGO_HEIGHT: u16 = 3;
+
+/// Where to draw the frontmatter `logo` in `corner` of `area`, or `None` when
+/// the area is too small for it.
+pub fn logo_placement(path: &str, corner: LogoCorner, area: Rect) -> Option<ImagePlacement> {
+    let width = LOGO_WIDTH.min(area.width.saturating_sub(2));
+    let height = LOGO_HEIGHT.min(area.height);
+    if width == 0 || height == 0 {
+        return None;
+    }
+    let x = match corner {
+        LogoCorner::TopLeft | LogoCorner::BottomLeft => area.x + 1,
+        LogoCorner::TopRight | LogoCorner::BottomRight => area.right() - 1 - width,
+    };
+    let y = match corner {
+        LogoCorner::TopLeft | LogoCorner::TopRight => area.y,
+        LogoCorner::BottomLeft | LogoCorner::BottomRight => area.bottom() - height,
+    };
+    Some(ImagePlacement {
+        x,
+        y,
+        width,
+        height,
+        path: path.to_string(),
+        clip_top: false,
+        full_height: height,
+    })
+}
+
+/// Position where a double-size heading should be drawn.
+/// Terminal backend writes these via escape sequences after ratatui render.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BigTextPlacement {
+    pub x: u16,
+    pub y: u16,
+    pub text: String,
+    pub style: ratatui::style::Style,
+}
+
 /// A single hyperlink cell to be rendered via direct stdout writes (bypassing ratatui buffer).
 #[derive(Clone, Debug)]
 pub struct HyperlinkCell {
@@ -31,49 +80,249 @@ pub struct HyperlinkCell {
     pub url: String,
 }
 
+/// Shrink `area` to the largest rect matching `aspect` (width/height in pixels),
+/// centered within it. `cell_aspect` is the height/width ratio of one cell.
+pub fn letterbox(area: Rect, aspect: f64, cell_aspect: f64) -> Rect {
+    if aspect <= 0.0 || cell_aspect <= 0.0 {
+        return area;
+    }
+    // Width in cells that matches the full height at the requested ratio.
+    let fit_w = (area.height as f64 * cell_aspect * aspect).round() as u16;
+    if fit_w <= area.width {
+        let x = area.x + (area.width - fit_w) / 2;
+        Rect::new(x, area.y, fit_w, area.height)
+    } else {
+        let fit_h = (area.width as f64 / aspect / cell_aspect).round() as u16;
+        let fit_h = fit_h.min(area.height);
+        let y = area.y + (area.height - fit_h) / 2;
+        Rect::new(area.x, y, area.width, fit_h)
+    }
+}
+
+/// Fill every cell of `area` with the given background color.
+pub fn fill_background(frame: &mut Frame, area: Rect, bg: ratatui::style::Color) {
+    let buf = frame.buffer_mut();
+    for y in area.y..area.y + area.height {
+        for x in area.x..area.x + area.width {
+            if let Some(cell) = buf.cell_mut((x, y)) {
+                cell.set_bg(bg);
+            }
+        }
+    }
+}
+
+/// Slide areas narrower or lower than this are drawn compactly: thinner
+/// margins, and no figlet headings in the terminal.
+pub const COMPACT_SIZE: (u16, u16) = (40, 12);
+
+pub fn is_compact(area: Rect) -> bool {
+    area.width < COMPACT_SIZE.0 || area.height < COMPACT_SIZE.1
+}
+
+/// Margin around the content of a slide drawn into `area`: 2 columns and
+/// 1 row, or 1 column and none when the area [`is_compact`].
+pub fn content_margin(area: Rect) -> Margin {
+    if is_compact(area) {
+        Margin::new(1, 0)
+    } else {
+        Margin::new(2, 1)
+    }
+}
+
 /// Draw a slide's main content area (dispatches by layout).
 /// Returns image placements for the terminal backend to render.
 pub fn draw_slide(
     slide: &Slide,
+    slot: LayoutSlot,
     scroll: u16,
     frame: &mut Frame,
     area: Rect,
 ) -> (Vec<ImagePlacement>, Vec<HyperlinkCell>) {
     match slide.layout {
-        SlideLayout::Default => draw_default(slide, scroll, frame, area),
-        SlideLayout::Center => draw_center(slide, scroll, frame, area),
+        SlideLayout::Default => draw_default(slide, slot, scroll, frame, area),
+        SlideLayout::Center => draw_center(slide, slot, scroll, frame, area),
         SlideLayout::TwoColumn => {
-            draw_two_column(slide, scroll, frame, area);
+            draw_two_column(slide, slot, scroll, frame, area);
             (Vec::new(), Vec::new())
         }
     }
 }
 
+/// Split `area` into the current slide and the column to its right where
+/// the peek and the presenter view show what comes next.
+pub fn next_slide_areas(area: Rect) -> [Rect; 2] {
+    Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)]).areas(area)
+}
+
+/// Presenter peek: the current slide on the left and a dimmed preview of
+/// `next` on the right. Only the current slide's images and links are returned.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_slide_with_peek(
+    slide: &Slide,
+    slot: LayoutSlot,
+    next: Option<&Slide>,
+    scroll: u16,
+    frame: &mut Frame,
+    area: Rect,
+    theme: &Theme,
+) -> (Vec<ImagePlacement>, Vec<HyperlinkCell>) {
+    let [main_area, peek_area] = next_slide_areas(area);
+    let result = draw_slide(slide, slot, scroll, frame, main_area);
+
+    let title = if next.is_some() { " next " } else { " end " };
+    let block = Block::new()
+        .borders(Borders::LEFT)
+        .title(title)
+        .border_style(ratatui::style::Style::default().fg(theme.list_bullet));
+    let inner = block.inner(peek_area);
+    frame.render_widget(Clear, peek_area);
+    frame.render_widget(block, peek_area);
+    if let Some(next) = next {
+        draw_slide(next, slot.following(), 0, frame, inner);
+    }
+
+    dim_area(frame, inner, theme);
+    result
+}
+
+/// Presenter view: the current slide on the left; the next slide (dimmed)
+/// above the speaker notes on the right, with the elapsed time in the notes
+/// title. Only the current slide's images and links are returned.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_presenter_view(
+    slide: &Slide,
+    slot: LayoutSlot,
+    next: Option<&Slide>,
+    scroll: u16,
+    elapsed_ms: f64,
+    frame: &mut Frame,
+    area: Rect,
+    theme: &Theme,
+) -> (Vec<ImagePlacement>, Vec<HyperlinkCell>) {
+    let [main_area, side_area] = next_slide_areas(area);
+    let [next_area, notes_area] =
+        Layout::vertical([Constraint::Percentage(45), Constraint::Percentage(55)]).areas(side_area);
+    let result = draw_slide(slide, slot, scroll, frame, main_area);
+    frame.render_widget(Clear, side_area);
+
+    let border_style = ratatui::style::Style::default().fg(theme.list_bullet);
+    let title = if next.is_some() { " next " } else { " end " };
+    let block = Block::new()
+        .borders(Borders::LEFT)
+        .title(title)
+        .border_style(border_style);
+    let inner = block.inner(next_area);
+    frame.render_widget(block, next_area);
+    if let Some(next) = next {
+        draw_slide(next, slot.following(), 0, frame, inner);
+    }
+    dim_area(frame, inner, theme);
+
+    let title = format!(" notes · {} ", crate::timer::format_clock(elapsed_ms));
+    let block = Block::new()
+        .borders(Borders::LEFT | Borders::TOP)
+        .title(title)
+        .border_style(border_style);
+    let notes = slide.notes.as_deref().unwrap_or("");
+    frame.render_widget(
+        Paragraph::new(notes)
+            .block(block)
+            .style(ratatui::style::Style::default().fg(theme.fg))
+            .wrap(Wrap { trim: false }),
+        notes_area,
+    );
+    result
+}
+
+/// Dim `area` by pulling its text colors halfway to the background.
+fn dim_area(frame: &mut Frame, area: Rect, theme: &Theme) {
+    let buf = frame.buffer_mut();
+    for y in area.y..area.y + area.height {
+        for x in area.x..area.x + area.width {
+            if let Some(cell) = buf.cell_mut((x, y)) {
+                let fg = match cell.fg {
+                    ratatui::style::Color::Reset => theme.fg,
+                    fg => fg,
+                };
+                cell.set_fg(blend_color(fg, theme.bg, 0.5));
+            }
+        }
+    }
+}
+
+/// Column of a two-column slide that has the audience's attention.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ActiveColumn {
+    Left,
+    Right,
+}
+
+impl ActiveColumn {
+    /// Cycle none → left → right → none.
+    pub fn cycle(active: Option<Self>) -> Option<Self> {
+        match active {
+            None => Some(Self::Left),
+            Some(Self::Left) => Some(Self::Right),
+            Some(Self::Right) => None,
+        }
+    }
+}
+
+/// Dim the column of a two-column slide that is not `active`, after it has
+/// been drawn into `area` by [`draw_slide`].
+pub fn dim_inactive_column(
+    slide: &Slide,
+    active: ActiveColumn,
+    frame: &mut Frame,
+    area: Rect,
+    theme: &Theme,
+) {
+    if !matches!(slide.layout, SlideLayout::TwoColumn) {
+        return;
+    }
+    let [left_area, right_area] = two_column_areas(area.inner(content_margin(area)));
+    let inactive = match active {
+        ActiveColumn::Left => right_area,
+        ActiveColumn::Right => left_area,
+    };
+    dim_area(frame, inactive, theme);
+}
+
 pub fn draw_default(
     slide: &Slide,
+    slot: LayoutSlot,
     scroll: u16,
     frame: &mut Frame,
     area: Rect,
 ) -> (Vec<ImagePlacement>, Vec<HyperlinkCell>) {
-    let content_area = area.inner(Margin::new(2, 1));
-    let (content, index_map) = rewrap_bg_lines(&slide.content, content_area.width);
+    let content_area = area.inner(content_margin(area));
+    let wrapped = wrapped_column(slide, slot, 0, content_area.width);
+    let (content, index_map) = (&wrapped.content, &wrapped.index_map);
 
-    fill_line_backgrounds(&content, scroll, frame, content_area);
+    fill_line_backgrounds(content, scroll, frame, content_area);
 
     let paragraph = Paragraph::new(content.clone())
         .wrap(Wrap { trim: false })
         .scroll((scroll, 0));
     frame.render_widget(paragraph, content_area);
 
-    let hyperlinks = collect_hyperlinks(&slide.semantics, &content, scroll, content_area, Alignment::Left, &index_map);
+    let hyperlinks = collect_hyperlinks(
+        &slide.semantics,
+        content,
+        scroll,
+        content_area,
+        Alignment::Left,
+        index_map,
+    );
 
-    let content_len = wrapped_content_height(&content, content_area.width);
+    let content_len = wrapped.height;
     draw_scrollbar(scroll, content_len, content_area.height, frame, area);
+    draw_overflow_markers(scroll, content_len, content_area, frame, area, &slide.theme);
 
     let mut placements = Vec::new();
     for img in &slide.images {
-        let li = remap_index(img.line_index, &index_map);
-        let y_off = wrapped_y_offset(&content, li, content_area.width);
+        let li = remap_index(img.line_index, index_map);
+        let y_off = wrapped_y_offset(content, li, content_area.width);
         if let Some(p) = compute_image_placement(
             content_area,
             y_off,
@@ -84,6 +333,7 @@ pub fn draw_default(
             0,
             0,
             img.max_width_percent,
+            slide.cell_aspect,
         ) {
             placements.push(p);
         }
@@ -93,19 +343,17 @@ pub fn draw_default(
 
 pub fn draw_center(
     slide: &Slide,
+    slot: LayoutSlot,
     scroll: u16,
     frame: &mut Frame,
     area: Rect,
 ) -> (Vec<ImagePlacement>, Vec<HyperlinkCell>) {
-    let content_area = area.inner(Margin::new(2, 1));
-    let (content, index_map) = rewrap_bg_lines(&slide.content, content_area.width);
-    let content_height = wrapped_content_height(&content, content_area.width) as u16;
-
-    let [centered_area] = Layout::vertical([Constraint::Length(content_height)])
-        .flex(Flex::Center)
-        .areas(content_area);
+    let content_area = area.inner(content_margin(area));
+    let wrapped = wrapped_column(slide, slot, 0, content_area.width);
+    let (content, index_map) = (&wrapped.content, &wrapped.index_map);
+    let centered_area = center_vertically(wrapped.height, content_area);
 
-    fill_line_backgrounds(&content, scroll, frame, centered_area);
+    fill_line_backgrounds(content, scroll, frame, centered_area);
 
     let paragraph = Paragraph::new(content.clone())
         .alignment(Alignment::Center)
@@ -113,12 +361,29 @@ pub fn draw_center(
         .scroll((scroll, 0));
     frame.render_widget(paragraph, centered_area);
 
-    let hyperlinks = collect_hyperlinks(&slide.semantics, &content, scroll, centered_area, Alignment::Center, &index_map);
+    let content_len = wrapped.height;
+    draw_overflow_markers(
+        scroll,
+        content_len,
+        centered_area,
+        frame,
+        area,
+        &slide.theme,
+    );
+
+    let hyperlinks = collect_hyperlinks(
+        &slide.semantics,
+        content,
+        scroll,
+        centered_area,
+        Alignment::Center,
+        index_map,
+    );
 
     let mut placements = Vec::new();
     for img in &slide.images {
-        let li = remap_index(img.line_index, &index_map);
-        let y_off = wrapped_y_offset(&content, li, centered_area.width);
+        let li = remap_index(img.line_index, index_map);
+        let y_off = wrapped_y_offset(content, li, centered_area.width);
         if let Some(p) = compute_image_placement(
             centered_area,
             y_off,
@@ -129,6 +394,7 @@ pub fn draw_center(
             img.pixel_width,
             img.pixel_height,
             img.max_width_percent,
+            slide.cell_aspect,
         ) {
             placements.push(p);
         }
@@ -136,29 +402,263 @@ pub fn draw_center(
     (placements, hyperlinks)
 }
 
-pub fn draw_two_column(slide: &Slide, scroll: u16, frame: &mut Frame, area: Rect) {
-    let content_area = area.inner(Margin::new(2, 1));
+/// Rect the content of `slide` is drawn into within `area` (as passed to
+/// [`draw_slide`]): the margin-inset area, vertically centered on the wrapped
+/// content height (image placeholder rows included) for the center layout.
+pub fn slide_content_area(slide: &Slide, slot: LayoutSlot, area: Rect) -> Rect {
+    let content_area = area.inner(content_margin(area));
+    match slide.layout {
+        SlideLayout::Center => {
+            let wrapped = wrapped_column(slide, slot, 0, content_area.width);
+            center_vertically(wrapped.height, content_area)
+        }
+        _ => content_area,
+    }
+}
+
+/// Vertically center content `content_height` rows tall within `content_area`.
+fn center_vertically(content_height: usize, content_area: Rect) -> Rect {
+    let [centered_area] = Layout::vertical([Constraint::Length(content_height as u16)])
+        .flex(Flex::Center)
+        .areas(content_area);
+    centered_area
+}
+
+/// Compute screen positions of the slide's double-size headings.
+/// Headings that would not fit at double width, or whose two rows are not
+/// both visible, are skipped and stay at normal size.
+pub fn big_heading_placements(
+    slide: &Slide,
+    slot: LayoutSlot,
+    scroll: u16,
+    area: Rect,
+) -> Vec<BigTextPlacement> {
+    if slide.big_headings.is_empty() {
+        return Vec::new();
+    }
+    let content_area = area.inner(content_margin(area));
+    let wrapped = wrapped_column(slide, slot, 0, content_area.width);
+    let (content, index_map) = (&wrapped.content, &wrapped.index_map);
+    let (rect, center) = match slide.layout {
+        SlideLayout::Default => (content_area, false),
+        SlideLayout::Center => (center_vertically(wrapped.height, content_area), true),
+        SlideLayout::TwoColumn => return Vec::new(),
+    };
+
+    let mut placements = Vec::new();
+    for heading in &slide.big_headings {
+        let big_width = Span::raw(heading.text.as_str()).width() * 2;
+        if big_width > rect.width as usize {
+            continue;
+        }
+        let li = remap_index(heading.line_index, index_map);
+        let y_off = wrapped_y_offset(content, li, rect.width) as i32 - scroll as i32;
+        if y_off < 0 || y_off + 1 >= rect.height as i32 {
+            continue;
+        }
+        let x_off = if center {
+            (rect.width - big_width as u16) / 2
+        } else {
+            0
+        };
+        placements.push(BigTextPlacement {
+            x: rect.x + x_off,
+            y: rect.y + y_off as u16,
+            text: heading.text.clone(),
+            style: heading.style,
+        });
+    }
+    placements
+}
+
+/// Delay between the words of an `animate: words` build.
+pub const WORD_STAGGER_MS: f64 = 120.0;
+/// How long each word takes to fade in.
+pub const WORD_FADE_MS: f64 = 300.0;
+
+/// Hide the words of the slide's word builds that are not due yet
+/// `elapsed_ms` after they started, and fade in the ones appearing, after
+/// the slide was drawn into `area` by [`draw_slide`]. Words appear in
+/// reading order, one every [`WORD_STAGGER_MS`].
+pub fn reveal_words(
+    slide: &Slide,
+    slot: LayoutSlot,
+    scroll: u16,
+    elapsed_ms: f64,
+    frame: &mut Frame,
+    area: Rect,
+) {
+    if slide.word_builds.is_empty() {
+        return;
+    }
+    let content_area = area.inner(content_margin(area));
+    let wrapped = wrapped_column(slide, slot, 0, content_area.width);
+    let (content, index_map) = (&wrapped.content, &wrapped.index_map);
+    let rect = match slide.layout {
+        SlideLayout::Default => content_area,
+        SlideLayout::Center => center_vertically(wrapped.height, content_area),
+        SlideLayout::TwoColumn => return,
+    };
 
+    let buf = frame.buffer_mut();
+    for build in &slide.word_builds {
+        let end = index_map
+            .get(build.end)
+            .copied()
+            .unwrap_or(content.lines.len());
+        let top = wrapped_y_offset(content, remap_index(build.start, index_map), rect.width);
+        let bottom = wrapped_y_offset(content, end, rect.width);
+        let mut word = 0;
+        for row in top..bottom {
+            let Some(y) = row.checked_sub(scroll as usize) else {
+                continue;
+            };
+            if y >= rect.height as usize {
+                break;
+            }
+            let mut in_word = false;
+            for x in rect.x..rect.right() {
+                let Some(cell) = buf.cell_mut((x, rect.y + y as u16)) else {
+                    continue;
+                };
+                if cell.symbol().trim().is_empty() {
+                    in_word = false;
+                    continue;
+                }
+                if !in_word {
+                    in_word = true;
+                    word += 1;
+                }
+                let since = elapsed_ms - (word - 1) as f64 * WORD_STAGGER_MS;
+                let t = (since / WORD_FADE_MS).clamp(0.0, 1.0) as f32;
+                if t <= 0.0 {
+                    cell.set_symbol(" ");
+                } else if t < 1.0 {
+                    let bg = match cell.bg {
+                        ratatui::style::Color::Reset => slide.theme.bg,
+                        bg => bg,
+                    };
+                    let fg = match cell.fg {
+                        ratatui::style::Color::Reset => slide.theme.fg,
+                        fg => fg,
+                    };
+                    cell.set_fg(blend_color(bg, fg, t));
+                }
+            }
+        }
+    }
+}
+
+/// Compute screen positions of the slide's embedded videos.
+/// The web frontend overlays `<video>` elements at these rects.
+pub fn video_placements(
+    slide: &Slide,
+    slot: LayoutSlot,
+    scroll: u16,
+    area: Rect,
+) -> Vec<ImagePlacement> {
+    if slide.videos.is_empty() {
+        return Vec::new();
+    }
+    let content_area = area.inner(content_margin(area));
+    let wrapped = wrapped_column(slide, slot, 0, content_area.width);
+    let (content, index_map) = (&wrapped.content, &wrapped.index_map);
+    let rect = match slide.layout {
+        SlideLayout::Default => content_area,
+        SlideLayout::Center => center_vertically(wrapped.height, content_area),
+        SlideLayout::TwoColumn => return Vec::new(),
+    };
+
+    slide
+        .videos
+        .iter()
+        .filter_map(|video| {
+            let li = remap_index(video.line_index, index_map);
+            let y_off = wrapped_y_offset(content, li, rect.width);
+            compute_image_placement(
+                rect,
+                y_off,
+                video.height,
+                scroll,
+                &video.path,
+                false,
+                0,
+                0,
+                None,
+                slide.cell_aspect,
+            )
+        })
+        .collect()
+}
+
+pub fn draw_two_column(
+    slide: &Slide,
+    slot: LayoutSlot,
+    scroll: u16,
+    frame: &mut Frame,
+    area: Rect,
+) {
+    let [left_area, right_area] = two_column_areas(area.inner(content_margin(area)));
+    draw_column(slide, slot, 0, scroll, frame, left_area);
+    if slide.right_content.is_some() {
+        draw_column(slide, slot, 1, scroll, frame, right_area);
+    }
+}
+
+/// Left and right column areas (gap excluded) within a two-column content area.
+fn two_column_areas(content_area: Rect) -> [Rect; 2] {
     let [left_area, _gap, right_area] = Layout::horizontal([
         Constraint::Percentage(48),
         Constraint::Percentage(4),
         Constraint::Percentage(48),
     ])
     .areas(content_area);
+    [left_area, right_area]
+}
 
-    let (left_content, _) = rewrap_bg_lines(&slide.content, left_area.width);
-    let left_para = Paragraph::new(left_content)
+/// Draw one column of a two-column slide with its own scrollbar in its last
+/// cell column. `scroll` is clamped to the column so a shorter column stops
+/// at its end instead of scrolling out of view.
+fn draw_column(
+    slide: &Slide,
+    slot: LayoutSlot,
+    column: usize,
+    scroll: u16,
+    frame: &mut Frame,
+    area: Rect,
+) {
+    let [text_area, bar_area] =
+        Layout::horizontal([Constraint::Fill(1), Constraint::Length(1)]).areas(area);
+    let wrapped = wrapped_column(slide, slot, column, text_area.width);
+    let content_len = wrapped.height;
+    let scroll = scroll.min(content_len.saturating_sub(text_area.height as usize) as u16);
+    let para = Paragraph::new(wrapped.content.clone())
         .wrap(Wrap { trim: false })
         .scroll((scroll, 0));
-    frame.render_widget(left_para, left_area);
+    frame.render_widget(para, text_area);
+    draw_scrollbar(scroll, content_len, text_area.height, frame, bar_area);
+}
 
-    if let Some(ref right) = slide.right_content {
-        let (right_content, _) = rewrap_bg_lines(right, right_area.width);
-        let right_para = Paragraph::new(right_content)
-            .wrap(Wrap { trim: false })
-            .scroll((scroll, 0));
-        frame.render_widget(right_para, right_area);
-    }
+/// How far the content of `slide` drawn into `area` (as passed to
+/// [`draw_slide`]) can scroll; zero when everything fits.
+pub fn max_scroll(slide: &Slide, slot: LayoutSlot, area: Rect) -> u16 {
+    let content_area = area.inner(content_margin(area));
+    let content_len = match slide.layout {
+        SlideLayout::TwoColumn => {
+            // Column text is one cell narrower than the column: the scrollbar
+            let [left_area, right_area] = two_column_areas(content_area);
+            let left = wrapped_column(slide, slot, 0, left_area.width.saturating_sub(1)).height;
+            let right = match slide.right_content {
+                Some(_) => {
+                    wrapped_column(slide, slot, 1, right_area.width.saturating_sub(1)).height
+                }
+                None => 0,
+            };
+            left.max(right)
+        }
+        _ => wrapped_column(slide, slot, 0, content_area.width).height,
+    };
+    content_len.saturating_sub(content_area.height as usize) as u16
 }
 
 pub fn draw_scrollbar(
@@ -180,16 +680,229 @@ pub fn draw_scrollbar(
     }
 }
 
+/// Draw "▲ more above" / "▼ more below" in the margin rows of `area` when
+/// content scrolled within `content_area` is cut off at the top or bottom.
+pub fn draw_overflow_markers(
+    scroll: u16,
+    content_len: usize,
+    content_area: Rect,
+    frame: &mut Frame,
+    area: Rect,
+    theme: &Theme,
+) {
+    if area.height < 2 {
+        return;
+    }
+    let style = ratatui::style::Style::default().fg(theme.list_bullet);
+    let mut marker = |text: &str, y: u16| {
+        let paragraph = Paragraph::new(text)
+            .alignment(Alignment::Center)
+            .style(style);
+        frame.render_widget(paragraph, Rect::new(area.x, y, area.width, 1));
+    };
+    if scroll > 0 {
+        marker("▲ more above", area.y);
+    }
+    if content_len > scroll as usize + content_area.height as usize {
+        marker("▼ more below", area.y + area.height - 1);
+    }
+}
+
+/// How long `j/k/d/u` scrolling animates between offsets.
+pub const SCROLL_ANIM_MS: f64 = 100.0;
+
+/// Scroll offset a fraction `t` (0..=1) of the way from `from` to `to`, eased out.
+pub fn eased_scroll(from: u16, to: u16, t: f64) -> u16 {
+    let t = t.clamp(0.0, 1.0);
+    let eased = 1.0 - (1.0 - t) * (1.0 - t);
+    (from as f64 + (to as f64 - from as f64) * eased).round() as u16
+}
+
+/// How long an image crossfades into the one replacing it on the next slide.
+pub const IMAGE_CROSSFADE_MS: f64 = 400.0;
+
+/// An image drawn where a different image sat on the previous slide.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImageCrossfade {
+    /// Path of the outgoing image.
+    pub from: String,
+    /// Path of the incoming image.
+    pub to: String,
+    /// Columns and rows the incoming image is drawn in.
+    pub cells: (u16, u16),
+}
+
+/// Pair up images of the next slide with images of the previous one drawn at
+/// the same position and size. When any pair is found, the presenter fades
+/// the images into each other instead of running the slide transition.
+pub fn image_crossfades(prev: &[ImagePlacement], next: &[ImagePlacement]) -> Vec<ImageCrossfade> {
+    let same_box = |a: &ImagePlacement, b: &ImagePlacement| {
+        (a.x, a.y, a.width, a.full_height) == (b.x, b.y, b.width, b.full_height)
+    };
+    next.iter()
+        .filter_map(|to| {
+            let from = prev.iter().find(|from| same_box(from, to))?;
+            (from.path != to.path).then(|| ImageCrossfade {
+                from: from.path.clone(),
+                to: to.path.clone(),
+                cells: (to.width, to.full_height),
+            })
+        })
+        .collect()
+}
+
+/// Tint the row a third of the way down the content area of `area` so the
+/// audience can follow along while a long slide scrolls past it.
+pub fn draw_reading_ruler(frame: &mut Frame, area: Rect, theme: &Theme) {
+    let content_area = area.inner(content_margin(area));
+    if content_area.height == 0 {
+        return;
+    }
+    let y = content_area.y + content_area.height / 3;
+    let buf = frame.buffer_mut();
+    for x in content_area.x..content_area.x + content_area.width {
+        if let Some(cell) = buf.cell_mut((x, y)) {
+            let bg = match cell.bg {
+                ratatui::style::Color::Reset => theme.bg,
+                bg => bg,
+            };
+            cell.set_bg(blend_color(bg, theme.fg, 0.12));
+        }
+    }
+}
+
+/// Split `area` into the outline sidebar and the slide area to its right.
+pub fn outline_areas(area: Rect) -> [Rect; 2] {
+    let width = (area.width / 4).clamp(16, 32).min(area.width / 2);
+    Layout::horizontal([Constraint::Length(width), Constraint::Min(0)]).areas(area)
+}
+
+/// Draw the deck outline: one row per slide title, section openers (H1
+/// slides) flush left and the slides under them indented, with the current
+/// slide highlighted and scrolled into view. Presenter-only slides are listed
+/// only when `presenter` is set.
+pub fn draw_outline(
+    slides: &[Slide],
+    current: usize,
+    presenter: bool,
+    frame: &mut Frame,
+    area: Rect,
+    theme: &Theme,
+) {
+    let style = ratatui::style::Style::default().fg(theme.fg).bg(theme.bg);
+    let block = Block::default()
+        .borders(Borders::RIGHT)
+        .border_style(style.fg(theme.surface))
+        .style(style);
+    let inner = block.inner(area);
+    frame.render_widget(Clear, area);
+    frame.render_widget(block, area);
+
+    let entries: Vec<(usize, &Slide)> = slides
+        .iter()
+        .enumerate()
+        .filter(|(_, slide)| presenter || !slide.presenter_only)
+        .collect();
+    let current_row = entries.iter().position(|(i, _)| *i == current).unwrap_or(0);
+    let offset = current_row
+        .saturating_sub(inner.height as usize / 2)
+        .min(entries.len().saturating_sub(inner.height as usize));
+
+    let lines: Vec<ratatui::text::Line> = entries
+        .iter()
+        .skip(offset)
+        .take(inner.height as usize)
+        .map(|(i, slide)| {
+            let title = slide
+                .title
+                .clone()
+                .unwrap_or_else(|| format!("Slide {}", i + 1));
+            let (indent, fg) = if slide.starts_section() {
+                (" ", theme.h1)
+            } else {
+                ("   ", theme.fg)
+            };
+            let line_style = if *i == current {
+                style.fg(theme.status_fg).bg(theme.status_bg)
+            } else {
+                style.fg(fg)
+            };
+            // Pad to the full width so the highlight spans the row
+            let text = format!("{indent}{title}");
+            let pad = (inner.width as usize).saturating_sub(text.width());
+            ratatui::text::Line::styled(format!("{text}{}", " ".repeat(pad)), line_style)
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+/// Split `area` into the slide area and the notes margin to its right.
+pub fn notes_margin_areas(area: Rect) -> [Rect; 2] {
+    let width = (area.width / 4).clamp(20, 36).min(area.width / 2);
+    Layout::horizontal([Constraint::Min(0), Constraint::Length(width)]).areas(area)
+}
+
+/// Draw the speaker notes of `slide`, dimmed, in a margin beside it, for
+/// presenters with a single screen.
+pub fn draw_notes_margin(slide: &Slide, frame: &mut Frame, area: Rect, theme: &Theme) {
+    let style = ratatui::style::Style::default().fg(theme.fg).bg(theme.bg);
+    let block = Block::default()
+        .borders(Borders::LEFT)
+        .border_style(style.fg(theme.surface))
+        .style(style);
+    let inner = block.inner(area).inner(Margin::new(1, 0));
+    frame.render_widget(Clear, area);
+    frame.render_widget(block, area);
+    let notes = slide.notes.as_deref().unwrap_or("");
+    frame.render_widget(
+        Paragraph::new(notes)
+            .style(style)
+            .wrap(Wrap { trim: false }),
+        inner,
+    );
+    dim_area(frame, inner, theme);
+}
+
+/// Draw a box listing parse warnings over the bottom of `area`.
+pub fn draw_warnings(warnings: &[ParseWarning], frame: &mut Frame, area: Rect, theme: &Theme) {
+    if warnings.is_empty() {
+        return;
+    }
+    let inner = area.inner(Margin::new(2, 1));
+    let height = (warnings.len() as u16 + 2).min(inner.height);
+    let [_, popup] =
+        Layout::vertical([Constraint::Fill(1), Constraint::Length(height)]).areas(inner);
+
+    let style = ratatui::style::Style::default().fg(theme.fg).bg(theme.bg);
+    let title = format!(" {} warning(s) — press any key ", warnings.len());
+    let block = Block::bordered()
+        .title(title)
+        .border_style(style.fg(theme.h2))
+        .style(style);
+    let lines: Vec<ratatui::text::Line> = warnings
+        .iter()
+        .map(|w| ratatui::text::Line::from(w.to_string()))
+        .collect();
+    frame.render_widget(Clear, popup);
+    frame.render_widget(Paragraph::new(lines).block(block), popup);
+}
+
+/// How long key hints stay in the status bar at startup and after `?`.
+pub const HINT_DURATION_MS: f64 = 4000.0;
+
 pub fn draw_status_bar(
     current_page: usize,
     total: usize,
     frame: &mut Frame,
     area: Rect,
     theme: &Theme,
+    show_hints: bool,
 ) {
-    draw_status_bar_with_options(current_page, total, frame, area, theme, false);
+    draw_status_bar_with_options(current_page, total, frame, area, theme, false, show_hints);
 }
 
+/// Draw the status bar: key hints and the page counter while `show_hints`,
+/// otherwise just the page counter.
 pub fn draw_status_bar_with_options(
     current_page: usize,
     total: usize,
@@ -197,29 +910,319 @@ pub fn draw_status_bar_with_options(
     area: Rect,
     theme: &Theme,
     is_web: bool,
+    show_hints: bool,
 ) {
     let quit_str = if is_web { "" } else { "  q:quit" };
-    let left = format!(" ←/→:page  ↓/↑:scroll{}", quit_str);
+    let left = format!(" ←/→:page  ↓/↑:scroll{}  ?:hints", quit_str);
     let right = format!("[{}/{}] ", current_page + 1, total);
 
     let style = ratatui::style::Style::default()
         .bg(theme.status_bg)
         .fg(theme.status_fg);
 
-    // Fill background
-    frame.render_widget(Paragraph::new("").style(style), area);
-
     let [left_area, right_area] =
         Layout::horizontal([Constraint::Fill(1), Constraint::Length(right.len() as u16)])
             .areas(area);
 
-    frame.render_widget(Paragraph::new(left).style(style), left_area);
+    if show_hints {
+        // Fill background
+        frame.render_widget(Paragraph::new("").style(style), area);
+        frame.render_widget(Paragraph::new(left).style(style), left_area);
+    }
     frame.render_widget(
         Paragraph::new(right).alignment(Alignment::Right).style(style),
         right_area,
     );
 }
 
+/// Draw the elapsed talk time in the status bar, left of the page counter,
+/// marked while paused.
+pub fn draw_timer(
+    elapsed_ms: f64,
+    paused: bool,
+    current_page: usize,
+    total: usize,
+    frame: &mut Frame,
+    area: Rect,
+    theme: &Theme,
+) {
+    let clock = crate::timer::format_clock(elapsed_ms);
+    let text = if paused {
+        format!("⏸ {clock} ")
+    } else {
+        format!("{clock} ")
+    };
+    // Same width as the counter drawn by `draw_status_bar_with_options`
+    let counter_width = format!("[{}/{}] ", current_page + 1, total).len() as u16;
+    let [timer_area, _] = Layout::horizontal([
+        Constraint::Length(text.width() as u16),
+        Constraint::Length(counter_width),
+    ])
+    .flex(Flex::End)
+    .areas(area);
+    let style = ratatui::style::Style::default()
+        .bg(theme.status_bg)
+        .fg(theme.status_fg);
+    frame.render_widget(Paragraph::new(text).style(style), timer_area);
+}
+
+/// Widest a section title gets in an `agenda: labels` footer.
+const AGENDA_LABEL_WIDTH: usize = 16;
+
+/// Draw section progress in the middle of the status bar: a dot or the
+/// title of each section (slides with an H1), sections passed in the status
+/// color and the current one highlighted. Labels that don't fit fall back to
+/// dots, and dots that don't fit to nothing. Presenter-only slides count only
+/// when `presenter` is set.
+pub fn draw_agenda(
+    slides: &[Slide],
+    current: usize,
+    presenter: bool,
+    agenda: Agenda,
+    frame: &mut Frame,
+    area: Rect,
+    theme: &Theme,
+) {
+    let sections: Vec<(usize, &Slide)> = slides
+        .iter()
+        .enumerate()
+        .filter(|(_, slide)| (presenter || !slide.presenter_only) && slide.starts_section())
+        .collect();
+    let current_section = sections.iter().rposition(|(i, _)| *i <= current);
+    let style = ratatui::style::Style::default()
+        .bg(theme.status_bg)
+        .fg(theme.status_fg);
+    let section_style = |k: usize| match current_section {
+        Some(c) if k == c => style
+            .fg(theme.h1)
+            .add_modifier(ratatui::style::Modifier::BOLD),
+        Some(c) if k < c => style,
+        _ => style.add_modifier(ratatui::style::Modifier::DIM),
+    };
+
+    let labels = |dots: bool| -> Vec<Span> {
+        let mut spans = vec![Span::styled(" ", style)];
+        for (k, (i, slide)) in sections.iter().enumerate() {
+            if k > 0 {
+                spans.push(Span::styled(if dots { " " } else { " · " }, style));
+            }
+            let text = if dots {
+                let passed = current_section.is_some_and(|c| k <= c);
+                (if passed { "●" } else { "○" }).to_string()
+            } else {
+                let title = slide
+                    .title
+                    .clone()
+                    .unwrap_or_else(|| format!("Slide {}", i + 1));
+                truncate_label(&title, AGENDA_LABEL_WIDTH)
+            };
+            spans.push(Span::styled(text, section_style(k)));
+        }
+        spans.push(Span::styled(" ", style));
+        spans
+    };
+    // Leave the sides to the hints, timer and page counter
+    let room = (area.width as usize).saturating_sub(32);
+    let fits = |spans: &[Span]| spans.iter().map(Span::width).sum::<usize>() <= room;
+    let spans = match agenda {
+        Agenda::Labels if fits(&labels(false)) => labels(false),
+        _ => labels(true),
+    };
+    if sections.is_empty() || !fits(&spans) {
+        return;
+    }
+    let line = ratatui::text::Line::from(spans);
+    let [agenda_area] = Layout::horizontal([Constraint::Length(line.width() as u16)])
+        .flex(Flex::Center)
+        .areas(area);
+    frame.render_widget(Paragraph::new(line), agenda_area);
+}
+
+/// `text` cut to `width` columns, ending in `…` when cut.
+fn truncate_label(text: &str, width: usize) -> String {
+    if text.width() <= width {
+        return text.to_string();
+    }
+    let mut out = String::new();
+    for c in text.chars() {
+        if out.width() + c.width().unwrap_or(0) >= width {
+            break;
+        }
+        out.push(c);
+    }
+    out.push('…');
+    out
+}
+
+/// Draw a break countdown in a box over the middle of the slide.
+pub fn draw_break(remaining_ms: f64, frame: &mut Frame, area: Rect, theme: &Theme) {
+    let style = ratatui::style::Style::default()
+        .fg(theme.fg)
+        .bg(theme.surface);
+    let [box_area] = Layout::horizontal([Constraint::Length(24)])
+        .flex(Flex::Center)
+        .areas(area);
+    let [box_area] = Layout::vertical([Constraint::Length(5)])
+        .flex(Flex::Center)
+        .areas(box_area);
+    let block = Block::bordered()
+        .title(" Break ")
+        .title_alignment(Alignment::Center)
+        .border_style(style.fg(theme.h1))
+        .style(style);
+    let lines = vec![
+        ratatui::text::Line::styled(
+            crate::timer::format_clock(remaining_ms),
+            style
+                .fg(theme.h1)
+                .add_modifier(ratatui::style::Modifier::BOLD),
+        ),
+        ratatui::text::Line::styled("back soon", style),
+    ];
+    frame.render_widget(Clear, box_area);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .alignment(Alignment::Center),
+        box_area,
+    );
+}
+
+/// Kiosk splash before playback: the deck title and a countdown, centered
+/// on a cleared screen.
+pub fn draw_kiosk_countdown(
+    title: Option<&str>,
+    remaining_ms: f64,
+    frame: &mut Frame,
+    area: Rect,
+    theme: &Theme,
+) {
+    let style = ratatui::style::Style::default().fg(theme.fg).bg(theme.bg);
+    let secs = (remaining_ms / 1000.0).ceil() as u64;
+    let unit = if secs == 1 { "second" } else { "seconds" };
+    let lines = vec![
+        ratatui::text::Line::styled(
+            title.unwrap_or_default().to_string(),
+            style
+                .fg(theme.h1)
+                .add_modifier(ratatui::style::Modifier::BOLD),
+        ),
+        ratatui::text::Line::default(),
+        ratatui::text::Line::styled(format!("starting in {secs} {unit}"), style),
+    ];
+    let [text_area] = Layout::vertical([Constraint::Length(lines.len() as u16)])
+        .flex(Flex::Center)
+        .areas(area);
+    frame.render_widget(Clear, area);
+    fill_background(frame, area, theme.bg);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .style(style)
+            .alignment(Alignment::Center),
+        text_area,
+    );
+}
+
+/// Notice drawn instead of the slide while the terminal is smaller than
+/// `need` (columns, rows), centered on a cleared screen.
+pub fn draw_too_small(need: (u16, u16), frame: &mut Frame, area: Rect, theme: &Theme) {
+    let style = ratatui::style::Style::default().fg(theme.fg).bg(theme.bg);
+    let need = format!("(need {}×{})", need.0, need.1);
+    let text = format!("terminal too small {need}");
+    // On two lines when it doesn't fit on one
+    let lines = if text.width() <= area.width as usize {
+        vec![ratatui::text::Line::from(text)]
+    } else {
+        vec!["terminal too small".into(), need.into()]
+    };
+    let [text_area] = Layout::vertical([Constraint::Length(lines.len() as u16)])
+        .flex(Flex::Center)
+        .areas(area);
+    frame.render_widget(Clear, area);
+    fill_background(frame, area, theme.bg);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .style(style)
+            .alignment(Alignment::Center),
+        text_area,
+    );
+}
+
+/// Draw a bordered box in place of an image that failed to load, naming
+/// the path and the error.
+pub fn draw_image_error(frame: &mut Frame, placement: &ImagePlacement, error: &str, theme: &Theme) {
+    let area = Rect::new(placement.x, placement.y, placement.width, placement.height)
+        .intersection(frame.area());
+    let style = ratatui::style::Style::default().fg(theme.fg);
+    let block = Block::bordered()
+        .title(" image unavailable ")
+        .border_style(style.fg(theme.h2));
+    let lines = vec![
+        ratatui::text::Line::styled(placement.path.clone(), style.fg(theme.link)),
+        ratatui::text::Line::styled(error.to_string(), style),
+    ];
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true }),
+        area,
+    );
+}
+
+/// How long a first `q` waits for the second one when quitting needs confirmation.
+pub const QUIT_CONFIRM_MS: f64 = 3000.0;
+
+/// Ask for a second `q` in the status bar, over the key hints.
+pub fn draw_quit_prompt(frame: &mut Frame, area: Rect, theme: &Theme) {
+    let style = ratatui::style::Style::default()
+        .bg(theme.status_bg)
+        .fg(theme.status_fg);
+    frame.render_widget(Paragraph::new(" Press q again to quit").style(style), area);
+}
+
+/// How long a notice stays in the status bar.
+pub const NOTICE_MS: f64 = 3000.0;
+
+/// Show a short notice in the status bar, over the key hints.
+pub fn draw_notice(text: &str, frame: &mut Frame, area: Rect, theme: &Theme) {
+    let style = ratatui::style::Style::default()
+        .bg(theme.status_bg)
+        .fg(theme.status_fg);
+    frame.render_widget(Paragraph::new(format!(" {text}")).style(style), area);
+}
+
+/// Offer to resume a talk from where it was left off, in the status bar
+/// over the key hints.
+pub fn draw_resume_prompt(
+    page: usize,
+    total: usize,
+    elapsed_ms: f64,
+    frame: &mut Frame,
+    area: Rect,
+    theme: &Theme,
+) {
+    let style = ratatui::style::Style::default()
+        .bg(theme.status_bg)
+        .fg(theme.status_fg);
+    let text = format!(
+        " Resume at slide {}/{} ({})? y/n",
+        page + 1,
+        total,
+        crate::timer::format_clock(elapsed_ms)
+    );
+    frame.render_widget(Paragraph::new(text).style(style), area);
+}
+
+/// Ask for a click in the status bar, over the key hints, while an embedded
+/// deck doesn't have keyboard focus.
+pub fn draw_focus_prompt(frame: &mut Frame, area: Rect, theme: &Theme) {
+    let style = ratatui::style::Style::default()
+        .bg(theme.status_bg)
+        .fg(theme.status_fg);
+    frame.render_widget(Paragraph::new(" Click to control").style(style), area);
+}
+
 /// Draw header items at the top-right of the area, overlaying the content.
 /// Items are displayed horizontally, separated by " │ ".
 /// Items with a URL are rendered in the theme's link color.
@@ -410,7 +1413,265 @@ pub fn wrapped_line_height(line: &ratatui::text::Line<'_>, width: u16) -> u16 {
     }
     let w = width as usize;
     let total_width = line.width();
-    ((total_width + w - 1) / w).max(1) as u16
+    total_width.div_ceil(w).max(1) as u16
+}
+
+/// Slide content re-wrapped for one width (see [`rewrap_bg_lines`]) with its
+/// total height in rows.
+#[derive(Debug)]
+pub struct WrappedContent {
+    content: Text<'static>,
+    index_map: Vec<usize>,
+    height: usize,
+}
+
+/// Most layouts a [`LayoutCache`] keeps; the least recently used go first.
+const LAYOUT_CACHE_SIZE: usize = 64;
+
+/// Slide content wrapped by page, pause step, column and width, kept by a
+/// player so redraws during transitions, scrolling and resizes don't re-wrap
+/// every line. Call [`forget`](Self::forget) after editing a slide in place
+/// and [`clear`](Self::clear) after re-parsing the deck.
+#[derive(Debug, Default)]
+pub struct LayoutCache(Mutex<LayoutMap>);
+
+/// `(page, step, column, width)`
+type LayoutKey = (usize, usize, usize, u16);
+
+#[derive(Debug, Default)]
+struct LayoutMap {
+    /// Layouts by key, with when each was last used.
+    entries: HashMap<LayoutKey, (Arc<WrappedContent>, u64)>,
+    uses: u64,
+}
+
+impl LayoutCache {
+    /// Where the layouts of slide `page` shown at pause `step` are kept;
+    /// `usize::MAX` for the whole slide, as drawn without [`Slide::at_step`].
+    pub fn slot(&self, page: usize, step: usize) -> LayoutSlot<'_> {
+        LayoutSlot {
+            cache: Some(self),
+            page,
+            step,
+        }
+    }
+
+    /// Forget the layouts of `page`, after its content changed in place.
+    pub fn forget(&self, page: usize) {
+        self.map().entries.retain(|key, _| key.0 != page);
+    }
+
+    /// Forget every layout, after the slides were re-parsed.
+    pub fn clear(&self) {
+        self.map().entries.clear();
+    }
+
+    fn map(&self) -> std::sync::MutexGuard<'_, LayoutMap> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// A slide's place in a [`LayoutCache`], passed to the draw functions along
+/// with the slide.
+#[derive(Clone, Copy, Debug)]
+pub struct LayoutSlot<'a> {
+    cache: Option<&'a LayoutCache>,
+    page: usize,
+    step: usize,
+}
+
+impl LayoutSlot<'_> {
+    /// No cache: the slide is wrapped again on every call, for one-off renders.
+    pub const NONE: LayoutSlot<'static> = LayoutSlot {
+        cache: None,
+        page: 0,
+        step: 0,
+    };
+
+    /// The next slide, drawn whole beside this one by the peek and the
+    /// presenter view.
+    fn following(self) -> Self {
+        Self {
+            page: self.page + 1,
+            step: usize::MAX,
+            ..self
+        }
+    }
+}
+
+/// Column `column` of `slide` (0 = `content`, 1 = `right_content`) wrapped to `width`.
+fn wrapped_column(
+    slide: &Slide,
+    slot: LayoutSlot,
+    column: usize,
+    width: u16,
+) -> Arc<WrappedContent> {
+    let empty = Text::default();
+    let content = match column {
+        0 => &slide.content,
+        _ => slide.right_content.as_ref().unwrap_or(&empty),
+    };
+    // Every step from the last pause on shows the whole slide
+    let step = slot.step.min(slide.fragments.len());
+    let key = (slot.page, step, column, width);
+    if let Some(cache) = slot.cache {
+        let mut map = cache.map();
+        map.uses += 1;
+        let uses = map.uses;
+        if let Some((wrapped, used)) = map.entries.get_mut(&key) {
+            *used = uses;
+            return Arc::clone(wrapped);
+        }
+    }
+    // Tables too wide for the column are narrowed rather than wrapped, and
+    // block letters are scaled to it
+    let fitted;
+    let too_wide = slide.tables.iter().any(|t| t.width() > width as usize);
+    let content = if column == 0 && (too_wide || !slide.big_texts.is_empty()) {
+        let mut text = content.clone();
+        for table in &slide.tables {
+            let lines = table.lines(width as usize);
+            let end = (table.line_index + lines.len()).min(text.lines.len());
+            text.lines.splice(table.line_index.min(end)..end, lines);
+        }
+        for big_text in &slide.big_texts {
+            let lines = big_text.lines(width as usize);
+            let end = (big_text.line_index + lines.len()).min(text.lines.len());
+            text.lines.splice(big_text.line_index.min(end)..end, lines);
+        }
+        fitted = text;
+        &fitted
+    } else {
+        content
+    };
+    let mut gutters = vec![0; content.lines.len()];
+    for gutter in slide.code_gutters.iter().filter(|g| g.column == column) {
+        let end = (gutter.line_index + gutter.height).min(gutters.len());
+        for width in &mut gutters[gutter.line_index.min(end)..end] {
+            *width = gutter.width;
+        }
+    }
+    let (content, index_map) = rewrap_bg_lines(content, &gutters, width);
+    let height = wrapped_content_height(&content, width);
+    let wrapped = Arc::new(WrappedContent {
+        content,
+        index_map,
+        height,
+    });
+    if let Some(cache) = slot.cache {
+        let mut map = cache.map();
+        if map.entries.len() >= LAYOUT_CACHE_SIZE {
+            let oldest = map.entries.iter().min_by_key(|(_, (_, used))| *used);
+            if let Some(&oldest) = oldest.map(|(key, _)| key) {
+                map.entries.remove(&oldest);
+            }
+        }
+        let uses = map.uses;
+        map.entries.insert(key, (Arc::clone(&wrapped), uses));
+    }
+    wrapped
+}
+
+/// Stand-ins for the symbols ratride and its fonts draw with, for
+/// `ascii_only` decks. Each is one column wide, like the symbol it replaces.
+const ASCII_GLYPHS: &[(char, char)] = &[
+    ('\u{a0}', ' '),
+    ('─', '-'),
+    ('━', '-'),
+    ('═', '='),
+    ('│', '|'),
+    ('┃', '|'),
+    ('║', '|'),
+    ('┌', '+'),
+    ('┬', '+'),
+    ('┐', '+'),
+    ('├', '+'),
+    ('┼', '+'),
+    ('┤', '+'),
+    ('└', '+'),
+    ('┴', '+'),
+    ('┘', '+'),
+    ('╭', '+'),
+    ('╮', '+'),
+    ('╰', '+'),
+    ('╯', '+'),
+    ('╔', '+'),
+    ('╗', '+'),
+    ('╚', '+'),
+    ('╝', '+'),
+    ('╠', '+'),
+    ('╣', '+'),
+    ('╦', '+'),
+    ('╩', '+'),
+    ('╬', '+'),
+    ('▌', '|'),
+    ('▐', '|'),
+    ('•', '*'),
+    ('●', '*'),
+    ('○', 'o'),
+    ('·', '.'),
+    ('▶', '>'),
+    ('▲', '^'),
+    ('▼', 'v'),
+    ('→', '>'),
+    ('←', '<'),
+    ('…', '.'),
+    ('█', '#'),
+    ('▀', '#'),
+    ('▄', '#'),
+    ('░', '.'),
+    ('“', '"'),
+    ('”', '"'),
+    ('‘', '\''),
+    ('’', '\''),
+    ('–', '-'),
+    ('—', '-'),
+    ('ℹ', 'i'),
+    ('✓', '+'),
+    ('✱', '*'),
+    ('⚠', '!'),
+    ('✗', 'x'),
+    ('⏸', '='),
+];
+
+/// Redraw the symbols in `buf` with [`ascii_text`], for `ascii_only` decks,
+/// leaving the cells of `images` alone.
+pub fn to_ascii(buf: &mut Buffer, images: &[ImagePlacement]) {
+    let area = buf.area;
+    for y in area.top()..area.bottom() {
+        for x in area.left()..area.right() {
+            let in_image = images.iter().any(|p| {
+                (p.x..p.x.saturating_add(p.width)).contains(&x)
+                    && (p.y..p.y.saturating_add(p.height)).contains(&y)
+            });
+            let cell = &mut buf[(x, y)];
+            if !in_image && !cell.symbol().is_ascii() {
+                let ascii = ascii_text(cell.symbol());
+                cell.set_symbol(&ascii);
+            }
+        }
+    }
+}
+
+/// `text` with each symbol swapped for its [`ASCII_GLYPHS`] stand-in, or
+/// for as many `?` as the columns it takes when it has none.
+pub fn ascii_text(text: &str) -> String {
+    let mut ascii = String::with_capacity(text.len());
+    for c in text.chars() {
+        match ascii_glyph(c) {
+            Some(glyph) => ascii.push(glyph),
+            None if c.is_ascii() => ascii.push(c),
+            None => ascii.extend(std::iter::repeat_n('?', c.width().unwrap_or(0))),
+        }
+    }
+    ascii
+}
+
+fn ascii_glyph(c: char) -> Option<char> {
+    ASCII_GLYPHS
+        .iter()
+        .find(|(from, _)| *from == c)
+        .map(|&(_, to)| to)
 }
 
 /// Total visual rows occupied by `content` after wrapping to `width` columns.
@@ -460,15 +1721,21 @@ fn fill_line_backgrounds(content: &Text<'_>, scroll: u16, frame: &mut Frame, are
 }
 
 /// Re-wrap code block lines (those with bg) so that each visual row keeps
-/// the 2-NBSP left padding. Returns (new content, index map from old→new line index).
-fn rewrap_bg_lines(content: &Text<'_>, max_width: u16) -> (Text<'static>, Vec<usize>) {
+/// the 2-NBSP left padding, and the `gutters[i]` characters of line `i`'s
+/// line-number column after it. Returns (new content, index map from
+/// old→new line index).
+fn rewrap_bg_lines(
+    content: &Text<'_>,
+    gutters: &[usize],
+    max_width: u16,
+) -> (Text<'static>, Vec<usize>) {
     let max = max_width as usize;
     let padding = "\u{00a0}\u{00a0}";
     let padding_width = 2usize;
     let mut new_lines: Vec<ratatui::text::Line<'static>> = Vec::new();
     let mut index_map: Vec<usize> = Vec::new();
 
-    for line in &content.lines {
+    for (i, line) in content.lines.iter().enumerate() {
         index_map.push(new_lines.len());
 
         if line.style.bg.is_none() || line.width() <= max {
@@ -500,6 +1767,20 @@ fn rewrap_bg_lines(content: &Text<'_>, max_width: u16) -> (Text<'static>, Vec<us
         };
         let code_chars = &styled_chars[skip..];
         let padding_style = ratatui::style::Style::default().bg(bg);
+        // Line numbers start the first row; the rows wrapped from it get a
+        // blank column of the same width
+        let gutter = gutters.get(i).copied().unwrap_or(0);
+        let (gutter, code_chars) = code_chars.split_at(gutter.min(code_chars.len()));
+        let blank_gutter: Vec<(char, ratatui::style::Style)> = gutter
+            .iter()
+            .map(|&(c, style)| (if c.is_ascii_digit() { '\u{00a0}' } else { c }, style))
+            .collect();
+        let effective_width = effective_width.saturating_sub(gutter.len()).max(1);
+        let sub_line = |chunk: &[(char, ratatui::style::Style)], first: bool| {
+            let prefix = if first { gutter } else { &blank_gutter };
+            let chars = [prefix, chunk].concat();
+            build_sub_line(&chars, padding, padding_style, line.style)
+        };
 
         let mut pos = 0;
         let mut width_acc = 0;
@@ -508,12 +1789,7 @@ fn rewrap_bg_lines(content: &Text<'_>, max_width: u16) -> (Text<'static>, Vec<us
         while pos < code_chars.len() {
             let ch_width = code_chars[pos].0.width().unwrap_or(0);
             if width_acc + ch_width > effective_width && width_acc > 0 {
-                new_lines.push(build_sub_line(
-                    &code_chars[chunk_start..pos],
-                    padding,
-                    padding_style,
-                    line.style,
-                ));
+                new_lines.push(sub_line(&code_chars[chunk_start..pos], chunk_start == 0));
                 chunk_start = pos;
                 width_acc = 0;
             }
@@ -522,12 +1798,7 @@ fn rewrap_bg_lines(content: &Text<'_>, max_width: u16) -> (Text<'static>, Vec<us
         }
 
         if chunk_start < code_chars.len() {
-            new_lines.push(build_sub_line(
-                &code_chars[chunk_start..],
-                padding,
-                padding_style,
-                line.style,
-            ));
+            new_lines.push(sub_line(&code_chars[chunk_start..], chunk_start == 0));
         }
     }
 
@@ -573,6 +1844,7 @@ fn build_sub_line(
 /// Compute image placement rect within a content area, accounting for scroll.
 /// When `center` is true and pixel dimensions are available, the image is
 /// horizontally centered based on its aspect ratio.
+#[allow(clippy::too_many_arguments)]
 fn compute_image_placement(
     content_area: Rect,
     y_offset: usize,
@@ -583,6 +1855,7 @@ fn compute_image_placement(
     pixel_width: u32,
     pixel_height: u32,
     max_width_percent: Option<f64>,
+    cell_aspect: f64,
 ) -> Option<ImagePlacement> {
     let y_start = y_offset as i32 - scroll as i32;
     let y_end = y_start + height as i32;
@@ -607,10 +1880,8 @@ fn compute_image_placement(
 
     let (x, w) = if center && pixel_width > 0 && pixel_height > 0 {
         // Estimate display width in cells from aspect ratio.
-        // Terminal cells are typically ~2x taller than wide in pixels.
-        let cell_aspect = 2.0_f64;
         let display_w =
-            ((height as f64) * (pixel_width as f64) / (pixel_height as f64) * cell_aspect) as u16;
+            ((height as f64) * (pixel_width as f64) / (pixel_height as f64) / cell_aspect) as u16;
         let display_w = display_w.min(max_w);
         let x_offset = (content_area.width.saturating_sub(display_w)) / 2;
         (content_area.x + x_offset, display_w)
@@ -634,3 +1905,119 @@ fn compute_image_placement(
         full_height: height,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layouts_are_kept_by_page_step_and_width() {
+        let md = "a\n\n<!-- pause -->\n\nb\n";
+        let (slides, _) = crate::markdown::parse_slides(
+            md,
+            &Theme::default(),
+            &crate::markdown::Frontmatter::default(),
+            None,
+            false,
+        );
+        let slide = &slides[0];
+        let cache = LayoutCache::default();
+        let first = wrapped_column(slide, cache.slot(0, 0), 0, 40);
+        let again = wrapped_column(slide, cache.slot(0, 0), 0, 40);
+        assert!(Arc::ptr_eq(&first, &again));
+        for other in [
+            wrapped_column(slide, cache.slot(0, 0), 0, 30),
+            wrapped_column(slide, cache.slot(1, 0), 0, 40),
+            wrapped_column(slide, LayoutSlot::NONE, 0, 40),
+        ] {
+            assert!(!Arc::ptr_eq(&first, &other));
+        }
+        // Past the last pause the whole slide shows, as it does unstepped
+        let whole = wrapped_column(slide, cache.slot(0, 1), 0, 40);
+        let unstepped = wrapped_column(slide, cache.slot(0, usize::MAX), 0, 40);
+        assert!(Arc::ptr_eq(&whole, &unstepped));
+
+        cache.forget(0);
+        let forgotten = wrapped_column(slide, cache.slot(0, 0), 0, 40);
+        assert!(!Arc::ptr_eq(&first, &forgotten));
+
+        // The least recently used layout makes room for new ones
+        for width in 1..LAYOUT_CACHE_SIZE as u16 {
+            wrapped_column(slide, cache.slot(2, 0), 0, width);
+        }
+        let kept = wrapped_column(slide, cache.slot(0, 0), 0, 40);
+        assert!(Arc::ptr_eq(&forgotten, &kept));
+        assert_eq!(cache.map().entries.len(), LAYOUT_CACHE_SIZE);
+        assert!(!cache.map().entries.contains_key(&(1, 0, 0, 40)));
+    }
+
+    #[test]
+    fn ascii_only_decks_draw_nothing_but_ascii() {
+        let md = "---\nascii_only: true\nmath: unicode\n\
+            agenda: dots\n---\n# “Intro” -- $\\alpha^2$\n\n- point\n\n> quote\n\n\
+            | a | b |\n|---|---|\n| 1 | 2 |\n\n```\n╔═╗ 漢\n```\n\n---\n\n# Next\n\n\
+            ```notes\nsay “hi”\n```\n";
+        let (fm, body) = crate::markdown::parse_frontmatter(md);
+        let (slides, _) = crate::markdown::parse_slides(body, &Theme::default(), &fm, None, false);
+        assert!(slides.iter().all(|slide| slide.ascii_only));
+        let mut terminal =
+            ratatui::Terminal::new(ratatui::backend::TestBackend::new(80, 30)).unwrap();
+        let image = ImagePlacement {
+            x: 70,
+            y: 0,
+            width: 2,
+            height: 1,
+            path: "a.png".to_string(),
+            clip_top: false,
+            full_height: 1,
+        };
+        let frame = terminal
+            .draw(|frame| {
+                let [outline, main, notes] = Layout::horizontal([
+                    Constraint::Length(20),
+                    Constraint::Min(0),
+                    Constraint::Length(20),
+                ])
+                .areas(frame.area());
+                let [main, status] =
+                    Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(main);
+                let theme = &slides[0].theme;
+                draw_outline(&slides, 0, true, frame, outline, theme);
+                draw_slide(&slides[0], LayoutSlot::NONE, 0, frame, main);
+                draw_notes_margin(&slides[1], frame, notes, theme);
+                draw_agenda(&slides, 0, false, Agenda::Dots, frame, status, theme);
+                frame.buffer_mut()[(70, 0)].set_symbol("▀");
+                to_ascii(frame.buffer_mut(), std::slice::from_ref(&image));
+            })
+            .unwrap();
+        let buffer = frame.buffer;
+        let text: String = buffer.content.iter().map(|cell| cell.symbol()).collect();
+        // The image's cells are the images backends', not ours
+        assert_eq!(buffer[(70, 0)].symbol(), "▀");
+        assert!(text.replacen('▀', "", 1).is_ascii(), "{text}");
+        assert!(text.contains("\"Intro\"") && text.contains("\\alpha^2"));
+        assert!(text.contains("+=+ ??"));
+    }
+
+    #[test]
+    fn only_numbered_code_repeats_its_gutter_on_wrapped_rows() {
+        let md = "```rust {numbers}\nlet long = 1234567890;\n```\n\n```\n12 │ 1234567890\n```\n";
+        let (slides, _) = crate::markdown::parse_slides(
+            md,
+            &Theme::default(),
+            &crate::markdown::Frontmatter::default(),
+            None,
+            false,
+        );
+        let wrapped = wrapped_column(&slides[0], LayoutSlot::NONE, 0, 16);
+        let rows: Vec<String> = (wrapped.content.lines.iter())
+            .map(|line| line.to_string().replace('\u{a0}', " "))
+            .collect();
+        assert_eq!(
+            rows[1..4],
+            ["  1 │ let long", "    │  = 12345", "    │ 67890;"]
+        );
+        // Code that only looks numbered wraps like any other
+        assert_eq!(rows[7..9], ["  12 │ 1234567", "  890"]);
+    }
+}
@@ -9,29 +9,38 @@ use std::time::{Duration, Instant};
 const RELOAD_SCRIPT: &str = r#"<script>(function(){var v="";setInterval(function(){fetch("/__v").then(function(r){return r.text()}).then(function(t){if(v&&t!==v)location.reload();v=t}).catch(function(){})},1000)})()</script>"#;
 
 /// Start a dev server with live reload for exported slides.
-pub fn serve(file: &str, out_dir: &str, theme: Option<&str>, port: u16) -> io::Result<()> {
+pub fn serve(files: &[String], out_dir: &str, theme: Option<&str>, port: u16) -> io::Result<()> {
     // Initial export
-    crate::export::export(file, out_dir, theme)?;
+    crate::export::export(files, out_dir, theme, None, &[])?;
 
     let out_path = fs::canonicalize(out_dir)?;
     let version = Arc::new(AtomicU64::new(1));
 
     // --- file watcher ---
-    let source_path = fs::canonicalize(file)?;
-    let watch_dir = source_path
-        .parent()
-        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no parent directory"))?
-        .to_path_buf();
+    let mut watch_dirs: Vec<std::path::PathBuf> = Vec::new();
+    for file in files {
+        let source_path = fs::canonicalize(file)?;
+        let watch_dir = source_path
+            .parent()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no parent directory"))?
+            .to_path_buf();
+        if !watch_dirs.iter().any(|d| watch_dir.starts_with(d)) {
+            watch_dirs.retain(|d| !d.starts_with(&watch_dir));
+            watch_dirs.push(watch_dir);
+        }
+    }
 
     let (tx, rx) = std::sync::mpsc::channel();
     let mut watcher = notify::recommended_watcher(tx)
-        .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
-    watcher
-        .watch(&watch_dir, notify::RecursiveMode::Recursive)
-        .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+        .map_err(io::Error::other)?;
+    for watch_dir in &watch_dirs {
+        watcher
+            .watch(watch_dir, notify::RecursiveMode::Recursive)
+            .map_err(io::Error::other)?;
+    }
 
     let version_w = Arc::clone(&version);
-    let file_w = file.to_string();
+    let files_w = files.to_vec();
     let out_dir_w = out_dir.to_string();
     let theme_w = theme.map(|s| s.to_string());
     let out_path_w = out_path.clone();
@@ -54,7 +63,9 @@ pub fn serve(file: &str, out_dir: &str, theme: Option<&str>, port: u16) -> io::R
                     continue;
                 }
                 last_reload = Instant::now();
-                if let Err(e) = crate::export::export(&file_w, &out_dir_w, theme_w.as_deref()) {
+                if let Err(e) =
+                    crate::export::export(&files_w, &out_dir_w, theme_w.as_deref(), None, &[])
+                {
                     eprintln!("export error: {}", e);
                     continue;
                 }
@@ -0,0 +1,334 @@
+//! Broadcast the deck to remote terminals over raw TCP / telnet.
+//!
+//! Viewers connect with `telnet host port` (or `nc`) and see the slide the
+//! host is currently presenting, rendered at their own terminal size when
+//! their client reports it via telnet NAWS. `ratride follow` is a built-in
+//! client for terminals without telnet.
+
+use std::io::{self, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::markdown::Slide;
+use crate::print;
+
+const IAC: u8 = 255;
+const SB: u8 = 250;
+const SE: u8 = 240;
+const NAWS: u8 = 31;
+/// IAC WILL ECHO, IAC WILL SUPPRESS-GO-AHEAD, IAC DO NAWS: stop the client
+/// echoing keystrokes and ask it to report its window size.
+const TELNET_SETUP: &[u8] = &[IAC, 251, 1, IAC, 251, 3, IAC, 253, NAWS];
+/// Size assumed for clients that never report one.
+const DEFAULT_SIZE: (u16, u16) = (80, 24);
+/// Largest size a viewer is rendered at, whatever it reports, so no viewer
+/// can make the presenter draw billions of cells.
+const MAX_SIZE: (u16, u16) = (500, 200);
+/// How often viewer threads re-check for a resize or disconnect.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+/// How long `follow` keeps trying to reach a broadcast that is starting up.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Handle to a running broadcast; the host calls `set_page` as it navigates.
+pub struct Broadcast {
+    /// Current page and the fragments of it revealed so far.
+    page: Arc<(Mutex<(usize, usize)>, Condvar)>,
+    slides: Arc<Vec<Slide>>,
+    /// End of the presenter's break countdown, shown to viewers instead of the slide.
+    break_until: Arc<Mutex<Option<Instant>>>,
+}
+
+impl Broadcast {
+    /// Serve `slides` to every viewer that connects to `listener`.
+    pub fn start(slides: Vec<Slide>, listener: TcpListener) -> Self {
+        // Viewers never see a presenter-only slide, even the first
+        let first = slides.iter().position(|s| !s.presenter_only).unwrap_or(0);
+        let page = Arc::new((Mutex::new((first, 0)), Condvar::new()));
+        let slides = Arc::new(slides);
+
+        let break_until = Arc::new(Mutex::new(None));
+
+        let page_l = Arc::clone(&page);
+        let slides_l = Arc::clone(&slides);
+        let break_l = Arc::clone(&break_until);
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let slides = Arc::clone(&slides_l);
+                let page = Arc::clone(&page_l);
+                let break_until = Arc::clone(&break_l);
+                std::thread::spawn(move || {
+                    let _ = serve_viewer(stream, &slides, &page, &break_until);
+                });
+            }
+        });
+
+        Self {
+            page,
+            slides,
+            break_until,
+        }
+    }
+
+    /// Show viewers a countdown to `until` instead of the slide, or go back
+    /// to the slide with `None`.
+    pub fn set_break(&self, until: Option<Instant>) {
+        *self.break_until.lock().unwrap() = until;
+        self.page.1.notify_all();
+    }
+
+    /// Show `page` with `step` fragments revealed to viewers; on
+    /// presenter-only slides they keep the last slide they saw.
+    pub fn set_page(&self, page: usize, step: usize) {
+        if self
+            .slides
+            .get(page)
+            .is_some_and(|slide| slide.presenter_only)
+        {
+            return;
+        }
+        let (lock, cvar) = &*self.page;
+        *lock.lock().unwrap() = (page, step);
+        cvar.notify_all();
+    }
+}
+
+/// Stream slides to one viewer until it disconnects.
+fn serve_viewer(
+    mut stream: TcpStream,
+    slides: &[Slide],
+    page: &(Mutex<(usize, usize)>, Condvar),
+    break_until: &Mutex<Option<Instant>>,
+) -> io::Result<()> {
+    stream.write_all(TELNET_SETUP)?;
+
+    let size = Arc::new(Mutex::new(DEFAULT_SIZE));
+    let closed = Arc::new(AtomicBool::new(false));
+    {
+        let mut reader = stream.try_clone()?;
+        let size = Arc::clone(&size);
+        let closed = Arc::clone(&closed);
+        std::thread::spawn(move || {
+            let mut buf = [0u8; 256];
+            loop {
+                match reader.read(&mut buf) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => {
+                        if let Some(s) = parse_naws(&buf[..n]) {
+                            *size.lock().unwrap() = s;
+                        }
+                    }
+                }
+            }
+            closed.store(true, Ordering::Relaxed);
+        });
+    }
+
+    let (lock, cvar) = page;
+    let mut shown = None;
+    while !closed.load(Ordering::Relaxed) {
+        let current = {
+            let guard = lock.lock().unwrap();
+            let (guard, _) = cvar.wait_timeout(guard, POLL_INTERVAL).unwrap();
+            *guard
+        };
+        let current_size = *size.lock().unwrap();
+        // Whole seconds left in the break, so the countdown redraws once a second
+        let break_secs = break_until
+            .lock()
+            .unwrap()
+            .and_then(|until| until.checked_duration_since(Instant::now()))
+            .map(|left| left.as_secs());
+        if shown != Some((current, current_size, break_secs)) {
+            shown = Some((current, current_size, break_secs));
+            let screen = match break_secs {
+                Some(secs) => render_break_screen(secs, current_size),
+                None => match slides.get(current.0) {
+                    Some(slide) => {
+                        let slide = slide.at_step(current.1);
+                        render_screen(&slide, current.0, slides.len(), current_size)
+                    }
+                    None => continue,
+                },
+            };
+            stream.write_all(screen.as_bytes())?;
+        }
+    }
+    Ok(())
+}
+
+/// Show the broadcast at `addr` (`host:port`) in this terminal until the
+/// presenter quits, reporting the terminal's size as it changes.
+pub fn follow(addr: &str) -> io::Result<()> {
+    let started = Instant::now();
+    let mut stream = loop {
+        match TcpStream::connect(addr) {
+            Ok(stream) => break stream,
+            Err(e) if started.elapsed() >= CONNECT_TIMEOUT => return Err(e),
+            Err(_) => std::thread::sleep(POLL_INTERVAL),
+        }
+    };
+
+    let closed = Arc::new(AtomicBool::new(false));
+    {
+        let mut writer = stream.try_clone()?;
+        let closed = Arc::clone(&closed);
+        std::thread::spawn(move || {
+            let mut sent = None;
+            while !closed.load(Ordering::Relaxed) {
+                let size = crossterm::terminal::size().unwrap_or(DEFAULT_SIZE);
+                if sent != Some(size) {
+                    sent = Some(size);
+                    if writer.write_all(&naws(size)).is_err() {
+                        break;
+                    }
+                }
+                std::thread::sleep(POLL_INTERVAL);
+            }
+        });
+    }
+
+    let mut out = io::stdout().lock();
+    let mut buf = [0u8; 8192];
+    // Bytes left of a telnet command split across reads
+    let mut skip = 0;
+    loop {
+        let n = match stream.read(&mut buf) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => n,
+        };
+        let mut screen = Vec::with_capacity(n);
+        for &byte in &buf[..n] {
+            if skip > 0 {
+                skip -= 1;
+            } else if byte == IAC {
+                // The host only sends three-byte option commands
+                skip = 2;
+            } else {
+                screen.push(byte);
+            }
+        }
+        out.write_all(&screen)?;
+        out.flush()?;
+    }
+    closed.store(true, Ordering::Relaxed);
+    out.write_all(b"\x1b[0m\x1b[?25h\x1b[H\x1b[2J")?;
+    out.flush()
+}
+
+/// A NAWS subnegotiation reporting a `(width, height)` window.
+fn naws((width, height): (u16, u16)) -> Vec<u8> {
+    let mut bytes = vec![IAC, SB, NAWS];
+    for byte in [width.to_be_bytes(), height.to_be_bytes()].concat() {
+        bytes.push(byte);
+        // A literal 255 is doubled so it doesn't read as IAC
+        if byte == IAC {
+            bytes.push(IAC);
+        }
+    }
+    bytes.extend([IAC, SE]);
+    bytes
+}
+
+/// Find the last NAWS subnegotiation (`IAC SB NAWS w w h h`) and return
+/// `(width, height)`, at most [`MAX_SIZE`]. A 255 among the size bytes
+/// arrives doubled.
+fn parse_naws(bytes: &[u8]) -> Option<(u16, u16)> {
+    let mut found = None;
+    let mut i = 0;
+    while i < bytes.len() {
+        if !bytes[i..].starts_with(&[IAC, SB, NAWS]) {
+            i += 1;
+            continue;
+        }
+        i += 3;
+        let mut size = Vec::with_capacity(4);
+        while size.len() < 4 && i < bytes.len() {
+            if bytes[i] == IAC {
+                // Anything but a second IAC ends the subnegotiation early
+                if bytes.get(i + 1) != Some(&IAC) {
+                    break;
+                }
+                i += 1;
+            }
+            size.push(bytes[i]);
+            i += 1;
+        }
+        if let [w1, w2, h1, h2] = size[..] {
+            let width = u16::from_be_bytes([w1, w2]);
+            let height = u16::from_be_bytes([h1, h2]);
+            if width > 0 && height > 0 {
+                found = Some((width.min(MAX_SIZE.0), height.min(MAX_SIZE.1)));
+            }
+        }
+    }
+    found
+}
+
+/// A full screen for a viewer during a break: the time left, centered.
+fn render_break_screen(secs: u64, (width, height): (u16, u16)) -> String {
+    let text = format!(
+        "Break - back in {}",
+        crate::timer::format_clock(secs as f64 * 1000.0)
+    );
+    let row = height / 2 + 1;
+    let col = (width as usize).saturating_sub(text.len()) / 2 + 1;
+    format!("\x1b[?25l\x1b[H\x1b[2J\x1b[{row};{col}H\x1b[1m{text}\x1b[0m")
+}
+
+/// A full screen for a viewer: the slide clipped to the window and a page indicator.
+fn render_screen(slide: &Slide, page: usize, total: usize, (width, height): (u16, u16)) -> String {
+    let rows = height.saturating_sub(1) as usize;
+    let mut out = String::from("\x1b[?25l\x1b[H\x1b[2J");
+    for line in print::render_slide(slide, width, height.saturating_sub(1))
+        .lines()
+        .take(rows)
+    {
+        out.push_str(line);
+        out.push_str("\r\n");
+    }
+    let indicator = format!("[{}/{}]", page + 1, total);
+    let col = (width as usize).saturating_sub(indicator.len()) + 1;
+    out.push_str(&format!("\x1b[{height};{col}H\x1b[2m{indicator}\x1b[0m"));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn naws_sizes_with_doubled_iac_round_trip() {
+        for size in [(80, 24), (255, 50), MAX_SIZE] {
+            assert_eq!(parse_naws(&naws(size)), Some(size));
+        }
+        // Other bytes around it, the later report winning
+        let mut bytes = b"hi".to_vec();
+        bytes.extend(naws((100, 30)));
+        bytes.extend([IAC, 251, 1]);
+        bytes.extend(naws((255, 100)));
+        assert_eq!(parse_naws(&bytes), Some((255, 100)));
+        // An undoubled IAC is the end of the subnegotiation, not a size byte
+        assert_eq!(parse_naws(&[IAC, SB, NAWS, 0, IAC, SE, 24]), None);
+        assert_eq!(parse_naws(&[IAC, SB, NAWS, 0, 80]), None);
+    }
+
+    #[test]
+    fn oversized_naws_reports_are_clamped() {
+        assert_eq!(parse_naws(&naws((0xffff, 0xffff))), Some(MAX_SIZE));
+        assert_eq!(parse_naws(&naws((511, 24))), Some((500, 24)));
+        assert_eq!(parse_naws(&naws((80, 0xff00))), Some((80, 200)));
+        // A viewer asking for the largest size gets a slide that size
+        let (slides, _) = crate::markdown::parse_slides(
+            "# Hi\n",
+            &crate::theme::Theme::default(),
+            &crate::markdown::Frontmatter::default(),
+            None,
+            false,
+        );
+        let size = parse_naws(&naws((0xffff, 0xffff))).unwrap();
+        let screen = render_screen(&slides[0], 0, 1, size);
+        assert!(screen.contains("Hi") && screen.contains("\x1b[200;496H"));
+    }
+}
@@ -0,0 +1,115 @@
+//! Recording a talk's navigation for replay. With `--record-input FILE`,
+//! every action the presenter takes is appended to a JSON Lines log with
+//! its time; `--replay FILE` takes the same actions again at those times.
+
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+/// One line of an input log.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct InputEvent {
+    /// Time since presenting started.
+    pub time_ms: u64,
+    #[serde(flatten)]
+    pub input: Input,
+}
+
+/// Something the presenter did.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Input {
+    /// An action, by its [`Action::name`](crate::action::Action::name).
+    Action(String),
+    /// A jump to a slide (0-based), as external controllers ask for.
+    Goto(usize),
+}
+
+/// An input log being written while presenting.
+pub struct InputLog {
+    file: File,
+    started: Instant,
+}
+
+impl InputLog {
+    /// Start a log at `path`, replacing an earlier one.
+    pub fn create(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(path)?;
+        Ok(Self {
+            file,
+            started: Instant::now(),
+        })
+    }
+
+    /// Note that `input` happened now.
+    pub fn record(&mut self, input: Input) -> io::Result<()> {
+        let event = InputEvent {
+            time_ms: self.started.elapsed().as_millis() as u64,
+            input,
+        };
+        let line = serde_json::to_string(&event).map_err(io::Error::other)?;
+        writeln!(self.file, "{line}")
+    }
+}
+
+/// Parse an input log; errors name the offending line.
+pub fn parse_log(text: &str) -> Result<Vec<InputEvent>, String> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| serde_json::from_str(line).map_err(|e| format!("line {}: {e}", i + 1)))
+        .collect()
+}
+
+/// An input log being played back.
+pub struct Replay {
+    events: VecDeque<InputEvent>,
+    started: Instant,
+}
+
+impl Replay {
+    /// Play back `events`, timed from now.
+    pub fn new(events: Vec<InputEvent>) -> Self {
+        Self {
+            events: events.into(),
+            started: Instant::now(),
+        }
+    }
+
+    /// The next input whose time has come, if any.
+    pub fn due(&mut self) -> Option<Input> {
+        let now = self.started.elapsed().as_millis() as u64;
+        if self.events.front()?.time_ms > now {
+            return None;
+        }
+        self.events.pop_front().map(|event| event.input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn input_log_lines_round_trip() {
+        let text = "{\"time_ms\":0,\"action\":\"next_slide\"}\n\n{\"time_ms\":1500,\"goto\":3}\n";
+        let events = parse_log(text).unwrap();
+        assert_eq!(events[0].input, Input::Action("next_slide".to_string()));
+        assert_eq!(events[1].input, Input::Goto(3));
+        let line = serde_json::to_string(&events[1]).unwrap();
+        assert_eq!(line, "{\"time_ms\":1500,\"goto\":3}");
+
+        let mut replay = Replay::new(events);
+        assert_eq!(replay.due(), Some(Input::Action("next_slide".to_string())));
+        assert_eq!(replay.due(), None);
+        assert!(parse_log("{\"time_ms\":0,\"dance\":1}").is_err());
+    }
+}
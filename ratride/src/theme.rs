@@ -1,20 +1,165 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+
 use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Theme {
+    #[serde(with = "hex_color")]
     pub fg: Color,
+    #[serde(with = "hex_color")]
     pub bg: Color,
+    #[serde(with = "hex_color")]
     pub h1: Color,
+    #[serde(with = "hex_color")]
     pub h2: Color,
+    #[serde(with = "hex_color")]
     pub h3: Color,
+    #[serde(with = "hex_color")]
     pub h4: Color,
+    #[serde(with = "hex_color")]
     pub inline_code_fg: Color,
+    #[serde(with = "hex_color")]
     pub surface: Color,
+    #[serde(with = "hex_color")]
     pub block_quote_prefix: Color,
+    #[serde(with = "hex_color")]
     pub list_bullet: Color,
+    #[serde(with = "hex_color")]
     pub status_fg: Color,
+    #[serde(with = "hex_color")]
     pub status_bg: Color,
+    #[serde(with = "hex_color")]
     pub link: Color,
+    /// Colors of GitHub-style alert blockquotes (`> [!NOTE]`).
+    #[serde(default)]
+    pub alerts: AlertColors,
+    /// Colors of added and removed lines in ```` ```diff ```` code blocks.
+    #[serde(default)]
+    pub diff: DiffColors,
+    /// Styles for Pandoc-style fenced divs (`::: {.warning}`), by class.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub divs: BTreeMap<String, DivStyle>,
+}
+
+/// Border and title colors of the five alert kinds, `> [!NOTE]` through
+/// `> [!CAUTION]`. A theme file without an `[alerts]` table uses Mocha's
+/// blue, green, mauve, yellow and red.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AlertColors {
+    #[serde(with = "hex_color")]
+    pub note: Color,
+    #[serde(with = "hex_color")]
+    pub tip: Color,
+    #[serde(with = "hex_color")]
+    pub important: Color,
+    #[serde(with = "hex_color")]
+    pub warning: Color,
+    #[serde(with = "hex_color")]
+    pub caution: Color,
+}
+
+impl AlertColors {
+    fn from_hex(note: &str, tip: &str, important: &str, warning: &str, caution: &str) -> Self {
+        Self {
+            note: hex(note),
+            tip: hex(tip),
+            important: hex(important),
+            warning: hex(warning),
+            caution: hex(caution),
+        }
+    }
+}
+
+impl Default for AlertColors {
+    fn default() -> Self {
+        Theme::catppuccin_mocha().alerts
+    }
+}
+
+/// Colors of the `+` and `-` lines of diff code blocks: their marker, a
+/// tint of their background, and their text when the block names no
+/// language. A theme file without a `[diff]` table uses Mocha's green and
+/// red.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DiffColors {
+    #[serde(with = "hex_color")]
+    pub added: Color,
+    #[serde(with = "hex_color")]
+    pub removed: Color,
+}
+
+impl DiffColors {
+    fn from_hex(added: &str, removed: &str) -> Self {
+        Self {
+            added: hex(added),
+            removed: hex(removed),
+        }
+    }
+}
+
+impl Default for DiffColors {
+    fn default() -> Self {
+        Theme::catppuccin_mocha().diff
+    }
+}
+
+/// How a fenced div of some class is drawn; unset parts fall back to a
+/// plain border in the block quote color.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct DivStyle {
+    #[serde(
+        default,
+        with = "opt_hex_color",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub bg: Option<Color>,
+    #[serde(
+        default,
+        with = "opt_hex_color",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub border: Option<Color>,
+    /// Shown before the div's first line.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+}
+
+/// (De)serialize colors as `#rrggbb` strings; named colors are accepted too.
+mod hex_color {
+    use ratatui::style::Color;
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+    pub fn serialize<S: Serializer>(color: &Color, s: S) -> Result<S::Ok, S::Error> {
+        match color {
+            Color::Rgb(r, g, b) => s.serialize_str(&format!("#{r:02x}{g:02x}{b:02x}")),
+            other => s.serialize_str(&other.to_string()),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Color, D::Error> {
+        let s = String::deserialize(d)?;
+        s.parse()
+            .map_err(|_| D::Error::custom(format!("invalid color `{s}`")))
+    }
+}
+
+mod opt_hex_color {
+    use ratatui::style::Color;
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(color: &Option<Color>, s: S) -> Result<S::Ok, S::Error> {
+        match color {
+            Some(color) => super::hex_color::serialize(color, s),
+            None => s.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Option<Color>, D::Error> {
+        super::hex_color::deserialize(d).map(Some)
+    }
 }
 
 fn hex(s: &str) -> Color {
@@ -40,7 +185,11 @@ impl Theme {
             status_fg: hex("cdd6f4"),
             status_bg: hex("313244"),
             link: hex("89b4fa"),
+            alerts: AlertColors::from_hex("89b4fa", "a6e3a1", "cba6f7", "f9e2af", "f38ba8"),
+            diff: DiffColors::from_hex("a6e3a1", "f38ba8"),
+            divs: BTreeMap::new(),
         }
+        .with_default_divs()
     }
 
     pub fn catppuccin_macchiato() -> Self {
@@ -58,7 +207,11 @@ impl Theme {
             status_fg: hex("cad3f5"),
             status_bg: hex("363a4f"),
             link: hex("8aadf4"),
+            alerts: AlertColors::from_hex("8aadf4", "a6da95", "c6a0f6", "eed49f", "ed8796"),
+            diff: DiffColors::from_hex("a6da95", "ed8796"),
+            divs: BTreeMap::new(),
         }
+        .with_default_divs()
     }
 
     pub fn catppuccin_frappe() -> Self {
@@ -76,7 +229,11 @@ impl Theme {
             status_fg: hex("c6d0f5"),
             status_bg: hex("414559"),
             link: hex("8caaee"),
+            alerts: AlertColors::from_hex("8caaee", "a6d189", "ca9ee6", "e5c890", "e78284"),
+            diff: DiffColors::from_hex("a6d189", "e78284"),
+            divs: BTreeMap::new(),
         }
+        .with_default_divs()
     }
 
     pub fn catppuccin_latte() -> Self {
@@ -94,7 +251,67 @@ impl Theme {
             status_fg: hex("4c4f69"),
             status_bg: hex("ccd0da"),
             link: hex("1e66f5"),
+            alerts: AlertColors::from_hex("1e66f5", "40a02b", "8839ef", "df8e1d", "d20f39"),
+            diff: DiffColors::from_hex("40a02b", "d20f39"),
+            divs: BTreeMap::new(),
         }
+        .with_default_divs()
+    }
+}
+
+impl Theme {
+    /// Pure black, white and yellow for washed-out projectors.
+    pub fn high_contrast() -> Self {
+        Self {
+            fg: hex("ffffff"),
+            bg: hex("000000"),
+            h1: hex("ffff00"),
+            h2: hex("ffff00"),
+            h3: hex("ffffff"),
+            h4: hex("ffffff"),
+            inline_code_fg: hex("ffff00"),
+            surface: hex("000000"),
+            block_quote_prefix: hex("ffff00"),
+            list_bullet: hex("ffff00"),
+            status_fg: hex("000000"),
+            status_bg: hex("ffff00"),
+            link: hex("ffff00"),
+            alerts: AlertColors::from_hex("00ffff", "00ff00", "ff00ff", "ffff00", "ff0000"),
+            diff: DiffColors::from_hex("00ff00", "ff0000"),
+            divs: BTreeMap::new(),
+        }
+        .with_default_divs()
+    }
+}
+
+impl Theme {
+    /// Fenced div styles for the usual callout classes (`note`, `tip`,
+    /// `warning`, `danger`) in this theme's colors.
+    fn with_default_divs(mut self) -> Self {
+        let callouts = [
+            ("note", self.link, "ℹ"),
+            ("tip", self.inline_code_fg, "✓"),
+            ("warning", self.block_quote_prefix, "⚠"),
+            ("danger", self.h4, "✗"),
+        ];
+        for (class, color, icon) in callouts {
+            let style = DivStyle {
+                bg: Some(self.surface),
+                border: Some(color),
+                icon: Some(icon.to_string()),
+            };
+            self.divs.insert(class.to_string(), style);
+        }
+        self
+    }
+
+    /// Style for a div with `classes`: the first one the theme styles.
+    pub fn div_style(&self, classes: &[String]) -> DivStyle {
+        classes
+            .iter()
+            .find_map(|class| self.divs.get(class))
+            .cloned()
+            .unwrap_or_default()
     }
 }
 
@@ -105,6 +322,28 @@ impl Default for Theme {
 }
 
 impl Theme {
+    /// Serialize the theme as TOML, e.g. as a starting point for a custom theme.
+    pub fn to_toml(&self) -> String {
+        toml::to_string(self).expect("theme serializes to TOML")
+    }
+
+    pub fn from_toml(s: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(s)
+    }
+
+    /// Read a theme file, e.g. one written by `ratride theme dump`.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| io::Error::new(e.kind(), format!("{}: {e}", path.display())))?;
+        Self::from_toml(&text).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {e}", path.display()),
+            )
+        })
+    }
+
+    #[cfg(feature = "syntect")]
     pub fn syntect_theme(&self) -> syntect::highlighting::Theme {
         let bytes: &[u8] = match (self.bg, self.fg) {
             // Match by bg color to identify which Catppuccin flavor
@@ -128,6 +367,15 @@ impl Theme {
     }
 }
 
+/// Names of the built-in themes, in the order they are listed and cycled.
+pub const THEME_NAMES: &[&str] = &["mocha", "macchiato", "frappe", "latte", "high-contrast"];
+
+/// Whether a `--theme` or frontmatter `theme:` value names a TOML theme
+/// file rather than a built-in theme.
+pub fn is_theme_file(name: &str) -> bool {
+    name.trim().ends_with(".toml")
+}
+
 /// Resolve a theme name to a Theme.
 /// Accepts both "catppuccin-mocha" and "mocha" forms.
 pub fn theme_from_name(name: &str) -> Option<Theme> {
@@ -140,7 +388,41 @@ pub fn theme_from_name(name: &str) -> Option<Theme> {
         "macchiato" => Some(Theme::catppuccin_macchiato()),
         "frappe" | "frappé" => Some(Theme::catppuccin_frappe()),
         "latte" => Some(Theme::catppuccin_latte()),
+        "high-contrast" => Some(Theme::high_contrast()),
         _ => None,
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn theme_toml_round_trips() {
+        let theme = Theme::catppuccin_latte();
+        let toml = theme.to_toml();
+        assert!(toml.contains("bg = \"#eff1f5\""));
+        let parsed = Theme::from_toml(&toml).unwrap();
+        assert_eq!(parsed.bg, theme.bg);
+        assert_eq!(parsed.link, theme.link);
+        assert_eq!(parsed.divs, theme.divs);
+        assert_eq!(parsed.alerts, theme.alerts);
+        assert_eq!(parsed.diff, theme.diff);
+        assert!(Theme::from_toml("fg = \"nope\"").is_err());
+    }
+
+    #[test]
+    fn theme_files_load_what_dump_wrote() {
+        assert!(is_theme_file("brand.toml"));
+        assert!(!is_theme_file("latte"));
+        let path = std::env::temp_dir().join(format!("ratride-theme-{}.toml", std::process::id()));
+        std::fs::write(&path, Theme::high_contrast().to_toml()).unwrap();
+        assert_eq!(Theme::load(&path).unwrap(), Theme::high_contrast());
+        std::fs::write(&path, "fg = \"nope\"").unwrap();
+        let err = Theme::load(&path).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains(&path.display().to_string()));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
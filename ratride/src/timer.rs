@@ -0,0 +1,105 @@
+//! Presenter stopwatch and break countdown.
+//!
+//! Times are milliseconds on whatever clock the frontend uses (time since
+//! startup in the terminal), passed in as `now`.
+
+/// Default length of a break started with `B`, in minutes.
+pub const DEFAULT_BREAK_MINUTES: f64 = 10.0;
+
+/// Elapsed talk time that can be paused and reset, plus an optional break countdown.
+#[derive(Clone, Debug, Default)]
+pub struct Timer {
+    /// Time counted before the current run.
+    banked_ms: f64,
+    /// When the current run started; `None` while paused.
+    running_since: Option<f64>,
+    /// When the break countdown ends.
+    break_until: Option<f64>,
+}
+
+impl Timer {
+    /// A timer that starts counting at `now`.
+    pub fn start(now: f64) -> Self {
+        Self {
+            running_since: Some(now),
+            ..Self::default()
+        }
+    }
+
+    /// A timer picking up at `elapsed_ms`, running from `now` unless `paused`.
+    pub fn resume(now: f64, elapsed_ms: f64, paused: bool) -> Self {
+        Self {
+            banked_ms: elapsed_ms,
+            running_since: (!paused).then_some(now),
+            ..Self::default()
+        }
+    }
+
+    pub fn elapsed_ms(&self, now: f64) -> f64 {
+        self.banked_ms + self.running_since.map_or(0.0, |since| now - since)
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.running_since.is_none()
+    }
+
+    pub fn toggle_pause(&mut self, now: f64) {
+        match self.running_since.take() {
+            Some(since) => self.banked_ms += now - since,
+            None => self.running_since = Some(now),
+        }
+    }
+
+    /// Set the elapsed time back to zero, keeping it paused or running.
+    pub fn reset(&mut self, now: f64) {
+        self.banked_ms = 0.0;
+        if self.running_since.is_some() {
+            self.running_since = Some(now);
+        }
+    }
+
+    /// Start a break of `minutes`, or end the one in progress.
+    pub fn toggle_break(&mut self, now: f64, minutes: f64) {
+        self.break_until = match self.break_remaining_ms(now) {
+            Some(_) => None,
+            None => Some(now + minutes * 60_000.0),
+        };
+    }
+
+    /// Time left in the current break, `None` when there is none.
+    pub fn break_remaining_ms(&self, now: f64) -> Option<f64> {
+        self.break_until
+            .map(|until| until - now)
+            .filter(|remaining| *remaining > 0.0)
+    }
+}
+
+/// `m:ss`, or `h:mm:ss` from an hour on.
+pub fn format_clock(ms: f64) -> String {
+    let secs = (ms.max(0.0) / 1000.0) as u64;
+    let (h, m, s) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if h > 0 {
+        format!("{h}:{m:02}:{s:02}")
+    } else {
+        format!("{m}:{s:02}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pausing_stops_the_clock() {
+        let mut timer = Timer::start(0.0);
+        timer.toggle_pause(1_000.0);
+        assert!(timer.is_paused());
+        assert_eq!(timer.elapsed_ms(5_000.0), 1_000.0);
+        timer.toggle_pause(5_000.0);
+        assert_eq!(timer.elapsed_ms(6_000.0), 2_000.0);
+        timer.reset(6_000.0);
+        assert_eq!(timer.elapsed_ms(7_000.0), 1_000.0);
+        assert_eq!(format_clock(3_725_000.0), "1:02:05");
+        assert_eq!(format_clock(65_000.0), "1:05");
+    }
+}
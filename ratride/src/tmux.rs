@@ -0,0 +1,94 @@
+//! Presenting in two tmux panes: the slides for the audience on one side
+//! and the presenter view on the other, kept in step by a `serve-term`
+//! broadcast on localhost.
+
+use std::io;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::process::Command;
+
+use crate::remote;
+
+/// Server socket (`tmux -L`), so the session stays apart from the user's own.
+const SOCKET: &str = "ratride";
+
+/// Open `deck` in a new tmux session and attach to it; returns once the
+/// session ends, which quitting the presenter pane does. `args` are passed
+/// on to the presenter's `serve-term`, which opens the audience pane with
+/// [`open_viewer`] once it is listening on a port of its choosing.
+pub fn present(exe: &Path, deck: &str, args: &[String]) -> io::Result<()> {
+    let deck = if remote::is_url(deck) {
+        deck.to_string()
+    } else {
+        Path::new(deck)
+            .canonicalize()?
+            .to_string_lossy()
+            .into_owned()
+    };
+    let session = format!("ratride-{}", std::process::id());
+    let exe = quote(&exe.to_string_lossy());
+
+    let mut presenter = format!(
+        "{exe} serve-term {} --port 0 --tmux-viewer --presenter",
+        quote(&deck)
+    );
+    for arg in args {
+        presenter.push(' ');
+        presenter.push_str(&quote(arg));
+    }
+    presenter.push_str(&format!("; tmux -L {SOCKET} kill-session -t {session}"));
+
+    tmux(&["new-session", "-d", "-s", &session, &presenter])?;
+    // Let the presenter's `y` reach the clipboard with OSC 52
+    tmux(&["set-option", "-s", "set-clipboard", "on"])?;
+    tmux(&["attach-session", "-t", &session])
+}
+
+/// From the presenter's pane, open the audience pane to its left following
+/// the broadcast at `addr`, leaving the presenter's pane focused.
+pub fn open_viewer(exe: &Path, addr: SocketAddr) -> io::Result<()> {
+    let pane = std::env::var("TMUX_PANE")
+        .map_err(|_| io::Error::other("--tmux-viewer only works inside tmux"))?;
+    let viewer = format!("{} follow {addr}", quote(&exe.to_string_lossy()));
+    tmux(&["split-window", "-d", "-h", "-b", "-t", &pane, &viewer])
+}
+
+/// Run a tmux command on ratride's server; works from inside tmux too.
+fn tmux(args: &[&str]) -> io::Result<()> {
+    let status = Command::new("tmux")
+        .arg("-L")
+        .arg(SOCKET)
+        .args(args)
+        .env_remove("TMUX")
+        .status()
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => io::Error::new(e.kind(), "--tmux needs tmux installed"),
+            _ => e,
+        })?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!(
+            "tmux {} failed ({status})",
+            args[0]
+        )))
+    }
+}
+
+/// Quote `s` for the shell tmux runs pane commands with.
+fn quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quotes_escape_single_quotes() {
+        assert_eq!(quote("slides.md"), "'slides.md'");
+        assert_eq!(quote("my talk/deck.md"), "'my talk/deck.md'");
+        assert_eq!(quote("it's $HOME"), r"'it'\''s $HOME'");
+        assert_eq!(quote(""), "''");
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use ratatui::buffer::Buffer;
 use ratatui::style::Color;
 use tachyonfx::{Effect, Interpolation, Motion, fx};
@@ -5,7 +7,97 @@ use tachyonfx::{Effect, Interpolation, Motion, fx};
 use crate::color::{anim_color, blend_color, hue_to_rgb};
 use crate::markdown::{SlideDirection, TransitionKind};
 
+/// Everything a transition needs to build its effect for the incoming slide.
+pub struct TransitionContext {
+    /// Background color of the incoming slide's theme.
+    pub bg: Color,
+    /// Last frame of the outgoing slide, if one was drawn.
+    pub prev_buf: Option<Buffer>,
+    /// Direction for directional transitions such as `slide`.
+    pub direction: SlideDirection,
+    /// Height of the slide area in rows.
+    pub rows: u16,
+    /// Number of content lines on the incoming slide.
+    pub content_lines: usize,
+    /// Duration of a single line's animation for the `lines*` transitions.
+    pub line_dur_ms: f32,
+    /// Delay between consecutive lines for the `lines*` transitions.
+    pub stagger_ms: f32,
+}
+
+/// A named slide transition. Implemented for any `Fn(&TransitionContext) -> Effect`.
+pub trait Transition {
+    fn effect(&self, ctx: &TransitionContext) -> Effect;
+}
+
+impl<F: Fn(&TransitionContext) -> Effect> Transition for F {
+    fn effect(&self, ctx: &TransitionContext) -> Effect {
+        self(ctx)
+    }
+}
+
+/// Transitions by name, as used in `<!-- transition: name -->`.
+///
+/// `Default` registers the built-in transitions; embedders can `register`
+/// their own under new names or replace a built-in. Each player keeps one
+/// for as long as it runs, and a deck's own names are checked against
+/// [`names`](Self::names) through `Frontmatter::transitions`.
+pub struct TransitionRegistry {
+    transitions: HashMap<String, Box<dyn Transition>>,
+}
+
+impl TransitionRegistry {
+    /// An empty registry with no transitions.
+    pub fn empty() -> Self {
+        Self {
+            transitions: HashMap::new(),
+        }
+    }
+
+    pub fn register(&mut self, name: impl Into<String>, transition: impl Transition + 'static) {
+        self.transitions.insert(name.into(), Box::new(transition));
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn Transition> {
+        self.transitions.get(name).map(|t| t.as_ref())
+    }
+
+    /// Every registered name, sorted.
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.transitions.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Build the effect for `kind`. Names missing from the registry fall back
+    /// to `slide`, matching how unknown directive values have always behaved.
+    pub fn create(&self, kind: &TransitionKind, ctx: &TransitionContext) -> Option<Effect> {
+        let name = kind.name()?;
+        let transition = self.get(name).or_else(|| self.get("slide"))?;
+        Some(transition.effect(ctx))
+    }
+}
+
+impl Default for TransitionRegistry {
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry.register("slide", slide);
+        registry.register("fade", fade);
+        registry.register("dissolve", dissolve);
+        registry.register("coalesce", coalesce);
+        registry.register("sweep-in", sweep_in);
+        registry.register("lines", lines);
+        registry.register("lines-cross", lines_cross);
+        registry.register("lines-rgb", lines_rgb);
+        registry.register("slide-rgb", slide_rgb);
+        registry
+    }
+}
+
+/// Build the effect for `kind` from the transitions in `registry`.
+#[allow(clippy::too_many_arguments)]
 pub fn create_transition(
+    registry: &TransitionRegistry,
     kind: &TransitionKind,
     bg: Color,
     prev_buf: Option<Buffer>,
@@ -14,304 +106,401 @@ pub fn create_transition(
     line_dur_ms: f32,
     stagger_ms: f32,
 ) -> Option<Effect> {
-    Some(match kind {
-        TransitionKind::None => return None,
-        TransitionKind::Slide(dir) => {
-            let prev = prev_buf.clone();
-            let horizontal = matches!(dir, SlideDirection::Right | SlideDirection::Left);
-            let positive = matches!(dir, SlideDirection::Right | SlideDirection::Down);
-            fx::effect_fn_buf(
-                (),
-                (400, Interpolation::QuadOut),
-                move |_state, ctx, buf| {
-                    let alpha = ctx.alpha();
-                    let area = ctx.area;
-                    let width = area.width;
-                    let height = area.height;
-
-                    if horizontal {
-                        let shift = ((1.0 - alpha) * width as f32) as u16;
-                        for y in area.y..area.y + height {
-                            let original: Vec<_> = (area.x..area.x + width)
-                                .map(|x| buf[(x, y)].clone())
-                                .collect();
-                            for x in area.x..area.x + width {
-                                let col = x - area.x;
-                                let cell = &mut buf[(x, y)];
-                                let src = if positive {
-                                    if col + shift < width { Some((col + shift) as usize) } else { None }
-                                } else {
-                                    if col >= shift { Some((col - shift) as usize) } else { None }
-                                };
-                                if let Some(s) = src {
-                                    *cell = original[s].clone();
-                                } else if let Some(old) =
-                                    prev.as_ref().and_then(|pb| pb.cell((x, y)))
-                                {
-                                    *cell = old.clone();
-                                } else {
-                                    cell.reset();
-                                }
-                            }
+    let direction = match kind {
+        TransitionKind::Slide(dir) => dir.clone(),
+        _ => SlideDirection::default(),
+    };
+    let ctx = TransitionContext {
+        bg,
+        prev_buf,
+        direction,
+        rows,
+        content_lines,
+        line_dur_ms,
+        stagger_ms,
+    };
+    registry.create(kind, &ctx)
+}
+
+fn slide(ctx: &TransitionContext) -> Effect {
+    let prev = ctx.prev_buf.clone();
+    let horizontal = matches!(ctx.direction, SlideDirection::Right | SlideDirection::Left);
+    let positive = matches!(ctx.direction, SlideDirection::Right | SlideDirection::Down);
+    fx::effect_fn_buf(
+        (),
+        (400, Interpolation::QuadOut),
+        move |_state, ctx, buf| {
+            let alpha = ctx.alpha();
+            let area = ctx.area;
+            let width = area.width;
+            let height = area.height;
+
+            if horizontal {
+                let shift = ((1.0 - alpha) * width as f32) as u16;
+                for y in area.y..area.y + height {
+                    let original: Vec<_> = (area.x..area.x + width)
+                        .map(|x| buf[(x, y)].clone())
+                        .collect();
+                    for x in area.x..area.x + width {
+                        let col = x - area.x;
+                        let cell = &mut buf[(x, y)];
+                        let src = if positive {
+                            if col + shift < width { Some((col + shift) as usize) } else { None }
+                        } else {
+                            if col >= shift { Some((col - shift) as usize) } else { None }
+                        };
+                        if let Some(s) = src {
+                            *cell = original[s].clone();
+                        } else if let Some(old) =
+                            prev.as_ref().and_then(|pb| pb.cell((x, y)))
+                        {
+                            *cell = old.clone();
+                        } else {
+                            cell.reset();
                         }
+                    }
+                }
+            } else {
+                let shift = ((1.0 - alpha) * height as f32) as u16;
+                let original: Vec<Vec<_>> = (area.y..area.y + height)
+                    .map(|y| {
+                        (area.x..area.x + width)
+                            .map(|x| buf[(x, y)].clone())
+                            .collect()
+                    })
+                    .collect();
+                for y in area.y..area.y + height {
+                    let row = y - area.y;
+                    let src_row = if positive {
+                        if row + shift < height { Some(row + shift) } else { None }
                     } else {
-                        let shift = ((1.0 - alpha) * height as f32) as u16;
-                        let original: Vec<Vec<_>> = (area.y..area.y + height)
-                            .map(|y| {
-                                (area.x..area.x + width)
-                                    .map(|x| buf[(x, y)].clone())
-                                    .collect()
-                            })
-                            .collect();
-                        for y in area.y..area.y + height {
-                            let row = y - area.y;
-                            let src_row = if positive {
-                                if row + shift < height { Some(row + shift) } else { None }
-                            } else {
-                                if row >= shift { Some(row - shift) } else { None }
-                            };
-                            for x in area.x..area.x + width {
-                                let col = (x - area.x) as usize;
-                                let cell = &mut buf[(x, y)];
-                                if let Some(sr) = src_row {
-                                    *cell = original[sr as usize][col].clone();
-                                } else if let Some(old) =
-                                    prev.as_ref().and_then(|pb| pb.cell((x, y)))
-                                {
-                                    *cell = old.clone();
-                                } else {
-                                    cell.reset();
-                                }
-                            }
+                        if row >= shift { Some(row - shift) } else { None }
+                    };
+                    for x in area.x..area.x + width {
+                        let col = (x - area.x) as usize;
+                        let cell = &mut buf[(x, y)];
+                        if let Some(sr) = src_row {
+                            *cell = original[sr as usize][col].clone();
+                        } else if let Some(old) =
+                            prev.as_ref().and_then(|pb| pb.cell((x, y)))
+                        {
+                            *cell = old.clone();
+                        } else {
+                            cell.reset();
                         }
                     }
-                },
-            )
-        }
-        TransitionKind::Fade => fx::fade_from_fg(bg, (600, Interpolation::SineOut)),
-        TransitionKind::Dissolve => fx::dissolve((500, Interpolation::Linear)).reversed(),
-        TransitionKind::Coalesce => fx::coalesce((500, Interpolation::QuadOut)),
-        TransitionKind::SweepIn => fx::sweep_in(
-            Motion::LeftToRight,
-            15,
-            0,
-            bg,
-            (600, Interpolation::QuadOut),
-        ),
-        TransitionKind::Lines => {
-            let prev = prev_buf.clone();
-            let approx_lines = rows as f32;
-            let duration_ms = line_dur_ms + stagger_ms * (approx_lines - 1.0).max(0.0);
-            fx::effect_fn_buf(
-                (),
-                (duration_ms as u32, Interpolation::Linear),
-                move |_state, ctx, buf| {
-                    let elapsed = ctx.alpha() * duration_ms;
-                    let area = ctx.area;
-                    let width = area.width;
-
-                    for y in area.y..area.y + area.height {
-                        let line_index = (y - area.y) as f32;
-                        let line_start = line_index * stagger_ms;
-                        let local_alpha = ((elapsed - line_start) / line_dur_ms).clamp(0.0, 1.0);
-                        let local_alpha = Interpolation::QuadOut.alpha(local_alpha);
-                        let shift = ((1.0 - local_alpha) * width as f32) as u16;
-
-                        let original: Vec<_> = (area.x..area.x + width)
-                            .map(|x| buf[(x, y)].clone())
-                            .collect();
-
-                        for x in area.x..area.x + width {
-                            let col = x - area.x;
-                            let src_col = col + shift;
-                            let cell = &mut buf[(x, y)];
-                            if src_col < width {
-                                *cell = original[src_col as usize].clone();
-                            } else {
-                                let d = (src_col - width) as f32;
-                                let fade = (d * 2.0 / width as f32).clamp(0.0, 1.0);
-                                if fade > 0.0 {
-                                    if let Some(old) = prev.as_ref().and_then(|pb| pb.cell((x, y)))
-                                    {
-                                        cell.set_char(old.symbol().chars().next().unwrap_or(' '));
-                                        cell.set_fg(blend_color(bg, old.fg, fade));
-                                        cell.set_bg(blend_color(bg, old.bg, fade));
-                                    }
-                                } else {
-                                    cell.reset();
-                                }
+                }
+            }
+        },
+    )
+}
+
+fn fade(ctx: &TransitionContext) -> Effect {
+    fx::fade_from_fg(ctx.bg, (600, Interpolation::SineOut))
+}
+
+fn dissolve(_ctx: &TransitionContext) -> Effect {
+    fx::dissolve((500, Interpolation::Linear)).reversed()
+}
+
+fn coalesce(_ctx: &TransitionContext) -> Effect {
+    fx::coalesce((500, Interpolation::QuadOut))
+}
+
+fn sweep_in(ctx: &TransitionContext) -> Effect {
+    fx::sweep_in(
+        Motion::LeftToRight,
+        15,
+        0,
+        ctx.bg,
+        (600, Interpolation::QuadOut),
+    )
+}
+
+fn lines(ctx: &TransitionContext) -> Effect {
+    let bg = ctx.bg;
+    let (line_dur_ms, stagger_ms) = (ctx.line_dur_ms, ctx.stagger_ms);
+    let prev = ctx.prev_buf.clone();
+    let approx_lines = ctx.rows as f32;
+    let duration_ms = line_dur_ms + stagger_ms * (approx_lines - 1.0).max(0.0);
+    fx::effect_fn_buf(
+        (),
+        (duration_ms as u32, Interpolation::Linear),
+        move |_state, ctx, buf| {
+            let elapsed = ctx.alpha() * duration_ms;
+            let area = ctx.area;
+            let width = area.width;
+
+            for y in area.y..area.y + area.height {
+                let line_index = (y - area.y) as f32;
+                let line_start = line_index * stagger_ms;
+                let local_alpha = ((elapsed - line_start) / line_dur_ms).clamp(0.0, 1.0);
+                let local_alpha = Interpolation::QuadOut.alpha(local_alpha);
+                let shift = ((1.0 - local_alpha) * width as f32) as u16;
+
+                let original: Vec<_> = (area.x..area.x + width)
+                    .map(|x| buf[(x, y)].clone())
+                    .collect();
+
+                for x in area.x..area.x + width {
+                    let col = x - area.x;
+                    let src_col = col + shift;
+                    let cell = &mut buf[(x, y)];
+                    if src_col < width {
+                        *cell = original[src_col as usize].clone();
+                    } else {
+                        let d = (src_col - width) as f32;
+                        let fade = (d * 2.0 / width as f32).clamp(0.0, 1.0);
+                        if fade > 0.0 {
+                            if let Some(old) = prev.as_ref().and_then(|pb| pb.cell((x, y)))
+                            {
+                                cell.set_char(old.symbol().chars().next().unwrap_or(' '));
+                                cell.set_fg(blend_color(bg, old.fg, fade));
+                                cell.set_bg(blend_color(bg, old.bg, fade));
                             }
+                        } else {
+                            cell.reset();
                         }
                     }
-                },
-            )
-        }
-        TransitionKind::LinesCross => {
-            let prev = prev_buf.clone();
-            let approx_lines = rows as f32;
-            let duration_ms = line_dur_ms + stagger_ms * (approx_lines - 1.0).max(0.0);
-            fx::effect_fn_buf(
-                (),
-                (duration_ms as u32, Interpolation::Linear),
-                move |_state, ctx, buf| {
-                    let elapsed = ctx.alpha() * duration_ms;
-                    let area = ctx.area;
-                    let width = area.width as f32;
-
-                    for y in area.y..area.y + area.height {
-                        let line_index = (y - area.y) as f32;
-                        let line_start = line_index * stagger_ms;
-                        let local_alpha = ((elapsed - line_start) / line_dur_ms).clamp(0.0, 1.0);
-                        let local_alpha = Interpolation::QuadOut.alpha(local_alpha);
-                        let visible_cols = (local_alpha * width) as u16;
-                        let is_odd = (y - area.y) % 2 == 1;
-
-                        for x in area.x..area.x + area.width {
-                            let col_offset = x - area.x;
-                            let should_blank = if is_odd {
-                                col_offset < area.width - visible_cols
-                            } else {
-                                col_offset >= visible_cols
-                            };
-                            if should_blank {
-                                let cell = &mut buf[(x, y)];
-                                let d = if is_odd {
-                                    (area.width - visible_cols - 1 - col_offset) as f32
-                                } else {
-                                    (col_offset - visible_cols) as f32
-                                };
-                                let fade = (d * 2.0 / area.width as f32).clamp(0.0, 1.0);
-                                if fade > 0.0 {
-                                    if let Some(old) = prev.as_ref().and_then(|pb| pb.cell((x, y)))
-                                    {
-                                        cell.set_char(old.symbol().chars().next().unwrap_or(' '));
-                                        cell.set_fg(blend_color(bg, old.fg, fade));
-                                        cell.set_bg(blend_color(bg, old.bg, fade));
-                                    }
-                                } else {
-                                    cell.reset();
-                                }
+                }
+            }
+        },
+    )
+}
+
+fn lines_cross(ctx: &TransitionContext) -> Effect {
+    let bg = ctx.bg;
+    let (line_dur_ms, stagger_ms) = (ctx.line_dur_ms, ctx.stagger_ms);
+    let prev = ctx.prev_buf.clone();
+    let approx_lines = ctx.rows as f32;
+    let duration_ms = line_dur_ms + stagger_ms * (approx_lines - 1.0).max(0.0);
+    fx::effect_fn_buf(
+        (),
+        (duration_ms as u32, Interpolation::Linear),
+        move |_state, ctx, buf| {
+            let elapsed = ctx.alpha() * duration_ms;
+            let area = ctx.area;
+            let width = area.width as f32;
+
+            for y in area.y..area.y + area.height {
+                let line_index = (y - area.y) as f32;
+                let line_start = line_index * stagger_ms;
+                let local_alpha = ((elapsed - line_start) / line_dur_ms).clamp(0.0, 1.0);
+                let local_alpha = Interpolation::QuadOut.alpha(local_alpha);
+                let visible_cols = (local_alpha * width) as u16;
+                let is_odd = (y - area.y) % 2 == 1;
+
+                for x in area.x..area.x + area.width {
+                    let col_offset = x - area.x;
+                    let should_blank = if is_odd {
+                        col_offset < area.width - visible_cols
+                    } else {
+                        col_offset >= visible_cols
+                    };
+                    if should_blank {
+                        let cell = &mut buf[(x, y)];
+                        let d = if is_odd {
+                            (area.width - visible_cols - 1 - col_offset) as f32
+                        } else {
+                            (col_offset - visible_cols) as f32
+                        };
+                        let fade = (d * 2.0 / area.width as f32).clamp(0.0, 1.0);
+                        if fade > 0.0 {
+                            if let Some(old) = prev.as_ref().and_then(|pb| pb.cell((x, y)))
+                            {
+                                cell.set_char(old.symbol().chars().next().unwrap_or(' '));
+                                cell.set_fg(blend_color(bg, old.fg, fade));
+                                cell.set_bg(blend_color(bg, old.bg, fade));
                             }
+                        } else {
+                            cell.reset();
                         }
                     }
-                },
-            )
-        }
-        TransitionKind::LinesRgb => {
-            let prev = prev_buf.clone();
-            let cl = content_lines as f32;
-            let duration_ms = line_dur_ms + stagger_ms * (cl - 1.0).max(0.0);
-            fx::effect_fn_buf(
-                (false, 0u16, 0u16),
-                (duration_ms as u32, Interpolation::Linear),
-                move |state, ctx, buf| {
-                    let area = ctx.area;
-                    let width = area.width;
-
-                    if !state.0 {
-                        state.0 = true;
-                        let mut first: u16 = area.y + area.height;
-                        let mut last: u16 = area.y;
-                        for y in area.y..area.y + area.height {
-                            for x in area.x..area.x + width {
-                                let sym = buf[(x, y)].symbol().chars().next().unwrap_or(' ');
-                                if sym != ' ' {
-                                    if y < first {
-                                        first = y;
-                                    }
-                                    if y > last {
-                                        last = y;
-                                    }
-                                    break;
-                                }
+                }
+            }
+        },
+    )
+}
+
+fn lines_rgb(ctx: &TransitionContext) -> Effect {
+    let bg = ctx.bg;
+    let (line_dur_ms, stagger_ms) = (ctx.line_dur_ms, ctx.stagger_ms);
+    let prev = ctx.prev_buf.clone();
+    let cl = ctx.content_lines as f32;
+    let duration_ms = line_dur_ms + stagger_ms * (cl - 1.0).max(0.0);
+    fx::effect_fn_buf(
+        (false, 0u16, 0u16),
+        (duration_ms as u32, Interpolation::Linear),
+        move |state, ctx, buf| {
+            let area = ctx.area;
+            let width = area.width;
+
+            if !state.0 {
+                state.0 = true;
+                let mut first: u16 = area.y + area.height;
+                let mut last: u16 = area.y;
+                for y in area.y..area.y + area.height {
+                    for x in area.x..area.x + width {
+                        let sym = buf[(x, y)].symbol().chars().next().unwrap_or(' ');
+                        if sym != ' ' {
+                            if y < first {
+                                first = y;
                             }
+                            if y > last {
+                                last = y;
+                            }
+                            break;
                         }
-                        if first > last {
-                            first = area.y;
-                            last = area.y + area.height - 1;
-                        }
-                        state.1 = first;
-                        state.2 = last;
                     }
+                }
+                if first > last {
+                    first = area.y;
+                    last = area.y + area.height - 1;
+                }
+                state.1 = first;
+                state.2 = last;
+            }
 
-                    let first_content = state.1;
-                    let last_content = state.2;
-                    let elapsed = ctx.alpha() * duration_ms;
-                    let global_fade = 1.0 - ctx.alpha();
+            let first_content = state.1;
+            let last_content = state.2;
+            let elapsed = ctx.alpha() * duration_ms;
+            let global_fade = 1.0 - ctx.alpha();
 
-                    for y in area.y..area.y + area.height {
-                        if y < first_content || y > last_content {
-                            continue;
-                        }
+            for y in area.y..area.y + area.height {
+                if y < first_content || y > last_content {
+                    continue;
+                }
 
-                        let line_index = (y - first_content) as f32;
-                        let line_start = line_index * stagger_ms;
-                        let local_alpha = ((elapsed - line_start) / line_dur_ms).clamp(0.0, 1.0);
-                        let local_alpha = Interpolation::QuadOut.alpha(local_alpha);
-                        let shift = ((1.0 - local_alpha) * width as f32) as u16;
+                let line_index = (y - first_content) as f32;
+                let line_start = line_index * stagger_ms;
+                let local_alpha = ((elapsed - line_start) / line_dur_ms).clamp(0.0, 1.0);
+                let local_alpha = Interpolation::QuadOut.alpha(local_alpha);
+                let shift = ((1.0 - local_alpha) * width as f32) as u16;
 
-                        let original: Vec<_> = (area.x..area.x + width)
-                            .map(|x| buf[(x, y)].clone())
-                            .collect();
-
-                        let color = anim_color(local_alpha);
-
-                        for x in area.x..area.x + width {
-                            let col = x - area.x;
-                            let cell = &mut buf[(x, y)];
-
-                            let (in_range, src_col) = {
-                                let sc = col + shift;
-                                if sc < width { (true, sc) } else { (false, 0) }
-                            };
-
-                            if in_range {
-                                *cell = original[src_col as usize].clone();
-                                if local_alpha < 1.0 {
-                                    cell.set_fg(color);
-                                }
-                            } else {
-                                if let Some(old) = prev.as_ref().and_then(|pb| pb.cell((x, y))) {
-                                    cell.set_char(old.symbol().chars().next().unwrap_or(' '));
-                                    cell.set_fg(blend_color(bg, old.fg, global_fade));
-                                    cell.set_bg(blend_color(bg, old.bg, global_fade));
-                                } else {
-                                    cell.reset();
-                                }
-                            }
-                        }
-                    }
-                },
-            )
-        }
-        TransitionKind::SlideRgb => {
-            let prev = prev_buf.clone();
-            let band_width = 100_u16;
-            fx::effect_fn_buf((), (800, Interpolation::Linear), move |_state, ctx, buf| {
-                let alpha = ctx.alpha();
-                let area = ctx.area;
-                let width = area.width as f32;
-                let edge_col = (alpha * (width + band_width as f32)) as u16;
+                let original: Vec<_> = (area.x..area.x + width)
+                    .map(|x| buf[(x, y)].clone())
+                    .collect();
 
-                for y in area.y..area.y + area.height {
-                    for x in area.x..area.x + area.width {
-                        let col_offset = x - area.x;
-                        if col_offset >= edge_col {
-                            let cell = &mut buf[(x, y)];
-                            if let Some(old) = prev.as_ref().and_then(|pb| pb.cell((x, y))) {
-                                *cell = old.clone();
-                            }
-                        } else if col_offset + band_width >= edge_col {
-                            let d = edge_col - col_offset;
-                            let t = d as f32 / band_width as f32;
-                            let hue = t * 300.0;
-                            let color = hue_to_rgb(hue);
-                            let cell = &mut buf[(x, y)];
+                let color = anim_color(local_alpha);
+
+                for x in area.x..area.x + width {
+                    let col = x - area.x;
+                    let cell = &mut buf[(x, y)];
+
+                    let (in_range, src_col) = {
+                        let sc = col + shift;
+                        if sc < width { (true, sc) } else { (false, 0) }
+                    };
+
+                    if in_range {
+                        *cell = original[src_col as usize].clone();
+                        if local_alpha < 1.0 {
                             cell.set_fg(color);
                         }
+                    } else {
+                        if let Some(old) = prev.as_ref().and_then(|pb| pb.cell((x, y))) {
+                            cell.set_char(old.symbol().chars().next().unwrap_or(' '));
+                            cell.set_fg(blend_color(bg, old.fg, global_fade));
+                            cell.set_bg(blend_color(bg, old.bg, global_fade));
+                        } else {
+                            cell.reset();
+                        }
+                    }
+                }
+            }
+        },
+    )
+}
+
+fn slide_rgb(ctx: &TransitionContext) -> Effect {
+    let prev = ctx.prev_buf.clone();
+    let band_width = 100_u16;
+    fx::effect_fn_buf((), (800, Interpolation::Linear), move |_state, ctx, buf| {
+        let alpha = ctx.alpha();
+        let area = ctx.area;
+        let width = area.width as f32;
+        let edge_col = (alpha * (width + band_width as f32)) as u16;
+
+        for y in area.y..area.y + area.height {
+            for x in area.x..area.x + area.width {
+                let col_offset = x - area.x;
+                if col_offset >= edge_col {
+                    let cell = &mut buf[(x, y)];
+                    if let Some(old) = prev.as_ref().and_then(|pb| pb.cell((x, y))) {
+                        *cell = old.clone();
                     }
+                } else if col_offset + band_width >= edge_col {
+                    let d = edge_col - col_offset;
+                    let t = d as f32 / band_width as f32;
+                    let hue = t * 300.0;
+                    let color = hue_to_rgb(hue);
+                    let cell = &mut buf[(x, y)];
+                    cell.set_fg(color);
                 }
-            })
+            }
         }
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::markdown::{parse_frontmatter, parse_slides};
+    use crate::theme::Theme;
+
+    fn context() -> TransitionContext {
+        TransitionContext {
+            bg: Color::Black,
+            prev_buf: None,
+            direction: SlideDirection::default(),
+            rows: 24,
+            content_lines: 3,
+            line_dur_ms: 400.0,
+            stagger_ms: 30.0,
+        }
+    }
+
+    #[test]
+    fn registered_transitions_are_built_by_name() {
+        let used = Rc::new(Cell::new(false));
+        let flag = Rc::clone(&used);
+        let mut registry = TransitionRegistry::default();
+        registry.register("flash", move |ctx: &TransitionContext| {
+            flag.set(true);
+            fade(ctx)
+        });
+
+        let kind = TransitionKind::Custom("flash".into());
+        assert!(registry.create(&kind, &context()).is_some());
+        assert!(used.get());
+        assert!(registry.names().contains(&"flash".to_string()));
+    }
+
+    #[test]
+    fn unregistered_transitions_are_warned_about() {
+        let md = "---\ntransition: warp\n---\n# One\n\n---\n\n<!-- transition: flash -->\n# Two\n";
+        let (mut frontmatter, body) = parse_frontmatter(md);
+        let (_, warnings) = parse_slides(body, &Theme::default(), &frontmatter, None, false);
+        let warnings: Vec<_> = warnings
+            .iter()
+            .map(|w| (w.line, w.message.as_str()))
+            .collect();
+        assert_eq!(
+            warnings,
+            [
+                (2, "unknown transition `warp`, using `slide`"),
+                (8, "unknown transition `flash`, using `slide`"),
+            ]
+        );
+
+        let mut registry = TransitionRegistry::default();
+        registry.register("warp", fade);
+        registry.register("flash", fade);
+        frontmatter.transitions = registry.names();
+        let (_, warnings) = parse_slides(body, &Theme::default(), &frontmatter, None, false);
+        assert!(warnings.is_empty());
+    }
+}
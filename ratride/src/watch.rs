@@ -0,0 +1,133 @@
+//! The files `--watch` keeps an eye on: the deck, its theme file and its
+//! images, and the directories they are in.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Files a [`WatchSet`] saw written.
+#[derive(Debug, Default, PartialEq)]
+pub struct WatchChanges {
+    /// The deck or its theme file, either of which means reading it again.
+    pub deck: bool,
+    /// Image paths, as the slides refer to them.
+    pub images: Vec<String>,
+}
+
+/// Watched files by [`watch_key`].
+#[derive(Debug)]
+pub struct WatchSet {
+    deck: PathBuf,
+    theme: Option<PathBuf>,
+    /// Image files, with the paths slides refer to them by.
+    images: HashMap<PathBuf, Vec<String>>,
+}
+
+impl WatchSet {
+    /// Watch the deck at `deck`, whose directory must exist.
+    pub fn new(deck: &Path) -> Option<Self> {
+        Some(Self {
+            deck: watch_key(deck)?,
+            theme: None,
+            images: HashMap::new(),
+        })
+    }
+
+    /// Watch the theme file `theme` and the local images among `images`
+    /// (relative to `base_dir`) instead of the ones watched so far.
+    pub fn set_files<'a>(
+        &mut self,
+        theme: Option<&Path>,
+        images: impl IntoIterator<Item = &'a str>,
+        base_dir: &Path,
+    ) {
+        self.theme = theme.and_then(watch_key);
+        self.images.clear();
+        for path in images.into_iter().filter(|path| !path.contains("://")) {
+            let Some(key) = watch_key(&base_dir.join(path)) else {
+                continue;
+            };
+            let refs = self.images.entry(key).or_default();
+            if !refs.iter().any(|p| p == path) {
+                refs.push(path.to_string());
+            }
+        }
+    }
+
+    /// Directories of the watched files. Watching these rather than the
+    /// files catches editors that save by replacing the file.
+    pub fn dirs(&self) -> HashSet<PathBuf> {
+        let files = std::iter::once(&self.deck)
+            .chain(&self.theme)
+            .chain(self.images.keys());
+        files
+            .filter_map(|file| file.parent().map(Path::to_path_buf))
+            .collect()
+    }
+
+    /// What writing the files at `paths` changed.
+    pub fn changes<'a>(&self, paths: impl IntoIterator<Item = &'a Path>) -> WatchChanges {
+        let mut changes = WatchChanges::default();
+        for key in paths.into_iter().filter_map(watch_key) {
+            changes.deck |= key == self.deck || self.theme.as_ref() == Some(&key);
+            for path in self.images.get(&key).into_iter().flatten() {
+                if !changes.images.contains(path) {
+                    changes.images.push(path.clone());
+                }
+            }
+        }
+        changes
+    }
+}
+
+/// `path` with its directory canonicalized, so event paths and the paths
+/// slides use compare equal. The file itself may not exist yet.
+pub fn watch_key(path: &Path) -> Option<PathBuf> {
+    let dir = path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    Some(dir.canonicalize().ok()?.join(path.file_name()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn only_directories_of_current_files_are_watched() {
+        let dir = std::env::temp_dir().join(format!("ratride-watch-test-{}", std::process::id()));
+        for sub in ["deck", "deck/img", "shared", "other"] {
+            fs::create_dir_all(dir.join(sub)).unwrap();
+        }
+        let dir = dir.canonicalize().unwrap();
+        let base = dir.join("deck");
+        let mut set = WatchSet::new(&base.join("talk.md")).unwrap();
+        let theme = dir.join("shared/dark.toml");
+        set.set_files(
+            Some(&theme),
+            ["img/a.png", "./img/a.png", "https://x/b.png"],
+            &base,
+        );
+        let dirs = HashSet::from([base.clone(), base.join("img"), dir.join("shared")]);
+        assert_eq!(set.dirs(), dirs);
+
+        // Writing the theme reloads the deck; a file of the same name as
+        // the deck elsewhere doesn't
+        let changes = set.changes([
+            base.join("img/a.png").as_path(),
+            theme.as_path(),
+            dir.join("other/talk.md").as_path(),
+        ]);
+        let images = vec!["img/a.png".to_string(), "./img/a.png".to_string()];
+        assert_eq!(changes, WatchChanges { deck: true, images });
+        assert!(!set.changes([dir.join("other/talk.md").as_path()]).deck);
+
+        // Directories no file needs anymore are dropped
+        set.set_files(None, [], &base);
+        assert_eq!(set.dirs(), HashSet::from([base.clone()]));
+        assert_eq!(set.changes([theme.as_path()]), WatchChanges::default());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}